@@ -377,7 +377,11 @@ impl EventLoop {
                                 position,
                                 button: match tool_type {
                                     android_activity::input::ToolType::Finger => {
-                                        event::ButtonSource::Touch { finger_id, force }
+                                        event::ButtonSource::Touch {
+                                            finger_id,
+                                            force,
+                                            contact: None,
+                                        }
                                     },
                                     // TODO mouse events
                                     android_activity::input::ToolType::Mouse => continue,
@@ -394,7 +398,11 @@ impl EventLoop {
                                 position,
                                 source: match tool_type {
                                     android_activity::input::ToolType::Finger => {
-                                        event::PointerSource::Touch { finger_id, force }
+                                        event::PointerSource::Touch {
+                                            finger_id,
+                                            force,
+                                            contact: None,
+                                        }
                                     },
                                     // TODO mouse events
                                     android_activity::input::ToolType::Mouse => continue,
@@ -420,7 +428,11 @@ impl EventLoop {
                                     position,
                                     button: match tool_type {
                                         android_activity::input::ToolType::Finger => {
-                                            event::ButtonSource::Touch { finger_id, force }
+                                            event::ButtonSource::Touch {
+                                                finger_id,
+                                                force,
+                                                contact: None,
+                                            }
                                         },
                                         // TODO mouse events
                                         android_activity::input::ToolType::Mouse => continue,
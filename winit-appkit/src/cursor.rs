@@ -33,7 +33,10 @@ impl CustomCursor {
     pub(crate) fn new(cursor: CustomCursorSource) -> Result<CustomCursor, RequestError> {
         let cursor = match cursor {
             CustomCursorSource::Image(cursor_image) => cursor_image,
-            CustomCursorSource::Animation { .. } | CustomCursorSource::Url { .. } => {
+            CustomCursorSource::Animation { .. }
+            | CustomCursorSource::Url { .. }
+            | CustomCursorSource::File(_)
+            | CustomCursorSource::Scaled(_) => {
                 return Err(NotSupportedError::new("unsupported cursor kind").into());
             },
         };
@@ -45,7 +45,7 @@ use tracing::{debug_span, trace, warn};
 use winit_common::core_foundation::MainRunLoop;
 use winit_core::cursor::Cursor;
 use winit_core::error::{NotSupportedError, RequestError};
-use winit_core::event::{SurfaceSizeWriter, WindowEvent};
+use winit_core::event::{DragPayload, SurfaceSizeWriter, WindowEvent};
 use winit_core::icon::Icon;
 use winit_core::monitor::{Fullscreen, MonitorHandle as CoreMonitorHandle, MonitorHandleProvider};
 use winit_core::window::{
@@ -386,7 +386,11 @@ define_class!(
             let position =
                 LogicalPosition::<f64>::from((dl.x, dl.y)).to_physical(self.scale_factor());
 
-            self.queue_event(WindowEvent::DragEntered { paths, position });
+            self.queue_event(WindowEvent::DragEntered {
+                paths,
+                payload: DragPayload::default(),
+                position,
+            });
 
             true
         }
@@ -447,7 +451,11 @@ define_class!(
             let position =
                 LogicalPosition::<f64>::from((dl.x, dl.y)).to_physical(self.scale_factor());
 
-            self.queue_event(WindowEvent::DragDropped { paths, position });
+            self.queue_event(WindowEvent::DragDropped {
+                paths,
+                payload: DragPayload::default(),
+                position,
+            });
 
             true
         }
@@ -4,5 +4,9 @@
 pub mod core_foundation;
 #[cfg(feature = "event-handler")]
 pub mod event_handler;
+#[cfg(feature = "xcursor")]
+pub mod xcursor;
+#[cfg(feature = "xdg-desktop-portal")]
+pub mod xdg_desktop_portal;
 #[cfg(feature = "xkb")]
 pub mod xkb;
@@ -0,0 +1,135 @@
+//! Loading of the named cursors of Xcursor themes.
+//!
+//! The lookup follows libXcursor: the theme directories are searched in `XCURSOR_PATH`, and the
+//! themes listed in `Inherits` of their `index.theme` are searched when the cursor is missing.
+
+use std::path::PathBuf;
+use std::{env, fs};
+
+use winit_core::cursor::{CursorFile, CursorIcon, CustomCursorSource};
+
+/// The size of cursors when the system doesn't configure one.
+pub const DEFAULT_CURSOR_SIZE: u32 = 24;
+
+/// The maximum depth of inherited themes, to not loop on themes inheriting each other.
+const MAX_INHERIT_DEPTH: usize = 16;
+
+/// Load the cursor for `icon` from the Xcursor `theme`, trying its alternative names as well.
+///
+/// Returns `None` when none of the themes have the cursor or its file can't be read.
+pub fn load_cursor(theme: &str, icon: CursorIcon) -> Option<CursorFile> {
+    let dirs = search_path();
+    let mut names = std::iter::once(icon.name()).chain(icon.alt_names().iter().copied());
+    let path = names.find_map(|name| {
+        find_cursor(&dirs, theme, name, 0).or_else(|| find_cursor(&dirs, "default", name, 0))
+    })?;
+
+    let data = fs::read(&path)
+        .inspect_err(|err| tracing::warn!("Failed to read the cursor {path:?}: {err}"))
+        .ok()?;
+    match CustomCursorSource::from_xcursor(&data) {
+        Ok(CustomCursorSource::File(file)) => Some(file),
+        Ok(_) => None,
+        Err(err) => {
+            tracing::warn!("Failed to load the cursor {path:?}: {err}");
+            None
+        },
+    }
+}
+
+/// The directories the themes are searched in.
+fn search_path() -> Vec<PathBuf> {
+    if let Some(path) = env::var_os("XCURSOR_PATH") {
+        return env::split_paths(&path).collect();
+    }
+
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+
+    let mut dirs = Vec::new();
+    dirs.extend(data_home.map(|data_home| data_home.join("icons")));
+    dirs.extend(home.map(|home| home.join(".icons")));
+    dirs.extend(["/usr/share/icons", "/usr/share/pixmaps"].map(PathBuf::from));
+    dirs
+}
+
+/// Find the file of the cursor `name` in `theme` or the themes it inherits.
+fn find_cursor(dirs: &[PathBuf], theme: &str, name: &str, depth: usize) -> Option<PathBuf> {
+    // The names are used as path components.
+    if depth > MAX_INHERIT_DEPTH || theme.is_empty() || theme.contains(['/', '\0']) {
+        return None;
+    }
+
+    let mut inherits = Vec::new();
+    for theme_dir in dirs.iter().map(|dir| dir.join(theme)) {
+        let path = theme_dir.join("cursors").join(name);
+        if path.is_file() {
+            return Some(path);
+        }
+
+        if let Ok(index) = fs::read_to_string(theme_dir.join("index.theme")) {
+            inherits.extend(parse_inherits(&index).filter(|inherited| *inherited != theme));
+        }
+    }
+
+    inherits.iter().find_map(|inherited| find_cursor(dirs, inherited, name, depth + 1))
+}
+
+/// The themes listed in the `Inherits` key of an `index.theme` file.
+fn parse_inherits(index: &str) -> impl Iterator<Item = String> + '_ {
+    index
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == "Inherits").then_some(value)
+        })
+        .flat_map(|value| value.split([',', ';']))
+        .map(str::trim)
+        .filter(|theme| !theme.is_empty())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    /// Create the cursors directory of the theme.
+    fn theme_dir(dir: &Path, theme: &str) -> PathBuf {
+        let path = dir.join(theme).join("cursors");
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn parses_inherits() {
+        let index = "[Icon Theme]\nName=Breeze\nInherits = hicolor, Adwaita;default\n";
+        assert_eq!(parse_inherits(index).collect::<Vec<_>>(), ["hicolor", "Adwaita", "default"]);
+        assert_eq!(parse_inherits("[Icon Theme]\nName=Breeze\n").count(), 0);
+    }
+
+    #[test]
+    fn finds_inherited_cursors() {
+        let root = env::temp_dir().join(format!("winit-xcursor-{}", std::process::id()));
+        let user = root.join("user");
+        let system = root.join("system");
+
+        fs::write(theme_dir(&system, "base").join("default"), b"").unwrap();
+        fs::write(theme_dir(&system, "base").join("pointer"), b"").unwrap();
+        fs::write(theme_dir(&user, "custom").join("pointer"), b"").unwrap();
+        // The theme inherits from itself and from a missing theme, which are skipped.
+        fs::write(user.join("custom/index.theme"), "Inherits=custom,missing,base\n").unwrap();
+
+        let dirs = [user.clone(), system.clone()];
+        let find = |theme, name| find_cursor(&dirs, theme, name, 0);
+        assert_eq!(find("custom", "pointer"), Some(user.join("custom/cursors/pointer")));
+        assert_eq!(find("custom", "default"), Some(system.join("base/cursors/default")));
+        assert_eq!(find("custom", "text"), None);
+        assert_eq!(find("../user/custom", "pointer"), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
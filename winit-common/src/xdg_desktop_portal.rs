@@ -0,0 +1,307 @@
+//! Access to the settings of the [XDG Desktop Portal].
+//!
+//! The portal is queried over D-Bus with `zbus` on a helper thread, so the event loop is never
+//! blocked on the portal. The settings are empty without the `dbus` feature or when the portal
+//! can't be reached.
+//!
+//! [XDG Desktop Portal]: https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Settings.html
+
+use std::collections::HashMap;
+#[cfg(feature = "dbus")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "dbus")]
+use std::thread;
+use std::time::Duration;
+
+use winit_core::event_loop::DesktopSettings;
+use winit_core::window::Theme;
+#[cfg(feature = "dbus")]
+use zbus::blocking::{Connection, Proxy};
+#[cfg(feature = "dbus")]
+use zbus::zvariant::{OwnedValue, Value};
+
+/// The namespaces holding the settings we know about, the keys are unique across them.
+#[cfg(feature = "dbus")]
+const NAMESPACES: &[&str] = &[
+    "org.freedesktop.appearance",
+    "org.gnome.desktop.interface",
+    "org.gnome.desktop.peripherals.mouse",
+    "org.kde.kdeglobals.KDE",
+];
+
+/// The settings read from the portal.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Settings {
+    /// The values of the settings as text, since KDE stores its numbers as strings.
+    values: HashMap<String, String>,
+}
+
+impl Settings {
+    /// Store the value of a setting, returning whether it changed.
+    ///
+    /// Settings outside of the namespaces we know about or of other types are ignored.
+    #[cfg(feature = "dbus")]
+    fn set(&mut self, namespace: &str, key: &str, value: &Value<'_>) -> bool {
+        if !NAMESPACES.contains(&namespace) {
+            return false;
+        }
+
+        let value = match value_string(value) {
+            Some(value) => value,
+            None => return false,
+        };
+        if self.values.get(key) == Some(&value) {
+            return false;
+        }
+
+        self.values.insert(key.to_owned(), value);
+        true
+    }
+
+    fn get<T: std::str::FromStr>(&self, key: &str) -> Option<T> {
+        self.values.get(key)?.parse().ok()
+    }
+
+    /// The `color-scheme` of the `org.freedesktop.appearance` namespace.
+    ///
+    /// Returns `None` when the user has no preference.
+    pub fn color_scheme(&self) -> Option<Theme> {
+        match self.get::<u32>("color-scheme")? {
+            1 => Some(Theme::Dark),
+            2 => Some(Theme::Light),
+            _ => None,
+        }
+    }
+
+    /// The input settings of GNOME, or else of KDE.
+    ///
+    /// Neither of them has a setting for the double click distance.
+    pub fn desktop_settings(&self) -> DesktopSettings {
+        let millis = |key: &str| self.get::<u64>(key).map(Duration::from_millis);
+
+        let mut settings = DesktopSettings::default();
+        settings.double_click_time =
+            millis("double-click").or_else(|| millis("DoubleClickInterval"));
+        settings.drag_threshold = self.get("drag-threshold").or_else(|| self.get("StartDragDist"));
+        settings.caret_blink_time =
+            millis("cursor-blink-time").or_else(|| millis("CursorBlinkRate"));
+        settings.caret_blink = self
+            .get("cursor-blink")
+            .or_else(|| settings.caret_blink_time.map(|time| !time.is_zero()));
+        settings.wheel_scroll_lines = self.get("WheelScrollLines");
+        settings
+    }
+}
+
+/// The value of a setting as text, for the types of the settings we know about.
+#[cfg(feature = "dbus")]
+fn value_string(value: &Value<'_>) -> Option<String> {
+    let value = match value {
+        // Some portals wrap the values in another variant.
+        Value::Value(value) => return value_string(value),
+        Value::Str(value) => value.to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::U8(value) => value.to_string(),
+        Value::I16(value) => value.to_string(),
+        Value::U16(value) => value.to_string(),
+        Value::I32(value) => value.to_string(),
+        Value::U32(value) => value.to_string(),
+        Value::I64(value) => value.to_string(),
+        Value::U64(value) => value.to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Follows the settings of the portal from a helper thread.
+///
+/// The helper stops once this is dropped. Without the `dbus` feature nothing is followed.
+#[derive(Debug)]
+pub struct SettingsWatcher {
+    #[cfg(feature = "dbus")]
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl SettingsWatcher {
+    /// Start reading the settings.
+    ///
+    /// `on_change` is called from the helper thread with the settings once they're read, and
+    /// again every time one of them changes.
+    #[cfg(feature = "dbus")]
+    pub fn spawn(on_change: impl Fn(Settings) + Send + 'static) -> Self {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let thread_shared = shared.clone();
+        let spawned =
+            thread::Builder::new().name(String::from("winit-portal-settings")).spawn(move || {
+                if let Err(err) = watch(&thread_shared, on_change) {
+                    if !thread_shared.lock().unwrap().stopped {
+                        tracing::debug!("failed to read the XDG Desktop Portal settings: {err}");
+                    }
+                }
+            });
+        if let Err(err) = spawned {
+            tracing::warn!("failed to spawn the XDG Desktop Portal settings thread: {err}");
+        }
+
+        Self { shared }
+    }
+
+    /// Start reading the settings, which does nothing without the `dbus` feature.
+    #[cfg(not(feature = "dbus"))]
+    pub fn spawn(_on_change: impl Fn(Settings) + Send + 'static) -> Self {
+        Self {}
+    }
+}
+
+#[cfg(feature = "dbus")]
+impl Drop for SettingsWatcher {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.stopped = true;
+        // The signals stop once the connection is closed, which ends the helper thread.
+        if let Some(connection) = shared.connection.take() {
+            let _ = connection.close();
+        }
+    }
+}
+
+/// The connection shared with the helper thread.
+#[cfg(feature = "dbus")]
+#[derive(Debug, Default)]
+struct Shared {
+    connection: Option<Connection>,
+    stopped: bool,
+}
+
+#[cfg(feature = "dbus")]
+fn watch(shared: &Mutex<Shared>, on_change: impl Fn(Settings)) -> zbus::Result<()> {
+    let connection = Connection::session()?;
+    {
+        let mut shared = shared.lock().unwrap();
+        if shared.stopped {
+            return Ok(());
+        }
+        shared.connection = Some(connection.clone());
+    }
+
+    let proxy = Proxy::new(
+        &connection,
+        "org.freedesktop.portal.Desktop",
+        "/org/freedesktop/portal/desktop",
+        "org.freedesktop.portal.Settings",
+    )?;
+
+    // Follow the changes before reading the settings, to not miss the ones made in between.
+    let changes = proxy.receive_signal("SettingChanged")?;
+    let namespaces: HashMap<String, HashMap<String, OwnedValue>> =
+        proxy.call("ReadAll", &(NAMESPACES,))?;
+
+    let mut settings = Settings::default();
+    for (namespace, values) in &namespaces {
+        for (key, value) in values {
+            settings.set(namespace, key, value);
+        }
+    }
+    on_change(settings.clone());
+
+    for message in changes {
+        let (namespace, key, value): (String, String, OwnedValue) =
+            match message.body().deserialize() {
+                Ok(args) => args,
+                Err(_) => continue,
+            };
+        if settings.set(&namespace, &key, &value) {
+            on_change(settings.clone());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "dbus"))]
+mod tests {
+    use super::*;
+
+    fn from_values(values: &[(&str, &str, Value<'_>)]) -> Settings {
+        let mut settings = Settings::default();
+        for (namespace, key, value) in values {
+            settings.set(namespace, key, value);
+        }
+        settings
+    }
+
+    #[test]
+    fn reads_color_scheme() {
+        let appearance = "org.freedesktop.appearance";
+        let settings = from_values(&[(appearance, "color-scheme", Value::U32(1))]);
+        assert_eq!(settings.color_scheme(), Some(Theme::Dark));
+
+        let settings = from_values(&[(appearance, "color-scheme", Value::U32(2))]);
+        assert_eq!(settings.color_scheme(), Some(Theme::Light));
+
+        // No preference.
+        let settings = from_values(&[(appearance, "color-scheme", Value::U32(0))]);
+        assert_eq!(settings.color_scheme(), None);
+
+        assert_eq!(Settings::default().color_scheme(), None);
+    }
+
+    #[test]
+    fn sets_known_settings() {
+        let mut settings = Settings::default();
+        let appearance = "org.freedesktop.appearance";
+
+        assert!(settings.set(appearance, "color-scheme", &Value::U32(1)));
+        assert!(!settings.set(appearance, "color-scheme", &Value::U32(1)));
+        // Values wrapped in another variant.
+        assert!(settings.set(appearance, "color-scheme", &Value::Value(Box::new(2u32.into()))));
+        assert_eq!(settings.color_scheme(), Some(Theme::Light));
+
+        // Unknown namespaces and types.
+        assert!(!settings.set("org.example.unknown", "color-scheme", &Value::U32(1)));
+        assert!(!settings.set(appearance, "accent-color", &Value::from((0.2, 0.5, 0.8))));
+        assert_eq!(settings.values.len(), 1);
+    }
+
+    #[test]
+    fn reads_gnome_settings() {
+        let interface = "org.gnome.desktop.interface";
+        let mouse = "org.gnome.desktop.peripherals.mouse";
+        let settings = from_values(&[
+            (interface, "cursor-blink", Value::Bool(false)),
+            (interface, "cursor-blink-time", Value::I32(1000)),
+            (interface, "gtk-theme", Value::from("Adwaita dark")),
+            (mouse, "double-click", Value::I32(300)),
+            (mouse, "drag-threshold", Value::I32(6)),
+        ]);
+        assert_eq!(settings.values["gtk-theme"], "Adwaita dark");
+
+        let mut expected = DesktopSettings::default();
+        expected.double_click_time = Some(Duration::from_millis(300));
+        expected.drag_threshold = Some(6);
+        expected.caret_blink = Some(false);
+        expected.caret_blink_time = Some(Duration::from_millis(1000));
+        assert_eq!(settings.desktop_settings(), expected);
+    }
+
+    #[test]
+    fn reads_kde_settings() {
+        // KDE stores its settings as strings.
+        let kde = "org.kde.kdeglobals.KDE";
+        let settings = from_values(&[
+            (kde, "CursorBlinkRate", Value::from("0")),
+            (kde, "DoubleClickInterval", Value::from("500")),
+            (kde, "WheelScrollLines", Value::from("5")),
+        ]);
+
+        let mut expected = DesktopSettings::default();
+        expected.double_click_time = Some(Duration::from_millis(500));
+        expected.caret_blink = Some(false);
+        expected.caret_blink_time = Some(Duration::ZERO);
+        expected.wheel_scroll_lines = Some(5);
+        assert_eq!(settings.desktop_settings(), expected);
+
+        // Nothing is made up when the portal doesn't answer.
+        assert_eq!(Settings::default().desktop_settings(), DesktopSettings::default());
+    }
+}
@@ -1,10 +1,11 @@
 //! End user application handling.
 
-use crate::event::{DeviceEvent, DeviceId, StartCause, WindowEvent};
+use crate::event::{ClipboardEvent, DeviceEvent, DeviceId, MonitorEvent, StartCause, WindowEvent};
 use crate::event_loop::ActiveEventLoop;
 use crate::window::WindowId;
 
 pub mod macos;
+pub mod x11;
 
 /// The handler of application-level events.
 pub trait ApplicationHandler {
@@ -211,6 +212,31 @@ pub trait ApplicationHandler {
         let _ = (event_loop, device_id, event);
     }
 
+    /// Emitted when a clipboard request made with the [`ActiveEventLoop`] is answered.
+    fn clipboard_event(&mut self, event_loop: &dyn ActiveEventLoop, event: ClipboardEvent) {
+        let _ = (event_loop, event);
+    }
+
+    /// Emitted when a monitor is connected, disconnected or changes its configuration.
+    ///
+    /// The monitors connected when the event loop starts are reported with
+    /// [`MonitorEvent::Added`] during its first iteration, right after
+    /// [`can_create_surfaces`][Self::can_create_surfaces].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    fn monitor_event(&mut self, event_loop: &dyn ActiveEventLoop, event: MonitorEvent) {
+        let _ = (event_loop, event);
+    }
+
+    /// Emitted when the input settings of the desktop have changed.
+    ///
+    /// The new settings can be queried with [`ActiveEventLoop::desktop_settings`].
+    fn desktop_settings_changed(&mut self, event_loop: &dyn ActiveEventLoop) {
+        let _ = event_loop;
+    }
+
     /// Emitted when the event loop is about to block and wait for new events.
     ///
     /// Most applications shouldn't need to hook into this event since there is no real relationship
@@ -343,6 +369,14 @@ pub trait ApplicationHandler {
     fn macos_handler(&mut self) -> Option<&mut dyn macos::ApplicationHandlerExtMacOS> {
         None
     }
+
+    /// The X11-specific handler.
+    ///
+    /// The return value from this should not change at runtime.
+    #[inline(always)]
+    fn x11_handler(&mut self) -> Option<&mut dyn x11::ApplicationHandlerExtX11> {
+        None
+    }
 }
 
 #[deny(clippy::missing_trait_methods)]
@@ -387,6 +421,21 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for &mut A {
         (**self).device_event(event_loop, device_id, event);
     }
 
+    #[inline]
+    fn clipboard_event(&mut self, event_loop: &dyn ActiveEventLoop, event: ClipboardEvent) {
+        (**self).clipboard_event(event_loop, event);
+    }
+
+    #[inline]
+    fn monitor_event(&mut self, event_loop: &dyn ActiveEventLoop, event: MonitorEvent) {
+        (**self).monitor_event(event_loop, event);
+    }
+
+    #[inline]
+    fn desktop_settings_changed(&mut self, event_loop: &dyn ActiveEventLoop) {
+        (**self).desktop_settings_changed(event_loop);
+    }
+
     #[inline]
     fn about_to_wait(&mut self, event_loop: &dyn ActiveEventLoop) {
         (**self).about_to_wait(event_loop);
@@ -411,6 +460,11 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for &mut A {
     fn macos_handler(&mut self) -> Option<&mut dyn macos::ApplicationHandlerExtMacOS> {
         (**self).macos_handler()
     }
+
+    #[inline]
+    fn x11_handler(&mut self) -> Option<&mut dyn x11::ApplicationHandlerExtX11> {
+        (**self).x11_handler()
+    }
 }
 
 #[deny(clippy::missing_trait_methods)]
@@ -455,6 +509,21 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for Box<A> {
         (**self).device_event(event_loop, device_id, event);
     }
 
+    #[inline]
+    fn clipboard_event(&mut self, event_loop: &dyn ActiveEventLoop, event: ClipboardEvent) {
+        (**self).clipboard_event(event_loop, event);
+    }
+
+    #[inline]
+    fn monitor_event(&mut self, event_loop: &dyn ActiveEventLoop, event: MonitorEvent) {
+        (**self).monitor_event(event_loop, event);
+    }
+
+    #[inline]
+    fn desktop_settings_changed(&mut self, event_loop: &dyn ActiveEventLoop) {
+        (**self).desktop_settings_changed(event_loop);
+    }
+
     #[inline]
     fn about_to_wait(&mut self, event_loop: &dyn ActiveEventLoop) {
         (**self).about_to_wait(event_loop);
@@ -479,4 +548,9 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for Box<A> {
     fn macos_handler(&mut self) -> Option<&mut dyn macos::ApplicationHandlerExtMacOS> {
         (**self).macos_handler()
     }
+
+    #[inline]
+    fn x11_handler(&mut self) -> Option<&mut dyn x11::ApplicationHandlerExtX11> {
+        (**self).x11_handler()
+    }
 }
@@ -0,0 +1,18 @@
+use crate::application::ApplicationHandler;
+use crate::event_loop::ActiveEventLoop;
+
+/// Additional events on [`ApplicationHandler`] that are specific to X11.
+///
+/// This can be registered with [`ApplicationHandler::x11_handler`].
+pub trait ApplicationHandlerExtX11: ApplicationHandler {
+    /// The settings shared by the XSettings manager have changed.
+    ///
+    /// The new settings can be queried with `ActiveEventLoopExtX11::xsettings`. A change of the
+    /// DPI is additionally reported to the affected windows with
+    /// [`WindowEvent::ScaleFactorChanged`].
+    ///
+    /// [`WindowEvent::ScaleFactorChanged`]: crate::event::WindowEvent::ScaleFactorChanged
+    fn xsettings_changed(&mut self, event_loop: &dyn ActiveEventLoop) {
+        let _ = event_loop;
+    }
+}
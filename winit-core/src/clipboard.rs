@@ -0,0 +1,22 @@
+//! Types for accessing the clipboard with the [`ActiveEventLoop`].
+//!
+//! [`ActiveEventLoop`]: crate::event_loop::ActiveEventLoop
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The selection to access.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ClipboardKind {
+    /// The regular clipboard, filled by copying.
+    #[default]
+    Clipboard,
+    /// The primary selection, filled by selecting text and pasted with the middle mouse button.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Requires the `zwp_primary_selection_device_manager_v1` protocol.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    Primary,
+}
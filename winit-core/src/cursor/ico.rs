@@ -0,0 +1,469 @@
+//! Parsing of the `.cur` and `.ani` cursor formats of Windows.
+
+use std::time::Duration;
+
+use super::{
+    BadCursorFile, BadImage, CursorFile, CursorFrame, CursorImage, MAX_CURSOR_SIZE, read_u16,
+    read_u32, size_distance,
+};
+
+const ICON_TYPE: u16 = 1;
+const CURSOR_TYPE: u16 = 2;
+
+const DIR_HEADER_LEN: usize = 6;
+const DIR_ENTRY_LEN: usize = 16;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// The `anih` flag telling that frames are stored as icons or cursors, rather than raw bitmaps.
+const ANI_ICON_FRAMES: u32 = 1;
+
+/// Delays of `.ani` files are given in jiffies.
+const JIFFY: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+pub(crate) fn parse_cur(data: &[u8]) -> Result<CursorFile, BadCursorFile> {
+    let images = parse_dir(data)?;
+    CursorFile::from_frames(
+        images.into_iter().map(|(nominal_size, image)| {
+            (nominal_size, CursorFrame { image, delay: Duration::ZERO })
+        }),
+    )
+}
+
+pub(crate) fn parse_ani(data: &[u8]) -> Result<CursorFile, BadCursorFile> {
+    if !data.starts_with(b"RIFF") || data.get(8..12) != Some(&b"ACON"[..]) {
+        return Err(BadCursorFile::BadSignature);
+    }
+
+    let riff_end = (read_u32(data, 4)? as usize).saturating_add(8).min(data.len());
+
+    let mut header = None;
+    let mut rates = None;
+    let mut sequence = None;
+    let mut icons = Vec::new();
+    let body = data.get(12..riff_end).ok_or(BadCursorFile::Malformed)?;
+    for (id, chunk) in read_chunks(body)? {
+        match id {
+            b"anih" => header = Some(AniHeader::parse(chunk)?),
+            b"rate" => rates = Some(read_u32s(chunk)),
+            b"seq " => sequence = Some(read_u32s(chunk)),
+            b"LIST" if chunk.starts_with(b"fram") => {
+                for (id, icon) in read_chunks(&chunk[4..])? {
+                    if id == b"icon" {
+                        icons.push(parse_dir(icon)?);
+                    }
+                }
+            },
+            _ => (),
+        }
+    }
+
+    let header = header.ok_or(BadCursorFile::Malformed)?;
+    if header.flags & ANI_ICON_FRAMES == 0 {
+        return Err(BadCursorFile::Unsupported);
+    }
+
+    let steps = if header.steps == 0 { icons.len() } else { header.steps as usize };
+    let steps = (0..steps)
+        .map(|step| {
+            let index = match &sequence {
+                Some(sequence) => *sequence.get(step).ok_or(BadCursorFile::Malformed)? as usize,
+                None => step,
+            };
+            let icon = icons.get(index).ok_or(BadCursorFile::Malformed)?;
+            let rate = rates.as_ref().and_then(|rates| rates.get(step)).unwrap_or(&header.rate);
+            Ok((icon, JIFFY * *rate))
+        })
+        .collect::<Result<Vec<_>, BadCursorFile>>()?;
+
+    // Every frame may hold several sizes, so build a variant for each size of the first frame
+    // using the closest size of the other frames.
+    let sizes = steps.first().map(|(icon, _)| icon.as_slice()).unwrap_or_default();
+    let frames = sizes.iter().flat_map(|&(nominal_size, _)| {
+        steps.iter().map(move |(icon, delay)| {
+            // The directories hold at least one image.
+            let (_, image) =
+                icon.iter().min_by_key(|(size, _)| size_distance(*size, nominal_size)).unwrap();
+            (nominal_size, CursorFrame { image: image.clone(), delay: *delay })
+        })
+    });
+
+    CursorFile::from_frames(frames)
+}
+
+/// The `anih` chunk of `.ani` files.
+struct AniHeader {
+    steps: u32,
+    rate: u32,
+    flags: u32,
+}
+
+impl AniHeader {
+    fn parse(chunk: &[u8]) -> Result<Self, BadCursorFile> {
+        Ok(Self {
+            steps: read_u32(chunk, 8)?,
+            rate: read_u32(chunk, 28)?,
+            flags: read_u32(chunk, 32)?,
+        })
+    }
+}
+
+/// The identifier and body of a RIFF chunk.
+type Chunk<'a> = (&'a [u8], &'a [u8]);
+
+/// Split RIFF data into its chunks.
+fn read_chunks(mut data: &[u8]) -> Result<Vec<Chunk<'_>>, BadCursorFile> {
+    let mut chunks = Vec::new();
+    while data.len() >= 8 {
+        let len = read_u32(data, 4)? as usize;
+        let chunk = data.get(8..8usize.saturating_add(len)).ok_or(BadCursorFile::Malformed)?;
+        chunks.push((&data[..4], chunk));
+
+        // Chunks are padded to an even length.
+        let next = (8 + len).saturating_add(len & 1).min(data.len());
+        data = &data[next..];
+    }
+
+    Ok(chunks)
+}
+
+fn read_u32s(chunk: &[u8]) -> Vec<u32> {
+    chunk.chunks_exact(4).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap())).collect()
+}
+
+/// Parse an icon or cursor directory, returning the nominal size and image of its entries.
+fn parse_dir(data: &[u8]) -> Result<Vec<(u32, CursorImage)>, BadCursorFile> {
+    let kind = read_u16(data, 2)?;
+    if read_u16(data, 0)? != 0 || !matches!(kind, ICON_TYPE | CURSOR_TYPE) {
+        return Err(BadCursorFile::BadSignature);
+    }
+
+    let count = read_u16(data, 4)? as usize;
+    if count == 0 {
+        return Err(BadCursorFile::Empty);
+    }
+
+    (0..count)
+        .map(|index| {
+            let entry = DIR_HEADER_LEN + index * DIR_ENTRY_LEN;
+            let nominal_size = match *data.get(entry).ok_or(BadCursorFile::Malformed)? {
+                0 => 256,
+                width => width as u32,
+            };
+
+            // Icons store their planes and bit count there instead of the hotspot.
+            let hotspot = match kind {
+                CURSOR_TYPE => (read_u16(data, entry + 4)?, read_u16(data, entry + 6)?),
+                _ => (0, 0),
+            };
+
+            let len = read_u32(data, entry + 8)? as usize;
+            let offset = read_u32(data, entry + 12)? as usize;
+            let image =
+                data.get(offset..offset.saturating_add(len)).ok_or(BadCursorFile::Malformed)?;
+
+            Ok((nominal_size, parse_bitmap(image, hotspot)?))
+        })
+        .collect()
+}
+
+/// Parse a device independent bitmap, holding the colors followed by a transparency mask.
+fn parse_bitmap(
+    data: &[u8],
+    (hotspot_x, hotspot_y): (u16, u16),
+) -> Result<CursorImage, BadCursorFile> {
+    if data.starts_with(PNG_SIGNATURE) {
+        return Err(BadCursorFile::Unsupported);
+    }
+
+    let header_len = read_u32(data, 0)? as usize;
+    let width = read_u32(data, 4)? as i32;
+    // The height covers both the colors and the mask.
+    let height = read_u32(data, 8)? as i32 / 2;
+    let bit_count = read_u16(data, 14)? as usize;
+    let compression = read_u32(data, 16)?;
+    let colors_used = read_u32(data, 32)? as usize;
+
+    // Only uncompressed bottom-up bitmaps are valid in cursors.
+    if header_len < 40 || compression != 0 || !matches!(bit_count, 1 | 4 | 8 | 24 | 32) {
+        return Err(BadCursorFile::Unsupported);
+    }
+
+    if width <= 0 || height <= 0 {
+        return Err(BadCursorFile::Malformed);
+    }
+
+    let (width, height) = (width as usize, height as usize);
+    if width > MAX_CURSOR_SIZE as usize || height > MAX_CURSOR_SIZE as usize {
+        let width = width.min(u16::MAX as usize) as u16;
+        let height = height.min(u16::MAX as usize) as u16;
+        return Err(BadImage::TooLarge { width, height }.into());
+    }
+
+    let palette_len = match (bit_count, colors_used) {
+        (32 | 24, _) => 0,
+        (_, 0) => 1 << bit_count,
+        (_, colors_used) => colors_used,
+    };
+    let palette = data
+        .get(header_len..header_len.saturating_add(palette_len.saturating_mul(4)))
+        .ok_or(BadCursorFile::Malformed)?;
+
+    // Rows are padded to 4 bytes.
+    let color_stride = (width * bit_count).div_ceil(32) * 4;
+    let mask_stride = width.div_ceil(32) * 4;
+    let colors_start = header_len + palette.len();
+    let mask_start = colors_start + color_stride * height;
+    let colors = data.get(colors_start..mask_start).ok_or(BadCursorFile::Malformed)?;
+    // Cursors with an alpha channel may omit the mask.
+    let mask = data.get(mask_start..mask_start + mask_stride * height);
+    if mask.is_none() && bit_count != 32 {
+        return Err(BadCursorFile::Malformed);
+    }
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        // Rows are stored bottom-up.
+        let row = height - 1 - y;
+        let colors = &colors[row * color_stride..][..color_stride];
+        let mask = mask.map(|mask| &mask[row * mask_stride..][..mask_stride]);
+
+        for x in 0..width {
+            let [r, g, b, a] = match bit_count {
+                32 => {
+                    let [b, g, r, a] = colors[x * 4..][..4].try_into().unwrap();
+                    [r, g, b, a]
+                },
+                24 => {
+                    let [b, g, r] = colors[x * 3..][..3].try_into().unwrap();
+                    [r, g, b, 255]
+                },
+                _ => {
+                    let bit = x * bit_count;
+                    let shift = 8 - bit_count - bit % 8;
+                    let index = (colors[bit / 8] >> shift) as usize & ((1 << bit_count) - 1);
+                    match palette.get(index * 4..index * 4 + 3) {
+                        Some(&[b, g, r]) => [r, g, b, 255],
+                        _ => [0, 0, 0, 255],
+                    }
+                },
+            };
+
+            // Pixels set in the mask are transparent, or invert the screen which can't be
+            // represented and is treated the same.
+            let masked = mask.is_some_and(|mask| mask[x / 8] & (0x80 >> (x % 8)) != 0);
+            rgba.extend_from_slice(&[r, g, b, if masked { 0 } else { a }]);
+        }
+    }
+
+    // Bitmaps without alpha values rely on the mask alone.
+    if bit_count == 32 && rgba.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+        if let Some(mask) = mask {
+            for (index, pixel) in rgba.chunks_exact_mut(4).enumerate() {
+                let (x, y) = (index % width, height - 1 - index / width);
+                pixel[3] =
+                    if mask[y * mask_stride + x / 8] & (0x80 >> (x % 8)) != 0 { 0 } else { 255 };
+            }
+        }
+    }
+
+    Ok(CursorImage::from_rgba(rgba, width as u16, height as u16, hotspot_x, hotspot_y)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 32 bits bitmap whose pixels are all `bgra`, with the left column masked out.
+    fn bitmap32(size: u32, bgra: [u8; 4]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for value in [40, size, size * 2] {
+            data.extend(value.to_le_bytes());
+        }
+        data.extend(1u16.to_le_bytes());
+        data.extend(32u16.to_le_bytes());
+        data.extend([0; 24]);
+
+        for _ in 0..size * size {
+            data.extend(bgra);
+        }
+
+        let mask_stride = size.div_ceil(32) * 4;
+        for _ in 0..size {
+            data.push(0x80);
+            data.extend(vec![0; mask_stride as usize - 1]);
+        }
+
+        data
+    }
+
+    /// A 1 bit bitmap with a black and white palette, white on the bottom row.
+    fn bitmap1(size: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        for value in [40, size, size * 2] {
+            data.extend(value.to_le_bytes());
+        }
+        data.extend(1u16.to_le_bytes());
+        data.extend(1u16.to_le_bytes());
+        data.extend([0; 24]);
+        data.extend([0, 0, 0, 0, 255, 255, 255, 0]);
+
+        let stride = size.div_ceil(32) * 4;
+        for row in 0..size {
+            let fill = if row == 0 { 0xff } else { 0 };
+            data.extend(vec![fill; stride as usize]);
+        }
+        data.extend(vec![0; (stride * size) as usize]);
+
+        data
+    }
+
+    fn cursor_dir(kind: u16, entries: &[(u8, (u16, u16), Vec<u8>)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for value in [0, kind, entries.len() as u16] {
+            data.extend(value.to_le_bytes());
+        }
+
+        let mut offset = DIR_HEADER_LEN + entries.len() * DIR_ENTRY_LEN;
+        for (size, hotspot, image) in entries {
+            data.extend([*size, *size, 0, 0]);
+            data.extend(hotspot.0.to_le_bytes());
+            data.extend(hotspot.1.to_le_bytes());
+            data.extend((image.len() as u32).to_le_bytes());
+            data.extend((offset as u32).to_le_bytes());
+            offset += image.len();
+        }
+
+        for (_, _, image) in entries {
+            data.extend(image);
+        }
+
+        data
+    }
+
+    fn chunk(id: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut data = id.to_vec();
+        data.extend((body.len() as u32).to_le_bytes());
+        data.extend(body);
+        if body.len() % 2 == 1 {
+            data.push(0);
+        }
+        data
+    }
+
+    fn ani(steps: u32, rate: u32, extra: &[Vec<u8>], icons: &[Vec<u8>]) -> Vec<u8> {
+        let mut header = Vec::new();
+        for value in [36, icons.len() as u32, steps, 0, 0, 0, 0, rate, ANI_ICON_FRAMES] {
+            header.extend(value.to_le_bytes());
+        }
+
+        let mut frames = b"fram".to_vec();
+        for icon in icons {
+            frames.extend(chunk(b"icon", icon));
+        }
+
+        let mut body = b"ACON".to_vec();
+        body.extend(chunk(b"anih", &header));
+        for chunk in extra {
+            body.extend(chunk);
+        }
+        body.extend(chunk(b"LIST", &frames));
+
+        chunk(b"RIFF", &body)
+    }
+
+    #[test]
+    fn parses_cur_sizes() {
+        let data = cursor_dir(CURSOR_TYPE, &[
+            (32, (3, 4), bitmap32(32, [0, 0, 255, 255])),
+            (16, (1, 2), bitmap32(16, [0, 0, 255, 255])),
+        ]);
+
+        let file = parse_cur(&data).unwrap();
+        let sizes: Vec<_> = file.variants().iter().map(|variant| variant.nominal_size()).collect();
+        assert_eq!(sizes, [16, 32]);
+
+        let image = file.best_variant(32).frames()[0].image();
+        assert_eq!((image.hotspot_x(), image.hotspot_y()), (3, 4));
+        assert_eq!(&image.buffer()[..8], [255, 0, 0, 0, 255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn uses_mask_without_alpha() {
+        let data = cursor_dir(CURSOR_TYPE, &[(4, (0, 0), bitmap32(4, [0, 255, 0, 0]))]);
+
+        let file = parse_cur(&data).unwrap();
+        let image = file.variants()[0].frames()[0].image();
+        assert_eq!(&image.buffer()[..8], [0, 255, 0, 0, 0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn parses_paletted_bitmap() {
+        let data = cursor_dir(CURSOR_TYPE, &[(8, (0, 0), bitmap1(8))]);
+
+        let file = parse_cur(&data).unwrap();
+        let image = file.variants()[0].frames()[0].image();
+        // The bottom row is stored first.
+        assert_eq!(&image.buffer()[..4], [0, 0, 0, 255]);
+        assert_eq!(&image.buffer()[7 * 8 * 4..][..4], [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn parses_ani_frames() {
+        let red = cursor_dir(CURSOR_TYPE, &[(8, (0, 0), bitmap32(8, [0, 0, 255, 255]))]);
+        let blue = cursor_dir(CURSOR_TYPE, &[(8, (0, 0), bitmap32(8, [255, 0, 0, 255]))]);
+        let sequence: Vec<u8> = [1u32, 0, 1].iter().flat_map(|value| value.to_le_bytes()).collect();
+        let rates: Vec<u8> = [6u32, 12, 3].iter().flat_map(|value| value.to_le_bytes()).collect();
+
+        let data = ani(3, 6, &[chunk(b"seq ", &sequence), chunk(b"rate", &rates)], &[red, blue]);
+        let file = parse_ani(&data).unwrap();
+        let frames = file.variants()[0].frames();
+
+        let colors: Vec<_> =
+            frames.iter().map(|frame| frame.image().buffer()[4..7].to_vec()).collect();
+        assert_eq!(colors, [[0, 0, 255], [255, 0, 0], [0, 0, 255]]);
+        let delays: Vec<_> = frames.iter().map(|frame| frame.delay()).collect();
+        assert_eq!(delays, [JIFFY * 6, JIFFY * 12, JIFFY * 3]);
+    }
+
+    #[test]
+    fn ani_defaults_to_frame_order() {
+        let icon = cursor_dir(CURSOR_TYPE, &[(8, (0, 0), bitmap32(8, [0, 0, 255, 255]))]);
+
+        let file = parse_ani(&ani(0, 10, &[], &[icon.clone(), icon])).unwrap();
+        let frames = file.variants()[0].frames();
+        assert_eq!(frames.len(), 2);
+        assert!(frames.iter().all(|frame| frame.delay() == JIFFY * 10));
+    }
+
+    #[test]
+    fn rejects_invalid_data() {
+        assert_eq!(parse_cur(b"Xcur"), Err(BadCursorFile::BadSignature));
+        assert_eq!(parse_cur(&cursor_dir(CURSOR_TYPE, &[])), Err(BadCursorFile::Empty));
+
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend([0; 32]);
+        let data = cursor_dir(CURSOR_TYPE, &[(8, (0, 0), png)]);
+        assert_eq!(parse_cur(&data), Err(BadCursorFile::Unsupported));
+
+        let data = cursor_dir(CURSOR_TYPE, &[(8, (0, 0), bitmap32(8, [0; 4]))]);
+        assert_eq!(parse_cur(&data[..data.len() - 100]), Err(BadCursorFile::Malformed));
+
+        let data = cursor_dir(CURSOR_TYPE, &[(8, (8, 0), bitmap32(8, [0; 4]))]);
+        assert!(matches!(
+            parse_cur(&data),
+            Err(BadCursorFile::BadImage(BadImage::HotspotOutOfBounds { .. }))
+        ));
+
+        assert_eq!(parse_ani(b"RIFF\0\0\0\0ACON"), Err(BadCursorFile::Malformed));
+    }
+
+    #[test]
+    fn rejects_empty_ani_frame() {
+        let icon = cursor_dir(CURSOR_TYPE, &[(8, (0, 0), bitmap32(8, [0, 0, 255, 255]))]);
+        let empty = cursor_dir(CURSOR_TYPE, &[]);
+
+        assert_eq!(parse_ani(&ani(0, 10, &[], &[icon, empty.clone()])), Err(BadCursorFile::Empty));
+        assert_eq!(parse_ani(&ani(0, 10, &[], &[empty])), Err(BadCursorFile::Empty));
+    }
+}
@@ -1,4 +1,5 @@
 use core::fmt;
+use std::cmp::Reverse;
 use std::error::Error;
 use std::hash::Hash;
 use std::ops::Deref;
@@ -10,6 +11,9 @@ pub use cursor_icon::CursorIcon;
 
 use crate::as_any::AsAny;
 
+mod ico;
+mod xcursor;
+
 /// The maximum width and height for a cursor when using [`CustomCursorSource::from_rgba`].
 pub const MAX_CURSOR_SIZE: u16 = 2048;
 
@@ -126,7 +130,8 @@ pub enum CustomCursorSource {
     ///
     /// ## Platform-specific
     ///
-    /// - **iOS / Android / Wayland / Windows / X11 / macOS / Orbital:** Unsupported
+    /// - **iOS / Android / Windows / macOS / Orbital:** Unsupported
+    /// - **X11:** Requires the RENDER extension version 0.8 or later.
     Animation(CursorAnimation),
     /// Creates a new cursor from a URL pointing to an image.
     /// It uses the [url css function](https://developer.mozilla.org/en-US/docs/Web/CSS/url),
@@ -138,6 +143,30 @@ pub enum CustomCursorSource {
     ///
     /// - **iOS / Android / Wayland / Windows / X11 / macOS / Orbital:** Unsupported
     Url { hotspot_x: u16, hotspot_y: u16, url: String },
+    /// Cursor decoded from a cursor file, with images at one or more nominal sizes.
+    ///
+    /// The backend picks the size closest to the cursor size of the system at the current scale
+    /// factor, and animates the cursor when that size has several frames.
+    ///
+    /// See [CustomCursorSource::from_xcursor], [CustomCursorSource::from_cur] and
+    /// [CustomCursorSource::from_ani] for more.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Web:** Only the first frame of the size closest to 24 pixels is shown.
+    /// - **iOS / Android / Windows / macOS / Orbital:** Unsupported
+    File(CursorFile),
+    /// Cursor backed by RGBA images drawn for several scale factors.
+    ///
+    /// The backend picks the image matching the scale factor, and shows it at the size of the
+    /// image for a scale of 1.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The scale factor is the one of `Xft.dpi`, as cursors are shared by all monitors.
+    /// - **Web:** The image with the lowest scale is used.
+    /// - **iOS / Android / Windows / macOS / Orbital:** Unsupported
+    Scaled(CursorImageSet),
 }
 
 impl CustomCursorSource {
@@ -162,6 +191,27 @@ impl CustomCursorSource {
     ) -> Result<Self, BadAnimation> {
         CursorAnimation::new(duration, cursors).map(Self::Animation)
     }
+
+    /// Decodes a cursor in the Xcursor format, used by the cursor themes on X11 and Wayland.
+    ///
+    /// Each nominal size may hold several images, which are played as an animation.
+    pub fn from_xcursor(data: &[u8]) -> Result<Self, BadCursorFile> {
+        xcursor::parse(data).map(Self::File)
+    }
+
+    /// Decodes a Windows `.cur` cursor, which may hold images at several sizes.
+    ///
+    /// Images compressed as PNG aren't supported.
+    pub fn from_cur(data: &[u8]) -> Result<Self, BadCursorFile> {
+        ico::parse_cur(data).map(Self::File)
+    }
+
+    /// Decodes a Windows `.ani` animated cursor.
+    ///
+    /// Images compressed as PNG aren't supported.
+    pub fn from_ani(data: &[u8]) -> Result<Self, BadCursorFile> {
+        ico::parse_ani(data).map(Self::File)
+    }
 }
 
 /// An error produced when using [`CustomCursorSource::from_rgba`] with invalid arguments.
@@ -235,6 +285,51 @@ impl fmt::Display for BadAnimation {
 
 impl Error for BadAnimation {}
 
+/// An error produced when decoding a cursor file with [`CustomCursorSource::from_xcursor`],
+/// [`CustomCursorSource::from_cur`] or [`CustomCursorSource::from_ani`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BadCursorFile {
+    /// Produced when the data doesn't start with the signature of the format.
+    BadSignature,
+    /// Produced when the data is truncated or its structures are inconsistent.
+    Malformed,
+    /// Produced when the file uses a feature of the format that isn't supported, such as images
+    /// compressed as PNG.
+    Unsupported,
+    /// Produced when the file holds no cursor image.
+    Empty,
+    /// Produced when one of the images of the file is invalid.
+    BadImage(BadImage),
+}
+
+impl From<BadImage> for BadCursorFile {
+    fn from(error: BadImage) -> Self {
+        Self::BadImage(error)
+    }
+}
+
+impl fmt::Display for BadCursorFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadSignature => write!(f, "The data isn't in the expected cursor format"),
+            Self::Malformed => write!(f, "The cursor file is truncated or malformed"),
+            Self::Unsupported => write!(f, "The cursor file uses an unsupported feature"),
+            Self::Empty => write!(f, "The cursor file holds no image"),
+            Self::BadImage(error) => write!(f, "The cursor file holds an invalid image: {error}"),
+        }
+    }
+}
+
+impl Error for BadCursorFile {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::BadImage(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub struct CursorImage {
     pub(crate) rgba: Vec<u8>,
@@ -245,7 +340,8 @@ pub struct CursorImage {
 }
 
 impl CursorImage {
-    pub(crate) fn from_rgba(
+    /// Creates a cursor image from an rgba buffer, as with [`CustomCursorSource::from_rgba`].
+    pub fn from_rgba(
         rgba: Vec<u8>,
         width: u16,
         height: u16,
@@ -303,6 +399,50 @@ impl CursorImage {
     }
 }
 
+/// Images of the same cursor drawn for several integer scale factors.
+///
+/// See [`CustomCursorSource::Scaled`] for more details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CursorImageSet {
+    /// Sorted by scale.
+    pub(crate) images: Vec<(CursorImage, u32)>,
+}
+
+impl CursorImageSet {
+    /// Create a set from the image for a scale factor of 1.
+    pub fn new(image: CursorImage) -> Self {
+        Self { images: vec![(image, 1)] }
+    }
+
+    /// Add the image for the integer `scale`, replacing the one previously given for it.
+    ///
+    /// A 64x64 image at a scale of 2 is shown at the same size as a 32x32 image at a scale of 1,
+    /// with the hotspot given in the pixels of each image.
+    pub fn with_image(mut self, image: CursorImage, scale: u32) -> Self {
+        let scale = scale.max(1);
+        match self.images.binary_search_by_key(&scale, |&(_, scale)| scale) {
+            Ok(index) => self.images[index] = (image, scale),
+            Err(index) => self.images.insert(index, (image, scale)),
+        }
+        self
+    }
+
+    /// The images with their scale, from the lowest scale to the highest.
+    pub fn images(&self) -> &[(CursorImage, u32)] {
+        &self.images
+    }
+
+    /// The image with the lowest scale at least `scale_factor`, or the highest scale otherwise.
+    pub fn best_image(&self, scale_factor: f64) -> (&CursorImage, u32) {
+        let (image, scale) = self
+            .images
+            .iter()
+            .find(|&&(_, scale)| scale as f64 >= scale_factor)
+            .unwrap_or_else(|| self.images.last().unwrap());
+        (image, *scale)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CursorAnimation {
     pub(crate) duration: Duration,
@@ -334,3 +474,133 @@ impl CursorAnimation {
         (self.duration, self.cursors)
     }
 }
+
+/// A cursor decoded from a cursor file.
+///
+/// See [`CustomCursorSource::File`] for more details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CursorFile {
+    /// Never empty, sorted by nominal size.
+    pub(crate) variants: Vec<CursorVariant>,
+}
+
+impl CursorFile {
+    /// Group the frames by nominal size, keeping the order of the frames of each size.
+    pub(crate) fn from_frames(
+        frames: impl IntoIterator<Item = (u32, CursorFrame)>,
+    ) -> Result<Self, BadCursorFile> {
+        let mut variants: Vec<CursorVariant> = Vec::new();
+        for (nominal_size, frame) in frames {
+            match variants.iter_mut().find(|variant| variant.nominal_size == nominal_size) {
+                Some(variant) => variant.frames.push(frame),
+                None => variants.push(CursorVariant { nominal_size, frames: vec![frame] }),
+            }
+        }
+
+        if variants.is_empty() {
+            return Err(BadCursorFile::Empty);
+        }
+
+        variants.sort_by_key(|variant| variant.nominal_size);
+        Ok(Self { variants })
+    }
+
+    /// The images of the cursor at each nominal size, from the smallest to the largest.
+    pub fn variants(&self) -> &[CursorVariant] {
+        &self.variants
+    }
+
+    /// The variant with the nominal size closest to `size`, preferring the larger one on ties.
+    pub fn best_variant(&self, size: u32) -> &CursorVariant {
+        self.variants
+            .iter()
+            .min_by_key(|variant| size_distance(variant.nominal_size, size))
+            .expect("cursor file without images")
+    }
+}
+
+/// The images of a [`CursorFile`] at one nominal size.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CursorVariant {
+    pub(crate) nominal_size: u32,
+    /// Never empty.
+    pub(crate) frames: Vec<CursorFrame>,
+}
+
+impl CursorVariant {
+    /// The size in pixels the images were designed for.
+    pub fn nominal_size(&self) -> u32 {
+        self.nominal_size
+    }
+
+    /// The frames of the animation, or a single frame for static cursors.
+    pub fn frames(&self) -> &[CursorFrame] {
+        &self.frames
+    }
+
+    /// Whether the variant has several frames to animate.
+    pub fn is_animated(&self) -> bool {
+        self.frames.len() > 1
+    }
+}
+
+/// A frame of a [`CursorVariant`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CursorFrame {
+    pub(crate) image: CursorImage,
+    pub(crate) delay: Duration,
+}
+
+impl CursorFrame {
+    pub fn image(&self) -> &CursorImage {
+        &self.image
+    }
+
+    /// How long the frame is shown before moving on to the next one.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+/// The key to pick the size closest to `target`, preferring the larger one on ties.
+fn size_distance(size: u32, target: u32) -> (u32, Reverse<u32>) {
+    (size.abs_diff(target), Reverse(size))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, BadCursorFile> {
+    data.get(offset..offset.saturating_add(2))
+        .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(BadCursorFile::Malformed)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, BadCursorFile> {
+    data.get(offset..offset.saturating_add(4))
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(BadCursorFile::Malformed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(size: u16) -> CursorImage {
+        CursorImage::from_rgba(vec![0; size as usize * size as usize * 4], size, size, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn picks_scaled_image() {
+        let images =
+            CursorImageSet::new(image(16)).with_image(image(48), 3).with_image(image(32), 2);
+        let scales: Vec<_> = images.images().iter().map(|&(_, scale)| scale).collect();
+        assert_eq!(scales, [1, 2, 3]);
+
+        assert_eq!(images.best_image(1.).1, 1);
+        assert_eq!(images.best_image(1.25).1, 2);
+        assert_eq!(images.best_image(2.).1, 2);
+        assert_eq!(images.best_image(4.).1, 3);
+
+        let images = images.with_image(image(24), 2);
+        assert_eq!(images.best_image(2.).0.width(), 24);
+    }
+}
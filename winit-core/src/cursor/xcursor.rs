@@ -0,0 +1,194 @@
+//! Parsing of the Xcursor format, used by the cursor themes on X11 and Wayland.
+
+use std::time::Duration;
+
+use super::{
+    BadCursorFile, BadImage, CursorFile, CursorFrame, CursorImage, MAX_CURSOR_SIZE, read_u32,
+};
+
+const MAGIC: &[u8] = b"Xcur";
+
+/// The type of the table of contents entries pointing to images.
+const IMAGE_TYPE: u32 = 0xfffd_0002;
+
+const TOC_ENTRY_LEN: usize = 12;
+
+pub(crate) fn parse(data: &[u8]) -> Result<CursorFile, BadCursorFile> {
+    if !data.starts_with(MAGIC) {
+        return Err(BadCursorFile::BadSignature);
+    }
+
+    let header_len = read_u32(data, 4)? as usize;
+    let toc_len = read_u32(data, 12)? as usize;
+
+    let mut frames = Vec::new();
+    for index in 0..toc_len {
+        let entry = header_len.saturating_add(index.saturating_mul(TOC_ENTRY_LEN));
+        if read_u32(data, entry)? != IMAGE_TYPE {
+            continue;
+        }
+
+        let nominal_size = read_u32(data, entry + 4)?;
+        let position = read_u32(data, entry + 8)? as usize;
+        frames.push((nominal_size, parse_image(data, position)?));
+    }
+
+    CursorFile::from_frames(frames)
+}
+
+/// Parse the image chunk at `position`.
+fn parse_image(data: &[u8], position: usize) -> Result<CursorFrame, BadCursorFile> {
+    let header_len = read_u32(data, position)? as usize;
+    if read_u32(data, position + 4)? != IMAGE_TYPE {
+        return Err(BadCursorFile::Malformed);
+    }
+
+    let width = read_u32(data, position + 16)?;
+    let height = read_u32(data, position + 20)?;
+    let hotspot_x = read_u32(data, position + 24)?;
+    let hotspot_y = read_u32(data, position + 28)?;
+    let delay = read_u32(data, position + 32)?;
+
+    let (width, height) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(width), Ok(height)) if width <= MAX_CURSOR_SIZE && height <= MAX_CURSOR_SIZE => {
+            (width, height)
+        },
+        _ => {
+            let width = width.min(u16::MAX as u32) as u16;
+            let height = height.min(u16::MAX as u32) as u16;
+            return Err(BadImage::TooLarge { width, height }.into());
+        },
+    };
+
+    let start = position.saturating_add(header_len);
+    let len = width as usize * height as usize * 4;
+    let pixels = data.get(start..start.saturating_add(len)).ok_or(BadCursorFile::Malformed)?;
+
+    // Pixels are premultiplied ARGB in little endian, while images are straight RGBA.
+    let rgba = pixels
+        .chunks_exact(4)
+        .flat_map(|pixel| {
+            let [b, g, r, a] = pixel.try_into().unwrap();
+            let unpremultiply = |channel: u8| match a {
+                0 => 0,
+                a => ((channel as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8,
+            };
+            [unpremultiply(r), unpremultiply(g), unpremultiply(b), a]
+        })
+        .collect();
+
+    let hotspot_x = hotspot_x.min(u16::MAX as u32) as u16;
+    let hotspot_y = hotspot_y.min(u16::MAX as u32) as u16;
+    let image = CursorImage::from_rgba(rgba, width, height, hotspot_x, hotspot_y)?;
+
+    Ok(CursorFrame { image, delay: Duration::from_millis(delay as u64) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMENT_TYPE: u32 = 0xfffe_0001;
+
+    struct Image {
+        nominal_size: u32,
+        size: u32,
+        hotspot: (u32, u32),
+        delay: u32,
+        pixel: u32,
+    }
+
+    impl Image {
+        fn new(nominal_size: u32, size: u32) -> Self {
+            Self { nominal_size, size, hotspot: (0, 0), delay: 0, pixel: 0xff00_00ff }
+        }
+    }
+
+    fn push(data: &mut Vec<u8>, values: &[u32]) {
+        data.extend(values.iter().flat_map(|value| value.to_le_bytes()));
+    }
+
+    fn build(images: &[Image]) -> Vec<u8> {
+        // One comment chunk before the images, which has to be skipped.
+        let toc_len = images.len() as u32 + 1;
+        let mut position = 16 + toc_len * 12;
+
+        let mut data = Vec::new();
+        push(&mut data, &[u32::from_le_bytes(*b"Xcur"), 16, 0x1_0000, toc_len]);
+        push(&mut data, &[COMMENT_TYPE, 1, position]);
+        position += 20;
+        for image in images {
+            push(&mut data, &[IMAGE_TYPE, image.nominal_size, position]);
+            position += 36 + image.size * image.size * 4;
+        }
+
+        push(&mut data, &[20, COMMENT_TYPE, 1, 1, 0]);
+        for image in images {
+            let Image { nominal_size, size, hotspot, delay, pixel } = *image;
+            push(&mut data, &[36, IMAGE_TYPE, nominal_size, 1, size, size]);
+            push(&mut data, &[hotspot.0, hotspot.1, delay]);
+            push(&mut data, &vec![pixel; (size * size) as usize]);
+        }
+
+        data
+    }
+
+    #[test]
+    fn parses_sizes_and_frames() {
+        let mut images = vec![Image::new(32, 32), Image::new(24, 24), Image::new(24, 24)];
+        images[0].hotspot = (4, 5);
+        images[1].delay = 50;
+        images[2].delay = 70;
+
+        let file = parse(&build(&images)).unwrap();
+        let sizes: Vec<_> = file.variants().iter().map(|variant| variant.nominal_size()).collect();
+        assert_eq!(sizes, [24, 32]);
+
+        let small = &file.variants()[0];
+        assert!(small.is_animated());
+        let delays: Vec<_> = small.frames().iter().map(|frame| frame.delay()).collect();
+        assert_eq!(delays, [Duration::from_millis(50), Duration::from_millis(70)]);
+
+        let large = file.variants()[1].frames()[0].image();
+        assert!(!file.variants()[1].is_animated());
+        assert_eq!((large.width(), large.height()), (32, 32));
+        assert_eq!((large.hotspot_x(), large.hotspot_y()), (4, 5));
+    }
+
+    #[test]
+    fn unpremultiplies_pixels() {
+        let mut image = Image::new(1, 1);
+        // Half transparent red, premultiplied.
+        image.pixel = 0x8080_0000;
+
+        let file = parse(&build(&[image])).unwrap();
+        assert_eq!(file.variants()[0].frames()[0].image().buffer(), [255, 0, 0, 128]);
+    }
+
+    #[test]
+    fn picks_closest_size() {
+        let images = [Image::new(24, 4), Image::new(32, 4), Image::new(48, 4)];
+        let file = parse(&build(&images)).unwrap();
+
+        assert_eq!(file.best_variant(16).nominal_size(), 24);
+        assert_eq!(file.best_variant(30).nominal_size(), 32);
+        assert_eq!(file.best_variant(40).nominal_size(), 48);
+        assert_eq!(file.best_variant(96).nominal_size(), 48);
+    }
+
+    #[test]
+    fn rejects_invalid_data() {
+        assert_eq!(parse(b"RIFF"), Err(BadCursorFile::BadSignature));
+        assert_eq!(parse(&build(&[])), Err(BadCursorFile::Empty));
+
+        let data = build(&[Image::new(24, 24)]);
+        assert_eq!(parse(&data[..data.len() - 1]), Err(BadCursorFile::Malformed));
+
+        let mut image = Image::new(24, 24);
+        image.hotspot = (24, 0);
+        assert!(matches!(
+            parse(&build(&[image])),
+            Err(BadCursorFile::BadImage(BadImage::HotspotOutOfBounds { .. }))
+        ));
+    }
+}
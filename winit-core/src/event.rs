@@ -2,7 +2,7 @@
 use std::cell::LazyCell;
 use std::cmp::Ordering;
 use std::f64;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf, Prefix};
 use std::sync::{Mutex, Weak};
 
 use dpi::{PhysicalPosition, PhysicalSize};
@@ -11,12 +11,14 @@ use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 
 use crate::Instant;
+use crate::clipboard::ClipboardKind;
 use crate::error::RequestError;
 use crate::event_loop::AsyncRequestSerial;
 use crate::keyboard::{self, ModifiersKeyState, ModifiersKeys, ModifiersState};
+use crate::monitor::MonitorHandle;
 #[cfg(doc)]
 use crate::window::Window;
-use crate::window::{ActivationToken, Theme};
+use crate::window::{ActivationToken, DecorationMode, Theme};
 
 /// Describes the reason the event loop is resuming.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -75,32 +77,67 @@ pub enum WindowEvent {
     /// The window has been destroyed.
     Destroyed,
 
-    /// A file drag operation has entered the window.
+    /// The popup has been dismissed, e.g. after a click outside of it while it has a grab.
+    ///
+    /// The popup is hidden and won't be shown again, thus it should be dropped.
+    ///
+    /// See [`WindowAttributes::with_popup`].
+    ///
+    /// [`WindowAttributes::with_popup`]: crate::window::WindowAttributes::with_popup
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Wayland** and **X11**.
+    PopupDismissed,
+
+    /// A drag operation has entered the window.
     DragEntered {
         /// List of paths that are being dragged onto the window.
+        ///
+        /// Empty when the dragged data is not a list of files, in which case the data could be
+        /// requested based on the [`payload`][Self::DragEntered::payload].
         paths: Vec<PathBuf>,
+        /// The MIME types offered by the drag operation.
+        payload: DragPayload,
         /// (x,y) coordinates in pixels relative to the top-left corner of the window. May be
         /// negative on some platforms if something is dragged over a window's decorations (title
         /// bar, frame, etc).
         position: PhysicalPosition<f64>,
     },
-    /// A file drag operation has moved over the window.
+    /// A drag operation has moved over the window.
     DragMoved {
         /// (x,y) coordinates in pixels relative to the top-left corner of the window. May be
         /// negative on some platforms if something is dragged over a window's decorations (title
         /// bar, frame, etc).
         position: PhysicalPosition<f64>,
     },
-    /// The file drag operation has dropped file(s) on the window.
+    /// The drag operation has dropped its data on the window.
+    ///
+    /// The data could still be requested with [`Window::request_drag_data`] while handling this
+    /// event.
     DragDropped {
         /// List of paths that are being dragged onto the window.
+        ///
+        /// Empty when the dropped data is not a list of files.
         paths: Vec<PathBuf>,
+        /// The MIME types offered by the drag operation.
+        payload: DragPayload,
         /// (x,y) coordinates in pixels relative to the top-left corner of the window. May be
         /// negative on some platforms if something is dragged over a window's decorations (title
         /// bar, frame, etc).
         position: PhysicalPosition<f64>,
     },
-    /// The file drag operation has been cancelled or left the window.
+    /// The data requested with [`Window::request_drag_data`] was received.
+    DragData {
+        /// The serial returned by [`Window::request_drag_data`].
+        serial: AsyncRequestSerial,
+        /// The MIME type the data was requested for.
+        mime_type: String,
+        /// The data, [`None`] when the data could not be transferred, e.g. when the drag left
+        /// the window before the data was received.
+        data: Option<Vec<u8>>,
+    },
+    /// The drag operation has been cancelled or left the window.
     DragLeft {
         /// (x,y) coordinates in pixels relative to the top-left corner of the window. May be
         /// negative on some platforms if something is dragged over a window's decorations (title
@@ -111,6 +148,16 @@ pub enum WindowEvent {
         /// - **Windows:** Always emits [`None`].
         position: Option<PhysicalPosition<f64>>,
     },
+    /// The drag operation started with [`Window::start_drag`] has finished.
+    DragFinished {
+        /// The serial returned by [`Window::start_drag`].
+        serial: AsyncRequestSerial,
+        /// The action performed by the target, [`None`] when the drag was cancelled or the data
+        /// was not accepted.
+        ///
+        /// When the action is [`DragAction::Move`], the data should be removed from the source.
+        action: Option<DragAction>,
+    },
 
     /// The window gained or lost focus.
     ///
@@ -273,7 +320,8 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **macOS**, **iOS**, and **Wayland**.
+    /// - Only available on **macOS**, **iOS**, **Wayland**, and **X11**.
+    /// - On X11, requires XInput 2.4.
     /// - On iOS, not recognized by default. It must be enabled when needed.
     PinchGesture {
         device_id: Option<DeviceId>,
@@ -289,7 +337,8 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **iOS** and **Wayland**.
+    /// - Only available on **iOS**, **Wayland**, and **X11**.
+    /// - On X11, requires XInput 2.4.
     /// - On iOS, not recognized by default. It must be enabled when needed.
     PanGesture {
         device_id: Option<DeviceId>,
@@ -298,6 +347,43 @@ pub enum WindowEvent {
         phase: TouchPhase,
     },
 
+    /// Multi-finger swipe gesture on a touchpad.
+    ///
+    /// Swipes are performed with three or more fingers, two-finger movement is reported as
+    /// scrolling or as a [`PanGesture`][Self::PanGesture] during a pinch instead.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Wayland** and **X11**.
+    /// - On X11, requires XInput 2.4.
+    SwipeGesture {
+        device_id: Option<DeviceId>,
+        /// The number of fingers taking part in the swipe.
+        fingers: u32,
+        /// Change in pixels of the swipe from last update.
+        delta: PhysicalPosition<f32>,
+        phase: TouchPhase,
+    },
+
+    /// Fingers resting on a touchpad without moving.
+    ///
+    /// A hold starts with [`TouchPhase::Started`] when fingers are put on the touchpad, and is
+    /// [`TouchPhase::Ended`] when they're lifted. It's [`TouchPhase::Cancelled`] when the fingers
+    /// start moving, in which case a scroll or another gesture typically follows.
+    ///
+    /// This is commonly used to stop kinetic scrolling.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Wayland**.
+    /// - On Wayland, requires version 3 of `zwp_pointer_gestures_v1`.
+    HoldGesture {
+        device_id: Option<DeviceId>,
+        /// The number of fingers resting on the touchpad.
+        fingers: u32,
+        phase: TouchPhase,
+    },
+
     /// Double tap gesture.
     ///
     /// On a Mac, smart magnification is triggered by a double tap with two fingers
@@ -325,8 +411,9 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **macOS**, **iOS**, and **Wayland**.
+    /// - Only available on **macOS**, **iOS**, **Wayland**, and **X11**.
     /// - On iOS, not recognized by default. It must be enabled when needed.
+    /// - On X11, requires XInput 2.4.
     RotationGesture {
         device_id: Option<DeviceId>,
         /// change in rotation in degrees
@@ -349,6 +436,82 @@ pub enum WindowEvent {
         stage: i64,
     },
 
+    /// A button of a tablet pad was pressed or released.
+    ///
+    /// Tablet pads are the buttons, rings, strips and dials on a drawing tablet which aren't
+    /// part of a tool, e.g. the ExpressKeys and touch rings of Wacom tablets. Their events are
+    /// delivered to the window focused by the pad.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Wayland**.
+    TabletPadButton {
+        device_id: Option<DeviceId>,
+        /// The index of the button on the pad, starting at `0`.
+        button: u32,
+        state: ElementState,
+    },
+
+    /// A finger moved on, or was lifted from, a ring of a tablet pad.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Wayland**.
+    TabletPadRing {
+        device_id: Option<DeviceId>,
+        /// The index of the ring on the pad, starting at `0`.
+        ring: u32,
+        /// The angle of the finger in degrees, clockwise from the top of the ring.
+        ///
+        /// `None` when the finger was lifted, which could be used to start kinetic scrolling.
+        angle: Option<f64>,
+    },
+
+    /// A finger moved on, or was lifted from, a strip of a tablet pad.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Wayland**.
+    TabletPadStrip {
+        device_id: Option<DeviceId>,
+        /// The index of the strip on the pad, starting at `0`.
+        strip: u32,
+        /// The position of the finger, from `0.0` at the top or left to `1.0` at the bottom or
+        /// right of the strip.
+        ///
+        /// `None` when the finger was lifted.
+        position: Option<f64>,
+    },
+
+    /// A dial of a tablet pad was turned.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Wayland**.
+    TabletPadDial {
+        device_id: Option<DeviceId>,
+        /// The index of the dial on the pad, starting at `0`.
+        dial: u32,
+        /// The rotation in logical detents, high resolution dials send fractions of a detent.
+        delta: f64,
+    },
+
+    /// The mode of a group of buttons, rings, strips and dials of a tablet pad changed.
+    ///
+    /// Applications are expected to give the controls of the group a different function in
+    /// each mode, usually switched with a dedicated button of the group.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Wayland**.
+    TabletPadModeSwitch {
+        device_id: Option<DeviceId>,
+        /// The index of the group on the pad, starting at `0`.
+        group: u32,
+        /// The new mode, starting at `0`.
+        mode: u32,
+    },
+
     /// The window's scale factor has changed.
     ///
     /// The following user actions can cause DPI changes:
@@ -383,9 +546,22 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - **iOS / Android / X11 / Wayland / Orbital:** Unsupported.
+    /// - **X11:** Reported when the XSettings of the screen or the `color-scheme` of the XDG
+    ///   Desktop Portal change.
+    /// - **Wayland:** Reported when the `color-scheme` of the XDG Desktop Portal changes.
+    /// - **iOS / Android / Orbital:** Unsupported.
     ThemeChanged(Theme),
 
+    /// The compositor changed who draws the decorations of the window.
+    ///
+    /// The mode the window was created with isn't reported, but can be queried from the platform
+    /// extension of the window.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Wayland**, where it requires `zxdg_decoration_manager_v1`.
+    DecorationModeChanged(DecorationMode),
+
     /// The window has been occluded (completely hidden from view).
     ///
     /// This is different to window visibility as it depends on whether the window is closed,
@@ -487,13 +663,25 @@ pub enum PointerSource {
         ///
         /// ## Platform-specific
         ///
-        /// - **MacOS / Orbital / Wayland / X11:** Always emits [`None`].
+        /// - **MacOS / Orbital / Wayland:** Always emits [`None`].
+        /// - **X11:** [`None`] if the touchscreen has no `Abs MT Pressure` valuator.
         /// - **Android:** Will never be [`None`]. If the device doesn't support pressure
         ///   sensitivity, force will either be 0.0 or 1.0. Also see the
         ///   [android documentation](https://developer.android.com/reference/android/view/MotionEvent#AXIS_PRESSURE).
         /// - **Web:** Will never be [`None`]. If the device doesn't support pressure sensitivity,
         ///   force will be 0.5 when a button is pressed or 0.0 otherwise.
         force: Option<Force>,
+
+        /// The size of the area in contact with the screen. May be [`None`] if the hardware
+        /// doesn't report it.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **X11:** [`None`] if the touchscreen has no `Abs MT Touch Major` valuator, if its
+        ///   valuators have no resolution or if the physical size of the monitor under the touch
+        ///   is unknown.
+        /// - **Android / iOS / macOS / Orbital / Wayland / Web / Windows:** Always emits [`None`].
+        contact: Option<TouchContact>,
     },
     TabletTool {
         /// Describes as which tool kind the interaction happened.
@@ -544,6 +732,7 @@ pub enum ButtonSource {
     Touch {
         finger_id: FingerId,
         force: Option<Force>,
+        contact: Option<TouchContact>,
     },
     TabletTool {
         kind: TabletToolKind,
@@ -601,6 +790,54 @@ impl DeviceId {
     }
 }
 
+/// The kind of an input device, see [`DeviceInfo`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DeviceKind {
+    Mouse,
+    Keyboard,
+    Touchpad,
+    Touchscreen,
+    /// A drawing tablet, or one of its tools on platforms reporting them as separate devices.
+    Tablet,
+    /// A device of another kind, like a joystick.
+    Other,
+}
+
+/// The description of an input device.
+///
+/// Returned by [`ActiveEventLoop::available_devices`] and [`ActiveEventLoop::device_info`].
+///
+/// [`ActiveEventLoop::available_devices`]: crate::event_loop::ActiveEventLoop::available_devices
+/// [`ActiveEventLoop::device_info`]: crate::event_loop::ActiveEventLoop::device_info
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DeviceInfo {
+    /// The identifier of the device.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Each capability of a seat, like its pointer or keyboard, and each tablet is
+    ///   a device, and matches the identifier on their window and device events.
+    /// - **X11:** Matches the identifier on device events. Window events carry the master pointer
+    ///   or keyboard the device is attached to instead, which isn't listed.
+    pub id: DeviceId,
+    /// The name of the device, as given by its driver or the system.
+    pub name: String,
+    pub kind: DeviceKind,
+    /// The vendor ID of the device on its bus, like the USB one.
+    pub vendor_id: Option<u16>,
+    /// The product ID of the device on its bus, like the USB one.
+    pub product_id: Option<u16>,
+}
+
+impl DeviceInfo {
+    /// Create the description of a device whose vendor and product IDs are unknown.
+    pub fn new(id: DeviceId, name: impl Into<String>, kind: DeviceKind) -> Self {
+        Self { id, name: name.into(), kind, vendor_id: None, product_id: None }
+    }
+}
+
 /// Identifier of a finger in a touch event.
 ///
 /// Whenever a touch event is received it contains a `FingerId` which uniquely identifies the finger
@@ -665,6 +902,130 @@ pub enum DeviceEvent {
     },
 
     Key(RawKeyEvent),
+
+    /// A button of a tablet pad was pressed or released.
+    ///
+    /// See [`WindowEvent::TabletPadButton`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Only delivered while one of the windows is focused by the pad.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows / X11:** Unsupported.
+    TabletPadButton {
+        /// The index of the button on the pad, starting at `0`.
+        button: u32,
+        state: ElementState,
+    },
+
+    /// A finger moved on, or was lifted from, a ring of a tablet pad.
+    ///
+    /// See [`WindowEvent::TabletPadRing`], its platform-specific notes are the same as
+    /// [`DeviceEvent::TabletPadButton`].
+    TabletPadRing {
+        /// The index of the ring on the pad, starting at `0`.
+        ring: u32,
+        /// The angle of the finger in degrees, clockwise from the top of the ring, [`None`] when
+        /// the finger was lifted.
+        angle: Option<f64>,
+    },
+
+    /// A finger moved on, or was lifted from, a strip of a tablet pad.
+    ///
+    /// See [`WindowEvent::TabletPadStrip`], its platform-specific notes are the same as
+    /// [`DeviceEvent::TabletPadButton`].
+    TabletPadStrip {
+        /// The index of the strip on the pad, starting at `0`.
+        strip: u32,
+        /// The position of the finger, from `0.0` at the top or left to `1.0` at the bottom or
+        /// right of the strip, [`None`] when the finger was lifted.
+        position: Option<f64>,
+    },
+
+    /// A dial of a tablet pad was turned.
+    ///
+    /// See [`WindowEvent::TabletPadDial`], its platform-specific notes are the same as
+    /// [`DeviceEvent::TabletPadButton`].
+    TabletPadDial {
+        /// The index of the dial on the pad, starting at `0`.
+        dial: u32,
+        /// The rotation in logical detents, high resolution dials send fractions of a detent.
+        delta: f64,
+    },
+
+    /// The mode of a group of buttons, rings, strips and dials of a tablet pad changed.
+    ///
+    /// See [`WindowEvent::TabletPadModeSwitch`], its platform-specific notes are the same as
+    /// [`DeviceEvent::TabletPadButton`].
+    TabletPadModeSwitch {
+        /// The index of the group on the pad, starting at `0`.
+        group: u32,
+        /// The new mode, starting at `0`.
+        mode: u32,
+    },
+
+    /// The device was connected.
+    ///
+    /// Its description is returned by [`ActiveEventLoop::device_info`]. Unlike the other device
+    /// events, it's delivered regardless of [`ActiveEventLoop::listen_device_events`].
+    ///
+    /// [`ActiveEventLoop::device_info`]: crate::event_loop::ActiveEventLoop::device_info
+    /// [`ActiveEventLoop::listen_device_events`]: crate::event_loop::ActiveEventLoop::listen_device_events
+    Added,
+
+    /// The device was disconnected.
+    ///
+    /// Like [`DeviceEvent::Added`], it's delivered regardless of
+    /// [`ActiveEventLoop::listen_device_events`].
+    ///
+    /// [`ActiveEventLoop::listen_device_events`]: crate::event_loop::ActiveEventLoop::listen_device_events
+    Removed,
+}
+
+/// Describes the answer to a clipboard request made with the [`ActiveEventLoop`].
+///
+/// [`ActiveEventLoop`]: crate::event_loop::ActiveEventLoop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardEvent {
+    /// The MIME types requested with [`ActiveEventLoop::request_clipboard_mime_types`] were
+    /// received.
+    ///
+    /// [`ActiveEventLoop::request_clipboard_mime_types`]: crate::event_loop::ActiveEventLoop::request_clipboard_mime_types
+    MimeTypes {
+        /// The serial returned by the request.
+        serial: AsyncRequestSerial,
+        /// The selection the MIME types were requested for.
+        kind: ClipboardKind,
+        /// The MIME types the clipboard content is offered in, empty when the clipboard is
+        /// empty.
+        mime_types: Vec<String>,
+    },
+    /// The data requested with [`ActiveEventLoop::request_clipboard_data`] was received.
+    ///
+    /// [`ActiveEventLoop::request_clipboard_data`]: crate::event_loop::ActiveEventLoop::request_clipboard_data
+    Data {
+        /// The serial returned by the request.
+        serial: AsyncRequestSerial,
+        /// The selection the data was requested from.
+        kind: ClipboardKind,
+        /// The MIME type the data was requested in.
+        mime_type: String,
+        /// The data, [`None`] when the clipboard is empty, its content is not offered in the
+        /// requested MIME type, or its owner stopped sending it for a few seconds.
+        data: Option<Vec<u8>>,
+    },
+}
+
+/// Describes a change of the monitors connected to the system.
+///
+/// The [`MonitorHandle`]s carry the state of the monitor at the time of the change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonitorEvent {
+    /// A monitor was connected or enabled.
+    Added(MonitorHandle),
+    /// A monitor was disconnected or disabled.
+    Removed(MonitorHandle),
+    /// The position, video mode or scale factor of a monitor changed.
+    Changed(MonitorHandle),
 }
 
 /// Describes a keyboard input as a raw device event.
@@ -1002,6 +1363,207 @@ pub enum Ime {
     Disabled,
 }
 
+/// The action performed by a drag and drop operation once the data is dropped.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DragAction {
+    /// The data is copied.
+    #[default]
+    Copy,
+    /// The data is moved, the source removes it once dropped.
+    Move,
+    /// A link to the data is created.
+    Link,
+}
+
+/// The data offered by a drag and drop operation.
+///
+/// Only the MIME types of the data are known while dragging, the data itself is transferred on
+/// demand with [`Window::request_drag_data`], and delivered with [`WindowEvent::DragData`].
+///
+/// ## Platform-specific
+///
+/// - **Windows / macOS:** Only dropping files is supported, the payload is always empty.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DragPayload {
+    mime_types: Vec<String>,
+    action: DragAction,
+}
+
+impl DragPayload {
+    /// Create a payload offering the given MIME types.
+    pub fn new(mime_types: Vec<String>, action: DragAction) -> Self {
+        Self { mime_types, action }
+    }
+
+    /// The MIME types offered by the source, in the order of its preference.
+    pub fn mime_types(&self) -> &[String] {
+        &self.mime_types
+    }
+
+    /// Whether the given MIME type is offered by the source.
+    pub fn has_mime_type(&self, mime_type: &str) -> bool {
+        self.mime_types.iter().any(|offered| offered == mime_type)
+    }
+
+    /// The action the source requested.
+    ///
+    /// It's performed once the data is dropped, unless another one is set with
+    /// [`Window::set_drag_action`].
+    pub fn action(&self) -> DragAction {
+        self.action
+    }
+}
+
+/// Data offered in one or several MIME types, the reader picking the one it understands best.
+///
+/// Offered by a drag and drop operation started with [`Window::start_drag`], or placed on the
+/// clipboard with [`ActiveEventLoop::set_clipboard`].
+///
+/// [`ActiveEventLoop::set_clipboard`]: crate::event_loop::ActiveEventLoop::set_clipboard
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct MimeData {
+    data: Vec<(String, Vec<u8>)>,
+}
+
+impl MimeData {
+    /// Create an empty offer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Offer the data in the given MIME type.
+    ///
+    /// Offering the same MIME type twice replaces the previous data.
+    pub fn with_data(mut self, mime_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        let mime_type = mime_type.into();
+        let data = data.into();
+        match self.data.iter_mut().find(|(offered, _)| *offered == mime_type) {
+            Some((_, offered)) => *offered = data,
+            None => self.data.push((mime_type, data)),
+        }
+        self
+    }
+
+    /// Offer UTF-8 text, as `text/plain;charset=utf-8`.
+    pub fn with_text(self, text: &str) -> Self {
+        self.with_data("text/plain;charset=utf-8", text)
+    }
+
+    /// Offer a list of files, as `text/uri-list`.
+    ///
+    /// Relative paths are skipped, since they have no `file` URI.
+    pub fn with_paths<P: AsRef<Path>>(self, paths: impl IntoIterator<Item = P>) -> Self {
+        let mut uri_list = String::new();
+        for uri in paths.into_iter().filter_map(|path| file_uri(path.as_ref())) {
+            uri_list.push_str(&uri);
+            uri_list.push_str("\r\n");
+        }
+        self.with_data("text/uri-list", uri_list)
+    }
+
+    /// The offered MIME types, in the order they were added.
+    pub fn mime_types(&self) -> impl Iterator<Item = &str> {
+        self.data.iter().map(|(mime_type, _)| mime_type.as_str())
+    }
+
+    /// The data offered in the given MIME type.
+    pub fn data(&self, mime_type: &str) -> Option<&[u8]> {
+        self.data.iter().find(|(offered, _)| offered == mime_type).map(|(_, data)| data.as_slice())
+    }
+}
+
+/// The `file` URI of an absolute path, like `file:///C:/dir` for `C:\dir` on Windows.
+fn file_uri(path: &Path) -> Option<String> {
+    if !path.is_absolute() {
+        return None;
+    }
+
+    fn push_encoded(uri: &mut String, name: &std::ffi::OsStr) {
+        for &byte in name.as_encoded_bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    uri.push(byte as char)
+                },
+                _ => uri.push_str(&format!("%{byte:02X}")),
+            }
+        }
+    }
+
+    let mut uri = String::from("file://");
+    // Whether the path is only the root directory.
+    let mut root = true;
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => match prefix.kind() {
+                Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => {
+                    uri.push('/');
+                    uri.push(letter as char);
+                    uri.push(':');
+                },
+                // The server is the host of the URI.
+                Prefix::UNC(server, share) | Prefix::VerbatimUNC(server, share) => {
+                    push_encoded(&mut uri, server);
+                    uri.push('/');
+                    push_encoded(&mut uri, share);
+                },
+                Prefix::Verbatim(_) | Prefix::DeviceNS(_) => return None,
+            },
+            Component::RootDir | Component::CurDir => (),
+            Component::ParentDir => {
+                uri.push_str("/..");
+                root = false;
+            },
+            Component::Normal(name) => {
+                uri.push('/');
+                push_encoded(&mut uri, name);
+                root = false;
+            },
+        }
+    }
+
+    if root {
+        uri.push('/');
+    }
+    Some(uri)
+}
+
+/// The data offered by a drag and drop operation started with [`Window::start_drag`].
+///
+/// The data is only transferred to the target once it requests it, in one of the offered MIME
+/// types.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct DragSource {
+    data: MimeData,
+    action: DragAction,
+}
+
+impl DragSource {
+    /// Create a drag source offering the given data.
+    pub fn new(data: MimeData) -> Self {
+        Self { data, action: DragAction::default() }
+    }
+
+    /// Set the action requested from the target.
+    ///
+    /// The default is [`DragAction::Copy`].
+    pub fn with_action(mut self, action: DragAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// The offered data.
+    pub fn data(&self) -> &MimeData {
+        &self.data
+    }
+
+    /// The action requested from the target.
+    pub fn action(&self) -> DragAction {
+        self.action
+    }
+}
+
 /// Describes touch-screen input state.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -1063,6 +1625,18 @@ impl Force {
     }
 }
 
+/// Describes the area of a touch in contact with the screen, approximated by an ellipse.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchContact {
+    /// The length of the major axis of the ellipse, in physical pixels.
+    pub major: f64,
+    /// The length of the minor axis of the ellipse, in physical pixels.
+    ///
+    /// Equal to [`major`](Self::major) when the contact is reported as a circle.
+    pub minor: f64,
+}
+
 /// Identifier for a specific analog axis on some device.
 pub type AxisId = u32;
 
@@ -1559,17 +2133,36 @@ mod tests {
 
             use crate::event::Ime::Enabled;
             use crate::event::WindowEvent::*;
-            use crate::event::{PointerKind, PointerSource};
+            use crate::event::{DragAction, DragPayload, PointerKind, PointerSource};
+            use crate::event_loop::AsyncRequestSerial;
 
             with_window_event(CloseRequested);
             with_window_event(Destroyed);
+            with_window_event(PopupDismissed);
             with_window_event(Focused(true));
             with_window_event(Moved((0, 0).into()));
             with_window_event(SurfaceResized((0, 0).into()));
-            with_window_event(DragEntered { paths: vec!["x.txt".into()], position: (0, 0).into() });
+            with_window_event(DragEntered {
+                paths: vec!["x.txt".into()],
+                payload: DragPayload::default(),
+                position: (0, 0).into(),
+            });
             with_window_event(DragMoved { position: (0, 0).into() });
-            with_window_event(DragDropped { paths: vec!["x.txt".into()], position: (0, 0).into() });
+            with_window_event(DragDropped {
+                paths: vec!["x.txt".into()],
+                payload: DragPayload::new(vec!["text/plain".into()], DragAction::Move),
+                position: (0, 0).into(),
+            });
+            with_window_event(DragData {
+                serial: AsyncRequestSerial::get(),
+                mime_type: "text/plain".into(),
+                data: Some(b"x".to_vec()),
+            });
             with_window_event(DragLeft { position: Some((0, 0).into()) });
+            with_window_event(DragFinished {
+                serial: AsyncRequestSerial::get(),
+                action: Some(DragAction::Copy),
+            });
             with_window_event(Ime(Enabled));
             with_window_event(PointerMoved {
                 device_id: None,
@@ -1610,6 +2203,7 @@ mod tests {
                 button: event::ButtonSource::Touch {
                     finger_id: fid,
                     force: Some(event::Force::Normalized(0.0)),
+                    contact: None,
                 },
             });
             with_window_event(PinchGesture {
@@ -1628,8 +2222,29 @@ mod tests {
                 delta: PhysicalPosition::<f32>::new(0.0, 0.0),
                 phase: event::TouchPhase::Started,
             });
+            with_window_event(SwipeGesture {
+                device_id: None,
+                fingers: 3,
+                delta: PhysicalPosition::<f32>::new(0.0, 0.0),
+                phase: event::TouchPhase::Started,
+            });
+            with_window_event(HoldGesture {
+                device_id: None,
+                fingers: 2,
+                phase: event::TouchPhase::Started,
+            });
             with_window_event(TouchpadPressure { device_id: None, pressure: 0.0, stage: 0 });
+            with_window_event(TabletPadButton {
+                device_id: None,
+                button: 0,
+                state: event::ElementState::Pressed,
+            });
+            with_window_event(TabletPadRing { device_id: None, ring: 0, angle: Some(90.0) });
+            with_window_event(TabletPadStrip { device_id: None, strip: 0, position: None });
+            with_window_event(TabletPadDial { device_id: None, dial: 0, delta: 1.0 });
+            with_window_event(TabletPadModeSwitch { device_id: None, group: 0, mode: 1 });
             with_window_event(ThemeChanged(crate::window::Theme::Light));
+            with_window_event(DecorationModeChanged(crate::window::DecorationMode::Server));
             with_window_event(Occluded(true));
         }};
         (device: $closure:expr) => {{
@@ -1641,6 +2256,13 @@ mod tests {
             with_device_event(PointerMotion { delta: (0.0, 0.0).into() });
             with_device_event(MouseWheel { delta: event::MouseScrollDelta::LineDelta(0.0, 0.0) });
             with_device_event(Button { button: 0, state: event::ElementState::Pressed });
+            with_device_event(TabletPadButton { button: 0, state: event::ElementState::Pressed });
+            with_device_event(TabletPadRing { ring: 0, angle: None });
+            with_device_event(TabletPadStrip { strip: 0, position: Some(0.5) });
+            with_device_event(TabletPadDial { dial: 0, delta: -1.0 });
+            with_device_event(TabletPadModeSwitch { group: 0, mode: 0 });
+            with_device_event(Added);
+            with_device_event(Removed);
         }};
     }
 
@@ -1756,4 +2378,51 @@ mod tests {
 
         let _ = event::Force::Calibrated { force: 0.0, max_possible_force: 0.0 }.clone();
     }
+
+    #[test]
+    fn mime_data_with_paths() {
+        let data = event::MimeData::new()
+            .with_paths(["/home/user/My Files/été.txt", "relative/path", "/tmp/a-b_c.~1"])
+            .with_text("text");
+        assert_eq!(data.mime_types().collect::<Vec<_>>(), [
+            "text/uri-list",
+            "text/plain;charset=utf-8"
+        ]);
+        assert_eq!(
+            data.data("text/uri-list"),
+            Some(
+                &b"file:///home/user/My%20Files/%C3%A9t%C3%A9.txt\r\nfile:///tmp/a-b_c.~1\r\n"[..]
+            )
+        );
+
+        // Offering a MIME type again replaces its data.
+        let data = data.with_paths(["/tmp/%"]);
+        assert_eq!(data.mime_types().count(), 2);
+        assert_eq!(data.data("text/uri-list"), Some(&b"file:///tmp/%25\r\n"[..]));
+        let data = data.with_paths(["/", "/tmp/../a//b/"]);
+        assert_eq!(data.data("text/uri-list"), Some(&b"file:///\r\nfile:///tmp/../a/b\r\n"[..]));
+        assert_eq!(data.data("image/png"), None);
+
+        let source = event::DragSource::new(data.clone()).with_action(event::DragAction::Move);
+        assert_eq!(source.data(), &data);
+        assert_eq!(source.action(), event::DragAction::Move);
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn mime_data_with_windows_paths() {
+        let data = event::MimeData::new().with_paths([
+            r"C:\Users\Me\My File.txt",
+            r"C:relative.txt",
+            r"\\server\share\dir",
+            r"\\?\D:\",
+        ]);
+        assert_eq!(
+            data.data("text/uri-list"),
+            Some(
+                &b"file:///C:/Users/Me/My%20File.txt\r\nfile://server/share/dir\r\nfile:///D:/\r\n"
+                    [..]
+            )
+        );
+    }
 }
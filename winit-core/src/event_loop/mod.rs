@@ -4,6 +4,7 @@ pub mod register;
 pub mod run_on_demand;
 
 use std::fmt::{self, Debug};
+use std::iter;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
@@ -12,8 +13,10 @@ use rwh_06::{DisplayHandle, HandleError, HasDisplayHandle};
 
 use crate::Instant;
 use crate::as_any::AsAny;
+use crate::clipboard::ClipboardKind;
 use crate::cursor::{CustomCursor, CustomCursorSource};
-use crate::error::RequestError;
+use crate::error::{NotSupportedError, RequestError};
+use crate::event::{DeviceId, DeviceInfo, MimeData};
 use crate::monitor::MonitorHandle;
 use crate::window::{Theme, Window, WindowAttributes};
 
@@ -45,6 +48,26 @@ pub trait ActiveEventLoop: AsAny + fmt::Debug {
         custom_cursor: CustomCursorSource,
     ) -> Result<CustomCursor, RequestError>;
 
+    /// Set the theme and base size in logical pixels of the cursors selected with a
+    /// [`CursorIcon`], reloading the cursors of all windows.
+    ///
+    /// `None` restores the value of the system.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Without a theme set here, the `Gtk/CursorThemeName` and `Gtk/CursorThemeSize`
+    ///   XSettings are followed. Both take precedence over `XCURSOR_THEME` and `XCURSOR_SIZE` in
+    ///   the environment.
+    /// - **Wayland:** Has no effect when the compositor draws the cursors itself.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`CursorIcon`]: crate::cursor::CursorIcon
+    fn set_cursor_theme(&self, name: Option<&str>, size: Option<u32>) -> Result<(), RequestError> {
+        let _ = (name, size);
+        Err(NotSupportedError::new("set_cursor_theme is not supported").into())
+    }
+
     /// Returns the list of all the monitors available on the system.
     ///
     /// ## Platform-specific
@@ -62,6 +85,33 @@ pub trait ActiveEventLoop: AsAny + fmt::Debug {
     /// - **Web:** Always returns `None` without `detailed monitor permissions`.
     fn primary_monitor(&self) -> Option<MonitorHandle>;
 
+    /// Returns the input devices connected to the system.
+    ///
+    /// Connected and disconnected devices are reported with [`DeviceEvent::Added`] and
+    /// [`DeviceEvent::Removed`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Only the physical devices are listed, not the master devices aggregating them.
+    ///   The vendor and product IDs are read from the `Device Product ID` property set by the
+    ///   libinput and evdev drivers.
+    /// - **Wayland:** The pointer, keyboard and touchscreen of each seat are listed as single
+    ///   devices, with touchpads reported as mice. Only tablets have vendor and product IDs.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, no device is listed.
+    ///
+    /// [`DeviceEvent::Added`]: crate::event::DeviceEvent::Added
+    /// [`DeviceEvent::Removed`]: crate::event::DeviceEvent::Removed
+    fn available_devices(&self) -> Box<dyn Iterator<Item = DeviceInfo>> {
+        Box::new(iter::empty())
+    }
+
+    /// Returns the description of the input device, if it's still connected.
+    ///
+    /// See [`ActiveEventLoop::available_devices`] for the platform-specific details.
+    fn device_info(&self, device_id: DeviceId) -> Option<DeviceInfo> {
+        self.available_devices().find(|device| device.id == device_id)
+    }
+
     /// Change if or when [`DeviceEvent`]s are captured.
     ///
     /// Since the [`DeviceEvent`] capture can lead to high CPU usage for unfocused windows, winit
@@ -81,9 +131,91 @@ pub trait ActiveEventLoop: AsAny + fmt::Debug {
     ///
     /// ## Platform-specific
     ///
-    /// - **iOS / Android / Wayland / x11 / Orbital:** Unsupported.
+    /// - **X11:** Read from the `color-scheme` of the XDG Desktop Portal, falling back to the
+    ///   `Net/ThemeName` XSetting.
+    /// - **Wayland:** Read from the `color-scheme` of the XDG Desktop Portal.
+    /// - **iOS / Android / Orbital:** Unsupported.
+    ///
+    /// On X11 and Wayland, the XDG Desktop Portal is only read with the `dbus` feature of winit.
     fn system_theme(&self) -> Option<Theme>;
 
+    /// Returns the input settings of the desktop.
+    ///
+    /// Changes are reported with [`ApplicationHandler::desktop_settings_changed`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Read from XSettings, falling back to the GNOME or KDE settings of the XDG Desktop
+    ///   Portal for the ones XSettings doesn't have, such as
+    ///   [`DesktopSettings::wheel_scroll_lines`] which only KDE provides.
+    /// - **Wayland:** Read from the GNOME or KDE settings of the XDG Desktop Portal.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported yet, all the settings are
+    ///   `None`.
+    ///
+    /// On X11 and Wayland, the XDG Desktop Portal is only read with the `dbus` feature of winit,
+    /// all the settings are `None` on Wayland without it.
+    ///
+    /// [`ApplicationHandler::desktop_settings_changed`]: crate::application::ApplicationHandler::desktop_settings_changed
+    fn desktop_settings(&self) -> DesktopSettings {
+        DesktopSettings::default()
+    }
+
+    /// Place the content on the clipboard, replacing the previous one.
+    ///
+    /// The content is served to other applications until another one replaces it.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The clipboard could only be set after an input event on one of the windows,
+    ///   such as a key press.
+    /// - **X11:** `text/plain;charset=utf-8` is also offered as `UTF8_STRING`.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    fn set_clipboard(&self, kind: ClipboardKind, content: MimeData) -> Result<(), RequestError> {
+        let _ = (kind, content);
+        Err(NotSupportedError::new("set_clipboard is not supported").into())
+    }
+
+    /// Request the MIME types the clipboard content is offered in.
+    ///
+    /// The MIME types are delivered with [`ClipboardEvent::MimeTypes`] carrying the returned
+    /// serial.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** `UTF8_STRING` is reported as `text/plain;charset=utf-8`.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`ClipboardEvent::MimeTypes`]: crate::event::ClipboardEvent::MimeTypes
+    fn request_clipboard_mime_types(
+        &self,
+        kind: ClipboardKind,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let _ = kind;
+        Err(NotSupportedError::new("request_clipboard_mime_types is not supported").into())
+    }
+
+    /// Request the clipboard content in the given MIME type.
+    ///
+    /// The data is delivered with [`ClipboardEvent::Data`] carrying the returned serial.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** `text/plain;charset=utf-8` is requested as `UTF8_STRING`.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`ClipboardEvent::Data`]: crate::event::ClipboardEvent::Data
+    fn request_clipboard_data(
+        &self,
+        kind: ClipboardKind,
+        mime_type: &str,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let _ = (kind, mime_type);
+        Err(NotSupportedError::new("request_clipboard_data is not supported").into())
+    }
+
     /// Sets the [`ControlFlow`].
     fn set_control_flow(&self, control_flow: ControlFlow);
 
@@ -266,6 +398,32 @@ pub enum DeviceEvents {
     Never,
 }
 
+/// The input settings of the desktop, see [`ActiveEventLoop::desktop_settings`].
+///
+/// The settings which the platform doesn't provide are `None`, in which case applications should
+/// use their own defaults.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct DesktopSettings {
+    /// The maximum time between the clicks of a double click.
+    pub double_click_time: Option<Duration>,
+
+    /// The maximum distance in physical pixels between the clicks of a double click.
+    pub double_click_distance: Option<u32>,
+
+    /// The distance in physical pixels the pointer has to move with a button held to start a drag.
+    pub drag_threshold: Option<u32>,
+
+    /// Whether the text caret blinks.
+    pub caret_blink: Option<bool>,
+
+    /// The duration of a full blink cycle of the text caret.
+    pub caret_blink_time: Option<Duration>,
+
+    /// The number of lines to scroll for a notch of the mouse wheel.
+    pub wheel_scroll_lines: Option<u32>,
+}
+
 /// A unique identifier of the winit's async request.
 ///
 /// This could be used to identify the async request once it's done
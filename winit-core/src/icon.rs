@@ -105,3 +105,45 @@ impl From<RgbaIcon> for Icon {
         Self(Arc::new(value))
     }
 }
+
+/// An icon made of bitmaps at several sizes and scales, letting the platform pick or publish the
+/// right ones.
+///
+/// ## Platform-specific
+///
+/// - **X11:** Every bitmap is published, leaving the choice to the window manager.
+/// - **Wayland:** Every bitmap is offered to the compositor along with its scale.
+/// - **Windows / macOS / Web / iOS / Android / Orbital:** Unsupported.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RgbaIconSet {
+    pub(crate) icons: Vec<(RgbaIcon, u32)>,
+}
+
+impl RgbaIconSet {
+    /// Create a set from the bitmap for a scale factor of 1.
+    pub fn new(icon: RgbaIcon) -> Self {
+        Self { icons: vec![(icon, 1)] }
+    }
+
+    /// Add a bitmap for the integer `scale`, where a 64x64 bitmap at a scale of 2 is shown at the
+    /// same size as a 32x32 bitmap at a scale of 1.
+    ///
+    /// Several sizes may be given for the same scale, letting the platform use the closest one.
+    pub fn with_icon(mut self, icon: RgbaIcon, scale: u32) -> Self {
+        self.icons.push((icon, scale.max(1)));
+        self
+    }
+
+    /// The bitmaps with their scale, in the order they were added.
+    pub fn icons(&self) -> &[(RgbaIcon, u32)] {
+        &self.icons
+    }
+}
+
+impl IconProvider for RgbaIconSet {}
+
+impl From<RgbaIconSet> for Icon {
+    fn from(value: RgbaIconSet) -> Self {
+        Self(Arc::new(value))
+    }
+}
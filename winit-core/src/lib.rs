@@ -9,6 +9,7 @@
 
 #[macro_use]
 pub mod as_any;
+pub mod clipboard;
 pub mod cursor;
 #[macro_use]
 pub mod error;
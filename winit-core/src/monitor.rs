@@ -109,6 +109,76 @@ pub trait MonitorHandleProvider: AsAny + fmt::Debug + Send + Sync {
 
     /// Returns all fullscreen video modes supported by this monitor.
     fn video_modes(&self) -> Box<dyn Iterator<Item = VideoMode>>;
+
+    /// Returns the width and height of the monitor in millimeters.
+    ///
+    /// Returns `None` if the size is unknown, some monitors and projectors report `0` instead.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    fn size_millimeters(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    /// Returns the area of the monitor which isn't covered by panels and docks, in the same
+    /// coordinate system as [`MonitorHandleProvider::position`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The `_NET_WORKAREA` of the current desktop clipped to the monitor, which doesn't
+    ///   account for panels on monitors in the middle of the desktop.
+    /// - **Android / iOS / macOS / Orbital / Wayland / Web / Windows:** Unsupported.
+    fn work_area(&self) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+        None
+    }
+
+    /// Returns the layout of the subpixels of the monitor, used for subpixel text rendering.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    fn subpixel_layout(&self) -> Option<SubpixelLayout> {
+        None
+    }
+
+    /// Returns the rotation and flip the content of the monitor is shown with.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    fn transform(&self) -> Option<MonitorTransform> {
+        None
+    }
+
+    /// Returns the manufacturer of the monitor.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The three letter PNP ID from the EDID of the monitor.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    fn make(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+
+    /// Returns the model of the monitor.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    fn model(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+
+    /// Returns a human-readable description of the monitor, such as its make and model along with
+    /// the connector it's plugged to.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows / X11:** Unsupported.
+    fn description(&self) -> Option<Cow<'_, str>> {
+        None
+    }
 }
 
 impl PartialEq for dyn MonitorHandleProvider + '_ {
@@ -181,6 +251,47 @@ impl fmt::Display for VideoMode {
     }
 }
 
+/// The layout of the red, green and blue subpixels of the pixels of a monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SubpixelLayout {
+    /// The pixels have no subpixels, or their layout doesn't follow any of the others.
+    None,
+    /// The subpixels are laid out horizontally, from red to blue.
+    HorizontalRgb,
+    /// The subpixels are laid out horizontally, from blue to red.
+    HorizontalBgr,
+    /// The subpixels are laid out vertically, from red to blue.
+    VerticalRgb,
+    /// The subpixels are laid out vertically, from blue to red.
+    VerticalBgr,
+}
+
+/// The rotation and flip the content of a monitor is shown with.
+///
+/// The rotations are counter-clockwise, and are applied after flipping around the vertical axis.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MonitorTransform {
+    /// The content is shown as is.
+    #[default]
+    Normal,
+    /// The content is rotated by 90 degrees.
+    Rotate90,
+    /// The content is rotated by 180 degrees.
+    Rotate180,
+    /// The content is rotated by 270 degrees.
+    Rotate270,
+    /// The content is flipped.
+    Flipped,
+    /// The content is flipped and rotated by 90 degrees.
+    Flipped90,
+    /// The content is flipped and rotated by 180 degrees.
+    Flipped180,
+    /// The content is flipped and rotated by 270 degrees.
+    Flipped270,
+}
+
 /// Fullscreen modes.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Fullscreen {
@@ -11,7 +11,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::as_any::AsAny;
 use crate::cursor::Cursor;
-use crate::error::RequestError;
+use crate::error::{NotSupportedError, RequestError};
+use crate::event::{DragAction, DragSource};
+use crate::event_loop::AsyncRequestSerial;
 use crate::icon::Icon;
 use crate::monitor::{Fullscreen, MonitorHandle};
 
@@ -71,6 +73,7 @@ pub struct WindowAttributes {
     pub cursor: Cursor,
     pub(crate) parent_window: Option<SendSyncRawWindowHandle>,
     pub fullscreen: Option<Fullscreen>,
+    pub popup: Option<PopupAttributes>,
     pub platform: Option<Box<dyn PlatformWindowAttributes>>,
 }
 
@@ -370,6 +373,26 @@ impl WindowAttributes {
         self
     }
 
+    /// Build the window as a popup, like a menu, a tooltip or the list of a combo box, placed
+    /// relative to its parent window.
+    ///
+    /// The default is `None`.
+    ///
+    /// A popup has no decorations and isn't managed like regular windows, thus the attributes
+    /// related to the decorations, the position and the window state are ignored. Its surface
+    /// size is still the one from [`WindowAttributes::with_surface_size`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Uses `xdg_popup`. The parent may also be a layer-shell surface.
+    /// - **X11:** Uses an override-redirect transient window, placed by winit.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    #[inline]
+    pub fn with_popup(mut self, popup: PopupAttributes) -> Self {
+        self.popup = Some(popup);
+        self
+    }
+
     /// Set the platform specific opaque attribute object.
     ///
     /// The interpretation will depend on the underlying backend that will be used.
@@ -404,6 +427,7 @@ impl Clone for WindowAttributes {
             cursor: self.cursor.clone(),
             parent_window: self.parent_window.clone(),
             fullscreen: self.fullscreen.clone(),
+            popup: self.popup.clone(),
             platform: self.platform.as_ref().map(|platform| platform.box_clone()),
         }
     }
@@ -430,6 +454,7 @@ impl Default for WindowAttributes {
             window_icon: Default::default(),
             transparent: Default::default(),
             fullscreen: Default::default(),
+            popup: Default::default(),
             maximized: Default::default(),
             position: Default::default(),
             platform: Default::default(),
@@ -1265,7 +1290,7 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// - **Wayland:** Sets the theme for the client side decorations. Using `None` will use dbus to
     ///   get the system preference.
     /// - **X11:** Sets `_GTK_THEME_VARIANT` hint to `dark` or `light` and if `None` is used, it
-    ///   will default to  [`Theme::Dark`].
+    ///   will follow the system theme, defaulting to [`Theme::Dark`].
     /// - **iOS / Android / Web / Orbital:** Unsupported.
     fn set_theme(&self, theme: Option<Theme>);
 
@@ -1275,8 +1300,11 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     ///
     /// ## Platform-specific
     ///
-    /// - **iOS / Android / x11 / Orbital:** Unsupported.
-    /// - **Wayland:** Only returns theme overrides.
+    /// - **iOS / Android / Orbital:** Unsupported.
+    /// - **X11 / Wayland:** Returns the theme override, or the system theme from
+    ///   [`ActiveEventLoop::system_theme`].
+    ///
+    /// [`ActiveEventLoop::system_theme`]: crate::event_loop::ActiveEventLoop::system_theme
     fn theme(&self) -> Option<Theme>;
 
     /// Prevents the window contents from being captured by other apps.
@@ -1381,6 +1409,70 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// - **iOS / Android / Web:** Always returns an [`RequestError::NotSupported`].
     fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), RequestError>;
 
+    /// Request the data of the drag and drop operation over the window in the given MIME type.
+    ///
+    /// The data is delivered with [`WindowEvent::DragData`] carrying the returned serial. The
+    /// data is only available while the drag is over the window, that is after
+    /// [`WindowEvent::DragEntered`], including while handling [`WindowEvent::DragDropped`]. The
+    /// MIME types offered by the source are listed in the [`DragPayload`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland / X11:** The drop is finished once all the requests made while handling
+    ///   [`WindowEvent::DragDropped`] are answered.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`WindowEvent::DragData`]: crate::event::WindowEvent::DragData
+    /// [`WindowEvent::DragEntered`]: crate::event::WindowEvent::DragEntered
+    /// [`WindowEvent::DragDropped`]: crate::event::WindowEvent::DragDropped
+    /// [`DragPayload`]: crate::event::DragPayload
+    fn request_drag_data(&self, mime_type: &str) -> Result<AsyncRequestSerial, RequestError> {
+        let _ = mime_type;
+        Err(NotSupportedError::new("request_drag_data is not supported").into())
+    }
+
+    /// Set the action performed once the drag and drop operation over the window is dropped.
+    ///
+    /// [`None`] rejects the drop. Each time a drag enters the window, the action is reset to
+    /// the one requested by the source, see [`DragPayload::action`]. It could be changed while
+    /// handling [`WindowEvent::DragEntered`] and [`WindowEvent::DragMoved`], e.g. depending on
+    /// the position of the drag.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** [`DragAction::Link`] is performed as a copy. The drop is cancelled when the
+    ///   source doesn't support the action.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`WindowEvent::DragEntered`]: crate::event::WindowEvent::DragEntered
+    /// [`WindowEvent::DragMoved`]: crate::event::WindowEvent::DragMoved
+    /// [`DragPayload::action`]: crate::event::DragPayload::action
+    fn set_drag_action(&self, action: Option<DragAction>) -> Result<(), RequestError> {
+        let _ = action;
+        Err(NotSupportedError::new("set_drag_action is not supported").into())
+    }
+
+    /// Start a drag and drop operation from the window, offering the given data.
+    ///
+    /// This should be called while the primary pointer button is pressed, the data is dropped
+    /// once it is released. The end of the operation is reported with
+    /// [`WindowEvent::DragFinished`] carrying the returned serial.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Pressing any other button or Escape while dragging cancels the operation, as does
+    ///   a target not answering within a few seconds.
+    /// - **Android / iOS / macOS / Orbital / Wayland / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`WindowEvent::DragFinished`]: crate::event::WindowEvent::DragFinished
+    fn start_drag(&self, source: DragSource) -> Result<AsyncRequestSerial, RequestError> {
+        let _ = source;
+        Err(NotSupportedError::new("start_drag is not supported").into())
+    }
+
     /// Show [window menu] at a specified position in surface coordinates.
     ///
     /// This is the context menu that is normally shown when interacting with
@@ -1537,6 +1629,173 @@ pub enum Theme {
     Dark,
 }
 
+/// Who draws the decorations of a window.
+///
+/// See [`WindowEvent::DecorationModeChanged`].
+///
+/// [`WindowEvent::DecorationModeChanged`]: crate::event::WindowEvent::DecorationModeChanged
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DecorationMode {
+    /// The decorations are drawn by the client, i.e. by winit or the application.
+    Client,
+
+    /// The decorations are drawn by the window manager or compositor.
+    Server,
+}
+
+/// The placement of a popup window relative to its parent.
+///
+/// The popup is placed by anchoring a point of it, picked with the [`gravity`], to a point of the
+/// anchor rectangle, picked with the [`anchor`], then moving it by the [`offset`]. When the popup
+/// doesn't fit in the screen, it's moved according to the [`constraint_adjustment`].
+///
+/// See [`WindowAttributes::with_popup`].
+///
+/// [`gravity`]: Self::gravity
+/// [`anchor`]: Self::anchor
+/// [`offset`]: Self::offset
+/// [`constraint_adjustment`]: Self::constraint_adjustment
+#[derive(Debug, Clone, PartialEq)]
+pub struct PopupAttributes {
+    /// The window the popup belongs to, which may be another popup.
+    pub parent: WindowId,
+
+    /// The position of the anchor rectangle, relative to the surface of the parent.
+    pub anchor_rect_position: Position,
+
+    /// The size of the anchor rectangle, e.g. the one of the item opening the popup.
+    pub anchor_rect_size: Size,
+
+    /// The point of the anchor rectangle the popup is placed at.
+    pub anchor: PopupAnchor,
+
+    /// The direction the popup extends from the anchor point.
+    ///
+    /// For example, [`PopupAnchor::BottomRight`] places the top-left corner of the popup at the
+    /// anchor point.
+    pub gravity: PopupAnchor,
+
+    /// How the popup is moved or resized to fit in the screen.
+    pub constraint_adjustment: PopupConstraintAdjustment,
+
+    /// The offset of the popup from the anchor point.
+    pub offset: Position,
+
+    /// Whether the popup takes the input until it's dismissed, which happens when clicking outside
+    /// of it.
+    ///
+    /// The popup should be created in response to an input event, like a click on the parent.
+    pub grab: bool,
+}
+
+impl PopupAttributes {
+    /// Create a popup below the given anchor rectangle of the parent, which may be of a zero size
+    /// to use a point.
+    pub fn new(parent: WindowId, anchor_rect_position: Position, anchor_rect_size: Size) -> Self {
+        Self {
+            parent,
+            anchor_rect_position,
+            anchor_rect_size,
+            anchor: PopupAnchor::BottomLeft,
+            gravity: PopupAnchor::BottomRight,
+            constraint_adjustment: PopupConstraintAdjustment::SLIDE_X
+                | PopupConstraintAdjustment::FLIP_Y,
+            offset: LogicalPosition::new(0, 0).into(),
+            grab: false,
+        }
+    }
+
+    /// Sets the point of the anchor rectangle the popup is placed at.
+    ///
+    /// The default is [`PopupAnchor::BottomLeft`].
+    #[inline]
+    pub fn with_anchor(mut self, anchor: PopupAnchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Sets the direction the popup extends from the anchor point.
+    ///
+    /// The default is [`PopupAnchor::BottomRight`].
+    #[inline]
+    pub fn with_gravity(mut self, gravity: PopupAnchor) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    /// Sets how the popup is moved or resized to fit in the screen.
+    ///
+    /// The default slides it horizontally and flips it vertically.
+    #[inline]
+    pub fn with_constraint_adjustment(
+        mut self,
+        constraint_adjustment: PopupConstraintAdjustment,
+    ) -> Self {
+        self.constraint_adjustment = constraint_adjustment;
+        self
+    }
+
+    /// Sets the offset of the popup from the anchor point.
+    ///
+    /// The default is no offset.
+    #[inline]
+    pub fn with_offset<P: Into<Position>>(mut self, offset: P) -> Self {
+        self.offset = offset.into();
+        self
+    }
+
+    /// Sets whether the popup takes the input until it's dismissed.
+    ///
+    /// The default is `false`.
+    #[inline]
+    pub fn with_grab(mut self, grab: bool) -> Self {
+        self.grab = grab;
+        self
+    }
+}
+
+/// A point on the edges of a rectangle, used to place popups.
+///
+/// See [`PopupAttributes`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PopupAnchor {
+    /// The center of the rectangle.
+    #[default]
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    BottomLeft,
+    TopRight,
+    BottomRight,
+}
+
+bitflags! {
+    /// How a popup is moved or resized when it doesn't fit in the screen.
+    ///
+    /// See [`PopupAttributes`].
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct PopupConstraintAdjustment: u32 {
+        /// Slide the popup horizontally until it fits.
+        const SLIDE_X = 1 << 0;
+        /// Slide the popup vertically until it fits.
+        const SLIDE_Y = 1 << 1;
+        /// Flip the anchor and gravity horizontally when it then fits.
+        const FLIP_X = 1 << 2;
+        /// Flip the anchor and gravity vertically when it then fits.
+        const FLIP_Y = 1 << 3;
+        /// Shrink the popup horizontally to fit.
+        const RESIZE_X = 1 << 4;
+        /// Shrink the popup vertically to fit.
+        const RESIZE_Y = 1 << 5;
+    }
+}
+
 /// ## Platform-specific
 ///
 /// - **X11:** Sets the WM's `XUrgencyHint`. No distinction between [`Critical`] and
@@ -2167,7 +2426,7 @@ mod tests {
             .is_some()
         );
 
-        let text: &[u8] = ['a' as u8; 8000].as_slice();
+        let text: &[u8] = [b'a'; 8000].as_slice();
         let text = std::str::from_utf8(text).unwrap();
         assert_eq!(
             ImeSurroundingText::new(text.into(), 0, 0),
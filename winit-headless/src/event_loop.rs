@@ -0,0 +1,513 @@
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+#[cfg(unix)]
+use std::io::{ErrorKind, Read, Write};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, Weak};
+use std::time::{Duration, Instant};
+
+use dpi::{LogicalSize, PhysicalSize};
+use winit_core::application::ApplicationHandler;
+use winit_core::cursor::{CustomCursor, CustomCursorSource};
+use winit_core::error::{EventLoopError, NotSupportedError, RequestError};
+use winit_core::event::{
+    DeviceEvent, DeviceId, MonitorEvent, StartCause, SurfaceSizeWriter, WindowEvent,
+};
+use winit_core::event_loop::pump_events::PumpStatus;
+use winit_core::event_loop::{
+    ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents,
+    EventLoopProxy as CoreEventLoopProxy, EventLoopProxyProvider,
+    OwnedDisplayHandle as CoreOwnedDisplayHandle,
+};
+use winit_core::monitor::MonitorHandle as CoreMonitorHandle;
+use winit_core::window::{Theme, Window as CoreWindow, WindowAttributes, WindowId};
+
+use crate::EventInjector;
+use crate::monitor::{MonitorHandle, VirtualMonitor};
+use crate::window::{Window, WindowState};
+
+/// Event queued by the [`EventInjector`] or by the windows themselves.
+#[derive(Debug)]
+pub(crate) enum QueuedEvent {
+    Window { window_id: WindowId, event: WindowEvent },
+    Device { device_id: Option<DeviceId>, event: DeviceEvent },
+    Monitor { event: MonitorEvent },
+    ScaleFactorChanged { window_id: WindowId, scale_factor: f64 },
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct SharedState {
+    pub(crate) events: VecDeque<QueuedEvent>,
+    pub(crate) redraws: Vec<WindowId>,
+    pub(crate) destroys: Vec<WindowId>,
+    pub(crate) windows: HashMap<WindowId, Weak<Mutex<WindowState>>>,
+    pub(crate) monitors: Vec<MonitorHandle>,
+    pub(crate) proxy_wake_up: bool,
+
+    /// Whether the loop has something to dispatch.
+    pending: bool,
+}
+
+impl SharedState {
+    pub(crate) fn window(&self, window_id: WindowId) -> Option<Arc<Mutex<WindowState>>> {
+        self.windows.get(&window_id).and_then(Weak::upgrade)
+    }
+
+    pub(crate) fn monitors(&self) -> std::vec::IntoIter<CoreMonitorHandle> {
+        let monitors: Vec<_> = self
+            .monitors
+            .iter()
+            .map(|monitor| CoreMonitorHandle(Arc::new(monitor.clone())))
+            .collect();
+        monitors.into_iter()
+    }
+
+    pub(crate) fn request_redraw(&mut self, window_id: WindowId) {
+        if !self.redraws.contains(&window_id) {
+            self.redraws.push(window_id);
+        }
+    }
+}
+
+/// State shared between the event loop, its windows, proxies and injectors.
+#[derive(Debug)]
+pub(crate) struct Shared {
+    state: Mutex<SharedState>,
+    condvar: Condvar,
+
+    /// Socket pair which becomes readable when the loop has something to dispatch, so it could
+    /// be polled by an external event loop.
+    #[cfg(unix)]
+    notifier: (UnixStream, UnixStream),
+}
+
+impl Shared {
+    fn new() -> Result<Self, EventLoopError> {
+        #[cfg(unix)]
+        let notifier = {
+            let (reader, writer) = UnixStream::pair().map_err(|err| os_error!(err))?;
+            reader.set_nonblocking(true).map_err(|err| os_error!(err))?;
+            writer.set_nonblocking(true).map_err(|err| os_error!(err))?;
+            (reader, writer)
+        };
+
+        // The default monitor is reported like the ones added later.
+        let monitor = MonitorHandle::new(VirtualMonitor::default());
+        let event = MonitorEvent::Added(CoreMonitorHandle(Arc::new(monitor.clone())));
+        let state = SharedState {
+            events: VecDeque::from([QueuedEvent::Monitor { event }]),
+            monitors: vec![monitor],
+            ..Default::default()
+        };
+
+        Ok(Self {
+            state: Mutex::new(state),
+            condvar: Condvar::new(),
+            #[cfg(unix)]
+            notifier,
+        })
+    }
+
+    pub(crate) fn state(&self) -> MutexGuard<'_, SharedState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Update the shared state and wake up the event loop.
+    pub(crate) fn update<T>(&self, f: impl FnOnce(&mut SharedState) -> T) -> T {
+        let mut state = self.state();
+        let result = f(&mut state);
+        state.pending = true;
+        drop(state);
+
+        self.condvar.notify_all();
+        #[cfg(unix)]
+        {
+            // The socket being full means that the loop is already marked as woken up.
+            let _ = (&self.notifier.1).write(&[0]);
+        }
+
+        result
+    }
+
+    /// Block until there's something to dispatch or the timeout has passed.
+    fn wait(&self, timeout: Option<Duration>) {
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut state = self.state();
+        while !state.pending {
+            state = match deadline {
+                Some(deadline) => {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    if timeout.is_zero() {
+                        break;
+                    }
+                    self.condvar.wait_timeout(state, timeout).unwrap().0
+                },
+                None => self.condvar.wait(state).unwrap(),
+            };
+        }
+
+        state.pending = false;
+        drop(state);
+
+        #[cfg(unix)]
+        {
+            let mut buf = [0u8; 64];
+            loop {
+                match (&self.notifier.0).read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(_) => continue,
+                    Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                    Err(_) => break,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EventLoop {
+    loop_running: bool,
+    window_target: ActiveEventLoop,
+}
+
+impl EventLoop {
+    pub fn new() -> Result<Self, EventLoopError> {
+        let shared = Arc::new(Shared::new()?);
+        Ok(Self {
+            loop_running: false,
+            window_target: ActiveEventLoop {
+                control_flow: Cell::new(ControlFlow::default()),
+                exit: Cell::new(None),
+                event_loop_proxy: Arc::new(EventLoopProxy { shared: shared.clone() }),
+                shared,
+            },
+        })
+    }
+
+    /// Create an injector to synthesize events delivered by this event loop.
+    pub fn event_injector(&self) -> EventInjector {
+        self.window_target.event_injector()
+    }
+
+    pub fn window_target(&self) -> &dyn RootActiveEventLoop {
+        &self.window_target
+    }
+
+    pub fn run_app_on_demand<A: ApplicationHandler>(
+        &mut self,
+        mut app: A,
+    ) -> Result<(), EventLoopError> {
+        self.window_target.clear_exit();
+        loop {
+            match self.pump_app_events(None, &mut app) {
+                PumpStatus::Exit(0) => break Ok(()),
+                PumpStatus::Exit(code) => break Err(EventLoopError::ExitFailure(code)),
+                PumpStatus::Continue => continue,
+            }
+        }
+    }
+
+    pub fn pump_app_events<A: ApplicationHandler>(
+        &mut self,
+        timeout: Option<Duration>,
+        mut app: A,
+    ) -> PumpStatus {
+        if !self.loop_running {
+            self.loop_running = true;
+
+            // Run the initial loop iteration.
+            self.single_iteration(&mut app, StartCause::Init);
+        }
+
+        // Consider the possibility that the `StartCause::Init` iteration could
+        // request to Exit.
+        if !self.window_target.exiting() {
+            self.poll_events_with_timeout(timeout, &mut app);
+        }
+
+        if let Some(code) = self.window_target.exit_code() {
+            self.loop_running = false;
+            PumpStatus::Exit(code)
+        } else {
+            PumpStatus::Continue
+        }
+    }
+
+    fn poll_events_with_timeout<A: ApplicationHandler>(
+        &mut self,
+        timeout: Option<Duration>,
+        app: &mut A,
+    ) {
+        let start = Instant::now();
+
+        let control_flow_timeout = match self.window_target.control_flow() {
+            ControlFlow::Wait => None,
+            ControlFlow::Poll => Some(Duration::ZERO),
+            ControlFlow::WaitUntil(deadline) => Some(deadline.saturating_duration_since(start)),
+        };
+        let timeout = min_timeout(control_flow_timeout, timeout);
+
+        self.window_target.shared.wait(timeout);
+
+        // NB: `StartCause::Init` is handled as a special case and doesn't need
+        // to be considered here
+        let cause = match self.window_target.control_flow() {
+            ControlFlow::Poll => StartCause::Poll,
+            ControlFlow::Wait => StartCause::WaitCancelled { start, requested_resume: None },
+            ControlFlow::WaitUntil(deadline) => {
+                if Instant::now() < deadline {
+                    StartCause::WaitCancelled { start, requested_resume: Some(deadline) }
+                } else {
+                    StartCause::ResumeTimeReached { start, requested_resume: deadline }
+                }
+            },
+        };
+
+        self.single_iteration(app, cause);
+    }
+
+    fn single_iteration<A: ApplicationHandler>(&mut self, app: &mut A, cause: StartCause) {
+        let window_target = &self.window_target;
+
+        app.new_events(window_target, cause);
+
+        // NB: For consistency all platforms must call `can_create_surfaces` even though the
+        // headless backend doesn't have a surface lifecycle.
+        if cause == StartCause::Init {
+            app.can_create_surfaces(window_target);
+        }
+
+        if std::mem::take(&mut window_target.shared.state().proxy_wake_up) {
+            app.proxy_wake_up(window_target);
+        }
+
+        // NOTE: Events are popped one by one, since handling an event could queue new ones.
+        while let Some(event) = {
+            let mut state = window_target.shared.state();
+            state.events.pop_front()
+        } {
+            match event {
+                QueuedEvent::Window { window_id, event } => {
+                    window_target.dispatch_window_event(app, window_id, event)
+                },
+                QueuedEvent::Device { device_id, event } => {
+                    app.device_event(window_target, device_id, event)
+                },
+                QueuedEvent::Monitor { event } => app.monitor_event(window_target, event),
+                QueuedEvent::ScaleFactorChanged { window_id, scale_factor } => {
+                    window_target.dispatch_scale_factor_changed(app, window_id, scale_factor)
+                },
+            }
+        }
+
+        let destroys = std::mem::take(&mut window_target.shared.state().destroys);
+        for window_id in destroys {
+            app.window_event(window_target, window_id, WindowEvent::Destroyed);
+        }
+
+        let redraws = std::mem::take(&mut window_target.shared.state().redraws);
+        for window_id in redraws {
+            if window_target.shared.state().window(window_id).is_some() {
+                app.window_event(window_target, window_id, WindowEvent::RedrawRequested);
+            }
+        }
+
+        app.about_to_wait(window_target);
+    }
+}
+
+#[cfg(unix)]
+impl AsFd for EventLoop {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.window_target.shared.notifier.0.as_fd()
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for EventLoop {
+    fn as_raw_fd(&self) -> RawFd {
+        self.window_target.shared.notifier.0.as_raw_fd()
+    }
+}
+
+#[derive(Debug)]
+pub struct EventLoopProxy {
+    shared: Arc<Shared>,
+}
+
+impl EventLoopProxyProvider for EventLoopProxy {
+    fn wake_up(&self) {
+        self.shared.update(|state| state.proxy_wake_up = true);
+    }
+}
+
+#[derive(Debug)]
+pub struct ActiveEventLoop {
+    control_flow: Cell<ControlFlow>,
+    exit: Cell<Option<i32>>,
+    event_loop_proxy: Arc<EventLoopProxy>,
+    pub(crate) shared: Arc<Shared>,
+}
+
+impl ActiveEventLoop {
+    /// Create an injector to synthesize events delivered by this event loop.
+    pub fn event_injector(&self) -> EventInjector {
+        EventInjector::new(self.shared.clone())
+    }
+
+    pub(crate) fn clear_exit(&self) {
+        self.exit.set(None)
+    }
+
+    pub(crate) fn exit_code(&self) -> Option<i32> {
+        self.exit.get()
+    }
+
+    fn dispatch_window_event<A: ApplicationHandler>(
+        &self,
+        app: &mut A,
+        window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        let Some(window) = self.shared.state().window(window_id) else {
+            tracing::debug!("dropping {event:?} for destroyed window {window_id:?}");
+            return;
+        };
+
+        // Reflect the event in the window state, so the getters on the window match what the
+        // application was told.
+        let mut window = window.lock().unwrap();
+        match event {
+            WindowEvent::SurfaceResized(size) => {
+                window.surface_size = size;
+                self.shared.state().request_redraw(window_id);
+            },
+            WindowEvent::Moved(position) => window.position = position,
+            WindowEvent::Focused(focused) => window.focused = focused,
+            WindowEvent::Occluded(occluded) => window.occluded = occluded,
+            _ => (),
+        }
+        drop(window);
+
+        app.window_event(self, window_id, event);
+    }
+
+    fn dispatch_scale_factor_changed<A: ApplicationHandler>(
+        &self,
+        app: &mut A,
+        window_id: WindowId,
+        scale_factor: f64,
+    ) {
+        let Some(window) = self.shared.state().window(window_id) else {
+            tracing::debug!("dropping scale factor change for destroyed window {window_id:?}");
+            return;
+        };
+
+        // Keep the logical size of the window, like compositors usually do.
+        let physical_size = {
+            let mut window = window.lock().unwrap();
+            let logical_size: LogicalSize<f64> =
+                window.surface_size.to_logical(window.scale_factor);
+            window.scale_factor = scale_factor;
+            logical_size.to_physical::<u32>(scale_factor)
+        };
+
+        let new_surface_size = Arc::new(Mutex::new(physical_size));
+        let event = WindowEvent::ScaleFactorChanged {
+            scale_factor,
+            surface_size_writer: SurfaceSizeWriter::new(Arc::downgrade(&new_surface_size)),
+        };
+
+        app.window_event(self, window_id, event);
+
+        let physical_size: PhysicalSize<u32> = *new_surface_size.lock().unwrap();
+        drop(new_surface_size);
+
+        self.dispatch_window_event(app, window_id, WindowEvent::SurfaceResized(physical_size));
+    }
+}
+
+impl RootActiveEventLoop for ActiveEventLoop {
+    fn create_proxy(&self) -> CoreEventLoopProxy {
+        CoreEventLoopProxy::new(self.event_loop_proxy.clone())
+    }
+
+    fn create_window(
+        &self,
+        window_attributes: WindowAttributes,
+    ) -> Result<Box<dyn CoreWindow>, RequestError> {
+        Ok(Box::new(Window::new(self, window_attributes)))
+    }
+
+    fn create_custom_cursor(
+        &self,
+        source: CustomCursorSource,
+    ) -> Result<CustomCursor, RequestError> {
+        let _ = source;
+        Err(NotSupportedError::new("create_custom_cursor is not supported").into())
+    }
+
+    fn available_monitors(&self) -> Box<dyn Iterator<Item = CoreMonitorHandle>> {
+        Box::new(self.shared.state().monitors())
+    }
+
+    fn primary_monitor(&self) -> Option<CoreMonitorHandle> {
+        self.shared.state().monitors().next()
+    }
+
+    fn listen_device_events(&self, _allowed: DeviceEvents) {}
+
+    fn system_theme(&self) -> Option<Theme> {
+        None
+    }
+
+    fn set_control_flow(&self, control_flow: ControlFlow) {
+        self.control_flow.set(control_flow)
+    }
+
+    fn control_flow(&self) -> ControlFlow {
+        self.control_flow.get()
+    }
+
+    fn exit(&self) {
+        self.exit.set(Some(0))
+    }
+
+    fn exiting(&self) -> bool {
+        self.exit.get().is_some()
+    }
+
+    fn owned_display_handle(&self) -> CoreOwnedDisplayHandle {
+        CoreOwnedDisplayHandle::new(Arc::new(OwnedDisplayHandle))
+    }
+
+    fn rwh_06_handle(&self) -> &dyn rwh_06::HasDisplayHandle {
+        self
+    }
+}
+
+impl rwh_06::HasDisplayHandle for ActiveEventLoop {
+    fn display_handle(&self) -> Result<rwh_06::DisplayHandle<'_>, rwh_06::HandleError> {
+        Err(rwh_06::HandleError::NotSupported)
+    }
+}
+
+/// The headless backend has no display to hand out.
+#[derive(Debug, Clone)]
+pub(crate) struct OwnedDisplayHandle;
+
+impl rwh_06::HasDisplayHandle for OwnedDisplayHandle {
+    fn display_handle(&self) -> Result<rwh_06::DisplayHandle<'_>, rwh_06::HandleError> {
+        Err(rwh_06::HandleError::NotSupported)
+    }
+}
+
+/// Returns the minimum `Option<Duration>`, taking into account that `None`
+/// equates to an infinite timeout, not a zero timeout (so can't just use
+/// `Option::min`)
+fn min_timeout(a: Option<Duration>, b: Option<Duration>) -> Option<Duration> {
+    a.map_or(b, |a_timeout| b.map_or(Some(a_timeout), |b_timeout| Some(a_timeout.min(b_timeout))))
+}
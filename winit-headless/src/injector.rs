@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use dpi::{PhysicalPosition, PhysicalSize};
+use winit_core::event::{
+    ButtonSource, DeviceEvent, DeviceId, ElementState, Ime, KeyEvent, Modifiers, MonitorEvent,
+    PointerKind, PointerSource, WindowEvent,
+};
+use winit_core::monitor::{MonitorHandle as CoreMonitorHandle, MonitorHandleProvider};
+use winit_core::window::WindowId;
+
+use crate::event_loop::{QueuedEvent, Shared};
+use crate::monitor::{MonitorHandle, VirtualMonitor};
+
+/// Handle to synthesize events on the headless event loop.
+///
+/// Injected events are queued in the order they were injected and delivered through the regular
+/// [`ApplicationHandler`] methods on the next iteration of the event loop. Injecting an event
+/// wakes up the event loop, so the injector could be used from other threads as well.
+///
+/// The events are delivered as is, with the exception of events which change the window state,
+/// like [`WindowEvent::SurfaceResized`], [`WindowEvent::Moved`], and [`WindowEvent::Focused`],
+/// which are applied to the window right before the application is notified about them.
+///
+/// [`ApplicationHandler`]: winit_core::application::ApplicationHandler
+#[derive(Debug, Clone)]
+pub struct EventInjector {
+    shared: Arc<Shared>,
+}
+
+impl EventInjector {
+    pub(crate) fn new(shared: Arc<Shared>) -> Self {
+        Self { shared }
+    }
+
+    /// Queue an arbitrary [`WindowEvent`] for the given window.
+    ///
+    /// Events for windows which don't exist by the time they're delivered are dropped.
+    pub fn window_event(&self, window_id: WindowId, event: WindowEvent) {
+        self.shared
+            .update(|state| state.events.push_back(QueuedEvent::Window { window_id, event }));
+    }
+
+    /// Queue an arbitrary [`DeviceEvent`].
+    pub fn device_event(&self, device_id: Option<DeviceId>, event: DeviceEvent) {
+        self.shared
+            .update(|state| state.events.push_back(QueuedEvent::Device { device_id, event }));
+    }
+
+    /// Resize the surface of the window.
+    ///
+    /// This emits [`WindowEvent::SurfaceResized`] followed by [`WindowEvent::RedrawRequested`].
+    pub fn resize(&self, window_id: WindowId, size: PhysicalSize<u32>) {
+        self.window_event(window_id, WindowEvent::SurfaceResized(size));
+    }
+
+    /// Change the scale factor of the window.
+    ///
+    /// This emits [`WindowEvent::ScaleFactorChanged`] with the surface size suggestion keeping
+    /// the logical size of the window, followed by [`WindowEvent::SurfaceResized`] with the size
+    /// picked by the application.
+    pub fn scale_factor_changed(&self, window_id: WindowId, scale_factor: f64) {
+        self.shared.update(|state| {
+            state.events.push_back(QueuedEvent::ScaleFactorChanged { window_id, scale_factor })
+        });
+    }
+
+    /// Change the keyboard focus of the window.
+    pub fn focus(&self, window_id: WindowId, focused: bool) {
+        self.window_event(window_id, WindowEvent::Focused(focused));
+    }
+
+    /// Request the window to be closed.
+    pub fn close_requested(&self, window_id: WindowId) {
+        self.window_event(window_id, WindowEvent::CloseRequested);
+    }
+
+    /// Move the mouse pointer into the window.
+    pub fn pointer_entered(&self, window_id: WindowId, position: PhysicalPosition<f64>) {
+        self.window_event(window_id, WindowEvent::PointerEntered {
+            device_id: None,
+            primary: true,
+            position,
+            kind: PointerKind::Mouse,
+        });
+    }
+
+    /// Move the mouse pointer out of the window.
+    pub fn pointer_left(&self, window_id: WindowId, position: Option<PhysicalPosition<f64>>) {
+        self.window_event(window_id, WindowEvent::PointerLeft {
+            device_id: None,
+            primary: true,
+            position,
+            kind: PointerKind::Mouse,
+        });
+    }
+
+    /// Move the mouse pointer over the window.
+    pub fn pointer_moved(&self, window_id: WindowId, position: PhysicalPosition<f64>) {
+        self.window_event(window_id, WindowEvent::PointerMoved {
+            device_id: None,
+            primary: true,
+            position,
+            source: PointerSource::Mouse,
+        });
+    }
+
+    /// Press or release a pointer button at the given position.
+    pub fn pointer_button(
+        &self,
+        window_id: WindowId,
+        state: ElementState,
+        position: PhysicalPosition<f64>,
+        button: impl Into<ButtonSource>,
+    ) {
+        self.window_event(window_id, WindowEvent::PointerButton {
+            device_id: None,
+            primary: true,
+            state,
+            position,
+            button: button.into(),
+        });
+    }
+
+    /// Deliver a keyboard event to the window.
+    pub fn keyboard_input(&self, window_id: WindowId, event: KeyEvent) {
+        self.window_event(window_id, WindowEvent::KeyboardInput {
+            device_id: None,
+            event,
+            is_synthetic: false,
+        });
+    }
+
+    /// Change the state of the keyboard modifiers.
+    pub fn modifiers_changed(&self, window_id: WindowId, modifiers: Modifiers) {
+        self.window_event(window_id, WindowEvent::ModifiersChanged(modifiers));
+    }
+
+    /// Deliver an input method event to the window.
+    pub fn ime(&self, window_id: WindowId, event: Ime) {
+        self.window_event(window_id, WindowEvent::Ime(event));
+    }
+
+    /// Add a virtual monitor.
+    ///
+    /// The monitor is available right away through the monitor APIs on the event loop and the
+    /// windows, and [`MonitorEvent::Added`] is delivered on the next iteration.
+    pub fn add_monitor(&self, monitor: VirtualMonitor) -> CoreMonitorHandle {
+        let monitor = MonitorHandle::new(monitor);
+        let event = MonitorEvent::Added(CoreMonitorHandle(Arc::new(monitor.clone())));
+        self.shared.update(|state| {
+            state.monitors.push(monitor.clone());
+            state.events.push_back(QueuedEvent::Monitor { event });
+        });
+        CoreMonitorHandle(Arc::new(monitor))
+    }
+
+    /// Remove a virtual monitor previously added with [`EventInjector::add_monitor`], or the
+    /// default one the event loop starts with.
+    ///
+    /// [`MonitorEvent::Removed`] is delivered on the next iteration if the monitor was present.
+    pub fn remove_monitor(&self, monitor: &CoreMonitorHandle) {
+        self.shared.update(|state| {
+            let Some(pos) = state.monitors.iter().position(|handle| handle.id() == monitor.id())
+            else {
+                return;
+            };
+            let removed = state.monitors.remove(pos);
+            let event = MonitorEvent::Removed(CoreMonitorHandle(Arc::new(removed)));
+            state.events.push_back(QueuedEvent::Monitor { event });
+        });
+    }
+}
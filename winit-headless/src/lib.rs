@@ -0,0 +1,319 @@
+//! # Winit's headless backend.
+//!
+//! A backend which doesn't connect to any display server. Windows and monitors only exist in
+//! memory, and input is synthesized with the [`EventInjector`], making it possible to drive an
+//! [`ApplicationHandler`] with exact, reproducible event sequences, e.g. in unit tests or on CI
+//! machines without a display.
+//!
+//! Events injected with the [`EventInjector`] are delivered in the order they were injected on the
+//! next iteration of the event loop, through the same [`ApplicationHandler`] methods used by the
+//! other backends.
+//!
+//! The event loop starts with a single 1920x1080 [`VirtualMonitor`], more could be added with
+//! [`EventInjector::add_monitor`].
+//!
+//! Windows don't have a native handle, thus the [`rwh_06`] handles are not available.
+//!
+//! ## Example
+//!
+//! ```
+//! use winit_core::application::ApplicationHandler;
+//! use winit_core::event::WindowEvent;
+//! use winit_core::event_loop::ActiveEventLoop;
+//! use winit_core::window::{Window, WindowAttributes, WindowId};
+//! use winit_headless::{ActiveEventLoopExtHeadless, EventLoop};
+//!
+//! #[derive(Default)]
+//! struct App {
+//!     window: Option<Box<dyn Window>>,
+//! }
+//!
+//! impl ApplicationHandler for App {
+//!     fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+//!         let window = event_loop.create_window(WindowAttributes::default()).unwrap();
+//!         // Ask to close the window right away.
+//!         event_loop.event_injector().unwrap().close_requested(window.id());
+//!         self.window = Some(window);
+//!     }
+//!
+//!     fn window_event(
+//!         &mut self,
+//!         event_loop: &dyn ActiveEventLoop,
+//!         _: WindowId,
+//!         event: WindowEvent,
+//!     ) {
+//!         if event == WindowEvent::CloseRequested {
+//!             event_loop.exit();
+//!         }
+//!     }
+//! }
+//!
+//! let mut event_loop = EventLoop::new().unwrap();
+//! event_loop.run_app_on_demand(App::default()).unwrap();
+//! ```
+//!
+//! [`ApplicationHandler`]: winit_core::application::ApplicationHandler
+
+use winit_core::event_loop::ActiveEventLoop as CoreActiveEventLoop;
+
+// NOTE: Only the notifier socket, used for `AsFd` on Unix, can fail to be created.
+#[cfg(unix)]
+macro_rules! os_error {
+    ($error:expr) => {{ winit_core::error::OsError::new(line!(), file!(), $error) }};
+}
+
+mod event_loop;
+mod injector;
+mod monitor;
+mod window;
+
+pub use self::event_loop::{ActiveEventLoop, EventLoop, EventLoopProxy};
+pub use self::injector::EventInjector;
+pub use self::monitor::{MonitorHandle, VirtualMonitor};
+pub use self::window::Window;
+
+/// Additional methods on [`ActiveEventLoop`] that are specific to the headless backend.
+///
+/// [`ActiveEventLoop`]: winit_core::event_loop::ActiveEventLoop
+pub trait ActiveEventLoopExtHeadless {
+    /// True if the [`ActiveEventLoop`] is headless.
+    ///
+    /// [`ActiveEventLoop`]: winit_core::event_loop::ActiveEventLoop
+    fn is_headless(&self) -> bool;
+
+    /// Create an [`EventInjector`] for the event loop.
+    ///
+    /// Returns `None` when the event loop is not headless.
+    fn event_injector(&self) -> Option<EventInjector>;
+}
+
+impl ActiveEventLoopExtHeadless for dyn CoreActiveEventLoop + '_ {
+    #[inline]
+    fn is_headless(&self) -> bool {
+        self.cast_ref::<ActiveEventLoop>().is_some()
+    }
+
+    #[inline]
+    fn event_injector(&self) -> Option<EventInjector> {
+        self.cast_ref::<ActiveEventLoop>().map(ActiveEventLoop::event_injector)
+    }
+}
+
+/// Additional methods on [`EventLoop`] that are specific to the headless backend.
+pub trait EventLoopExtHeadless {
+    /// True if the [`EventLoop`] is headless.
+    fn is_headless(&self) -> bool;
+
+    /// Create an [`EventInjector`] for the event loop.
+    ///
+    /// Returns `None` when the event loop is not headless.
+    fn event_injector(&self) -> Option<EventInjector>;
+}
+
+/// Additional methods when building event loop that are specific to the headless backend.
+pub trait EventLoopBuilderExtHeadless {
+    /// Force using the headless backend.
+    ///
+    /// Unlike the other backends, the headless event loop could be created on any thread.
+    fn with_headless(&mut self) -> &mut Self;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use dpi::{PhysicalPosition, PhysicalSize};
+    use winit_core::application::ApplicationHandler;
+    use winit_core::event::{ElementState, MonitorEvent, MouseButton, StartCause, WindowEvent};
+    use winit_core::event_loop::ActiveEventLoop;
+    use winit_core::event_loop::pump_events::PumpStatus;
+    use winit_core::window::{Window, WindowAttributes, WindowId};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct App {
+        window: Option<Box<dyn Window>>,
+        events: Vec<WindowEvent>,
+        monitor_events: Vec<MonitorEvent>,
+        new_size: Option<PhysicalSize<u32>>,
+    }
+
+    impl ApplicationHandler for App {
+        fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+            let attributes =
+                WindowAttributes::default().with_surface_size(PhysicalSize::new(64, 32));
+            self.window = Some(event_loop.create_window(attributes).unwrap());
+        }
+
+        fn window_event(
+            &mut self,
+            _: &dyn ActiveEventLoop,
+            window_id: WindowId,
+            mut event: WindowEvent,
+        ) {
+            assert_eq!(Some(window_id), self.window.as_ref().map(|window| window.id()));
+            if let WindowEvent::ScaleFactorChanged { surface_size_writer, .. } = &mut event {
+                if let Some(size) = self.new_size {
+                    surface_size_writer.request_surface_size(size).unwrap();
+                }
+            }
+            self.events.push(event);
+        }
+
+        fn monitor_event(&mut self, _: &dyn ActiveEventLoop, event: MonitorEvent) {
+            self.monitor_events.push(event);
+        }
+    }
+
+    fn pump(event_loop: &mut EventLoop, app: &mut App) {
+        assert_eq!(
+            event_loop.pump_app_events(Some(Duration::ZERO), &mut *app),
+            PumpStatus::Continue
+        );
+    }
+
+    #[test]
+    fn injected_events_are_delivered_in_order() {
+        let mut event_loop = EventLoop::new().unwrap();
+        let injector = event_loop.event_injector();
+        let mut app = App::default();
+
+        pump(&mut event_loop, &mut app);
+        assert_eq!(app.events, [WindowEvent::RedrawRequested]);
+        app.events.clear();
+
+        let window_id = app.window.as_ref().unwrap().id();
+        let position = PhysicalPosition::new(10., 20.);
+        injector.focus(window_id, true);
+        injector.pointer_moved(window_id, position);
+        injector.pointer_button(window_id, ElementState::Pressed, position, MouseButton::Left);
+        injector.resize(window_id, PhysicalSize::new(128, 64));
+        pump(&mut event_loop, &mut app);
+
+        assert!(matches!(app.events[..], [
+            WindowEvent::Focused(true),
+            WindowEvent::PointerMoved { .. },
+            WindowEvent::PointerButton { state: ElementState::Pressed, .. },
+            WindowEvent::SurfaceResized(PhysicalSize { width: 128, height: 64 }),
+            WindowEvent::RedrawRequested,
+        ]));
+
+        let window = app.window.as_ref().unwrap();
+        assert!(window.has_focus());
+        assert_eq!(window.surface_size(), PhysicalSize::new(128, 64));
+    }
+
+    #[test]
+    fn scale_factor_change_keeps_logical_size() {
+        let mut event_loop = EventLoop::new().unwrap();
+        let injector = event_loop.event_injector();
+        let mut app = App::default();
+        pump(&mut event_loop, &mut app);
+        app.events.clear();
+
+        let window_id = app.window.as_ref().unwrap().id();
+        injector.scale_factor_changed(window_id, 2.);
+        pump(&mut event_loop, &mut app);
+        assert!(matches!(app.events[..], [
+            WindowEvent::ScaleFactorChanged { scale_factor: 2., .. },
+            WindowEvent::SurfaceResized(PhysicalSize { width: 128, height: 64 }),
+            WindowEvent::RedrawRequested,
+        ]));
+        assert_eq!(app.window.as_ref().unwrap().scale_factor(), 2.);
+        app.events.clear();
+
+        // The application could override the suggested size.
+        app.new_size = Some(PhysicalSize::new(10, 10));
+        injector.scale_factor_changed(window_id, 1.);
+        pump(&mut event_loop, &mut app);
+        assert_eq!(app.events[1], WindowEvent::SurfaceResized(PhysicalSize::new(10, 10)));
+        assert_eq!(app.window.as_ref().unwrap().surface_size(), PhysicalSize::new(10, 10));
+    }
+
+    #[test]
+    fn virtual_monitors() {
+        let event_loop = EventLoop::new().unwrap();
+        let injector = event_loop.event_injector();
+        let monitor = injector.add_monitor(
+            VirtualMonitor::new("HEADLESS-2", PhysicalSize::new(1280, 720))
+                .with_position(PhysicalPosition::new(1920, 0))
+                .with_scale_factor(2.),
+        );
+
+        let event_loop = event_loop.window_target();
+        assert_eq!(event_loop.available_monitors().count(), 2);
+        let monitor = event_loop.available_monitors().find(|handle| *handle == monitor).unwrap();
+        assert_eq!(monitor.name().as_deref(), Some("HEADLESS-2"));
+        assert_eq!(monitor.scale_factor(), 2.);
+        assert_eq!(monitor.current_video_mode().unwrap().size(), PhysicalSize::new(1280, 720));
+
+        injector.remove_monitor(&monitor);
+        assert_eq!(event_loop.available_monitors().count(), 1);
+    }
+
+    #[test]
+    fn monitor_events() {
+        let mut event_loop = EventLoop::new().unwrap();
+        let injector = event_loop.event_injector();
+        let mut app = App::default();
+
+        // The default monitor is reported on startup.
+        pump(&mut event_loop, &mut app);
+        let default_monitor = match &app.monitor_events[..] {
+            [MonitorEvent::Added(monitor)] => monitor.clone(),
+            events => panic!("unexpected monitor events: {events:?}"),
+        };
+        app.monitor_events.clear();
+
+        let monitor =
+            injector.add_monitor(VirtualMonitor::new("HEADLESS-2", PhysicalSize::new(1280, 720)));
+        injector.remove_monitor(&default_monitor);
+        pump(&mut event_loop, &mut app);
+        assert_eq!(app.monitor_events, [
+            MonitorEvent::Added(monitor),
+            MonitorEvent::Removed(default_monitor.clone()),
+        ]);
+        app.monitor_events.clear();
+
+        // Removing a monitor which is already gone does nothing.
+        injector.remove_monitor(&default_monitor);
+        pump(&mut event_loop, &mut app);
+        assert!(app.monitor_events.is_empty());
+    }
+
+    #[test]
+    fn exit_from_injected_event() {
+        struct ExitApp(Option<Box<dyn Window>>, bool);
+
+        impl ApplicationHandler for ExitApp {
+            fn new_events(&mut self, _: &dyn ActiveEventLoop, cause: StartCause) {
+                self.1 |= cause == StartCause::Init;
+            }
+
+            fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+                let window = event_loop.create_window(WindowAttributes::default()).unwrap();
+                event_loop.event_injector().unwrap().close_requested(window.id());
+                self.0 = Some(window);
+            }
+
+            fn window_event(
+                &mut self,
+                event_loop: &dyn ActiveEventLoop,
+                _: WindowId,
+                event: WindowEvent,
+            ) {
+                if event == WindowEvent::CloseRequested {
+                    self.0 = None;
+                    event_loop.exit();
+                }
+            }
+        }
+
+        let mut event_loop = EventLoop::new().unwrap();
+        let mut app = ExitApp(None, false);
+        event_loop.run_app_on_demand(&mut app).unwrap();
+        assert!(app.1);
+        assert!(app.0.is_none());
+    }
+}
@@ -0,0 +1,129 @@
+use std::borrow::Cow;
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dpi::{PhysicalPosition, PhysicalSize};
+use winit_core::monitor::{MonitorHandleProvider, VideoMode};
+
+/// Description of a monitor simulated by the headless backend.
+///
+/// Virtual monitors are registered with [`EventInjector::add_monitor`] and are reported through
+/// the regular monitor APIs, like [`ActiveEventLoop::available_monitors`].
+///
+/// [`EventInjector::add_monitor`]: crate::EventInjector::add_monitor
+/// [`ActiveEventLoop::available_monitors`]: winit_core::event_loop::ActiveEventLoop::available_monitors
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualMonitor {
+    pub(crate) name: String,
+    pub(crate) position: PhysicalPosition<i32>,
+    pub(crate) scale_factor: f64,
+    pub(crate) video_mode: VideoMode,
+    pub(crate) video_modes: Vec<VideoMode>,
+}
+
+impl VirtualMonitor {
+    /// Create a monitor with the given name and resolution.
+    ///
+    /// The monitor is placed at the origin, has a scale factor of `1.0` and a refresh rate of
+    /// 60Hz.
+    pub fn new(name: impl Into<String>, size: PhysicalSize<u32>) -> Self {
+        let video_mode = VideoMode::new(size, None, NonZeroU32::new(60_000));
+        Self {
+            name: name.into(),
+            position: PhysicalPosition::new(0, 0),
+            scale_factor: 1.,
+            video_mode,
+            video_modes: vec![video_mode],
+        }
+    }
+
+    /// Place the monitor at the given position in desktop coordinates.
+    pub fn with_position(mut self, position: PhysicalPosition<i32>) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Set the scale factor of the monitor.
+    ///
+    /// Windows created while this monitor is the first one available use this scale factor.
+    pub fn with_scale_factor(mut self, scale_factor: f64) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Set the refresh rate of the current video mode.
+    pub fn with_refresh_rate_millihertz(mut self, refresh_rate: Option<NonZeroU32>) -> Self {
+        let mode =
+            VideoMode::new(self.video_mode.size(), self.video_mode.bit_depth(), refresh_rate);
+        for video_mode in self.video_modes.iter_mut().filter(|mode| **mode == self.video_mode) {
+            *video_mode = mode;
+        }
+        self.video_mode = mode;
+        self
+    }
+
+    /// Add an additional video mode the monitor claims to support.
+    pub fn with_video_mode(mut self, video_mode: VideoMode) -> Self {
+        if !self.video_modes.contains(&video_mode) {
+            self.video_modes.push(video_mode);
+        }
+        self
+    }
+
+    pub(crate) fn contains(&self, position: PhysicalPosition<i32>) -> bool {
+        let size = self.video_mode.size();
+        let x = i64::from(position.x) - i64::from(self.position.x);
+        let y = i64::from(position.y) - i64::from(self.position.y);
+        (0..i64::from(size.width)).contains(&x) && (0..i64::from(size.height)).contains(&y)
+    }
+}
+
+impl Default for VirtualMonitor {
+    fn default() -> Self {
+        Self::new("HEADLESS-1", PhysicalSize::new(1920, 1080))
+    }
+}
+
+/// Handle to a [`VirtualMonitor`] registered with the event loop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorHandle {
+    id: u64,
+    pub(crate) monitor: VirtualMonitor,
+}
+
+impl MonitorHandle {
+    pub(crate) fn new(monitor: VirtualMonitor) -> Self {
+        static MONITOR_ID: AtomicU64 = AtomicU64::new(1);
+        Self { id: MONITOR_ID.fetch_add(1, Ordering::Relaxed), monitor }
+    }
+}
+
+impl MonitorHandleProvider for MonitorHandle {
+    fn id(&self) -> u128 {
+        self.id as u128
+    }
+
+    fn native_id(&self) -> u64 {
+        self.id
+    }
+
+    fn name(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(&self.monitor.name))
+    }
+
+    fn position(&self) -> Option<PhysicalPosition<i32>> {
+        Some(self.monitor.position)
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.monitor.scale_factor
+    }
+
+    fn current_video_mode(&self) -> Option<VideoMode> {
+        Some(self.monitor.video_mode)
+    }
+
+    fn video_modes(&self) -> Box<dyn Iterator<Item = VideoMode>> {
+        Box::new(self.monitor.video_modes.clone().into_iter())
+    }
+}
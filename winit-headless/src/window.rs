@@ -0,0 +1,402 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use dpi::{PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
+use winit_core::cursor::Cursor;
+use winit_core::error::{NotSupportedError, RequestError};
+use winit_core::event::{Ime, WindowEvent};
+use winit_core::icon::Icon;
+use winit_core::monitor::{Fullscreen, MonitorHandle as CoreMonitorHandle};
+use winit_core::window::{
+    CursorGrabMode, ImeCapabilities, ImeRequest, ImeRequestError, ResizeDirection, Theme,
+    UserAttentionType, Window as CoreWindow, WindowAttributes, WindowButtons, WindowId,
+    WindowLevel,
+};
+
+use crate::event_loop::{ActiveEventLoop, QueuedEvent, Shared};
+
+/// The default surface size, matching what most backends pick when no size was requested.
+const DEFAULT_SURFACE_SIZE: PhysicalSize<u32> = PhysicalSize::new(800, 600);
+
+#[derive(Debug)]
+pub(crate) struct WindowState {
+    pub(crate) title: String,
+    pub(crate) surface_size: PhysicalSize<u32>,
+    pub(crate) position: PhysicalPosition<i32>,
+    pub(crate) scale_factor: f64,
+    pub(crate) min_surface_size: Option<Size>,
+    pub(crate) max_surface_size: Option<Size>,
+    pub(crate) surface_resize_increments: Option<Size>,
+    pub(crate) visible: bool,
+    pub(crate) resizable: bool,
+    pub(crate) enabled_buttons: WindowButtons,
+    pub(crate) minimized: bool,
+    pub(crate) maximized: bool,
+    pub(crate) fullscreen: Option<Fullscreen>,
+    pub(crate) decorations: bool,
+    pub(crate) focused: bool,
+    pub(crate) occluded: bool,
+    pub(crate) theme: Option<Theme>,
+    pub(crate) ime_capabilities: Option<ImeCapabilities>,
+}
+
+impl WindowState {
+    /// Clamp the size to the min and max surface size constraints.
+    fn clamp_surface_size(&self, size: PhysicalSize<u32>) -> PhysicalSize<u32> {
+        let mut size = size;
+        if let Some(min_size) = self.min_surface_size {
+            let min_size = min_size.to_physical::<u32>(self.scale_factor);
+            size.width = size.width.max(min_size.width);
+            size.height = size.height.max(min_size.height);
+        }
+        if let Some(max_size) = self.max_surface_size {
+            let max_size = max_size.to_physical::<u32>(self.scale_factor);
+            size.width = size.width.min(max_size.width);
+            size.height = size.height.min(max_size.height);
+        }
+        size
+    }
+}
+
+/// A window which exists only in memory.
+///
+/// The state of the window is updated synchronously by the setters, and by the events injected
+/// with the [`EventInjector`] once they're delivered.
+///
+/// [`EventInjector`]: crate::EventInjector
+#[derive(Debug)]
+pub struct Window {
+    window_id: WindowId,
+    state: Arc<Mutex<WindowState>>,
+    shared: Arc<Shared>,
+}
+
+impl Window {
+    pub(crate) fn new(event_loop: &ActiveEventLoop, attributes: WindowAttributes) -> Self {
+        static WINDOW_ID: AtomicUsize = AtomicUsize::new(1);
+        let window_id = WindowId::from_raw(WINDOW_ID.fetch_add(1, Ordering::Relaxed));
+
+        let shared = event_loop.shared.clone();
+        let scale_factor = shared
+            .state()
+            .monitors
+            .first()
+            .map(|monitor| monitor.monitor.scale_factor)
+            .unwrap_or(1.);
+
+        let mut state = WindowState {
+            title: attributes.title,
+            surface_size: DEFAULT_SURFACE_SIZE,
+            position: attributes
+                .position
+                .map(|position| position.to_physical(scale_factor))
+                .unwrap_or_default(),
+            scale_factor,
+            min_surface_size: attributes.min_surface_size,
+            max_surface_size: attributes.max_surface_size,
+            surface_resize_increments: attributes.surface_resize_increments,
+            visible: attributes.visible,
+            resizable: attributes.resizable,
+            enabled_buttons: attributes.enabled_buttons,
+            minimized: false,
+            maximized: attributes.maximized,
+            fullscreen: attributes.fullscreen,
+            decorations: attributes.decorations,
+            focused: false,
+            occluded: false,
+            theme: attributes.preferred_theme,
+            ime_capabilities: None,
+        };
+        let surface_size = attributes
+            .surface_size
+            .map(|size| size.to_physical(scale_factor))
+            .unwrap_or(DEFAULT_SURFACE_SIZE);
+        state.surface_size = state.clamp_surface_size(surface_size);
+
+        let state = Arc::new(Mutex::new(state));
+        shared.update(|shared| {
+            shared.windows.insert(window_id, Arc::downgrade(&state));
+            // Like on other backends, the first frame is requested by the window itself.
+            shared.request_redraw(window_id);
+        });
+
+        Self { window_id, state, shared }
+    }
+
+    fn queue_window_event(&self, event: WindowEvent) {
+        let window_id = self.window_id;
+        self.shared
+            .update(|state| state.events.push_back(QueuedEvent::Window { window_id, event }));
+    }
+}
+
+impl CoreWindow for Window {
+    fn id(&self) -> WindowId {
+        self.window_id
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.state.lock().unwrap().scale_factor
+    }
+
+    fn request_redraw(&self) {
+        self.shared.update(|state| state.request_redraw(self.window_id));
+    }
+
+    fn pre_present_notify(&self) {}
+
+    fn reset_dead_keys(&self) {}
+
+    fn surface_position(&self) -> PhysicalPosition<i32> {
+        PhysicalPosition::new(0, 0)
+    }
+
+    fn outer_position(&self) -> Result<PhysicalPosition<i32>, RequestError> {
+        Ok(self.state.lock().unwrap().position)
+    }
+
+    fn set_outer_position(&self, position: Position) {
+        let mut state = self.state.lock().unwrap();
+        let position = position.to_physical(state.scale_factor);
+        if state.position != position {
+            state.position = position;
+            drop(state);
+            self.queue_window_event(WindowEvent::Moved(position));
+        }
+    }
+
+    fn surface_size(&self) -> PhysicalSize<u32> {
+        self.state.lock().unwrap().surface_size
+    }
+
+    fn request_surface_size(&self, size: Size) -> Option<PhysicalSize<u32>> {
+        let mut state = self.state.lock().unwrap();
+        let size = state.clamp_surface_size(size.to_physical(state.scale_factor));
+        state.surface_size = size;
+        Some(size)
+    }
+
+    fn outer_size(&self) -> PhysicalSize<u32> {
+        self.surface_size()
+    }
+
+    fn safe_area(&self) -> PhysicalInsets<u32> {
+        PhysicalInsets::new(0, 0, 0, 0)
+    }
+
+    fn set_min_surface_size(&self, min_size: Option<Size>) {
+        self.state.lock().unwrap().min_surface_size = min_size;
+    }
+
+    fn set_max_surface_size(&self, max_size: Option<Size>) {
+        self.state.lock().unwrap().max_surface_size = max_size;
+    }
+
+    fn surface_resize_increments(&self) -> Option<PhysicalSize<u32>> {
+        let state = self.state.lock().unwrap();
+        state.surface_resize_increments.map(|increments| increments.to_physical(state.scale_factor))
+    }
+
+    fn set_surface_resize_increments(&self, increments: Option<Size>) {
+        self.state.lock().unwrap().surface_resize_increments = increments;
+    }
+
+    fn set_title(&self, title: &str) {
+        self.state.lock().unwrap().title = title.to_owned();
+    }
+
+    fn set_transparent(&self, _transparent: bool) {}
+
+    fn set_blur(&self, _blur: bool) {}
+
+    fn set_visible(&self, visible: bool) {
+        self.state.lock().unwrap().visible = visible;
+    }
+
+    fn is_visible(&self) -> Option<bool> {
+        Some(self.state.lock().unwrap().visible)
+    }
+
+    fn set_resizable(&self, resizable: bool) {
+        self.state.lock().unwrap().resizable = resizable;
+    }
+
+    fn is_resizable(&self) -> bool {
+        self.state.lock().unwrap().resizable
+    }
+
+    fn set_enabled_buttons(&self, buttons: WindowButtons) {
+        self.state.lock().unwrap().enabled_buttons = buttons;
+    }
+
+    fn enabled_buttons(&self) -> WindowButtons {
+        self.state.lock().unwrap().enabled_buttons
+    }
+
+    fn set_minimized(&self, minimized: bool) {
+        self.state.lock().unwrap().minimized = minimized;
+    }
+
+    fn is_minimized(&self) -> Option<bool> {
+        Some(self.state.lock().unwrap().minimized)
+    }
+
+    fn set_maximized(&self, maximized: bool) {
+        self.state.lock().unwrap().maximized = maximized;
+    }
+
+    fn is_maximized(&self) -> bool {
+        self.state.lock().unwrap().maximized
+    }
+
+    fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.state.lock().unwrap().fullscreen = fullscreen;
+    }
+
+    fn fullscreen(&self) -> Option<Fullscreen> {
+        self.state.lock().unwrap().fullscreen.clone()
+    }
+
+    fn set_decorations(&self, decorations: bool) {
+        self.state.lock().unwrap().decorations = decorations;
+    }
+
+    fn is_decorated(&self) -> bool {
+        self.state.lock().unwrap().decorations
+    }
+
+    fn set_window_level(&self, _level: WindowLevel) {}
+
+    fn set_window_icon(&self, _window_icon: Option<Icon>) {}
+
+    fn request_ime_update(&self, request: ImeRequest) -> Result<(), ImeRequestError> {
+        let mut state = self.state.lock().unwrap();
+        let event = match request {
+            ImeRequest::Enable(request) => {
+                if state.ime_capabilities.is_some() {
+                    return Err(ImeRequestError::AlreadyEnabled);
+                }
+                state.ime_capabilities = Some(*request.capabilities());
+                Ime::Enabled
+            },
+            ImeRequest::Update(_) if state.ime_capabilities.is_none() => {
+                return Err(ImeRequestError::NotEnabled);
+            },
+            ImeRequest::Update(_) => return Ok(()),
+            ImeRequest::Disable => {
+                if state.ime_capabilities.take().is_none() {
+                    return Ok(());
+                }
+                Ime::Disabled
+            },
+        };
+        drop(state);
+
+        self.queue_window_event(WindowEvent::Ime(event));
+        Ok(())
+    }
+
+    fn ime_capabilities(&self) -> Option<ImeCapabilities> {
+        self.state.lock().unwrap().ime_capabilities
+    }
+
+    fn focus_window(&self) {}
+
+    fn has_focus(&self) -> bool {
+        self.state.lock().unwrap().focused
+    }
+
+    fn request_user_attention(&self, _request_type: Option<UserAttentionType>) {}
+
+    fn set_theme(&self, theme: Option<Theme>) {
+        self.state.lock().unwrap().theme = theme;
+    }
+
+    fn theme(&self) -> Option<Theme> {
+        self.state.lock().unwrap().theme
+    }
+
+    fn set_content_protected(&self, _protected: bool) {}
+
+    fn title(&self) -> String {
+        self.state.lock().unwrap().title.clone()
+    }
+
+    fn set_cursor(&self, _cursor: Cursor) {}
+
+    fn set_cursor_position(&self, _position: Position) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("set_cursor_position is not supported").into())
+    }
+
+    fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), RequestError> {
+        match mode {
+            CursorGrabMode::None => Ok(()),
+            _ => Err(NotSupportedError::new("cursor grabbing is not supported").into()),
+        }
+    }
+
+    fn set_cursor_visible(&self, _visible: bool) {}
+
+    fn drag_window(&self) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("drag_window is not supported").into())
+    }
+
+    fn drag_resize_window(&self, _direction: ResizeDirection) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("drag_resize_window is not supported").into())
+    }
+
+    fn show_window_menu(&self, _position: Position) {}
+
+    fn set_cursor_hittest(&self, _hittest: bool) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("set_cursor_hittest is not supported").into())
+    }
+
+    fn current_monitor(&self) -> Option<CoreMonitorHandle> {
+        let position = self.state.lock().unwrap().position;
+        let state = self.shared.state();
+        state
+            .monitors
+            .iter()
+            .find(|monitor| monitor.monitor.contains(position))
+            .or_else(|| state.monitors.first())
+            .map(|monitor| CoreMonitorHandle(Arc::new(monitor.clone())))
+    }
+
+    fn available_monitors(&self) -> Box<dyn Iterator<Item = CoreMonitorHandle>> {
+        Box::new(self.shared.state().monitors())
+    }
+
+    fn primary_monitor(&self) -> Option<CoreMonitorHandle> {
+        self.shared.state().monitors().next()
+    }
+
+    fn rwh_06_display_handle(&self) -> &dyn rwh_06::HasDisplayHandle {
+        self
+    }
+
+    fn rwh_06_window_handle(&self) -> &dyn rwh_06::HasWindowHandle {
+        self
+    }
+}
+
+impl rwh_06::HasWindowHandle for Window {
+    fn window_handle(&self) -> Result<rwh_06::WindowHandle<'_>, rwh_06::HandleError> {
+        Err(rwh_06::HandleError::NotSupported)
+    }
+}
+
+impl rwh_06::HasDisplayHandle for Window {
+    fn display_handle(&self) -> Result<rwh_06::DisplayHandle<'_>, rwh_06::HandleError> {
+        Err(rwh_06::HandleError::NotSupported)
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        let window_id = self.window_id;
+        self.shared.update(|state| {
+            state.windows.remove(&window_id);
+            state.destroys.push(window_id);
+        });
+    }
+}
@@ -569,7 +569,7 @@ impl WinitView {
                                     data: tool_data,
                                 }
                             } else {
-                                ButtonSource::Touch { finger_id, force }
+                                ButtonSource::Touch { finger_id, force, contact: None }
                             },
                         },
                     });
@@ -585,6 +585,7 @@ impl WinitView {
                         (ivars.primary_finger.get().unwrap() == finger_id, PointerSource::Touch {
                             finger_id,
                             force,
+                            contact: None,
                         })
                     };
 
@@ -627,7 +628,7 @@ impl WinitView {
                                         data: tool_data,
                                     }
                                 } else {
-                                    ButtonSource::Touch { finger_id, force }
+                                    ButtonSource::Touch { finger_id, force, contact: None }
                                 },
                             },
                         });
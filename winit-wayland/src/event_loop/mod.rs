@@ -18,18 +18,22 @@ use sctk::reexports::calloop_wayland_source::WaylandSource;
 use sctk::reexports::client::{Connection, QueueHandle, globals};
 use tracing::warn;
 use winit_core::application::ApplicationHandler;
+use winit_core::clipboard::ClipboardKind;
 use winit_core::cursor::{CustomCursor as CoreCustomCursor, CustomCursorSource};
 use winit_core::error::{EventLoopError, NotSupportedError, OsError, RequestError};
-use winit_core::event::{DeviceEvent, StartCause, SurfaceSizeWriter, WindowEvent};
+use winit_core::event::{
+    ClipboardEvent, DeviceEvent, DeviceId, DeviceInfo, MimeData, MonitorEvent, StartCause,
+    SurfaceSizeWriter, WindowEvent,
+};
 use winit_core::event_loop::pump_events::PumpStatus;
 use winit_core::event_loop::{
-    ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents,
-    OwnedDisplayHandle as CoreOwnedDisplayHandle,
+    ActiveEventLoop as RootActiveEventLoop, AsyncRequestSerial, ControlFlow, DesktopSettings,
+    DeviceEvents, OwnedDisplayHandle as CoreOwnedDisplayHandle,
 };
 use winit_core::monitor::MonitorHandle as CoreMonitorHandle;
 use winit_core::window::Theme;
 
-use crate::types::cursor::WaylandCustomCursor;
+use crate::types::cursor::{CursorTheme, WaylandCustomCursor};
 
 mod proxy;
 pub mod sink;
@@ -46,9 +50,13 @@ use super::{WindowId, logical_to_physical_rounded};
 type WaylandDispatcher = calloop::Dispatcher<'static, WaylandSource<WinitState>, WinitState>;
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum Event {
     WindowEvent { window_id: WindowId, event: WindowEvent },
-    DeviceEvent { event: DeviceEvent },
+    DeviceEvent { device_id: Option<DeviceId>, event: DeviceEvent },
+    ClipboardEvent { event: ClipboardEvent },
+    MonitorEvent { event: MonitorEvent },
+    DesktopSettingsChanged,
 }
 
 /// The Wayland event loop.
@@ -419,8 +427,15 @@ impl EventLoop {
                 Event::WindowEvent { window_id, event } => {
                     app.window_event(&self.active_event_loop, window_id, event)
                 },
-                Event::DeviceEvent { event } => {
-                    app.device_event(&self.active_event_loop, None, event)
+                Event::DeviceEvent { device_id, event } => {
+                    app.device_event(&self.active_event_loop, device_id, event)
+                },
+                Event::ClipboardEvent { event } => {
+                    app.clipboard_event(&self.active_event_loop, event)
+                },
+                Event::MonitorEvent { event } => app.monitor_event(&self.active_event_loop, event),
+                Event::DesktopSettingsChanged => {
+                    app.desktop_settings_changed(&self.active_event_loop)
                 },
             }
         }
@@ -434,12 +449,22 @@ impl EventLoop {
                 Event::WindowEvent { window_id, event } => {
                     app.window_event(&self.active_event_loop, window_id, event)
                 },
-                Event::DeviceEvent { event } => {
-                    app.device_event(&self.active_event_loop, None, event)
+                Event::DeviceEvent { device_id, event } => {
+                    app.device_event(&self.active_event_loop, device_id, event)
+                },
+                Event::ClipboardEvent { event } => {
+                    app.clipboard_event(&self.active_event_loop, event)
+                },
+                Event::MonitorEvent { event } => app.monitor_event(&self.active_event_loop, event),
+                Event::DesktopSettingsChanged => {
+                    app.desktop_settings_changed(&self.active_event_loop)
                 },
             }
         }
 
+        // Answer the drag and drop requests, which could've been made while handling the drop.
+        let mut wake_up = self.with_state(|state| state.process_drag_requests());
+
         // Collect the window ids
         self.with_state(|state| {
             window_ids.extend(state.window_requests.get_mut().keys());
@@ -486,7 +511,6 @@ impl EventLoop {
         app.about_to_wait(&self.active_event_loop);
 
         // Update the window frames and schedule redraws.
-        let mut wake_up = false;
         for window_id in window_ids.drain(..) {
             wake_up |= self.with_state(|state| match state.windows.get_mut().get_mut(&window_id) {
                 Some(window) => {
@@ -625,26 +649,84 @@ impl RootActiveEventLoop for ActiveEventLoop {
         self.exit.get().is_some()
     }
 
+    fn available_devices(&self) -> Box<dyn Iterator<Item = DeviceInfo>> {
+        let devices: Vec<_> = self.state.borrow().devices.values().cloned().collect();
+        Box::new(devices.into_iter())
+    }
+
     #[inline]
     fn listen_device_events(&self, _allowed: DeviceEvents) {}
 
+    fn set_cursor_theme(&self, name: Option<&str>, size: Option<u32>) -> Result<(), RequestError> {
+        let cursor_theme =
+            CursorTheme { name: name.map(Into::into), size: size.filter(|&size| size > 0) };
+        self.state.borrow_mut().set_cursor_theme(cursor_theme);
+        Ok(())
+    }
+
+    fn set_clipboard(&self, kind: ClipboardKind, content: MimeData) -> Result<(), RequestError> {
+        self.state.borrow_mut().set_clipboard(&self.queue_handle, kind, content)
+    }
+
+    fn request_clipboard_mime_types(
+        &self,
+        kind: ClipboardKind,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = self.state.borrow_mut().request_clipboard(kind, None);
+        self.event_loop_awakener.ping();
+        Ok(serial)
+    }
+
+    fn request_clipboard_data(
+        &self,
+        kind: ClipboardKind,
+        mime_type: &str,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = self.state.borrow_mut().request_clipboard(kind, Some(mime_type));
+        self.event_loop_awakener.ping();
+        Ok(serial)
+    }
+
     fn create_custom_cursor(
         &self,
         cursor: CustomCursorSource,
     ) -> Result<CoreCustomCursor, RequestError> {
-        let cursor_image = match cursor {
-            CustomCursorSource::Image(cursor_image) => cursor_image,
-            CustomCursorSource::Animation { .. } | CustomCursorSource::Url { .. } => {
+        let cursor = match cursor {
+            CustomCursorSource::Image(cursor_image) => WaylandCustomCursor::Image(cursor_image),
+            CustomCursorSource::Animation(animation) => {
+                let (duration, cursors) = animation.into_raw();
+                let frames = cursors
+                    .iter()
+                    .map(|cursor| match cursor.cast_ref::<WaylandCustomCursor>() {
+                        Some(WaylandCustomCursor::Image(image)) => Some(image.clone()),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| NotSupportedError::new("cursor frames from another backend"))?;
+
+                // The duration covers the whole animation, so split it between the frames.
+                let frame_delay = (duration / frames.len() as u32).max(Duration::from_millis(1));
+                WaylandCustomCursor::Animation(
+                    frames.into_iter().map(|image| (image, frame_delay)).collect(),
+                )
+            },
+            CustomCursorSource::File(file) => WaylandCustomCursor::File(file),
+            CustomCursorSource::Scaled(images) => WaylandCustomCursor::Scaled(images),
+            CustomCursorSource::Url { .. } => {
                 return Err(NotSupportedError::new("unsupported cursor kind").into());
             },
         };
 
-        Ok(CoreCustomCursor(Arc::new(WaylandCustomCursor(cursor_image))))
+        Ok(CoreCustomCursor(Arc::new(cursor)))
     }
 
     #[inline]
     fn system_theme(&self) -> Option<Theme> {
-        None
+        self.state.borrow().system_theme
+    }
+
+    fn desktop_settings(&self) -> DesktopSettings {
+        self.state.borrow().desktop_settings
     }
 
     fn create_window(
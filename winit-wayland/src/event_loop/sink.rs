@@ -2,7 +2,7 @@
 
 use std::vec::Drain;
 
-use winit_core::event::{DeviceEvent, WindowEvent};
+use winit_core::event::{ClipboardEvent, DeviceEvent, DeviceId, MonitorEvent, WindowEvent};
 use winit_core::window::WindowId;
 
 use super::Event;
@@ -27,8 +27,26 @@ impl EventSink {
 
     /// Add new device event to a queue.
     #[inline]
-    pub fn push_device_event(&mut self, event: DeviceEvent) {
-        self.window_events.push(Event::DeviceEvent { event });
+    pub fn push_device_event(&mut self, device_id: Option<DeviceId>, event: DeviceEvent) {
+        self.window_events.push(Event::DeviceEvent { device_id, event });
+    }
+
+    /// Add new clipboard event to a queue.
+    #[inline]
+    pub fn push_clipboard_event(&mut self, event: ClipboardEvent) {
+        self.window_events.push(Event::ClipboardEvent { event });
+    }
+
+    /// Add new monitor event to a queue.
+    #[inline]
+    pub fn push_monitor_event(&mut self, event: MonitorEvent) {
+        self.window_events.push(Event::MonitorEvent { event });
+    }
+
+    /// Add the change of the desktop settings to a queue.
+    #[inline]
+    pub fn push_desktop_settings_changed(&mut self) {
+        self.window_events.push(Event::DesktopSettingsChanged);
     }
 
     /// Add new window event to a queue.
@@ -0,0 +1,147 @@
+//! Layer-shell surfaces, used for panels, docks, overlays and wallpapers.
+
+use bitflags::bitflags;
+use dpi::LogicalInsets;
+use sctk::shell::wlr_layer;
+use winit_core::monitor::MonitorHandle;
+
+/// The layer of a layer-shell surface, from the bottom to the top.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layer {
+    /// Below the regular windows, like a wallpaper.
+    Background,
+
+    /// Below the regular windows, but above the background.
+    Bottom,
+
+    /// Above the regular windows, like a panel.
+    #[default]
+    Top,
+
+    /// Above everything, including fullscreen windows, like an on-screen display.
+    Overlay,
+}
+
+impl From<Layer> for wlr_layer::Layer {
+    fn from(layer: Layer) -> Self {
+        match layer {
+            Layer::Background => Self::Background,
+            Layer::Bottom => Self::Bottom,
+            Layer::Top => Self::Top,
+            Layer::Overlay => Self::Overlay,
+        }
+    }
+}
+
+bitflags! {
+    /// The edges of the output a layer-shell surface is anchored to.
+    ///
+    /// Two adjacent edges anchor the surface to a corner, while two opposite edges stretch it
+    /// along them when its size isn't set in that direction. Without any edge, the surface is
+    /// centered.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Anchor: u32 {
+        const TOP = 1;
+        const BOTTOM = 2;
+        const LEFT = 4;
+        const RIGHT = 8;
+    }
+}
+
+impl From<Anchor> for wlr_layer::Anchor {
+    fn from(anchor: Anchor) -> Self {
+        Self::from_bits_truncate(anchor.bits())
+    }
+}
+
+/// How a layer-shell surface gets the keyboard focus.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyboardInteractivity {
+    /// The surface never gets the keyboard focus.
+    #[default]
+    None,
+
+    /// The surface takes the keyboard focus while it's on the [`Layer::Top`] or
+    /// [`Layer::Overlay`], like a lock screen.
+    Exclusive,
+
+    /// The surface gets the keyboard focus like regular windows, e.g. when clicked.
+    ///
+    /// Requires version 4 of `zwlr_layer_shell_v1`.
+    OnDemand,
+}
+
+impl From<KeyboardInteractivity> for wlr_layer::KeyboardInteractivity {
+    fn from(interactivity: KeyboardInteractivity) -> Self {
+        match interactivity {
+            KeyboardInteractivity::None => Self::None,
+            KeyboardInteractivity::Exclusive => Self::Exclusive,
+            KeyboardInteractivity::OnDemand => Self::OnDemand,
+        }
+    }
+}
+
+/// The attributes of a window created as a layer-shell surface.
+///
+/// See [`WindowAttributesWayland::with_layer_surface`].
+///
+/// [`WindowAttributesWayland::with_layer_surface`]: crate::WindowAttributesWayland::with_layer_surface
+#[derive(Debug, Default, Clone)]
+pub struct LayerSurfaceAttributes {
+    pub(crate) layer: Layer,
+    pub(crate) anchor: Anchor,
+    pub(crate) exclusive_zone: i32,
+    pub(crate) margin: LogicalInsets<i32>,
+    pub(crate) keyboard_interactivity: KeyboardInteractivity,
+    pub(crate) output: Option<MonitorHandle>,
+}
+
+impl LayerSurfaceAttributes {
+    /// Create the attributes of a surface on the given layer.
+    #[inline]
+    pub fn new(layer: Layer) -> Self {
+        Self { layer, ..Default::default() }
+    }
+
+    /// Anchor the surface to the given edges of the output.
+    #[inline]
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Reserve space for the surface along the edge it's anchored to.
+    ///
+    /// A positive value is the logical size other surfaces should keep clear of, like for a
+    /// panel. Zero, the default, moves the surface out of the areas reserved by other surfaces,
+    /// while `-1` ignores them.
+    #[inline]
+    pub fn with_exclusive_zone(mut self, exclusive_zone: i32) -> Self {
+        self.exclusive_zone = exclusive_zone;
+        self
+    }
+
+    /// Keep the surface away from the edges it's anchored to by the given logical distance.
+    #[inline]
+    pub fn with_margin(mut self, margin: LogicalInsets<i32>) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// How the surface gets the keyboard focus.
+    #[inline]
+    pub fn with_keyboard_interactivity(
+        mut self,
+        keyboard_interactivity: KeyboardInteractivity,
+    ) -> Self {
+        self.keyboard_interactivity = keyboard_interactivity;
+        self
+    }
+
+    /// Show the surface on the given monitor, instead of the one picked by the compositor.
+    #[inline]
+    pub fn with_output(mut self, monitor: MonitorHandle) -> Self {
+        self.output = Some(monitor);
+        self
+    }
+}
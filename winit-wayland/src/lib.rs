@@ -7,13 +7,16 @@
 //!
 //! ## Client-side decorations
 //!
-//! Winit provides client-side decorations by default, but the behaviour can
-//! be controlled with the following feature flags:
+//! Winit provides client-side decorations in the style of GNOME's Adwaita by default, but the
+//! behaviour can be controlled with the following feature flags:
 //!
-//! * `wayland-csd-adwaita` (default).
-//! * `wayland-csd-adwaita-crossfont`.
-//! * `wayland-csd-adwaita-notitle`.
-//! * `wayland-csd-adwaita-notitlebar`.
+//! * `wayland-csd-adwaita` (default): draw the title of the window with `ab_glyph`.
+//! * `wayland-csd-adwaita-crossfont`: the same as `wayland-csd-adwaita`.
+//! * `wayland-csd-adwaita-notitle`: don't draw the title, like without any of these features.
+//! * `wayland-csd-adwaita-notitlebar`: don't draw the title bar.
+//!
+//! The builtin decorations are a [`Frame`], and applications can draw the decorations themselves
+//! with their own one instead, see [`WindowAttributesWayland::with_frame`].
 
 #![allow(clippy::mutable_key_type)]
 
@@ -27,7 +30,7 @@ use sctk::shm::slot::{Buffer, CreateBufferError, SlotPool};
 use wayland_client::protocol::wl_shm::Format;
 use winit_core::event_loop::ActiveEventLoop as CoreActiveEventLoop;
 use winit_core::window::{
-    ActivationToken, PlatformWindowAttributes, Window as CoreWindow, WindowId,
+    ActivationToken, DecorationMode, PlatformWindowAttributes, Window as CoreWindow, WindowId,
 };
 
 macro_rules! os_error {
@@ -35,6 +38,7 @@ macro_rules! os_error {
 }
 
 mod event_loop;
+mod layer_shell;
 mod output;
 mod seat;
 mod state;
@@ -42,7 +46,10 @@ mod types;
 mod window;
 
 pub use self::event_loop::{ActiveEventLoop, EventLoop};
-pub use self::window::Window;
+pub use self::layer_shell::{Anchor, KeyboardInteractivity, Layer, LayerSurfaceAttributes};
+pub use self::window::{
+    Frame, FrameCanvas, FrameEdge, FrameFactory, FrameHit, FrameSource, FrameState, Window,
+};
 
 /// Additional methods on [`ActiveEventLoop`] that are specific to Wayland.
 pub trait ActiveEventLoopExtWayland {
@@ -79,8 +86,18 @@ pub trait EventLoopBuilderExtWayland {
 ///
 /// [`Window`]: crate::window::Window
 pub trait WindowExtWayland {
-    /// Returns `xdg_toplevel` of the window or [`None`] if the window is X11 window.
+    /// Returns `xdg_toplevel` of the window or [`None`] if the window is X11 window or a
+    /// layer-shell surface.
     fn xdg_toplevel(&self) -> Option<NonNull<c_void>>;
+
+    /// Returns the decoration mode negotiated with the compositor or [`None`] if the window is
+    /// X11 window.
+    ///
+    /// Without `zxdg_decoration_manager_v1` the decorations are always drawn by the client.
+    /// Changes are reported with [`WindowEvent::DecorationModeChanged`].
+    ///
+    /// [`WindowEvent::DecorationModeChanged`]: winit_core::event::WindowEvent::DecorationModeChanged
+    fn decoration_mode(&self) -> Option<DecorationMode>;
 }
 
 impl WindowExtWayland for dyn CoreWindow + '_ {
@@ -88,6 +105,11 @@ impl WindowExtWayland for dyn CoreWindow + '_ {
     fn xdg_toplevel(&self) -> Option<NonNull<c_void>> {
         self.cast_ref::<Window>()?.xdg_toplevel()
     }
+
+    #[inline]
+    fn decoration_mode(&self) -> Option<DecorationMode> {
+        Some(self.cast_ref::<Window>()?.decoration_mode())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -102,6 +124,8 @@ pub struct WindowAttributesWayland {
     pub(crate) name: Option<ApplicationName>,
     pub(crate) activation_token: Option<ActivationToken>,
     pub(crate) prefer_csd: bool,
+    pub(crate) frame: FrameSource,
+    pub(crate) layer_surface: Option<LayerSurfaceAttributes>,
 }
 
 impl WindowAttributesWayland {
@@ -135,6 +159,44 @@ impl WindowAttributesWayland {
         self.prefer_csd = prefer_csd;
         self
     }
+
+    /// Builds the window with the given drawer of its client-side decorations.
+    ///
+    /// The decorations are drawn by the builtin frame of winit by default. They're only drawn when
+    /// the compositor asks for client-side decorations, see [`WindowExtWayland::decoration_mode`].
+    #[inline]
+    pub fn with_frame_source(mut self, frame: FrameSource) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    /// Builds the window with the given client-side decorations frame.
+    ///
+    /// The `frame` function creates the [`Frame`] drawn instead of the builtin one when the
+    /// compositor asks for client-side decorations. It may be called again when the decorations
+    /// are shown again.
+    ///
+    /// This is a shorthand for [`with_frame_source`] with [`FrameSource::Custom`].
+    ///
+    /// [`with_frame_source`]: Self::with_frame_source
+    #[inline]
+    pub fn with_frame(self, frame: impl Fn() -> Box<dyn Frame> + Send + Sync + 'static) -> Self {
+        self.with_frame_source(FrameSource::Custom(FrameFactory::new(frame)))
+    }
+
+    /// Builds the window as a layer-shell surface, like a panel or an on-screen display.
+    ///
+    /// Such windows are placed by the compositor on a layer of an output instead of being
+    /// managed like regular windows. They have no decorations and can't be moved, resized,
+    /// maximized or made fullscreen by the user. Their size is the surface size of the window,
+    /// except in the directions where they're stretched between two opposite edges.
+    ///
+    /// The creation of the window fails when `zwlr_layer_shell_v1` isn't available.
+    #[inline]
+    pub fn with_layer_surface(mut self, layer_surface: LayerSurfaceAttributes) -> Self {
+        self.layer_surface = Some(layer_surface);
+        self
+    }
 }
 
 impl PlatformWindowAttributes for WindowAttributesWayland {
@@ -179,3 +241,21 @@ fn image_to_buffer(
 
     Ok(buffer)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sets_the_frame_source() {
+        let attributes = WindowAttributesWayland::default();
+        assert!(matches!(attributes.frame, FrameSource::Builtin));
+
+        let attributes = attributes.with_frame(|| unreachable!());
+        assert!(matches!(attributes.frame, FrameSource::Custom(_)));
+        let attributes = attributes.with_frame_source(FrameSource::Application);
+        assert!(matches!(attributes.frame, FrameSource::Application));
+        let attributes = attributes.with_frame_source(FrameSource::Builtin);
+        assert!(matches!(attributes.frame, FrameSource::Builtin));
+    }
+}
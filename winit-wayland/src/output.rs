@@ -4,8 +4,10 @@ use std::num::NonZeroU32;
 use dpi::{LogicalPosition, PhysicalPosition};
 use sctk::output::{Mode, OutputData};
 use sctk::reexports::client::Proxy;
-use sctk::reexports::client::protocol::wl_output::WlOutput;
-use winit_core::monitor::{MonitorHandleProvider as CoreMonitorHandle, VideoMode};
+use sctk::reexports::client::protocol::wl_output::{Subpixel, Transform, WlOutput};
+use winit_core::monitor::{
+    MonitorHandleProvider as CoreMonitorHandle, MonitorTransform, SubpixelLayout, VideoMode,
+};
 
 #[derive(Clone, Debug)]
 pub struct MonitorHandle {
@@ -70,6 +72,62 @@ impl CoreMonitorHandle for MonitorHandle {
 
         Box::new(modes.into_iter().map(wayland_mode_to_core_mode))
     }
+
+    fn size_millimeters(&self) -> Option<(u32, u32)> {
+        let output_data = self.proxy.data::<OutputData>().unwrap();
+        let (width, height) = output_data.with_output_info(|info| info.physical_size);
+        match (u32::try_from(width), u32::try_from(height)) {
+            (Ok(width), Ok(height)) if width != 0 && height != 0 => Some((width, height)),
+            _ => None,
+        }
+    }
+
+    fn subpixel_layout(&self) -> Option<SubpixelLayout> {
+        let output_data = self.proxy.data::<OutputData>().unwrap();
+        match output_data.with_output_info(|info| info.subpixel) {
+            Subpixel::None => Some(SubpixelLayout::None),
+            Subpixel::HorizontalRgb => Some(SubpixelLayout::HorizontalRgb),
+            Subpixel::HorizontalBgr => Some(SubpixelLayout::HorizontalBgr),
+            Subpixel::VerticalRgb => Some(SubpixelLayout::VerticalRgb),
+            Subpixel::VerticalBgr => Some(SubpixelLayout::VerticalBgr),
+            _ => None,
+        }
+    }
+
+    fn transform(&self) -> Option<MonitorTransform> {
+        let output_data = self.proxy.data::<OutputData>().unwrap();
+        match output_data.with_output_info(|info| info.transform) {
+            Transform::Normal => Some(MonitorTransform::Normal),
+            Transform::_90 => Some(MonitorTransform::Rotate90),
+            Transform::_180 => Some(MonitorTransform::Rotate180),
+            Transform::_270 => Some(MonitorTransform::Rotate270),
+            Transform::Flipped => Some(MonitorTransform::Flipped),
+            Transform::Flipped90 => Some(MonitorTransform::Flipped90),
+            Transform::Flipped180 => Some(MonitorTransform::Flipped180),
+            Transform::Flipped270 => Some(MonitorTransform::Flipped270),
+            _ => None,
+        }
+    }
+
+    fn make(&self) -> Option<Cow<'_, str>> {
+        let output_data = self.proxy.data::<OutputData>().unwrap();
+        output_data.with_output_info(|info| non_empty(&info.make))
+    }
+
+    fn model(&self) -> Option<Cow<'_, str>> {
+        let output_data = self.proxy.data::<OutputData>().unwrap();
+        output_data.with_output_info(|info| non_empty(&info.model))
+    }
+
+    fn description(&self) -> Option<Cow<'_, str>> {
+        let output_data = self.proxy.data::<OutputData>().unwrap();
+        output_data.with_output_info(|info| info.description.clone().map(Cow::Owned))
+    }
+}
+
+/// Compositors send an empty string when they don't know the make or model.
+fn non_empty(value: &str) -> Option<Cow<'static, str>> {
+    (!value.is_empty()).then(|| Cow::Owned(value.to_owned()))
 }
 
 impl PartialEq for MonitorHandle {
@@ -0,0 +1,363 @@
+//! Clipboard and primary selection handling.
+
+use std::cell::Cell;
+use std::io::{self, Read, Write};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use sctk::data_device_manager::{DataDeviceManagerState, ReadPipe, WritePipe};
+use sctk::data_device_manager::data_source::{CopyPasteSource, DataSourceData, DataSourceHandler};
+use sctk::primary_selection::device::PrimarySelectionDeviceHandler;
+use sctk::primary_selection::selection::{PrimarySelectionSource, PrimarySelectionSourceHandler};
+use sctk::reexports::calloop::PostAction;
+use sctk::reexports::calloop::timer::{TimeoutAction, Timer};
+use sctk::reexports::client::protocol::wl_data_device_manager::DndAction;
+use sctk::reexports::client::protocol::wl_data_source::WlDataSource;
+use sctk::reexports::client::{Connection, QueueHandle, delegate_dispatch};
+use sctk::reexports::protocols::wp::primary_selection::zv1::client::zwp_primary_selection_device_v1::ZwpPrimarySelectionDeviceV1;
+use sctk::reexports::protocols::wp::primary_selection::zv1::client::zwp_primary_selection_source_v1::ZwpPrimarySelectionSourceV1;
+use tracing::warn;
+use winit_core::clipboard::ClipboardKind;
+use winit_core::error::{NotSupportedError, RequestError};
+use winit_core::event::{ClipboardEvent, MimeData};
+use winit_core::event_loop::AsyncRequestSerial;
+
+use crate::seat::{WinitPointerDataExt, WinitSeatState};
+use crate::state::WinitState;
+
+/// How long the owner of a selection has to send the next part of its content.
+const SELECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The content of the selections we own.
+#[derive(Debug, Default)]
+pub struct ClipboardState {
+    clipboard: Option<(CopyPasteSource, MimeData)>,
+    primary: Option<(PrimarySelectionSource, MimeData)>,
+}
+
+impl WinitState {
+    /// The seat with the latest input along with the serial of that input.
+    fn clipboard_seat(&self) -> Option<(&WinitSeatState, u32)> {
+        self.seats
+            .values()
+            .map(|seat_state| {
+                let keyboard_serial =
+                    seat_state.keyboard_state.as_ref().map(|keyboard| keyboard.latest_serial);
+                let pointer_serial = seat_state
+                    .pointer
+                    .as_ref()
+                    .map(|pointer| pointer.pointer().winit_data().latest_button_serial());
+                (seat_state, keyboard_serial.max(pointer_serial).unwrap_or_default())
+            })
+            .max_by_key(|&(_, serial)| serial)
+    }
+
+    /// Place the content on the selection of the seat with the latest input.
+    pub fn set_clipboard(
+        &mut self,
+        queue_handle: &QueueHandle<Self>,
+        kind: ClipboardKind,
+        content: MimeData,
+    ) -> Result<(), RequestError> {
+        let (seat_state, serial) = self.clipboard_seat().ok_or(RequestError::Ignored)?;
+        match kind {
+            ClipboardKind::Clipboard => {
+                let (manager, device) =
+                    match (self.data_device_manager.as_ref(), seat_state.data_device.as_ref()) {
+                        (Some(manager), Some(device)) => (manager, device),
+                        _ => {
+                            return Err(NotSupportedError::new(
+                                "wl_data_device_manager is not available",
+                            )
+                            .into());
+                        },
+                    };
+                let source = manager.create_copy_paste_source(queue_handle, content.mime_types());
+                source.set_selection(device, serial);
+                self.clipboard.clipboard = Some((source, content));
+            },
+            ClipboardKind::Primary => {
+                let (manager, device) = match (
+                    self.primary_selection_manager.as_ref(),
+                    seat_state.primary_selection_device.as_ref(),
+                ) {
+                    (Some(manager), Some(device)) => (manager, device),
+                    _ => {
+                        return Err(NotSupportedError::new(
+                            "zwp_primary_selection_device_manager_v1 is not available",
+                        )
+                        .into());
+                    },
+                };
+                let source = manager.create_selection_source(queue_handle, content.mime_types());
+                source.set_selection(device, serial);
+                self.clipboard.primary = Some((source, content));
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Request the content of the selection, or its MIME types when `mime_type` is `None`.
+    pub fn request_clipboard(
+        &mut self,
+        kind: ClipboardKind,
+        mime_type: Option<&str>,
+    ) -> AsyncRequestSerial {
+        let serial = AsyncRequestSerial::get();
+        let seat_state = self.clipboard_seat().map(|(seat_state, _)| seat_state);
+
+        let mime_types = seat_state.and_then(|seat_state| selection_mime_types(seat_state, kind));
+        let Some(mime_type) = mime_type else {
+            let mime_types = mime_types.unwrap_or_default();
+            let event = ClipboardEvent::MimeTypes { serial, kind, mime_types };
+            self.events_sink.push_clipboard_event(event);
+            return serial;
+        };
+
+        let offered =
+            mime_types.is_some_and(|mime_types| mime_types.iter().any(|m| m == mime_type));
+        let pipe = match seat_state {
+            Some(seat_state) if offered => receive_selection(seat_state, kind, mime_type),
+            _ => Ok(None),
+        };
+
+        let mime_type = mime_type.to_owned();
+        let pipe = match pipe {
+            Ok(Some(pipe)) => pipe,
+            Ok(None) => {
+                let event = ClipboardEvent::Data { serial, kind, mime_type, data: None };
+                self.events_sink.push_clipboard_event(event);
+                return serial;
+            },
+            Err(err) => {
+                warn!("Failed to receive the clipboard content: {err}");
+                let event = ClipboardEvent::Data { serial, kind, mime_type, data: None };
+                self.events_sink.push_clipboard_event(event);
+                return serial;
+            },
+        };
+
+        // The time of the last read, `None` once the content is read.
+        let last_read = Rc::new(Cell::new(Some(Instant::now())));
+        let pipe_last_read = last_read.clone();
+
+        let mut contents = Vec::new();
+        let event_mime_type = mime_type.clone();
+        let result = self.loop_handle.insert_source(pipe, move |_, file, state| {
+            let mut buffer = [0; 4096];
+            // SAFETY: the file is only read from, never replaced.
+            let data = match unsafe { file.get_mut() }.read(&mut buffer) {
+                Ok(0) => Some(std::mem::take(&mut contents)),
+                Ok(len) => {
+                    contents.extend_from_slice(&buffer[..len]);
+                    pipe_last_read.set(Some(Instant::now()));
+                    return PostAction::Continue;
+                },
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                    return PostAction::Continue;
+                },
+                Err(err) => {
+                    warn!("Failed to read the clipboard content: {err}");
+                    None
+                },
+            };
+
+            pipe_last_read.set(None);
+            let mime_type = event_mime_type.clone();
+            let event = ClipboardEvent::Data { serial, kind, mime_type, data };
+            state.events_sink.push_clipboard_event(event);
+            PostAction::Remove
+        });
+
+        let token = match result {
+            Ok(token) => token,
+            Err(err) => {
+                warn!("Failed to read the clipboard content: {err}");
+                let event = ClipboardEvent::Data { serial, kind, mime_type, data: None };
+                self.events_sink.push_clipboard_event(event);
+                return serial;
+            },
+        };
+
+        // Stop reading when the owner of the selection stops sending its content.
+        let timer = Timer::from_duration(SELECTION_TIMEOUT);
+        let result = self.loop_handle.insert_source(timer, move |_, _, state| {
+            let deadline = match last_read.get() {
+                Some(last_read) => last_read + SELECTION_TIMEOUT,
+                None => return TimeoutAction::Drop,
+            };
+            if deadline > Instant::now() {
+                return TimeoutAction::ToInstant(deadline);
+            }
+
+            warn!("The owner of the selection didn't send its content in time");
+            state.loop_handle.remove(token);
+            let mime_type = mime_type.clone();
+            let event = ClipboardEvent::Data { serial, kind, mime_type, data: None };
+            state.events_sink.push_clipboard_event(event);
+            TimeoutAction::Drop
+        });
+        if let Err(err) = result {
+            warn!("Failed to time the clipboard request: {err}");
+        }
+
+        serial
+    }
+
+    /// Write the data to the pipe of the client requesting the selection.
+    fn send_selection(&self, pipe: WritePipe, data: Option<&[u8]>) {
+        // Closing the pipe right away tells the reader there's no data.
+        let Some(data) = data else { return };
+
+        let data = data.to_vec();
+        let mut offset = 0;
+        let result = self.loop_handle.insert_source(pipe, move |_, file, _| {
+            // Only write what fits in the pipe to not block, the source is polled again until
+            // everything is written.
+            let end = data.len().min(offset + 4096);
+            // SAFETY: the file is only written to, never replaced.
+            match unsafe { file.get_mut() }.write(&data[offset..end]) {
+                Ok(len) => {
+                    offset += len;
+                    if offset < data.len() {
+                        return PostAction::Continue;
+                    }
+                },
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                    return PostAction::Continue;
+                },
+                Err(err) => warn!("Failed to write the clipboard content: {err}"),
+            }
+
+            PostAction::Remove
+        });
+
+        if let Err(err) = result {
+            warn!("Failed to write the clipboard content: {err}");
+        }
+    }
+}
+
+fn selection_mime_types(seat_state: &WinitSeatState, kind: ClipboardKind) -> Option<Vec<String>> {
+    match kind {
+        ClipboardKind::Clipboard => {
+            let offer = seat_state.data_device.as_ref()?.data().selection_offer()?;
+            Some(offer.with_mime_types(|mime_types| mime_types.to_vec()))
+        },
+        ClipboardKind::Primary => {
+            let offer = seat_state.primary_selection_device.as_ref()?.data().selection_offer()?;
+            Some(offer.with_mime_types(|mime_types| mime_types.to_vec()))
+        },
+    }
+}
+
+fn receive_selection(
+    seat_state: &WinitSeatState,
+    kind: ClipboardKind,
+    mime_type: &str,
+) -> io::Result<Option<ReadPipe>> {
+    match kind {
+        ClipboardKind::Clipboard => {
+            let offer = match seat_state
+                .data_device
+                .as_ref()
+                .and_then(|device| device.data().selection_offer())
+            {
+                Some(offer) => offer,
+                None => return Ok(None),
+            };
+            offer.receive(mime_type.to_owned()).map(Some).map_err(io::Error::other)
+        },
+        ClipboardKind::Primary => {
+            let offer = match seat_state
+                .primary_selection_device
+                .as_ref()
+                .and_then(|device| device.data().selection_offer())
+            {
+                Some(offer) => offer,
+                None => return Ok(None),
+            };
+            offer.receive(mime_type.to_owned()).map(Some)
+        },
+    }
+}
+
+impl DataSourceHandler for WinitState {
+    fn accept_mime(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlDataSource,
+        _: Option<String>,
+    ) {
+    }
+
+    fn send_request(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &WlDataSource,
+        mime: String,
+        fd: WritePipe,
+    ) {
+        let data = match self.clipboard.clipboard.as_ref() {
+            Some((ours, content)) if ours.inner() == source => content.data(&mime),
+            _ => None,
+        };
+        self.send_selection(fd, data);
+    }
+
+    fn cancelled(&mut self, _: &Connection, _: &QueueHandle<Self>, source: &WlDataSource) {
+        if self.clipboard.clipboard.as_ref().is_some_and(|(ours, _)| ours.inner() == source) {
+            self.clipboard.clipboard = None;
+        }
+    }
+
+    fn dnd_dropped(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataSource) {}
+
+    fn dnd_finished(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataSource) {}
+
+    fn action(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataSource, _: DndAction) {}
+}
+
+impl PrimarySelectionSourceHandler for WinitState {
+    fn send_request(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &ZwpPrimarySelectionSourceV1,
+        mime: String,
+        write_pipe: WritePipe,
+    ) {
+        let data = match self.clipboard.primary.as_ref() {
+            Some((ours, content)) if ours.inner() == source => content.data(&mime),
+            _ => None,
+        };
+        self.send_selection(write_pipe, data);
+    }
+
+    fn cancelled(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &ZwpPrimarySelectionSourceV1,
+    ) {
+        if self.clipboard.primary.as_ref().is_some_and(|(ours, _)| ours.inner() == source) {
+            self.clipboard.primary = None;
+        }
+    }
+}
+
+impl PrimarySelectionDeviceHandler for WinitState {
+    fn selection(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &ZwpPrimarySelectionDeviceV1,
+    ) {
+    }
+}
+
+delegate_dispatch!(WinitState: [WlDataSource: DataSourceData] => DataDeviceManagerState);
+sctk::delegate_primary_selection!(WinitState);
@@ -0,0 +1,623 @@
+//! Drag and drop handling through the `wl_data_device`.
+
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str::Utf8Error;
+use std::{fmt, mem};
+
+use dpi::{LogicalPosition, PhysicalPosition};
+use percent_encoding::percent_decode_str;
+use sctk::data_device_manager::DataDeviceManagerState;
+use sctk::data_device_manager::data_device::{DataDeviceData, DataDeviceHandler};
+use sctk::data_device_manager::data_offer::{DataOfferData, DataOfferHandler, DragOffer};
+use sctk::globals::GlobalData;
+use sctk::reexports::calloop::{PostAction, RegistrationToken};
+use sctk::reexports::client::backend::ObjectId;
+use sctk::reexports::client::protocol::wl_data_device::WlDataDevice;
+use sctk::reexports::client::protocol::wl_data_device_manager::{DndAction, WlDataDeviceManager};
+use sctk::reexports::client::protocol::wl_data_offer::WlDataOffer;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Connection, Proxy, QueueHandle, delegate_dispatch};
+use tracing::warn;
+use winit_core::event::{DragAction, DragPayload, WindowEvent};
+use winit_core::event_loop::AsyncRequestSerial;
+
+use crate::WindowId;
+use crate::state::WinitState;
+
+/// The MIME type used to transfer the list of files.
+const URI_LIST_MIME_TYPE: &str = "text/uri-list";
+
+/// The state of the drag and drop operation over one of our windows.
+#[derive(Debug)]
+pub struct DragState {
+    /// The offer we've got on enter.
+    offer: DragOffer,
+
+    /// The window the drag is over.
+    window_id: WindowId,
+
+    /// The last position of the drag.
+    position: PhysicalPosition<f64>,
+
+    /// The MIME type we've accepted.
+    mime_type: String,
+
+    /// The token of the source reading the list of files.
+    read_token: Option<RegistrationToken>,
+
+    /// The MIME types and the action offered by the source.
+    payload: DragPayload,
+
+    /// The paths being dragged, present once the drag was reported to the application.
+    paths: Option<Vec<PathBuf>>,
+
+    /// Whether the drop was performed.
+    dropped: bool,
+
+    /// Whether the drop was reported, the offer is finished once the data requested while
+    /// handling it is read.
+    drop_reported: bool,
+
+    /// The data requests being read.
+    requests: Vec<DragDataRequest>,
+}
+
+/// The data of the drag offer requested with `Window::request_drag_data`.
+#[derive(Debug)]
+struct DragDataRequest {
+    serial: AsyncRequestSerial,
+    mime_type: String,
+    token: RegistrationToken,
+}
+
+impl DragState {
+    /// Set the action performed once dropped, `None` to reject the drop.
+    fn set_action(&self, action: Option<DragAction>) {
+        // Actions can't be changed once dropped.
+        if self.dropped {
+            return;
+        }
+
+        match action {
+            Some(action) => {
+                // Wayland has no notion of links.
+                let action = match action {
+                    DragAction::Move => DndAction::Move,
+                    DragAction::Copy | DragAction::Link => DndAction::Copy,
+                };
+                self.offer.accept_mime_type(self.offer.serial, Some(self.mime_type.clone()));
+                self.offer.set_actions(action, action);
+            },
+            None => {
+                self.offer.accept_mime_type(self.offer.serial, None);
+                self.offer.set_actions(DndAction::empty(), DndAction::empty());
+            },
+        }
+    }
+}
+
+impl WinitState {
+    fn drag_state(&mut self, data_device: &WlDataDevice) -> Option<&mut DragState> {
+        let seat = data_device.data::<DataDeviceData>()?.seat();
+        self.seats.get_mut(&seat.id())?.drag.as_mut()
+    }
+
+    /// Cancel the drag and drop operation on the seat.
+    pub(super) fn cancel_drag(&mut self, seat: &ObjectId) {
+        let drag = match self.seats.get_mut(seat).and_then(|seat_state| seat_state.drag.take()) {
+            Some(drag) => drag,
+            None => return,
+        };
+
+        if let Some(token) = drag.read_token {
+            self.loop_handle.remove(token);
+        }
+
+        for request in drag.requests {
+            self.loop_handle.remove(request.token);
+            self.events_sink.push_window_event(
+                WindowEvent::DragData {
+                    serial: request.serial,
+                    mime_type: request.mime_type,
+                    data: None,
+                },
+                drag.window_id,
+            );
+        }
+
+        if drag.dropped {
+            drag.offer.destroy();
+        }
+    }
+
+    /// Read the drag offer in the given MIME type.
+    fn receive_drag_offer(
+        &self,
+        offer: &DragOffer,
+        mime_type: &str,
+        callback: impl FnOnce(&mut WinitState, io::Result<Vec<u8>>) + 'static,
+    ) -> io::Result<RegistrationToken> {
+        // Read the offer without blocking, since the source could be our own client.
+        let pipe = offer.receive(mime_type.to_owned())?;
+        let mut callback = Some(callback);
+        let mut contents = Vec::new();
+        self.loop_handle
+            .insert_source(pipe, move |_, file, state| {
+                let mut buffer = [0; 4096];
+                // SAFETY: the file is only read from, never replaced.
+                let data = match unsafe { file.get_mut() }.read(&mut buffer) {
+                    Ok(0) => Ok(std::mem::take(&mut contents)),
+                    Ok(len) => {
+                        contents.extend_from_slice(&buffer[..len]);
+                        return PostAction::Continue;
+                    },
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                        return PostAction::Continue;
+                    },
+                    Err(err) => Err(err),
+                };
+
+                if let Some(callback) = callback.take() {
+                    callback(state, data);
+                }
+                PostAction::Remove
+            })
+            .map_err(|err| err.error.into())
+    }
+
+    /// Handle the list of files of the drag offer once it's read.
+    fn drag_offer_read(&mut self, seat: &ObjectId, offer: &WlDataOffer, data: io::Result<Vec<u8>>) {
+        let drag = match self.seats.get_mut(seat).and_then(|seat_state| seat_state.drag.as_mut()) {
+            Some(drag) if drag.offer.inner() == offer => drag,
+            _ => return,
+        };
+
+        drag.read_token = None;
+
+        // The drag is still reported without the files, their data could be requested in the
+        // other offered MIME types.
+        let paths = data
+            .map_err(UriListParseError::Io)
+            .and_then(|data| parse_uri_list(&data))
+            .inspect_err(|err| warn!("Failed to read the dragged files: {err}"))
+            .unwrap_or_default();
+
+        self.dispatched_events = true;
+        self.report_drag(seat, paths);
+    }
+
+    /// Report the drag to the application once its paths are known.
+    fn report_drag(&mut self, seat: &ObjectId, paths: Vec<PathBuf>) {
+        let drag = match self.seats.get_mut(seat).and_then(|seat_state| seat_state.drag.as_mut()) {
+            Some(drag) => drag,
+            None => return,
+        };
+
+        self.events_sink.push_window_event(
+            WindowEvent::DragEntered {
+                paths: paths.clone(),
+                payload: drag.payload.clone(),
+                position: drag.position,
+            },
+            drag.window_id,
+        );
+        drag.paths = Some(paths);
+
+        if drag.dropped {
+            self.report_drop(seat);
+        }
+    }
+
+    /// Deliver the drop to the application, the offer is finished by
+    /// [`WinitState::process_drag_requests`].
+    fn report_drop(&mut self, seat: &ObjectId) {
+        let drag = match self.seats.get_mut(seat).and_then(|seat_state| seat_state.drag.as_mut()) {
+            Some(drag) => drag,
+            None => return,
+        };
+
+        drag.drop_reported = true;
+        self.events_sink.push_window_event(
+            WindowEvent::DragDropped {
+                paths: drag.paths.clone().unwrap_or_default(),
+                payload: drag.payload.clone(),
+                position: drag.position,
+            },
+            drag.window_id,
+        );
+    }
+
+    /// Deliver the data requested with `Window::request_drag_data` once it's read.
+    fn drag_data_read(
+        &mut self,
+        seat: &ObjectId,
+        serial: AsyncRequestSerial,
+        data: io::Result<Vec<u8>>,
+    ) {
+        let drag = match self.seats.get_mut(seat).and_then(|seat_state| seat_state.drag.as_mut()) {
+            Some(drag) => drag,
+            None => return,
+        };
+
+        let index = match drag.requests.iter().position(|request| request.serial == serial) {
+            Some(index) => index,
+            None => return,
+        };
+
+        let request = drag.requests.remove(index);
+        let data = data.inspect_err(|err| warn!("Failed to read the dragged data: {err}")).ok();
+        self.dispatched_events = true;
+        self.events_sink.push_window_event(
+            WindowEvent::DragData { serial, mime_type: request.mime_type, data },
+            drag.window_id,
+        );
+    }
+
+    /// The seat dragging over the window.
+    fn drag_seat(&self, window_id: WindowId) -> Option<ObjectId> {
+        self.seats
+            .iter()
+            .find(|(_, seat_state)| {
+                seat_state.drag.as_ref().is_some_and(|drag| drag.window_id == window_id)
+            })
+            .map(|(seat, _)| seat.clone())
+    }
+
+    /// Start reading the drag offer of the seat in the given MIME type, returns whether the data
+    /// is available.
+    fn request_drag_data(
+        &mut self,
+        seat: &ObjectId,
+        serial: AsyncRequestSerial,
+        mime_type: &str,
+    ) -> bool {
+        // The data is only available once the drag was reported.
+        let drag = match self.seats[seat].drag.as_ref() {
+            Some(drag) if drag.paths.is_some() && drag.payload.has_mime_type(mime_type) => drag,
+            _ => return false,
+        };
+
+        let seat_id = seat.clone();
+        let token = match self.receive_drag_offer(&drag.offer, mime_type, move |state, data| {
+            state.drag_data_read(&seat_id, serial, data)
+        }) {
+            Ok(token) => token,
+            Err(err) => {
+                warn!("Failed to receive the dragged data: {err}");
+                return false;
+            },
+        };
+
+        let drag = self.seats.get_mut(seat).unwrap().drag.as_mut().unwrap();
+        drag.requests.push(DragDataRequest { serial, mime_type: mime_type.to_owned(), token });
+        true
+    }
+
+    /// Apply the drag and drop requests of the windows, and finish the drops once the data
+    /// requested while handling them is read.
+    ///
+    /// Returns whether events were pushed, since they're only delivered on the next dispatch.
+    pub(crate) fn process_drag_requests(&mut self) -> bool {
+        let requests: Vec<_> = self
+            .window_requests
+            .get_mut()
+            .iter()
+            .map(|(window_id, requests)| {
+                let data = mem::take(&mut *requests.drag_data.lock().unwrap());
+                let action = requests.drag_action.lock().unwrap().take();
+                (*window_id, data, action)
+            })
+            .collect();
+
+        let mut pushed = false;
+        for (window_id, data, action) in requests {
+            let seat = self.drag_seat(window_id);
+            if let Some((seat, action)) = seat.as_ref().zip(action) {
+                self.seats[seat].drag.as_ref().unwrap().set_action(action);
+            }
+
+            for (serial, mime_type) in data {
+                if !seat
+                    .as_ref()
+                    .is_some_and(|seat| self.request_drag_data(seat, serial, &mime_type))
+                {
+                    pushed = true;
+                    self.events_sink.push_window_event(
+                        WindowEvent::DragData { serial, mime_type, data: None },
+                        window_id,
+                    );
+                }
+            }
+        }
+
+        for seat_state in self.seats.values_mut() {
+            if seat_state
+                .drag
+                .as_ref()
+                .is_some_and(|drag| drag.drop_reported && drag.requests.is_empty())
+            {
+                let drag = seat_state.drag.take().unwrap();
+                drag.offer.finish();
+                drag.offer.destroy();
+            }
+        }
+
+        pushed
+    }
+}
+
+impl DataDeviceHandler for WinitState {
+    fn enter(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        data_device: &WlDataDevice,
+        x: f64,
+        y: f64,
+        surface: &WlSurface,
+    ) {
+        let data = match data_device.data::<DataDeviceData>() {
+            Some(data) => data,
+            None => return,
+        };
+
+        let seat = data.seat().id();
+        self.cancel_drag(&seat);
+
+        let offer = match data.drag_offer() {
+            Some(offer) => offer,
+            None => return,
+        };
+
+        // Drags over the decorations are not reported.
+        let window_id = crate::make_wid(surface);
+        let scale_factor = match self.windows.get_mut().get(&window_id) {
+            Some(window) => window.lock().unwrap().scale_factor(),
+            None => {
+                offer.accept_mime_type(offer.serial, None);
+                return;
+            },
+        };
+
+        let mime_types = offer.with_mime_types(|mime_types| mime_types.to_vec());
+        let has_uri_list = mime_types.iter().any(|mime_type| mime_type == URI_LIST_MIME_TYPE);
+        let mime_type = match mime_types.first() {
+            Some(_) if has_uri_list => URI_LIST_MIME_TYPE.to_owned(),
+            Some(mime_type) => mime_type.clone(),
+            None => {
+                offer.accept_mime_type(offer.serial, None);
+                return;
+            },
+        };
+
+        offer.accept_mime_type(offer.serial, Some(mime_type.clone()));
+        // Wayland has no notion of links, and the `ask` action is treated as a copy.
+        let (action, preferred_action) = if !offer.source_actions.contains(DndAction::Copy)
+            && offer.source_actions.contains(DndAction::Move)
+        {
+            (DragAction::Move, DndAction::Move)
+        } else {
+            (DragAction::Copy, DndAction::Copy)
+        };
+        offer.set_actions(DndAction::Copy | DndAction::Move, preferred_action);
+        let payload = DragPayload::new(mime_types, action);
+
+        // The action set for a previous drag doesn't apply.
+        if let Some(requests) = self.window_requests.get_mut().get(&window_id) {
+            requests.drag_action.lock().unwrap().take();
+        }
+
+        let read_token = if has_uri_list {
+            let wl_offer = offer.inner().clone();
+            let seat_id = seat.clone();
+            match self.receive_drag_offer(&offer, URI_LIST_MIME_TYPE, move |state, data| {
+                state.drag_offer_read(&seat_id, &wl_offer, data)
+            }) {
+                Ok(read_token) => Some(read_token),
+                Err(err) => {
+                    warn!("Failed to receive the dragged files: {err}");
+                    None
+                },
+            }
+        } else {
+            None
+        };
+
+        let position = LogicalPosition::new(x, y).to_physical(scale_factor);
+        if let Some(seat_state) = self.seats.get_mut(&seat) {
+            seat_state.drag = Some(DragState {
+                offer,
+                window_id,
+                position,
+                mime_type,
+                read_token,
+                payload,
+                paths: None,
+                dropped: false,
+                drop_reported: false,
+                requests: Vec::new(),
+            });
+        }
+
+        // Otherwise the drag is reported once the files are read.
+        if read_token.is_none() {
+            self.report_drag(&seat, Vec::new());
+        }
+    }
+
+    fn leave(&mut self, _: &Connection, _: &QueueHandle<Self>, data_device: &WlDataDevice) {
+        let (window_id, position, entered) = match self.drag_state(data_device) {
+            // The leave is also sent after the drop, in which case the offer is kept alive until
+            // the drop is finished.
+            Some(drag) if !drag.dropped => (drag.window_id, drag.position, drag.paths.is_some()),
+            _ => return,
+        };
+
+        if entered {
+            self.events_sink
+                .push_window_event(WindowEvent::DragLeft { position: Some(position) }, window_id);
+        }
+
+        let seat = data_device.data::<DataDeviceData>().unwrap().seat().id();
+        self.cancel_drag(&seat);
+    }
+
+    fn motion(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        data_device: &WlDataDevice,
+        x: f64,
+        y: f64,
+    ) {
+        let windows = self.windows.get_mut();
+        let drag = match data_device
+            .data::<DataDeviceData>()
+            .and_then(|data| self.seats.get_mut(&data.seat().id()))
+            .and_then(|seat_state| seat_state.drag.as_mut())
+        {
+            Some(drag) => drag,
+            None => return,
+        };
+
+        let scale_factor = match windows.get(&drag.window_id) {
+            Some(window) => window.lock().unwrap().scale_factor(),
+            None => return,
+        };
+
+        drag.position = LogicalPosition::new(x, y).to_physical(scale_factor);
+        if drag.paths.is_some() {
+            self.events_sink.push_window_event(
+                WindowEvent::DragMoved { position: drag.position },
+                drag.window_id,
+            );
+        }
+    }
+
+    fn selection(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataDevice) {}
+
+    fn drop_performed(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        data_device: &WlDataDevice,
+    ) {
+        let drag = match self.drag_state(data_device) {
+            Some(drag) => drag,
+            None => return,
+        };
+
+        drag.dropped = true;
+
+        // Otherwise the drop is reported once the files are read.
+        if drag.paths.is_some() {
+            let seat = data_device.data::<DataDeviceData>().unwrap().seat().id();
+            self.report_drop(&seat);
+        }
+    }
+}
+
+impl DataOfferHandler for WinitState {
+    fn source_actions(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &mut DragOffer,
+        _: DndAction,
+    ) {
+    }
+
+    fn selected_action(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &mut DragOffer,
+        _: DndAction,
+    ) {
+    }
+}
+
+#[derive(Debug)]
+enum UriListParseError {
+    Io(io::Error),
+    InvalidUtf8(Utf8Error),
+    UnexpectedUri(String),
+}
+
+impl fmt::Display for UriListParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UriListParseError::Io(err) => write!(f, "{err}"),
+            UriListParseError::InvalidUtf8(err) => write!(f, "invalid UTF-8: {err}"),
+            UriListParseError::UnexpectedUri(uri) => write!(f, "not a local file: {uri}"),
+        }
+    }
+}
+
+/// Parse the `text/uri-list` into the list of local paths.
+fn parse_uri_list(data: &[u8]) -> Result<Vec<PathBuf>, UriListParseError> {
+    let data = std::str::from_utf8(data).map_err(UriListParseError::InvalidUtf8)?;
+    let mut paths = Vec::new();
+    // Lines starting with `#` are comments.
+    for uri in data.lines().map(str::trim).filter(|uri| !uri.is_empty() && !uri.starts_with('#')) {
+        // The format is `file://host/path`, where the host is typically omitted.
+        let path = match uri.strip_prefix("file://") {
+            Some(path) => path.strip_prefix("localhost").unwrap_or(path),
+            None => return Err(UriListParseError::UnexpectedUri(uri.to_owned())),
+        };
+
+        if !path.starts_with('/') {
+            return Err(UriListParseError::UnexpectedUri(uri.to_owned()));
+        }
+
+        let path =
+            percent_decode_str(path).decode_utf8().map_err(UriListParseError::InvalidUtf8)?;
+        paths.push(Path::new(path.as_ref()).canonicalize().map_err(UriListParseError::Io)?);
+    }
+
+    Ok(paths)
+}
+
+delegate_dispatch!(WinitState: [WlDataDeviceManager: GlobalData] => DataDeviceManagerState);
+delegate_dispatch!(WinitState: [WlDataDevice: DataDeviceData] => DataDeviceManagerState);
+delegate_dispatch!(WinitState: [WlDataOffer: DataOfferData] => DataDeviceManagerState);
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn parses_uri_lists() {
+        let dir = std::env::temp_dir().join(format!("winit-uri-list-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let file = dir.join("a b%.txt");
+        fs::write(&file, b"").unwrap();
+        let uri = format!("{}/a%20b%25.txt", dir.display());
+
+        // Percent-encoding, comments, CRLF line endings and the `localhost` host.
+        let list = format!("# Dragged files\r\nfile://{uri}\r\n\r\nfile://localhost{uri}\r\n");
+        let paths = parse_uri_list(list.as_bytes()).unwrap();
+        assert_eq!(paths, [file.clone(), file]);
+        assert_eq!(parse_uri_list(b"# Nothing\n").unwrap(), Vec::<PathBuf>::new());
+
+        // Only local files are supported.
+        let remote = format!("file://example.org{uri}");
+        assert!(matches!(
+            parse_uri_list(remote.as_bytes()),
+            Err(UriListParseError::UnexpectedUri(uri)) if uri == remote
+        ));
+        assert!(matches!(
+            parse_uri_list(b"https://example.org/a.txt"),
+            Err(UriListParseError::UnexpectedUri(_))
+        ));
+        assert!(matches!(parse_uri_list(b"file:///%FF"), Err(UriListParseError::InvalidUtf8(_))));
+        let missing = format!("file://{}/missing", dir.display());
+        assert!(matches!(parse_uri_list(missing.as_bytes()), Err(UriListParseError::Io(_))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -10,9 +10,10 @@ use sctk::reexports::client::protocol::wl_keyboard::{
 };
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle, WEnum};
+use sctk::seat::Capability as SeatCapability;
 use tracing::warn;
 use winit_common::xkb::Context;
-use winit_core::event::{ElementState, WindowEvent};
+use winit_core::event::{DeviceId, ElementState, WindowEvent};
 use winit_core::keyboard::ModifiersState;
 
 use crate::WindowId;
@@ -35,6 +36,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                 return;
             },
         };
+        let device_id = seat_state.device(SeatCapability::Keyboard);
         let keyboard_state = match seat_state.keyboard_state.as_mut() {
             Some(keyboard_state) => keyboard_state,
             None => {
@@ -59,7 +61,8 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     warn!("unknown keymap format 0x{:x}", value)
                 },
             },
-            WlKeyboardEvent::Enter { surface, .. } => {
+            WlKeyboardEvent::Enter { serial, surface, .. } => {
+                keyboard_state.latest_serial = serial;
                 let window_id = crate::make_wid(&surface);
 
                 // Mark the window as focused.
@@ -129,14 +132,16 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     state.events_sink.push_window_event(WindowEvent::Focused(false), window_id);
                 }
             },
-            WlKeyboardEvent::Key { key, state: WEnum::Value(key_state), .. }
+            WlKeyboardEvent::Key { serial, key, state: WEnum::Value(key_state), .. }
                 if matches!(key_state, WlKeyState::Repeated | WlKeyState::Pressed) =>
             {
+                keyboard_state.latest_serial = serial;
                 let key = key + 8;
                 key_input(
                     keyboard_state,
                     &mut state.events_sink,
                     data,
+                    device_id,
                     key,
                     ElementState::Pressed,
                     key_state == WlKeyState::Repeated,
@@ -174,6 +179,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                             Some(seat_state) => seat_state,
                             None => return TimeoutAction::Drop,
                         };
+                        let device_id = seat_state.device(SeatCapability::Keyboard);
 
                         let keyboard_state = match seat_state.keyboard_state.as_mut() {
                             Some(keyboard_state) => keyboard_state,
@@ -191,6 +197,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                             keyboard_state,
                             &mut state.events_sink,
                             data,
+                            device_id,
                             repeat_keycode,
                             ElementState::Pressed,
                             true,
@@ -204,13 +211,17 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     })
                     .ok();
             },
-            WlKeyboardEvent::Key { key, state: WEnum::Value(WlKeyState::Released), .. } => {
+            WlKeyboardEvent::Key {
+                serial, key, state: WEnum::Value(WlKeyState::Released), ..
+            } => {
+                keyboard_state.latest_serial = serial;
                 let key = key + 8;
 
                 key_input(
                     keyboard_state,
                     &mut state.events_sink,
                     data,
+                    device_id,
                     key,
                     ElementState::Released,
                     false,
@@ -291,6 +302,9 @@ pub struct KeyboardState {
 
     /// The current repeat raw key.
     pub current_repeat: Option<u32>,
+
+    /// The serial of the latest enter or key event.
+    pub latest_serial: u32,
 }
 
 impl KeyboardState {
@@ -302,6 +316,7 @@ impl KeyboardState {
             repeat_info: RepeatInfo::default(),
             repeat_token: None,
             current_repeat: None,
+            latest_serial: 0,
         }
     }
 }
@@ -363,6 +378,7 @@ fn key_input(
     keyboard_state: &mut KeyboardState,
     event_sink: &mut EventSink,
     data: &KeyboardData,
+    device_id: Option<DeviceId>,
     keycode: u32,
     state: ElementState,
     repeat: bool,
@@ -374,7 +390,7 @@ fn key_input(
 
     if let Some(mut key_context) = keyboard_state.xkb_context.key_context() {
         let event = key_context.process_key_event(keycode, state, repeat);
-        let event = WindowEvent::KeyboardInput { device_id: None, event, is_synthetic: false };
+        let event = WindowEvent::KeyboardInput { device_id, event, is_synthetic: false };
         event_sink.push_window_event(event, window_id);
     }
 }
@@ -1,8 +1,11 @@
 //! Seat handling.
 
+use std::mem;
 use std::sync::Arc;
 
 use foldhash::HashMap;
+use sctk::data_device_manager::data_device::DataDevice;
+use sctk::primary_selection::device::PrimarySelectionDevice;
 use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_touch::WlTouch;
@@ -12,21 +15,28 @@ use sctk::reexports::protocols::wp::text_input::zv3::client::zwp_text_input_v3::
 use sctk::seat::pointer::{ThemeSpec, ThemedPointer};
 use sctk::seat::{Capability as SeatCapability, SeatHandler, SeatState};
 use tracing::warn;
+use wayland_protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gesture_hold_v1::ZwpPointerGestureHoldV1;
 use wayland_protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gesture_pinch_v1::ZwpPointerGesturePinchV1;
+use wayland_protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gesture_swipe_v1::ZwpPointerGestureSwipeV1;
 use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_seat_v2::ZwpTabletSeatV2;
-use winit_core::event::WindowEvent;
+use winit_core::event::{DeviceEvent, DeviceId, DeviceInfo, DeviceKind, WindowEvent};
 use winit_core::keyboard::ModifiersState;
 
 use crate::state::WinitState;
+use crate::types::cursor::CursorTheme;
 
+mod clipboard;
+mod data_device;
 mod keyboard;
 mod pointer;
 mod text_input;
 mod touch;
 
+pub use clipboard::ClipboardState;
+use data_device::DragState;
 use keyboard::{KeyboardData, KeyboardState};
 pub use pointer::pointer_gesture::{PointerGestureData, PointerGesturesState};
-pub use pointer::relative_pointer::RelativePointerState;
+pub use pointer::relative_pointer::{RelativePointerData, RelativePointerState};
 pub use pointer::{PointerConstraintsState, WinitPointerData, WinitPointerDataExt};
 use text_input::TextInputData;
 pub use text_input::{ClientState as TextInputClientState, TextInputState};
@@ -60,6 +70,21 @@ pub struct WinitSeatState {
     /// The pinch pointer gesture bound on the seat.
     pointer_gesture_pinch: Option<ZwpPointerGesturePinchV1>,
 
+    /// The swipe pointer gesture bound on the seat.
+    pointer_gesture_swipe: Option<ZwpPointerGestureSwipeV1>,
+
+    /// The hold pointer gesture bound on the seat.
+    pointer_gesture_hold: Option<ZwpPointerGestureHoldV1>,
+
+    /// The data device bound on the seat.
+    data_device: Option<DataDevice>,
+
+    /// The primary selection device bound on the seat.
+    primary_selection_device: Option<PrimarySelectionDevice>,
+
+    /// The drag and drop operation over one of our windows.
+    drag: Option<DragState>,
+
     /// The keyboard bound on the seat.
     keyboard_state: Option<KeyboardState>,
 
@@ -68,12 +93,72 @@ pub struct WinitSeatState {
 
     /// Whether we have pending modifiers.
     modifiers_pending: bool,
+
+    /// The devices reported for the capabilities of the seat.
+    devices: Vec<(SeatCapability, DeviceId)>,
+
+    /// The devices reported for the tablets of the tablet seat.
+    tablet_devices: Vec<DeviceId>,
 }
 
 impl WinitSeatState {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// The device reported for the capability of the seat.
+    pub fn device(&self, capability: SeatCapability) -> Option<DeviceId> {
+        self.devices.iter().find(|(cap, _)| *cap == capability).map(|&(_, device_id)| device_id)
+    }
+
+    /// Take the device reported for the capability, along with the ones of the tablets.
+    ///
+    /// The tablet seat is destroyed when any capability is removed, so its tablets go as well.
+    fn take_devices(&mut self, capability: SeatCapability) -> Vec<DeviceId> {
+        let mut devices = mem::take(&mut self.tablet_devices);
+        if let Some(index) = self.devices.iter().position(|(cap, _)| *cap == capability) {
+            devices.push(self.devices.remove(index).1);
+        }
+        devices
+    }
+
+    /// Destroy the pointer bound on the seat and the objects created from it.
+    fn remove_pointer(
+        &mut self,
+        pointer_surfaces: &mut HashMap<ObjectId, Arc<ThemedPointer<WinitPointerData>>>,
+    ) {
+        if let Some(relative_pointer) = self.relative_pointer.take() {
+            relative_pointer.destroy();
+        }
+
+        if let Some(pointer_gesture_pinch) = self.pointer_gesture_pinch.take() {
+            pointer_gesture_pinch.destroy();
+        }
+
+        if let Some(pointer_gesture_swipe) = self.pointer_gesture_swipe.take() {
+            pointer_gesture_swipe.destroy();
+        }
+
+        if let Some(pointer_gesture_hold) = self.pointer_gesture_hold.take() {
+            pointer_gesture_hold.destroy();
+        }
+
+        if let Some(pointer) = self.pointer.take() {
+            let pointer_data = pointer.pointer().winit_data();
+
+            // Remove the cursor from the mapping.
+            let surface_id = pointer.surface().id();
+            let _ = pointer_surfaces.remove(&surface_id);
+
+            // Remove the inner locks/confines before dropping the pointer.
+            pointer_data.unlock_pointer();
+            pointer_data.unconfine_pointer();
+
+            if pointer.pointer().version() >= 3 {
+                pointer.pointer().release();
+            }
+        }
+    }
 }
 
 impl SeatHandler for WinitState {
@@ -105,49 +190,6 @@ impl SeatHandler for WinitState {
                 seat_state.keyboard_state =
                     Some(KeyboardState::new(keyboard, self.loop_handle.clone()));
             },
-            SeatCapability::Pointer if seat_state.pointer.is_none() => {
-                let surface = self.compositor_state.create_surface(queue_handle);
-                let viewport = self
-                    .viewporter_state
-                    .as_ref()
-                    .map(|state| state.get_viewport(&surface, queue_handle));
-                let surface_id = surface.id();
-                let pointer_data = WinitPointerData::new(seat.clone(), viewport);
-                let themed_pointer = self
-                    .seat_state
-                    .get_pointer_with_theme_and_data(
-                        queue_handle,
-                        &seat,
-                        self.shm.wl_shm(),
-                        surface,
-                        ThemeSpec::System,
-                        pointer_data,
-                    )
-                    .expect("failed to create pointer with present capability.");
-
-                seat_state.relative_pointer = self.relative_pointer.as_ref().map(|manager| {
-                    manager.get_relative_pointer(
-                        themed_pointer.pointer(),
-                        queue_handle,
-                        sctk::globals::GlobalData,
-                    )
-                });
-
-                seat_state.pointer_gesture_pinch = self.pointer_gestures.as_ref().map(|manager| {
-                    manager.get_pinch_gesture(
-                        themed_pointer.pointer(),
-                        queue_handle,
-                        PointerGestureData::default(),
-                    )
-                });
-
-                let themed_pointer = Arc::new(themed_pointer);
-
-                // Register cursor surface.
-                self.pointer_surfaces.insert(surface_id, themed_pointer.clone());
-
-                seat_state.pointer = Some(themed_pointer);
-            },
             _ => (),
         }
 
@@ -166,6 +208,28 @@ impl SeatHandler for WinitState {
         {
             seat_state.tablet = Some(Arc::new(tablet_state.get_tablet_seat(&seat, queue_handle)));
         }
+
+        if let Some(data_device_manager) =
+            seat_state.data_device.is_none().then_some(self.data_device_manager.as_ref()).flatten()
+        {
+            seat_state.data_device = Some(data_device_manager.get_data_device(queue_handle, &seat));
+        }
+
+        if let Some(primary_selection_manager) = seat_state
+            .primary_selection_device
+            .is_none()
+            .then_some(self.primary_selection_manager.as_ref())
+            .flatten()
+        {
+            seat_state.primary_selection_device =
+                Some(primary_selection_manager.get_selection_device(queue_handle, &seat));
+        }
+
+        if capability == SeatCapability::Pointer {
+            self.add_pointer(queue_handle, &seat);
+        }
+
+        self.add_seat_device(&seat, capability);
     }
 
     fn remove_capability(
@@ -200,37 +264,18 @@ impl SeatHandler for WinitState {
                     }
                 }
             },
-            SeatCapability::Pointer => {
-                if let Some(relative_pointer) = seat_state.relative_pointer.take() {
-                    relative_pointer.destroy();
-                }
-
-                if let Some(pointer_gesture_pinch) = seat_state.pointer_gesture_pinch.take() {
-                    pointer_gesture_pinch.destroy();
-                }
-
-                if let Some(pointer) = seat_state.pointer.take() {
-                    let pointer_data = pointer.pointer().winit_data();
-
-                    // Remove the cursor from the mapping.
-                    let surface_id = pointer.surface().id();
-                    let _ = self.pointer_surfaces.remove(&surface_id);
-
-                    // Remove the inner locks/confines before dropping the pointer.
-                    pointer_data.unlock_pointer();
-                    pointer_data.unconfine_pointer();
-
-                    if pointer.pointer().version() >= 3 {
-                        pointer.pointer().release();
-                    }
-                }
-            },
+            SeatCapability::Pointer => seat_state.remove_pointer(&mut self.pointer_surfaces),
             SeatCapability::Keyboard => {
                 seat_state.keyboard_state = None;
                 self.on_keyboard_destroy(&seat.id());
             },
             _ => (),
         }
+
+        let devices = self.seats.get_mut(&seat.id()).unwrap().take_devices(capability);
+        for device_id in devices {
+            self.remove_device(device_id);
+        }
     }
 
     fn new_seat(
@@ -248,12 +293,193 @@ impl SeatHandler for WinitState {
         _queue_handle: &QueueHandle<Self>,
         seat: WlSeat,
     ) {
-        let _ = self.seats.remove(&seat.id());
+        self.cancel_drag(&seat.id());
+        if let Some(seat_state) = self.seats.remove(&seat.id()) {
+            // Dropping the data device releases it.
+            drop(seat_state.data_device);
+            let devices = seat_state.devices.into_iter().map(|(_, device_id)| device_id);
+            for device_id in devices.chain(seat_state.tablet_devices) {
+                self.remove_device(device_id);
+            }
+        }
         self.on_keyboard_destroy(&seat.id());
     }
 }
 
 impl WinitState {
+    /// Bind the pointer of the seat, loading its named cursors from the cursor theme.
+    fn add_pointer(&mut self, queue_handle: &QueueHandle<Self>, seat: &WlSeat) {
+        let seat_state = match self.seats.get_mut(&seat.id()) {
+            Some(seat_state) if seat_state.pointer.is_none() => seat_state,
+            _ => return,
+        };
+
+        let surface = self.compositor_state.create_surface(queue_handle);
+        let viewport =
+            self.viewporter_state.as_ref().map(|state| state.get_viewport(&surface, queue_handle));
+        let surface_id = surface.id();
+        let theme_name = self.cursor_theme.name();
+        let pointer_data = WinitPointerData::new(seat.clone(), viewport);
+        let themed_pointer = self
+            .seat_state
+            .get_pointer_with_theme_and_data(
+                queue_handle,
+                seat,
+                self.shm.wl_shm(),
+                surface,
+                ThemeSpec::Named { name: &theme_name, size: self.cursor_theme.size() },
+                pointer_data,
+            )
+            .expect("failed to create pointer with present capability.");
+
+        seat_state.relative_pointer = self.relative_pointer.as_ref().map(|manager| {
+            manager.get_relative_pointer(
+                themed_pointer.pointer(),
+                queue_handle,
+                RelativePointerData::new(seat.id()),
+            )
+        });
+
+        seat_state.pointer_gesture_pinch = self.pointer_gestures.as_ref().map(|manager| {
+            manager.get_pinch_gesture(
+                themed_pointer.pointer(),
+                queue_handle,
+                PointerGestureData::new(seat.id()),
+            )
+        });
+
+        seat_state.pointer_gesture_swipe = self.pointer_gestures.as_ref().map(|manager| {
+            manager.get_swipe_gesture(
+                themed_pointer.pointer(),
+                queue_handle,
+                PointerGestureData::new(seat.id()),
+            )
+        });
+
+        seat_state.pointer_gesture_hold =
+            self.pointer_gestures.as_ref().filter(|manager| manager.version() >= 3).map(
+                |manager| {
+                    manager.get_hold_gesture(
+                        themed_pointer.pointer(),
+                        queue_handle,
+                        PointerGestureData::new(seat.id()),
+                    )
+                },
+            );
+
+        let themed_pointer = Arc::new(themed_pointer);
+
+        // Register cursor surface.
+        self.pointer_surfaces.insert(surface_id, themed_pointer.clone());
+
+        seat_state.pointer = Some(themed_pointer);
+    }
+
+    /// The device reported for the capability of the seat.
+    pub fn seat_device(&self, seat: &ObjectId, capability: SeatCapability) -> Option<DeviceId> {
+        self.seats.get(seat)?.device(capability)
+    }
+
+    /// Change the theme of the named cursors.
+    ///
+    /// The theme of a pointer can't change once it's created, and binding the pointers again
+    /// would drop their state, so the named cursors of the new theme are loaded by the windows.
+    pub fn set_cursor_theme(&mut self, cursor_theme: CursorTheme) {
+        if self.cursor_theme == cursor_theme {
+            return;
+        }
+        self.themed_cursors.lock().unwrap().set_theme(&cursor_theme);
+        self.cursor_theme = cursor_theme;
+
+        let cursor_size = self.cursor_theme.size();
+        for window in self.windows.get_mut().values() {
+            window.lock().unwrap().cursor_theme_changed(cursor_size);
+        }
+    }
+
+    /// Register an input device, reporting it to the application.
+    pub fn add_device(
+        &mut self,
+        name: String,
+        kind: DeviceKind,
+        product_id: Option<(u16, u16)>,
+    ) -> DeviceId {
+        self.last_device_id += 1;
+        let device_id = DeviceId::from_raw(self.last_device_id);
+
+        let mut info = DeviceInfo::new(device_id, name, kind);
+        if let Some((vendor_id, product_id)) = product_id {
+            info.vendor_id = Some(vendor_id);
+            info.product_id = Some(product_id);
+        }
+        self.devices.insert(device_id, info);
+
+        self.events_sink.push_device_event(Some(device_id), DeviceEvent::Added);
+        device_id
+    }
+
+    /// Forget an input device, reporting its removal to the application.
+    pub fn remove_device(&mut self, device_id: DeviceId) {
+        if self.devices.remove(&device_id).is_some() {
+            self.events_sink.push_device_event(Some(device_id), DeviceEvent::Removed);
+        }
+    }
+
+    /// Report the tablet added by the tablet seat of one of the seats.
+    pub(crate) fn add_tablet_device(
+        &mut self,
+        tablet_seat: &ZwpTabletSeatV2,
+        name: String,
+        product_id: Option<(u16, u16)>,
+    ) -> Option<DeviceId> {
+        let seat_id = self
+            .seats
+            .iter()
+            .find(|(_, seat_state)| seat_state.tablet.as_deref() == Some(tablet_seat))?
+            .0
+            .clone();
+
+        let device_id = self.add_device(name, DeviceKind::Tablet, product_id);
+        self.seats.get_mut(&seat_id).unwrap().tablet_devices.push(device_id);
+        Some(device_id)
+    }
+
+    /// Report the removal of a tablet.
+    pub(crate) fn remove_tablet_device(&mut self, device_id: DeviceId) {
+        for seat_state in self.seats.values_mut() {
+            seat_state.tablet_devices.retain(|&id| id != device_id);
+        }
+        self.remove_device(device_id);
+    }
+
+    /// Report the device of a new capability of the seat.
+    ///
+    /// Wayland doesn't expose the physical devices behind a seat, so each capability is reported
+    /// as a single device named after the seat.
+    fn add_seat_device(&mut self, seat: &WlSeat, capability: SeatCapability) {
+        let (kind, role) = match capability {
+            SeatCapability::Pointer => (DeviceKind::Mouse, "pointer"),
+            SeatCapability::Keyboard => (DeviceKind::Keyboard, "keyboard"),
+            SeatCapability::Touch => (DeviceKind::Touchscreen, "touch"),
+            _ => return,
+        };
+
+        let known = match self.seats.get(&seat.id()) {
+            Some(seat_state) => seat_state.devices.iter().any(|(cap, _)| *cap == capability),
+            None => return,
+        };
+        if known {
+            return;
+        }
+
+        let name = match self.seat_state.info(seat).and_then(|info| info.name) {
+            Some(seat_name) => format!("{seat_name} {role}"),
+            None => String::from(role),
+        };
+        let device_id = self.add_device(name, kind, None);
+        self.seats.get_mut(&seat.id()).unwrap().devices.push((capability, device_id));
+    }
+
     fn on_keyboard_destroy(&mut self, seat: &ObjectId) {
         for (window_id, window) in self.windows.get_mut() {
             let mut window = window.lock().unwrap();
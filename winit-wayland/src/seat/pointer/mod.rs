@@ -25,7 +25,7 @@ use sctk::globals::GlobalData;
 use sctk::seat::pointer::{
     PointerData, PointerDataExt, PointerEvent, PointerEventKind, PointerHandler,
 };
-use sctk::seat::SeatState;
+use sctk::seat::{Capability as SeatCapability, SeatState};
 
 use dpi::{LogicalPosition, PhysicalPosition};
 use winit_core::event::{
@@ -55,6 +55,7 @@ impl PointerHandler for WinitState {
                 return;
             },
         };
+        let device_id = seat_state.device(SeatCapability::Pointer);
 
         let themed_pointer = match seat_state.pointer.as_ref() {
             Some(pointer) => pointer,
@@ -130,7 +131,7 @@ impl PointerHandler for WinitState {
                     self.events_sink.push_window_event(
                         WindowEvent::PointerEntered {
                             primary: true,
-                            device_id: None,
+                            device_id,
                             position,
                             kind: PointerKind::Mouse,
                         },
@@ -151,7 +152,7 @@ impl PointerHandler for WinitState {
                     self.events_sink.push_window_event(
                         WindowEvent::PointerLeft {
                             primary: true,
-                            device_id: None,
+                            device_id,
                             position: Some(position),
                             kind: PointerKind::Mouse,
                         },
@@ -162,7 +163,7 @@ impl PointerHandler for WinitState {
                     self.events_sink.push_window_event(
                         WindowEvent::PointerMoved {
                             primary: true,
-                            device_id: None,
+                            device_id,
                             position,
                             source: PointerSource::Mouse,
                         },
@@ -183,7 +184,7 @@ impl PointerHandler for WinitState {
                     self.events_sink.push_window_event(
                         WindowEvent::PointerButton {
                             primary: true,
-                            device_id: None,
+                            device_id,
                             state,
                             position,
                             button,
@@ -239,7 +240,7 @@ impl PointerHandler for WinitState {
                     };
 
                     self.events_sink.push_window_event(
-                        WindowEvent::MouseWheel { device_id: None, delta, phase },
+                        WindowEvent::MouseWheel { device_id, delta, phase },
                         window_id,
                     )
                 },
@@ -4,12 +4,21 @@ use std::sync::Mutex;
 use dpi::{LogicalPosition, PhysicalPosition};
 use sctk::compositor::SurfaceData;
 use sctk::globals::GlobalData;
+use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle, delegate_dispatch};
+use sctk::reexports::protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gesture_hold_v1::{
+    self, ZwpPointerGestureHoldV1,
+};
 use sctk::reexports::protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gesture_pinch_v1::{
     Event, ZwpPointerGesturePinchV1,
 };
+use sctk::reexports::protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gesture_swipe_v1::{
+    self, ZwpPointerGestureSwipeV1,
+};
 use sctk::reexports::protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gestures_v1::ZwpPointerGesturesV1;
+use sctk::seat::Capability as SeatCapability;
 use winit_core::event::{TouchPhase, WindowEvent};
 use winit_core::window::WindowId;
 
@@ -27,25 +36,35 @@ impl PointerGesturesState {
         globals: &GlobalList,
         queue_handle: &QueueHandle<WinitState>,
     ) -> Result<Self, BindError> {
-        let pointer_gestures = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        // v3 adds the hold gesture.
+        let pointer_gestures = globals.bind(queue_handle, 1..=3, GlobalData)?;
         Ok(Self { pointer_gestures })
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct PointerGestureData {
+    /// The seat of the pointer the gesture was created from.
+    seat: ObjectId,
     inner: Mutex<PointerGestureDataInner>,
 }
 
+impl PointerGestureData {
+    pub fn new(seat: ObjectId) -> Self {
+        Self { seat, inner: Default::default() }
+    }
+}
+
 #[derive(Debug)]
 pub struct PointerGestureDataInner {
     window_id: Option<WindowId>,
+    fingers: u32,
     previous_pinch: f64,
 }
 
 impl Default for PointerGestureDataInner {
     fn default() -> Self {
-        Self { window_id: Default::default(), previous_pinch: 1.0 }
+        Self { window_id: Default::default(), fingers: 0, previous_pinch: 1.0 }
     }
 }
 
@@ -87,13 +106,10 @@ impl Dispatch<ZwpPointerGesturePinchV1, PointerGestureData, WinitState> for Poin
                     return;
                 }
 
-                // Don't handle events from a subsurface.
-                if surface.data::<SurfaceData>().is_none_or(|data| data.parent_surface().is_some())
-                {
-                    return;
-                }
-
-                let window_id = crate::make_wid(&surface);
+                let window_id = match gesture_window_id(&surface) {
+                    Some(window_id) => window_id,
+                    None => return,
+                };
 
                 pointer_gesture_data.window_id = Some(window_id);
                 pointer_gesture_data.previous_pinch = 1.;
@@ -136,22 +152,143 @@ impl Dispatch<ZwpPointerGesturePinchV1, PointerGestureData, WinitState> for Poin
             _ => unreachable!("Unknown event {event:?}"),
         };
 
+        let device_id = state.seat_device(&data.seat, SeatCapability::Pointer);
+
         // The chance of only one of these events being necessary is extremely small,
         // so it is easier to just send all three
         state.events_sink.push_window_event(
-            WindowEvent::PanGesture { device_id: None, delta: pan_delta, phase },
+            WindowEvent::PanGesture { device_id, delta: pan_delta, phase },
             window_id,
         );
         state.events_sink.push_window_event(
-            WindowEvent::PinchGesture { device_id: None, delta: pinch_delta, phase },
+            WindowEvent::PinchGesture { device_id, delta: pinch_delta, phase },
             window_id,
         );
         state.events_sink.push_window_event(
-            WindowEvent::RotationGesture { device_id: None, delta: rotation_delta, phase },
+            WindowEvent::RotationGesture { device_id, delta: rotation_delta, phase },
             window_id,
         );
     }
 }
 
+impl Dispatch<ZwpPointerGestureSwipeV1, PointerGestureData, WinitState> for PointerGesturesState {
+    fn event(
+        state: &mut WinitState,
+        _proxy: &ZwpPointerGestureSwipeV1,
+        event: <ZwpPointerGestureSwipeV1 as Proxy>::Event,
+        data: &PointerGestureData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+        let mut pointer_gesture_data = data.inner.lock().unwrap();
+        let (window_id, phase, delta) = match event {
+            zwp_pointer_gesture_swipe_v1::Event::Begin { surface, fingers, .. } => {
+                let window_id = match gesture_window_id(&surface) {
+                    Some(window_id) => window_id,
+                    None => return,
+                };
+
+                pointer_gesture_data.window_id = Some(window_id);
+                pointer_gesture_data.fingers = fingers;
+
+                (window_id, TouchPhase::Started, PhysicalPosition::new(0., 0.))
+            },
+            zwp_pointer_gesture_swipe_v1::Event::Update { dx, dy, .. } => {
+                let window_id = match pointer_gesture_data.window_id {
+                    Some(window_id) => window_id,
+                    _ => return,
+                };
+
+                let scale_factor = match state.windows.get_mut().get_mut(&window_id) {
+                    Some(window) => window.lock().unwrap().scale_factor(),
+                    None => return,
+                };
+
+                let delta = LogicalPosition::new(dx as f32, dy as f32).to_physical(scale_factor);
+                (window_id, TouchPhase::Moved, delta)
+            },
+            zwp_pointer_gesture_swipe_v1::Event::End { cancelled, .. } => {
+                let window_id = match pointer_gesture_data.window_id {
+                    Some(window_id) => window_id,
+                    _ => return,
+                };
+
+                let phase = if cancelled == 0 { TouchPhase::Ended } else { TouchPhase::Cancelled };
+                (window_id, phase, PhysicalPosition::new(0., 0.))
+            },
+            _ => unreachable!("Unknown event {event:?}"),
+        };
+
+        let fingers = pointer_gesture_data.fingers;
+        if phase == TouchPhase::Ended || phase == TouchPhase::Cancelled {
+            *pointer_gesture_data = Default::default();
+        }
+
+        let device_id = state.seat_device(&data.seat, SeatCapability::Pointer);
+        state.events_sink.push_window_event(
+            WindowEvent::SwipeGesture { device_id, fingers, delta, phase },
+            window_id,
+        );
+    }
+}
+
+impl Dispatch<ZwpPointerGestureHoldV1, PointerGestureData, WinitState> for PointerGesturesState {
+    fn event(
+        state: &mut WinitState,
+        _proxy: &ZwpPointerGestureHoldV1,
+        event: <ZwpPointerGestureHoldV1 as Proxy>::Event,
+        data: &PointerGestureData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+        let mut pointer_gesture_data = data.inner.lock().unwrap();
+        let (window_id, fingers, phase) = match event {
+            zwp_pointer_gesture_hold_v1::Event::Begin { surface, fingers, .. } => {
+                let window_id = match gesture_window_id(&surface) {
+                    Some(window_id) => window_id,
+                    None => return,
+                };
+
+                pointer_gesture_data.window_id = Some(window_id);
+                pointer_gesture_data.fingers = fingers;
+
+                (window_id, fingers, TouchPhase::Started)
+            },
+            zwp_pointer_gesture_hold_v1::Event::End { cancelled, .. } => {
+                let window_id = match pointer_gesture_data.window_id {
+                    Some(window_id) => window_id,
+                    _ => return,
+                };
+                let fingers = pointer_gesture_data.fingers;
+
+                // Reset the state.
+                *pointer_gesture_data = Default::default();
+
+                // The hold is cancelled when the fingers start moving.
+                let phase = if cancelled == 0 { TouchPhase::Ended } else { TouchPhase::Cancelled };
+                (window_id, fingers, phase)
+            },
+            _ => unreachable!("Unknown event {event:?}"),
+        };
+
+        let device_id = state.seat_device(&data.seat, SeatCapability::Pointer);
+        state
+            .events_sink
+            .push_window_event(WindowEvent::HoldGesture { device_id, fingers, phase }, window_id);
+    }
+}
+
+/// The window of a gesture starting on `surface`.
+fn gesture_window_id(surface: &WlSurface) -> Option<WindowId> {
+    // Don't handle events from a subsurface.
+    if surface.data::<SurfaceData>().is_none_or(|data| data.parent_surface().is_some()) {
+        return None;
+    }
+
+    Some(crate::make_wid(surface))
+}
+
 delegate_dispatch!(WinitState: [ZwpPointerGesturesV1: GlobalData] => PointerGesturesState);
 delegate_dispatch!(WinitState: [ZwpPointerGesturePinchV1: PointerGestureData] => PointerGesturesState);
+delegate_dispatch!(WinitState: [ZwpPointerGestureSwipeV1: PointerGestureData] => PointerGesturesState);
+delegate_dispatch!(WinitState: [ZwpPointerGestureHoldV1: PointerGestureData] => PointerGesturesState);
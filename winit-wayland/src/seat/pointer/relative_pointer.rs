@@ -2,6 +2,7 @@
 
 use std::ops::Deref;
 
+use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::globals::{BindError, GlobalList};
 use sctk::reexports::client::{delegate_dispatch, Dispatch};
 use sctk::reexports::client::{Connection, QueueHandle};
@@ -11,6 +12,7 @@ use sctk::reexports::protocols::wp::relative_pointer::zv1::{
 };
 
 use sctk::globals::GlobalData;
+use sctk::seat::Capability as SeatCapability;
 
 use winit_core::event::DeviceEvent;
 use crate::state::WinitState;
@@ -32,6 +34,19 @@ impl RelativePointerState {
     }
 }
 
+/// The data of a relative pointer.
+#[derive(Debug)]
+pub struct RelativePointerData {
+    /// The seat of the pointer the relative pointer was created from.
+    seat: ObjectId,
+}
+
+impl RelativePointerData {
+    pub fn new(seat: ObjectId) -> Self {
+        Self { seat }
+    }
+}
+
 impl Deref for RelativePointerState {
     type Target = ZwpRelativePointerManagerV1;
 
@@ -52,12 +67,12 @@ impl Dispatch<ZwpRelativePointerManagerV1, GlobalData, WinitState> for RelativeP
     }
 }
 
-impl Dispatch<ZwpRelativePointerV1, GlobalData, WinitState> for RelativePointerState {
+impl Dispatch<ZwpRelativePointerV1, RelativePointerData, WinitState> for RelativePointerState {
     fn event(
         state: &mut WinitState,
         _proxy: &ZwpRelativePointerV1,
         event: <ZwpRelativePointerV1 as wayland_client::Proxy>::Event,
-        _data: &GlobalData,
+        data: &RelativePointerData,
         _conn: &Connection,
         _qhandle: &QueueHandle<WinitState>,
     ) {
@@ -67,11 +82,12 @@ impl Dispatch<ZwpRelativePointerV1, GlobalData, WinitState> for RelativePointerS
             },
             _ => return,
         };
-        state
-            .events_sink
-            .push_device_event(DeviceEvent::PointerMotion { delta: (dx_unaccel, dy_unaccel) });
+        let device_id = state.seat_device(&data.seat, SeatCapability::Pointer);
+        state.events_sink.push_device_event(device_id, DeviceEvent::PointerMotion {
+            delta: (dx_unaccel, dy_unaccel),
+        });
     }
 }
 
-delegate_dispatch!(WinitState: [ZwpRelativePointerV1: GlobalData] => RelativePointerState);
+delegate_dispatch!(WinitState: [ZwpRelativePointerV1: RelativePointerData] => RelativePointerState);
 delegate_dispatch!(WinitState: [ZwpRelativePointerManagerV1: GlobalData] => RelativePointerState);
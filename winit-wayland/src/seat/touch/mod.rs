@@ -5,6 +5,7 @@ use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::protocol::wl_touch::WlTouch;
 use sctk::reexports::client::{Connection, Proxy, QueueHandle};
+use sctk::seat::Capability as SeatCapability;
 use sctk::seat::touch::{TouchData, TouchHandler};
 use tracing::warn;
 use winit_core::event::{
@@ -38,6 +39,7 @@ impl TouchHandler for WinitState {
                 return;
             },
         };
+        let device_id = seat_state.device(SeatCapability::Touch);
 
         // Update the state of the point.
         let location = LogicalPosition::<f64>::from(position);
@@ -53,7 +55,7 @@ impl TouchHandler for WinitState {
 
         self.events_sink.push_window_event(
             WindowEvent::PointerEntered {
-                device_id: None,
+                device_id,
                 primary,
                 position,
                 kind: PointerKind::Touch(finger_id),
@@ -62,11 +64,11 @@ impl TouchHandler for WinitState {
         );
         self.events_sink.push_window_event(
             WindowEvent::PointerButton {
-                device_id: None,
+                device_id,
                 primary,
                 state: ElementState::Pressed,
                 position,
-                button: ButtonSource::Touch { finger_id, force: None },
+                button: ButtonSource::Touch { finger_id, force: None, contact: None },
             },
             window_id,
         );
@@ -88,6 +90,7 @@ impl TouchHandler for WinitState {
                 return;
             },
         };
+        let device_id = seat_state.device(SeatCapability::Touch);
 
         // Remove the touch point.
         let touch_point = match seat_state.touch_map.remove(&id) {
@@ -115,17 +118,17 @@ impl TouchHandler for WinitState {
 
         self.events_sink.push_window_event(
             WindowEvent::PointerButton {
-                device_id: None,
+                device_id,
                 primary,
                 state: ElementState::Released,
                 position,
-                button: ButtonSource::Touch { finger_id, force: None },
+                button: ButtonSource::Touch { finger_id, force: None, contact: None },
             },
             window_id,
         );
         self.events_sink.push_window_event(
             WindowEvent::PointerLeft {
-                device_id: None,
+                device_id,
                 primary,
                 position: Some(position),
                 kind: PointerKind::Touch(finger_id),
@@ -150,6 +153,7 @@ impl TouchHandler for WinitState {
                 return;
             },
         };
+        let device_id = seat_state.device(SeatCapability::Touch);
 
         // Remove the touch point.
         let touch_point = match seat_state.touch_map.get_mut(&id) {
@@ -169,12 +173,13 @@ impl TouchHandler for WinitState {
 
         self.events_sink.push_window_event(
             WindowEvent::PointerMoved {
-                device_id: None,
+                device_id,
                 primary,
                 position: touch_point.location.to_physical(scale_factor),
                 source: PointerSource::Touch {
                     finger_id: FingerId::from_raw(id as usize),
                     force: None,
+                    contact: None,
                 },
             },
             window_id,
@@ -189,6 +194,7 @@ impl TouchHandler for WinitState {
                 return;
             },
         };
+        let device_id = seat_state.device(SeatCapability::Touch);
 
         for (id, touch_point) in seat_state.touch_map.drain() {
             let window_id = crate::make_wid(&touch_point.surface);
@@ -202,7 +208,7 @@ impl TouchHandler for WinitState {
 
             self.events_sink.push_window_event(
                 WindowEvent::PointerLeft {
-                    device_id: None,
+                    device_id,
                     primary,
                     position: Some(position),
                     kind: PointerKind::Touch(FingerId::from_raw(id as usize)),
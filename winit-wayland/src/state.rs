@@ -4,7 +4,9 @@ use std::sync::{Arc, Mutex};
 
 use foldhash::HashMap;
 use sctk::compositor::{CompositorHandler, CompositorState};
+use sctk::data_device_manager::DataDeviceManagerState;
 use sctk::output::{OutputHandler, OutputState};
+use sctk::primary_selection::PrimarySelectionManagerState;
 use sctk::reexports::calloop::LoopHandle;
 use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::globals::GlobalList;
@@ -15,20 +17,28 @@ use sctk::registry::{ProvidesRegistryState, RegistryState};
 use sctk::seat::SeatState;
 use sctk::seat::pointer::ThemedPointer;
 use sctk::shell::WaylandSurface;
+use sctk::shell::wlr_layer::{LayerShell, LayerShellHandler, LayerSurface, LayerSurfaceConfigure};
 use sctk::shell::xdg::XdgShell;
+use sctk::shell::xdg::popup::{Popup, PopupConfigure, PopupHandler};
 use sctk::shell::xdg::window::{Window, WindowConfigure, WindowHandler};
 use sctk::shm::slot::SlotPool;
 use sctk::shm::{Shm, ShmHandler};
 use sctk::subcompositor::SubcompositorState;
+use winit_common::xdg_desktop_portal::{Settings as PortalSettings, SettingsWatcher};
 use winit_core::error::OsError;
+use winit_core::event::{DeviceId, DeviceInfo, MonitorEvent, WindowEvent};
+use winit_core::event_loop::DesktopSettings;
+use winit_core::monitor::MonitorHandle as CoreMonitorHandle;
+use winit_core::window::Theme;
 
 use crate::WindowId;
 use crate::event_loop::sink::EventSink;
 use crate::output::MonitorHandle;
 use crate::seat::{
-    PointerConstraintsState, PointerGesturesState, RelativePointerState, TextInputState,
-    WinitPointerData, WinitPointerDataExt, WinitSeatState,
+    ClipboardState, PointerConstraintsState, PointerGesturesState, RelativePointerState,
+    TextInputState, WinitPointerData, WinitPointerDataExt, WinitSeatState,
 };
+use crate::types::cursor::{CursorTheme, ThemedCursors};
 use crate::types::kwin_blur::KWinBlurManager;
 use crate::types::wp_fractional_scaling::FractionalScalingManager;
 use crate::types::wp_tablet_input_v2::TabletManager;
@@ -61,6 +71,9 @@ pub struct WinitState {
     /// The XDG shell that is used for windows.
     pub xdg_shell: XdgShell,
 
+    /// The layer shell that is used for layer surfaces.
+    pub layer_shell: Option<LayerShell>,
+
     /// The currently present windows.
     pub windows: RefCell<HashMap<WindowId, Arc<Mutex<WindowState>>>>,
 
@@ -79,6 +92,27 @@ pub struct WinitState {
     /// Currently present cursor surfaces.
     pub pointer_surfaces: HashMap<ObjectId, Arc<ThemedPointer<WinitPointerData>>>,
 
+    /// The theme the named cursors of the pointers are loaded from.
+    pub cursor_theme: CursorTheme,
+
+    /// The named cursors of the theme set by the application.
+    pub themed_cursors: Arc<Mutex<ThemedCursors>>,
+
+    /// The input devices of the seats.
+    pub devices: HashMap<DeviceId, DeviceInfo>,
+
+    /// The raw value of the last identifier given to a device.
+    pub last_device_id: i64,
+
+    /// The data device manager used for drag and drop and the clipboard.
+    pub data_device_manager: Option<DataDeviceManagerState>,
+
+    /// The primary selection manager.
+    pub primary_selection_manager: Option<PrimarySelectionManagerState>,
+
+    /// The content of the selections we own.
+    pub clipboard: ClipboardState,
+
     /// The state of the text input on the client.
     pub text_input_state: Option<TextInputState>,
 
@@ -119,6 +153,15 @@ pub struct WinitState {
     /// KWin blur manager.
     pub kwin_blur_manager: Option<KWinBlurManager>,
 
+    /// The system theme, as last read from the XDG Desktop Portal.
+    pub system_theme: Option<Theme>,
+
+    /// The input settings, as last read from the XDG Desktop Portal.
+    pub desktop_settings: DesktopSettings,
+
+    /// The helper thread following the settings of the XDG Desktop Portal.
+    _portal_settings_watcher: SettingsWatcher,
+
     /// Loop handle to re-register event sources, such as keyboard repeat.
     pub loop_handle: LoopHandle<'static, Self>,
 
@@ -169,6 +212,20 @@ impl WinitState {
             };
 
         let shm = Shm::bind(globals, queue_handle).map_err(|err| os_error!(err))?;
+
+        // The settings are read on a helper thread, to not block on the portal.
+        let (portal_settings_sender, portal_settings_channel) = calloop::channel::channel();
+        loop_handle
+            .insert_source(portal_settings_channel, |event, _, state: &mut WinitState| {
+                if let calloop::channel::Event::Msg(settings) = event {
+                    state.set_portal_settings(settings);
+                }
+            })
+            .map_err(|err| os_error!(err.error))?;
+        let portal_settings_watcher = SettingsWatcher::spawn(move |settings| {
+            let _ = portal_settings_sender.send(settings);
+        });
+
         let image_pool = Arc::new(Mutex::new(SlotPool::new(2, &shm).unwrap()));
 
         Ok(Self {
@@ -180,6 +237,7 @@ impl WinitState {
             shm,
 
             xdg_shell: XdgShell::bind(globals, queue_handle).map_err(|err| os_error!(err))?,
+            layer_shell: LayerShell::bind(globals, queue_handle).ok(),
             xdg_activation: XdgActivationState::bind(globals, queue_handle).ok(),
             xdg_toplevel_icon_manager: XdgToplevelIconManagerState::bind(globals, queue_handle)
                 .ok(),
@@ -195,6 +253,10 @@ impl WinitState {
             kwin_blur_manager: KWinBlurManager::new(globals, queue_handle).ok(),
 
             seats,
+            data_device_manager: DataDeviceManagerState::bind(globals, queue_handle).ok(),
+            primary_selection_manager: PrimarySelectionManagerState::bind(globals, queue_handle)
+                .ok(),
+            clipboard: ClipboardState::default(),
             text_input_state: TextInputState::new(globals, queue_handle).ok(),
 
             relative_pointer: RelativePointerState::new(globals, queue_handle).ok(),
@@ -203,10 +265,17 @@ impl WinitState {
                 .map(Arc::new)
                 .ok(),
             pointer_surfaces: Default::default(),
+            cursor_theme: Default::default(),
+            themed_cursors: Default::default(),
+            devices: Default::default(),
+            last_device_id: 0,
             pointer_gestures: PointerGesturesState::new(globals, queue_handle).ok(),
 
             monitors: Arc::new(Mutex::new(monitors)),
             events_sink: EventSink::new(),
+            system_theme: None,
+            desktop_settings: Default::default(),
+            _portal_settings_watcher: portal_settings_watcher,
             loop_handle,
             // Make it true by default.
             dispatched_events: true,
@@ -214,6 +283,32 @@ impl WinitState {
         })
     }
 
+    /// Update the settings of the XDG Desktop Portal, notifying the changes of the system theme
+    /// to the windows following it and the changes of the input settings to the user.
+    fn set_portal_settings(&mut self, portal_settings: PortalSettings) {
+        // Wake up the event loop to deliver the changes and redraw the decorations.
+        self.dispatched_events = true;
+
+        let desktop_settings = portal_settings.desktop_settings();
+        if self.desktop_settings != desktop_settings {
+            self.desktop_settings = desktop_settings;
+            self.events_sink.push_desktop_settings_changed();
+        }
+
+        let system_theme = portal_settings.color_scheme();
+        if self.system_theme == system_theme {
+            return;
+        }
+
+        self.system_theme = system_theme;
+        for (window_id, window) in self.windows.get_mut().iter() {
+            let follows_system = window.lock().unwrap().set_system_theme(system_theme);
+            if let (true, Some(theme)) = (follows_system, system_theme) {
+                self.events_sink.push_window_event(WindowEvent::ThemeChanged(theme), *window_id);
+            }
+        }
+    }
+
     pub fn scale_factor_changed(
         &mut self,
         surface: &WlSurface,
@@ -268,6 +363,32 @@ impl WinitState {
 
         updates[pos].close_window = true;
     }
+
+    /// Record the configure of the window.
+    fn window_configured(&mut self, window_id: WindowId, resized: bool) {
+        let pos = if let Some(pos) =
+            self.window_compositor_updates.iter().position(|update| update.window_id == window_id)
+        {
+            pos
+        } else {
+            self.window_compositor_updates.push(WindowCompositorUpdate::new(window_id));
+            self.window_compositor_updates.len() - 1
+        };
+
+        self.window_compositor_updates[pos].resized |= resized;
+
+        // NOTE: configure demands wl_surface::commit, however winit doesn't commit on behalf of the
+        // users, since it can break a lot of things, thus it'll ask users to redraw instead.
+        self.window_requests
+            .get_mut()
+            .get(&window_id)
+            .unwrap()
+            .redraw_requested
+            .store(true, Ordering::Relaxed);
+
+        // Manually mark that we've got an event, since configure may not generate a resize.
+        self.dispatched_events = true;
+    }
 }
 
 impl ShmHandler for WinitState {
@@ -292,36 +413,87 @@ impl WindowHandler for WinitState {
     ) {
         let window_id = super::make_wid(window.wl_surface());
 
-        let pos = if let Some(pos) =
-            self.window_compositor_updates.iter().position(|update| update.window_id == window_id)
-        {
-            pos
-        } else {
-            self.window_compositor_updates.push(WindowCompositorUpdate::new(window_id));
-            self.window_compositor_updates.len() - 1
-        };
+        // Populate the configure to the window.
+        let mut window_state = self
+            .windows
+            .get_mut()
+            .get_mut(&window_id)
+            .expect("got configure for dead window.")
+            .lock()
+            .unwrap();
+        let decoration_mode = window_state.is_configured().then(|| window_state.decoration_mode());
+        let resized = window_state.configure(configure, &self.shm, &self.subcompositor_state);
+
+        // Report the decoration mode changes after the initial configure.
+        let new_decoration_mode = window_state.decoration_mode();
+        drop(window_state);
+        if decoration_mode.is_some_and(|mode| mode != new_decoration_mode) {
+            self.events_sink.push_window_event(
+                WindowEvent::DecorationModeChanged(new_decoration_mode),
+                window_id,
+            );
+        }
+
+        self.window_configured(window_id, resized);
+    }
+}
+
+impl LayerShellHandler for WinitState {
+    fn closed(&mut self, _: &Connection, _: &QueueHandle<Self>, layer: &LayerSurface) {
+        let window_id = super::make_wid(layer.wl_surface());
+        Self::queue_close(&mut self.window_compositor_updates, window_id);
+    }
+
+    fn configure(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        layer: &LayerSurface,
+        configure: LayerSurfaceConfigure,
+        _serial: u32,
+    ) {
+        let window_id = super::make_wid(layer.wl_surface());
 
         // Populate the configure to the window.
-        self.window_compositor_updates[pos].resized |= self
+        let resized = self
             .windows
             .get_mut()
             .get_mut(&window_id)
             .expect("got configure for dead window.")
             .lock()
             .unwrap()
-            .configure(configure, &self.shm, &self.subcompositor_state);
+            .configure_surface(configure.new_size);
 
-        // NOTE: configure demands wl_surface::commit, however winit doesn't commit on behalf of the
-        // users, since it can break a lot of things, thus it'll ask users to redraw instead.
-        self.window_requests
+        self.window_configured(window_id, resized);
+    }
+}
+
+impl PopupHandler for WinitState {
+    fn configure(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        popup: &Popup,
+        configure: PopupConfigure,
+    ) {
+        let window_id = super::make_wid(popup.wl_surface());
+
+        // Populate the configure to the window.
+        let resized = self
+            .windows
             .get_mut()
-            .get(&window_id)
+            .get_mut(&window_id)
+            .expect("got configure for dead window.")
+            .lock()
             .unwrap()
-            .redraw_requested
-            .store(true, Ordering::Relaxed);
+            .configure_surface((configure.width as u32, configure.height as u32));
 
-        // Manually mark that we've got an event, since configure may not generate a resize.
-        self.dispatched_events = true;
+        self.window_configured(window_id, resized);
+    }
+
+    fn done(&mut self, _: &Connection, _: &QueueHandle<Self>, popup: &Popup) {
+        let window_id = super::make_wid(popup.wl_surface());
+        self.events_sink.push_window_event(WindowEvent::PopupDismissed, window_id);
     }
 }
 
@@ -331,17 +503,23 @@ impl OutputHandler for WinitState {
     }
 
     fn new_output(&mut self, _: &Connection, _: &QueueHandle<Self>, output: WlOutput) {
-        self.monitors.lock().unwrap().push(MonitorHandle::new(output));
+        let added = MonitorHandle::new(output);
+        self.monitors.lock().unwrap().push(added.clone());
+        let event = MonitorEvent::Added(CoreMonitorHandle(Arc::new(added)));
+        self.events_sink.push_monitor_event(event);
     }
 
     fn update_output(&mut self, _: &Connection, _: &QueueHandle<Self>, updated: WlOutput) {
         let mut monitors = self.monitors.lock().unwrap();
         let updated = MonitorHandle::new(updated);
-        if let Some(pos) = monitors.iter().position(|output| output == &updated) {
-            monitors[pos] = updated
+        let event = if let Some(pos) = monitors.iter().position(|output| output == &updated) {
+            monitors[pos] = updated.clone();
+            MonitorEvent::Changed(CoreMonitorHandle(Arc::new(updated)))
         } else {
-            monitors.push(updated)
-        }
+            monitors.push(updated.clone());
+            MonitorEvent::Added(CoreMonitorHandle(Arc::new(updated)))
+        };
+        self.events_sink.push_monitor_event(event);
     }
 
     fn output_destroyed(&mut self, _: &Connection, _: &QueueHandle<Self>, removed: WlOutput) {
@@ -349,6 +527,8 @@ impl OutputHandler for WinitState {
         let removed = MonitorHandle::new(removed);
         if let Some(pos) = monitors.iter().position(|output| output == &removed) {
             monitors.remove(pos);
+            let event = MonitorEvent::Removed(CoreMonitorHandle(Arc::new(removed)));
+            self.events_sink.push_monitor_event(event);
         }
     }
 }
@@ -452,3 +632,5 @@ sctk::delegate_registry!(WinitState);
 sctk::delegate_shm!(WinitState);
 sctk::delegate_xdg_shell!(WinitState);
 sctk::delegate_xdg_window!(WinitState);
+sctk::delegate_layer!(WinitState);
+sctk::delegate_xdg_popup!(WinitState);
@@ -1,16 +1,102 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
 use cursor_icon::CursorIcon;
+use sctk::reexports::client::protocol::wl_callback::{self, WlCallback};
 use sctk::reexports::client::protocol::wl_shm::Format;
+use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle, delegate_dispatch};
 use sctk::shm::slot::{Buffer, SlotPool};
-use winit_core::cursor::{CursorImage, CustomCursorProvider};
+use winit_common::xcursor::{self, DEFAULT_CURSOR_SIZE};
+use winit_core::cursor::{
+    CursorFile, CursorImage, CursorImageSet, CursorVariant, CustomCursor as CoreCustomCursor,
+    CustomCursorProvider,
+};
+use winit_core::window::WindowId;
 
 use crate::image_to_buffer;
+use crate::state::WinitState;
 
 // Wrap in our own type to not impl trait on global type.
 #[derive(Debug)]
-pub struct WaylandCustomCursor(pub(crate) CursorImage);
+pub enum WaylandCustomCursor {
+    Image(CursorImage),
+    /// The frames with the time they're shown.
+    Animation(Vec<(CursorImage, Duration)>),
+    /// The size is picked when setting the cursor, to match the scale factor of the window.
+    File(CursorFile),
+    /// The image is picked when setting the cursor, to match the scale factor of the window.
+    Scaled(CursorImageSet),
+}
+
 impl CustomCursorProvider for WaylandCustomCursor {
     fn is_animated(&self) -> bool {
-        false
+        match self {
+            Self::Image(_) | Self::Scaled(_) => false,
+            Self::Animation(_) => true,
+            Self::File(file) => file.variants().iter().any(CursorVariant::is_animated),
+        }
+    }
+}
+
+/// The theme of the named cursors, falling back to the environment like the system theme of sctk.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CursorTheme {
+    /// The name set by the application.
+    pub name: Option<String>,
+
+    /// The size set by the application.
+    pub size: Option<u32>,
+}
+
+impl CursorTheme {
+    /// The name of the theme, read from `XCURSOR_THEME` when not set.
+    pub fn name(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| env::var("XCURSOR_THEME").ok())
+            .unwrap_or_else(|| String::from("default"))
+    }
+
+    /// The size of cursors in logical pixels, read from `XCURSOR_SIZE` when not set.
+    pub fn size(&self) -> u32 {
+        self.size
+            .or_else(|| env::var("XCURSOR_SIZE").ok().and_then(|size| size.parse().ok()))
+            .unwrap_or(DEFAULT_CURSOR_SIZE)
+    }
+}
+
+/// The named cursors loaded from the cursor theme set by the application.
+///
+/// The pointers load their named cursors from the theme they're created with, which can't change
+/// afterwards, so the cursors of the theme set by the application are drawn like custom cursors.
+#[derive(Debug, Default)]
+pub struct ThemedCursors {
+    /// The name of the theme, `None` when the application didn't set one.
+    theme: Option<String>,
+
+    /// The loaded cursors, `None` for the ones missing from the theme.
+    cursors: HashMap<CursorIcon, Option<CoreCustomCursor>>,
+}
+
+impl ThemedCursors {
+    /// Change the theme, dropping the cursors loaded from the previous one.
+    pub fn set_theme(&mut self, theme: &CursorTheme) {
+        self.theme = (*theme != CursorTheme::default()).then(|| theme.name());
+        self.cursors.clear();
+    }
+
+    /// The cursor for `icon` from the theme of the application, if it set one.
+    pub fn get(&mut self, icon: CursorIcon) -> Option<CoreCustomCursor> {
+        let theme = self.theme.as_deref()?;
+        self.cursors
+            .entry(icon)
+            .or_insert_with(|| {
+                let file = xcursor::load_cursor(theme, icon)?;
+                Some(CoreCustomCursor(Arc::new(WaylandCustomCursor::File(file))))
+            })
+            .clone()
     }
 }
 
@@ -18,6 +104,7 @@ impl CustomCursorProvider for WaylandCustomCursor {
 pub enum SelectedCursor {
     Named(CursorIcon),
     Custom(CustomCursor),
+    Animated(AnimatedCursor),
 }
 
 impl Default for SelectedCursor {
@@ -33,11 +120,12 @@ pub struct CustomCursor {
     pub h: i32,
     pub hotspot_x: i32,
     pub hotspot_y: i32,
+    /// The scale the image was drawn for, instead of the one of the window.
+    pub scale: Option<u32>,
 }
 
 impl CustomCursor {
-    pub(crate) fn new(pool: &mut SlotPool, image: &WaylandCustomCursor) -> Self {
-        let image = &image.0;
+    pub(crate) fn new(pool: &mut SlotPool, image: &CursorImage) -> Self {
         let buffer = image_to_buffer(
             image.width() as i32,
             image.height() as i32,
@@ -53,6 +141,86 @@ impl CustomCursor {
             h: image.height() as i32,
             hotspot_x: image.hotspot_x() as i32,
             hotspot_y: image.hotspot_y() as i32,
+            scale: None,
+        }
+    }
+
+    /// Show the image at the size it has for `scale`, rather than for the scale of the window.
+    pub(crate) fn with_scale(mut self, scale: u32) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+}
+
+/// Custom cursor frames swapped on the pointer surface as they come due.
+#[derive(Debug)]
+pub struct AnimatedCursor {
+    /// The frames with the time they're shown.
+    pub frames: Vec<(CustomCursor, Duration)>,
+    pub current: usize,
+    /// The time the current frame was first presented, in milliseconds.
+    pub shown_at: Option<u32>,
+    /// The frame callback in flight on the pointer surface.
+    pub frame_callback: Option<WlCallback>,
+}
+
+impl AnimatedCursor {
+    pub(crate) fn new<'a>(
+        pool: &mut SlotPool,
+        frames: impl IntoIterator<Item = (&'a CursorImage, Duration)>,
+    ) -> Self {
+        let frames = frames
+            .into_iter()
+            .map(|(image, delay)| (CustomCursor::new(pool, image), delay))
+            .collect();
+        AnimatedCursor { frames, current: 0, shown_at: None, frame_callback: None }
+    }
+
+    /// The frame currently shown.
+    pub fn current_frame(&self) -> &CustomCursor {
+        &self.frames[self.current].0
+    }
+
+    /// Handle the frame `callback` presented at `time`, returning whether the next frame is due.
+    ///
+    /// Returns `None` for callbacks requested for a previous cursor.
+    pub fn frame_done(&mut self, callback: &WlCallback, time: u32) -> Option<bool> {
+        if self.frame_callback.as_ref() != Some(callback) {
+            return None;
         }
+        self.frame_callback = None;
+
+        let shown_at = *self.shown_at.get_or_insert(time);
+        if Duration::from_millis(time.wrapping_sub(shown_at) as u64) < self.frames[self.current].1 {
+            return Some(false);
+        }
+
+        self.current = (self.current + 1) % self.frames.len();
+        self.shown_at = Some(time);
+        Some(true)
     }
 }
+
+/// User data of the frame callbacks timing cursor animations.
+#[derive(Debug)]
+pub struct CursorFrameData {
+    pub window_id: WindowId,
+}
+
+impl Dispatch<WlCallback, CursorFrameData, WinitState> for AnimatedCursor {
+    fn event(
+        state: &mut WinitState,
+        callback: &WlCallback,
+        event: <WlCallback as Proxy>::Event,
+        data: &CursorFrameData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        let wl_callback::Event::Done { callback_data } = event else { return };
+        if let Some(window) = state.windows.get_mut().get(&data.window_id) {
+            window.lock().unwrap().cursor_frame_done(callback, callback_data);
+        }
+    }
+}
+
+delegate_dispatch!(WinitState: [WlCallback: CursorFrameData] => AnimatedCursor);
@@ -13,24 +13,32 @@ use sctk::reexports::client::{
     Connection, Dispatch, Proxy, QueueHandle, WEnum, delegate_dispatch, event_created_child,
 };
 use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_manager_v2::ZwpTabletManagerV2;
-use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_pad_v2::ZwpTabletPadV2;
 use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_seat_v2::{
     self, ZwpTabletSeatV2,
 };
 use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_tool_v2::{
     ButtonState, Event as ToolEvent, Type as ToolType, ZwpTabletToolV2,
 };
-use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_v2::ZwpTabletV2;
-use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_pad_dial_v2::ZwpTabletPadDialV2;
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_v2::{self, ZwpTabletV2};
+use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_pad_dial_v2::{
+    Event as DialEvent, ZwpTabletPadDialV2,
+};
 use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_pad_group_v2::{
-    self, ZwpTabletPadGroupV2,
+    self, Event as GroupEvent, ZwpTabletPadGroupV2,
+};
+use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_pad_ring_v2::{
+    Event as RingEvent, ZwpTabletPadRingV2,
+};
+use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_pad_strip_v2::{
+    Event as StripEvent, ZwpTabletPadStripV2,
+};
+use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_pad_v2::{
+    self, ButtonState as PadButtonState, Event as PadEvent, ZwpTabletPadV2,
 };
-use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_pad_ring_v2::ZwpTabletPadRingV2;
-use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_pad_strip_v2::ZwpTabletPadStripV2;
-use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_pad_v2;
 use winit_core::event::{
-    ButtonSource, ElementState, Force, PointerKind, PointerSource, TabletToolButton,
-    TabletToolData as CoreTabletToolData, TabletToolKind, TabletToolTilt, WindowEvent,
+    ButtonSource, DeviceEvent, DeviceId, ElementState, Force, PointerKind, PointerSource,
+    TabletToolButton, TabletToolData as CoreTabletToolData, TabletToolKind, TabletToolTilt,
+    WindowEvent,
 };
 
 use crate::state::WinitState;
@@ -46,8 +54,8 @@ impl TabletManager {
         globals: &GlobalList,
         queue_handle: &QueueHandle<WinitState>,
     ) -> Result<Self, BindError> {
-        // Ignore v2 since we are not interested in its events.
-        let manager = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        // v2 adds the pad dials.
+        let manager = globals.bind(queue_handle, 1..=2, GlobalData)?;
         Ok(Self { manager })
     }
 
@@ -94,12 +102,16 @@ impl Dispatch<ZwpTabletSeatV2, (), WinitState> for TabletManager {
 
     fn event(
         _: &mut WinitState,
-        _: &ZwpTabletSeatV2,
-        _: <ZwpTabletSeatV2 as Proxy>::Event,
+        tablet_seat: &ZwpTabletSeatV2,
+        event: <ZwpTabletSeatV2 as Proxy>::Event,
         _: &(),
         _: &Connection,
         _: &QueueHandle<WinitState>,
     ) {
+        if let zwp_tablet_seat_v2::Event::TabletAdded { id } = event {
+            id.data::<TabletData>().unwrap().inner.lock().unwrap().tablet_seat =
+                Some(tablet_seat.clone());
+        }
     }
 }
 
@@ -130,8 +142,11 @@ impl Dispatch<ZwpTabletToolV2, TabletToolData, WinitState> for TabletManager {
             },
             ToolEvent::Capability { .. } => {},
             ToolEvent::Done => (),
-            ToolEvent::ProximityIn { serial, surface, .. } => {
-                data.pending.push(TabletEvent::Enter { serial, surface });
+            ToolEvent::ProximityIn { serial, tablet, surface } => {
+                let device_id = tablet
+                    .data::<TabletData>()
+                    .and_then(|tablet| tablet.inner.lock().unwrap().device_id);
+                data.pending.push(TabletEvent::Enter { serial, surface, device_id });
             },
             ToolEvent::ProximityOut => data.pending.push(TabletEvent::Left),
             ToolEvent::Down { serial } => {
@@ -189,9 +204,10 @@ impl Dispatch<ZwpTabletToolV2, TabletToolData, WinitState> for TabletManager {
             ToolEvent::Frame { .. } => {
                 let kind = data.ty;
                 for event in std::mem::take(&mut data.pending) {
-                    if let TabletEvent::Enter { surface, serial } = &event {
+                    if let TabletEvent::Enter { surface, serial, device_id } = &event {
                         data.latest_enter_serial = Some(*serial);
                         data.surface = Some(surface.clone());
+                        data.device_id = *device_id;
                     }
 
                     // Handle events only for top-level surface.
@@ -217,16 +233,17 @@ impl Dispatch<ZwpTabletToolV2, TabletToolData, WinitState> for TabletManager {
                     };
 
                     let position = data.position.to_physical(window.scale_factor());
+                    let device_id = data.device_id;
 
                     let window_event = match event {
                         TabletEvent::Enter { .. } => WindowEvent::PointerEntered {
-                            device_id: None,
+                            device_id,
                             position,
                             primary: true,
                             kind: PointerKind::TabletTool(kind),
                         },
                         TabletEvent::Moved => WindowEvent::PointerMoved {
-                            device_id: None,
+                            device_id,
                             position,
                             primary: true,
                             source: PointerSource::TabletTool {
@@ -241,7 +258,7 @@ impl Dispatch<ZwpTabletToolV2, TabletToolData, WinitState> for TabletManager {
                             }
 
                             WindowEvent::PointerButton {
-                                device_id: None,
+                                device_id,
                                 state,
                                 position,
                                 primary: true,
@@ -253,7 +270,7 @@ impl Dispatch<ZwpTabletToolV2, TabletToolData, WinitState> for TabletManager {
                             }
                         },
                         TabletEvent::Left => WindowEvent::PointerLeft {
-                            device_id: None,
+                            device_id,
                             position: Some(position),
                             primary: true,
                             kind: PointerKind::TabletTool(kind),
@@ -267,6 +284,7 @@ impl Dispatch<ZwpTabletToolV2, TabletToolData, WinitState> for TabletManager {
                         data.surface = None;
                         data.latest_button_serial = None;
                         data.latest_enter_serial = None;
+                        data.device_id = None;
                         data.tool_state = Default::default();
                     }
                 }
@@ -297,6 +315,9 @@ pub(crate) struct TabletToolDataInner {
     /// Position relative to the surface.
     pub(crate) position: LogicalPosition<f64>,
 
+    /// The device reported for the tablet the tool is in proximity of.
+    pub(crate) device_id: Option<DeviceId>,
+
     // NOTE: even though we don't utilize serials
     // right now, track them anyway.
     /// The serial of the latest enter event for the pointer
@@ -312,41 +333,124 @@ pub(crate) struct TabletToolDataInner {
 // can technically change before the `frame` event.
 #[derive(Debug, Clone)]
 pub(crate) enum TabletEvent {
-    Enter { serial: u32, surface: WlSurface },
+    Enter { serial: u32, surface: WlSurface, device_id: Option<DeviceId> },
     Left,
     Moved,
     Button { button: TabletToolButton, state: ElementState, serial: Option<u32> },
 }
 
-impl Dispatch<ZwpTabletV2, (), WinitState> for TabletManager {
+impl Dispatch<ZwpTabletV2, TabletData, WinitState> for TabletManager {
     fn event(
-        _: &mut WinitState,
-        _: &ZwpTabletV2,
-        _: <ZwpTabletV2 as Proxy>::Event,
-        _: &(),
+        state: &mut WinitState,
+        tablet: &ZwpTabletV2,
+        event: <ZwpTabletV2 as Proxy>::Event,
+        data: &TabletData,
         _: &Connection,
         _: &QueueHandle<WinitState>,
     ) {
+        let mut data = data.inner.lock().unwrap();
+        match event {
+            zwp_tablet_v2::Event::Name { name } => data.name = name,
+            zwp_tablet_v2::Event::Id { vid, pid } => {
+                data.product_id = u16::try_from(vid).ok().zip(u16::try_from(pid).ok());
+            },
+            zwp_tablet_v2::Event::Done if data.device_id.is_none() => {
+                let tablet_seat = match data.tablet_seat.as_ref() {
+                    Some(tablet_seat) => tablet_seat,
+                    None => return,
+                };
+                data.device_id =
+                    state.add_tablet_device(tablet_seat, data.name.clone(), data.product_id);
+            },
+            zwp_tablet_v2::Event::Removed => {
+                if let Some(device_id) = data.device_id.take() {
+                    state.remove_tablet_device(device_id);
+                }
+                tablet.destroy();
+            },
+            _ => (),
+        }
     }
 }
 
-impl Dispatch<ZwpTabletPadV2, (), WinitState> for TabletManager {
+/// The description of a tablet, reported as a device once it's complete.
+#[derive(Debug, Default)]
+struct TabletData {
+    inner: Mutex<TabletDataInner>,
+}
+
+#[derive(Debug, Default)]
+struct TabletDataInner {
+    /// The tablet seat which added the tablet.
+    tablet_seat: Option<ZwpTabletSeatV2>,
+
+    name: String,
+
+    /// The vendor and product IDs.
+    product_id: Option<(u16, u16)>,
+
+    /// The device reported for the tablet, once the description is `done`.
+    device_id: Option<DeviceId>,
+}
+
+impl Dispatch<ZwpTabletPadV2, TabletPadData, WinitState> for TabletManager {
     event_created_child!(WinitState, ZwpTabletPadV2, [
         zwp_tablet_pad_v2::EVT_GROUP_OPCODE => (ZwpTabletPadGroupV2, Default::default()),
     ]);
 
     fn event(
-        _: &mut WinitState,
-        _: &ZwpTabletPadV2,
-        _: <ZwpTabletPadV2 as Proxy>::Event,
-        _: &(),
+        state: &mut WinitState,
+        pad: &ZwpTabletPadV2,
+        event: <ZwpTabletPadV2 as Proxy>::Event,
+        data: &TabletPadData,
         _: &Connection,
         _: &QueueHandle<WinitState>,
     ) {
+        match event {
+            PadEvent::Group { pad_group } => {
+                let mut data = data.inner.lock().unwrap();
+                pad_group.data::<TabletPadFeatureData>().unwrap().attach(pad, data.groups.len());
+                data.groups.push(pad_group);
+            },
+            PadEvent::Enter { tablet, surface, .. } => {
+                let mut data = data.inner.lock().unwrap();
+                data.surface = Some(surface);
+                data.device_id = tablet
+                    .data::<TabletData>()
+                    .and_then(|tablet| tablet.inner.lock().unwrap().device_id);
+            },
+            PadEvent::Leave { .. } => {
+                let mut data = data.inner.lock().unwrap();
+                data.surface = None;
+                data.device_id = None;
+            },
+            PadEvent::Button { button, state: button_state, .. } => {
+                if let Some(event) = pad_button_event(button, button_state) {
+                    push_pad_event(state, pad, event);
+                }
+            },
+            PadEvent::Removed => {
+                let mut data = data.inner.lock().unwrap();
+                for ring in data.rings.drain(..) {
+                    ring.destroy();
+                }
+                for strip in data.strips.drain(..) {
+                    strip.destroy();
+                }
+                for dial in data.dials.drain(..) {
+                    dial.destroy();
+                }
+                for group in data.groups.drain(..) {
+                    group.destroy();
+                }
+                pad.destroy();
+            },
+            _ => (),
+        }
     }
 }
 
-impl Dispatch<ZwpTabletPadGroupV2, (), WinitState> for TabletManager {
+impl Dispatch<ZwpTabletPadGroupV2, TabletPadFeatureData, WinitState> for TabletManager {
     event_created_child!(WinitState, ZwpTabletPadGroupV2, [
         zwp_tablet_pad_group_v2::EVT_RING_OPCODE => (ZwpTabletPadRingV2, Default::default()),
         zwp_tablet_pad_group_v2::EVT_STRIP_OPCODE => (ZwpTabletPadStripV2, Default::default()),
@@ -354,59 +458,346 @@ impl Dispatch<ZwpTabletPadGroupV2, (), WinitState> for TabletManager {
     ]);
 
     fn event(
-        _: &mut WinitState,
+        state: &mut WinitState,
         _: &ZwpTabletPadGroupV2,
-        _: <ZwpTabletPadGroupV2 as Proxy>::Event,
-        _: &(),
+        event: <ZwpTabletPadGroupV2 as Proxy>::Event,
+        data: &TabletPadFeatureData,
         _: &Connection,
         _: &QueueHandle<WinitState>,
     ) {
+        let (pad, group) = match data.pad() {
+            Some(pad) => pad,
+            None => return,
+        };
+        let pad_data = pad.data::<TabletPadData>().unwrap();
+
+        // Rings, strips and dials are numbered across all the groups of the pad.
+        match event {
+            GroupEvent::Ring { ring } => {
+                let mut pad_data = pad_data.inner.lock().unwrap();
+                ring.data::<TabletPadFeatureData>().unwrap().attach(&pad, pad_data.rings.len());
+                pad_data.rings.push(ring);
+            },
+            GroupEvent::Strip { strip } => {
+                let mut pad_data = pad_data.inner.lock().unwrap();
+                strip.data::<TabletPadFeatureData>().unwrap().attach(&pad, pad_data.strips.len());
+                pad_data.strips.push(strip);
+            },
+            GroupEvent::Dial { dial } => {
+                let mut pad_data = pad_data.inner.lock().unwrap();
+                dial.data::<TabletPadFeatureData>().unwrap().attach(&pad, pad_data.dials.len());
+                pad_data.dials.push(dial);
+            },
+            GroupEvent::ModeSwitch { mode, .. } => {
+                push_pad_event(state, &pad, DeviceEvent::TabletPadModeSwitch { group, mode });
+            },
+            _ => (),
+        }
     }
 }
 
-impl Dispatch<ZwpTabletPadRingV2, (), WinitState> for TabletManager {
+impl Dispatch<ZwpTabletPadRingV2, TabletPadFeatureData, WinitState> for TabletManager {
     fn event(
-        _: &mut WinitState,
+        state: &mut WinitState,
         _: &ZwpTabletPadRingV2,
-        _: <ZwpTabletPadRingV2 as Proxy>::Event,
-        _: &(),
+        event: <ZwpTabletPadRingV2 as Proxy>::Event,
+        data: &TabletPadFeatureData,
         _: &Connection,
         _: &QueueHandle<WinitState>,
     ) {
+        if let Some((pad, event)) = data.handle(|inner| inner.ring_event(event)) {
+            push_pad_event(state, &pad, event);
+        }
     }
 }
 
-impl Dispatch<ZwpTabletPadStripV2, (), WinitState> for TabletManager {
+impl Dispatch<ZwpTabletPadStripV2, TabletPadFeatureData, WinitState> for TabletManager {
     fn event(
-        _: &mut WinitState,
+        state: &mut WinitState,
         _: &ZwpTabletPadStripV2,
-        _: <ZwpTabletPadStripV2 as Proxy>::Event,
-        _: &(),
+        event: <ZwpTabletPadStripV2 as Proxy>::Event,
+        data: &TabletPadFeatureData,
         _: &Connection,
         _: &QueueHandle<WinitState>,
     ) {
+        if let Some((pad, event)) = data.handle(|inner| inner.strip_event(event)) {
+            push_pad_event(state, &pad, event);
+        }
     }
 }
 
-impl Dispatch<ZwpTabletPadDialV2, (), WinitState> for TabletManager {
+impl Dispatch<ZwpTabletPadDialV2, TabletPadFeatureData, WinitState> for TabletManager {
     fn event(
-        _: &mut WinitState,
+        state: &mut WinitState,
         _: &ZwpTabletPadDialV2,
-        _: <ZwpTabletPadDialV2 as Proxy>::Event,
-        _: &(),
+        event: <ZwpTabletPadDialV2 as Proxy>::Event,
+        data: &TabletPadFeatureData,
         _: &Connection,
         _: &QueueHandle<WinitState>,
     ) {
+        if let Some((pad, event)) = data.handle(|inner| inner.dial_event(event)) {
+            push_pad_event(state, &pad, event);
+        }
+    }
+}
+
+/// Send the event as a device event and to the window focused by the pad.
+///
+/// Nothing is sent when none of the windows are focused, like the other pad events.
+fn push_pad_event(state: &mut WinitState, pad: &ZwpTabletPadV2, event: DeviceEvent) {
+    let data = pad.data::<TabletPadData>().unwrap().inner.lock().unwrap();
+    let window_id = match data.surface.as_ref() {
+        Some(surface) => crate::make_wid(surface),
+        None => return,
+    };
+
+    if state.windows.get_mut().contains_key(&window_id) {
+        state.events_sink.push_device_event(data.device_id, event);
+        if let Some(event) = pad_window_event(event, data.device_id) {
+            state.events_sink.push_window_event(event, window_id);
+        }
+    }
+}
+
+/// The event of a button of a pad.
+fn pad_button_event(button: u32, state: WEnum<PadButtonState>) -> Option<DeviceEvent> {
+    let state = match state {
+        WEnum::Value(PadButtonState::Released) => ElementState::Released,
+        WEnum::Value(PadButtonState::Pressed) => ElementState::Pressed,
+        _ => return None,
+    };
+    Some(DeviceEvent::TabletPadButton { button, state })
+}
+
+/// The window event matching a pad device event.
+fn pad_window_event(event: DeviceEvent, device_id: Option<DeviceId>) -> Option<WindowEvent> {
+    let event = match event {
+        DeviceEvent::TabletPadButton { button, state } => {
+            WindowEvent::TabletPadButton { device_id, button, state }
+        },
+        DeviceEvent::TabletPadRing { ring, angle } => {
+            WindowEvent::TabletPadRing { device_id, ring, angle }
+        },
+        DeviceEvent::TabletPadStrip { strip, position } => {
+            WindowEvent::TabletPadStrip { device_id, strip, position }
+        },
+        DeviceEvent::TabletPadDial { dial, delta } => {
+            WindowEvent::TabletPadDial { device_id, dial, delta }
+        },
+        DeviceEvent::TabletPadModeSwitch { group, mode } => {
+            WindowEvent::TabletPadModeSwitch { device_id, group, mode }
+        },
+        _ => return None,
+    };
+    Some(event)
+}
+
+#[derive(Debug, Default)]
+struct TabletPadData {
+    inner: Mutex<TabletPadDataInner>,
+}
+
+#[derive(Debug, Default)]
+struct TabletPadDataInner {
+    /// Surface the pad is focused on.
+    surface: Option<WlSurface>,
+
+    /// The device of the tablet the pad is attached to, set when it enters a surface.
+    device_id: Option<DeviceId>,
+
+    /// The objects offered by the pad, which must be destroyed along with it.
+    groups: Vec<ZwpTabletPadGroupV2>,
+    rings: Vec<ZwpTabletPadRingV2>,
+    strips: Vec<ZwpTabletPadStripV2>,
+    dials: Vec<ZwpTabletPadDialV2>,
+}
+
+/// The data of a group, ring, strip or dial of a pad.
+#[derive(Debug, Default)]
+struct TabletPadFeatureData {
+    inner: Mutex<TabletPadFeatureDataInner>,
+}
+
+#[derive(Debug, Default)]
+struct TabletPadFeatureDataInner {
+    /// The pad offering the feature, set once the event offering it is received.
+    pad: Option<ZwpTabletPadV2>,
+
+    /// The index of the feature among the ones of the same kind on the pad.
+    index: u32,
+
+    /// The value to send on the next `frame`, `Some(None)` when the finger was lifted.
+    pending: Option<Option<f64>>,
+}
+
+impl TabletPadFeatureData {
+    fn attach(&self, pad: &ZwpTabletPadV2, index: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.pad = Some(pad.clone());
+        inner.index = index as u32;
+    }
+
+    fn pad(&self) -> Option<(ZwpTabletPadV2, u32)> {
+        let inner = self.inner.lock().unwrap();
+        Some((inner.pad.clone()?, inner.index))
+    }
+
+    /// Handle an event of the feature, returning the pad along with the event to send.
+    fn handle(
+        &self,
+        f: impl FnOnce(&mut TabletPadFeatureDataInner) -> Option<DeviceEvent>,
+    ) -> Option<(ZwpTabletPadV2, DeviceEvent)> {
+        let mut inner = self.inner.lock().unwrap();
+        let event = f(&mut inner)?;
+        Some((inner.pad.clone()?, event))
+    }
+}
+
+impl TabletPadFeatureDataInner {
+    fn ring_event(&mut self, event: RingEvent) -> Option<DeviceEvent> {
+        match event {
+            RingEvent::Angle { degrees } => self.pending = Some(Some(degrees)),
+            RingEvent::Stop => self.pending = Some(None),
+            RingEvent::Frame { .. } => {
+                let angle = self.pending.take()?;
+                return Some(DeviceEvent::TabletPadRing { ring: self.index, angle });
+            },
+            _ => (),
+        }
+        None
+    }
+
+    fn strip_event(&mut self, event: StripEvent) -> Option<DeviceEvent> {
+        match event {
+            StripEvent::Position { position } => {
+                self.pending = Some(Some(position as f64 / u16::MAX as f64))
+            },
+            StripEvent::Stop => self.pending = Some(None),
+            StripEvent::Frame { .. } => {
+                let position = self.pending.take()?;
+                return Some(DeviceEvent::TabletPadStrip { strip: self.index, position });
+            },
+            _ => (),
+        }
+        None
+    }
+
+    fn dial_event(&mut self, event: DialEvent) -> Option<DeviceEvent> {
+        match event {
+            DialEvent::Delta { value120 } => {
+                let delta = self.pending.flatten().unwrap_or_default() + value120 as f64 / 120.;
+                self.pending = Some(Some(delta));
+            },
+            DialEvent::Frame { .. } => {
+                let delta = self.pending.take()??;
+                return Some(DeviceEvent::TabletPadDial { dial: self.index, delta });
+            },
+            _ => (),
+        }
+        None
     }
 }
 
 delegate_dispatch!(WinitState: [ZwpTabletManagerV2: GlobalData] => TabletManager);
 delegate_dispatch!(WinitState: [ZwpTabletManagerV2: ()] => TabletManager);
 delegate_dispatch!(WinitState: [ZwpTabletSeatV2: ()] => TabletManager);
-delegate_dispatch!(WinitState: [ZwpTabletV2: ()] => TabletManager);
+delegate_dispatch!(WinitState: [ZwpTabletV2: TabletData] => TabletManager);
 delegate_dispatch!(WinitState: [ZwpTabletToolV2: TabletToolData] => TabletManager);
-delegate_dispatch!(WinitState: [ZwpTabletPadV2: ()] => TabletManager);
-delegate_dispatch!(WinitState: [ZwpTabletPadGroupV2: ()] => TabletManager);
-delegate_dispatch!(WinitState: [ZwpTabletPadRingV2: ()] => TabletManager);
-delegate_dispatch!(WinitState: [ZwpTabletPadStripV2: ()] => TabletManager);
-delegate_dispatch!(WinitState: [ZwpTabletPadDialV2: ()] => TabletManager);
+delegate_dispatch!(WinitState: [ZwpTabletPadV2: TabletPadData] => TabletManager);
+delegate_dispatch!(WinitState: [ZwpTabletPadGroupV2: TabletPadFeatureData] => TabletManager);
+delegate_dispatch!(WinitState: [ZwpTabletPadRingV2: TabletPadFeatureData] => TabletManager);
+delegate_dispatch!(WinitState: [ZwpTabletPadStripV2: TabletPadFeatureData] => TabletManager);
+delegate_dispatch!(WinitState: [ZwpTabletPadDialV2: TabletPadFeatureData] => TabletManager);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(index: u32) -> TabletPadFeatureDataInner {
+        TabletPadFeatureDataInner { index, ..Default::default() }
+    }
+
+    #[test]
+    fn ring_events_are_sent_on_frame() {
+        let mut ring = feature(1);
+        assert_eq!(ring.ring_event(RingEvent::Frame { time: 0 }), None);
+        assert_eq!(ring.ring_event(RingEvent::Angle { degrees: 90. }), None);
+        assert_eq!(
+            ring.ring_event(RingEvent::Frame { time: 0 }),
+            Some(DeviceEvent::TabletPadRing { ring: 1, angle: Some(90.) })
+        );
+        // The value is only sent once.
+        assert_eq!(ring.ring_event(RingEvent::Frame { time: 0 }), None);
+        assert_eq!(ring.ring_event(RingEvent::Stop), None);
+        assert_eq!(
+            ring.ring_event(RingEvent::Frame { time: 0 }),
+            Some(DeviceEvent::TabletPadRing { ring: 1, angle: None })
+        );
+    }
+
+    #[test]
+    fn strip_positions_are_normalized() {
+        let mut strip = feature(0);
+        strip.strip_event(StripEvent::Position { position: u16::MAX as u32 });
+        assert_eq!(
+            strip.strip_event(StripEvent::Frame { time: 0 }),
+            Some(DeviceEvent::TabletPadStrip { strip: 0, position: Some(1.) })
+        );
+        strip.strip_event(StripEvent::Position { position: 0 });
+        assert_eq!(
+            strip.strip_event(StripEvent::Frame { time: 0 }),
+            Some(DeviceEvent::TabletPadStrip { strip: 0, position: Some(0.) })
+        );
+        strip.strip_event(StripEvent::Stop);
+        assert_eq!(
+            strip.strip_event(StripEvent::Frame { time: 0 }),
+            Some(DeviceEvent::TabletPadStrip { strip: 0, position: None })
+        );
+    }
+
+    #[test]
+    fn dial_deltas_accumulate_until_frame() {
+        let mut dial = feature(2);
+        assert_eq!(dial.dial_event(DialEvent::Frame { time: 0 }), None);
+        dial.dial_event(DialEvent::Delta { value120: 120 });
+        dial.dial_event(DialEvent::Delta { value120: 60 });
+        assert_eq!(
+            dial.dial_event(DialEvent::Frame { time: 0 }),
+            Some(DeviceEvent::TabletPadDial { dial: 2, delta: 1.5 })
+        );
+        dial.dial_event(DialEvent::Delta { value120: -120 });
+        assert_eq!(
+            dial.dial_event(DialEvent::Frame { time: 0 }),
+            Some(DeviceEvent::TabletPadDial { dial: 2, delta: -1. })
+        );
+    }
+
+    #[test]
+    fn pad_events_map_to_window_events() {
+        let device_id = Some(DeviceId::from_raw(3));
+
+        let event = pad_button_event(4, WEnum::Value(PadButtonState::Pressed)).unwrap();
+        assert_eq!(event, DeviceEvent::TabletPadButton { button: 4, state: ElementState::Pressed });
+        assert_eq!(
+            pad_window_event(event, device_id),
+            Some(WindowEvent::TabletPadButton {
+                device_id,
+                button: 4,
+                state: ElementState::Pressed
+            })
+        );
+        assert_eq!(pad_button_event(4, WEnum::Unknown(2)), None);
+
+        let event = DeviceEvent::TabletPadModeSwitch { group: 1, mode: 2 };
+        assert_eq!(
+            pad_window_event(event, device_id),
+            Some(WindowEvent::TabletPadModeSwitch { device_id, group: 1, mode: 2 })
+        );
+        let event = DeviceEvent::TabletPadDial { dial: 0, delta: 0.5 };
+        assert_eq!(
+            pad_window_event(event, None),
+            Some(WindowEvent::TabletPadDial { device_id: None, dial: 0, delta: 0.5 })
+        );
+        assert_eq!(pad_window_event(DeviceEvent::Added, device_id), None);
+    }
+}
@@ -10,7 +10,7 @@ use wayland_client::protocol::wl_shm::Format;
 use wayland_client::{Connection, Dispatch, Proxy, QueueHandle, delegate_dispatch};
 use wayland_protocols::xdg::toplevel_icon::v1::client::xdg_toplevel_icon_manager_v1::XdgToplevelIconManagerV1;
 use wayland_protocols::xdg::toplevel_icon::v1::client::xdg_toplevel_icon_v1::XdgToplevelIconV1;
-use winit_core::icon::{Icon, RgbaIcon};
+use winit_core::icon::{Icon, RgbaIcon, RgbaIconSet};
 
 use crate::image_to_buffer;
 use crate::state::WinitState;
@@ -29,30 +29,42 @@ pub enum ToplevelIconError {
 
 #[derive(Debug)]
 pub struct ToplevelIcon {
-    buffer: Buffer,
+    /// The buffers with their scale.
+    buffers: Vec<(Buffer, i32)>,
 }
 
 impl ToplevelIcon {
     pub fn new(icon: Icon, pool: &mut SlotPool) -> Result<Self, ToplevelIconError> {
-        let icon = match icon.cast_ref::<RgbaIcon>() {
-            Some(icon) => icon,
-            None => return Err(ToplevelIconError::Unsupported),
+        let icons = if let Some(icon) = icon.cast_ref::<RgbaIcon>() {
+            vec![(icon, 1)]
+        } else if let Some(icons) = icon.cast_ref::<RgbaIconSet>() {
+            icons.icons().iter().map(|(icon, scale)| (icon, *scale)).collect()
+        } else {
+            return Err(ToplevelIconError::Unsupported);
         };
 
-        let buffer = image_to_buffer(
-            icon.width() as i32,
-            icon.height() as i32,
-            icon.buffer(),
-            Format::Argb8888,
-            pool,
-        )
-        .unwrap();
-
-        Ok(Self { buffer })
+        let buffers = icons
+            .into_iter()
+            .map(|(icon, scale)| {
+                let buffer = image_to_buffer(
+                    icon.width() as i32,
+                    icon.height() as i32,
+                    icon.buffer(),
+                    Format::Argb8888,
+                    pool,
+                )
+                .unwrap();
+                (buffer, scale as i32)
+            })
+            .collect();
+
+        Ok(Self { buffers })
     }
 
     pub fn add_buffer(&self, xdg_toplevel_icon: &XdgToplevelIconV1) {
-        xdg_toplevel_icon.add_buffer(self.buffer.wl_buffer(), 1);
+        for (buffer, scale) in &self.buffers {
+            xdg_toplevel_icon.add_buffer(buffer.wl_buffer(), *scale);
+        }
     }
 }
 
@@ -0,0 +1,698 @@
+//! The builtin decorations frame, drawn in the style of GNOME's Adwaita.
+//!
+//! The look follows `sctk-adwaita`: a title bar with rounded top corners and round buttons, a
+//! thin border and a shadow the window can be resized from.
+
+#[cfg(feature = "csd-adwaita")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "csd-adwaita")]
+use std::sync::OnceLock;
+#[cfg(feature = "csd-adwaita")]
+use std::{env, fs};
+
+#[cfg(feature = "csd-adwaita")]
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont, VariableFont, point};
+use dpi::{LogicalInsets, LogicalPosition};
+use winit_core::window::{ResizeDirection, Theme};
+
+use super::frame::{Frame, FrameCanvas, FrameEdge, FrameHit, FrameState};
+
+/// Whether the title bar is hidden, leaving only the border.
+const HIDE_TITLEBAR: bool = cfg!(feature = "csd-adwaita-notitlebar");
+
+/// The height of the title bar.
+const HEADER_SIZE: u32 = 35;
+
+/// The radius of the top corners of the title bar.
+const CORNER_RADIUS: f64 = 10.;
+
+/// The width of the border around the window.
+const BORDER_SIZE: u32 = 1;
+
+/// The width of the shadow, which the window can be resized from.
+const SHADOW_SIZE: u32 = 12;
+
+/// The length along the edges of the corners resizing the window in two directions.
+const RESIZE_CORNER_SIZE: f64 = 24.;
+
+/// The diameter of the buttons.
+const BUTTON_SIZE: f64 = 24.;
+
+/// The distance between the buttons and the edges of the title bar.
+const BUTTON_MARGIN: f64 = 5.;
+
+/// The distance between two buttons.
+const BUTTON_SPACING: f64 = 13.;
+
+/// The distance between the title and the ends of the title bar or the buttons.
+#[cfg(feature = "csd-adwaita")]
+const TITLE_MARGIN: f64 = 12.;
+
+/// The size of the title in logical pixels, GNOME's 11pt.
+#[cfg(feature = "csd-adwaita")]
+const TITLE_SIZE: f32 = 11. * 96. / 72.;
+
+/// The font files tried for the title, in order of preference.
+#[cfg(feature = "csd-adwaita")]
+const TITLE_FONTS: &[&str] = &[
+    "Cantarell-VF.otf",
+    "Cantarell-Bold.otf",
+    "Cantarell-Regular.otf",
+    "NotoSans-Bold.ttf",
+    "DejaVuSans-Bold.ttf",
+    "LiberationSans-Bold.ttf",
+    "Ubuntu-B.ttf",
+    "FreeSansBold.ttf",
+];
+
+/// The maximum depth of the font directories searched for the title font.
+#[cfg(feature = "csd-adwaita")]
+const MAX_FONT_DIR_DEPTH: usize = 4;
+
+/// The decorations winit draws unless the application draws its own.
+///
+/// The title is only drawn with the `csd-adwaita` feature, when one of the [`TITLE_FONTS`] is
+/// installed.
+#[derive(Debug, Default)]
+pub struct BuiltinFrame {
+    /// The title rendered for the last draw.
+    #[cfg(feature = "csd-adwaita")]
+    title: Option<TitleText>,
+}
+
+impl BuiltinFrame {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Frame for BuiltinFrame {
+    fn borders(&self, state: &FrameState) -> LogicalInsets<u32> {
+        let border = if state.maximized || state.tiled { 0 } else { BORDER_SIZE };
+        let header = if HIDE_TITLEBAR { border } else { HEADER_SIZE };
+        LogicalInsets::new(header, border, border, border)
+    }
+
+    fn shadow(&self, state: &FrameState) -> LogicalInsets<u32> {
+        let shadow = if state.maximized || state.tiled { 0 } else { SHADOW_SIZE };
+        LogicalInsets::new(shadow, shadow, shadow, shadow)
+    }
+
+    fn draw(&mut self, state: &FrameState, canvas: &mut FrameCanvas<'_>) {
+        let outline = Outline::new(self, state);
+        let colors = Colors::new(state);
+        let buttons = buttons(state, &outline);
+        let scale = canvas.scale() as f64;
+        let (width, height) = (canvas.width() as usize, canvas.height() as usize);
+        let origin = canvas.position();
+        let draw_header = canvas.edge() == FrameEdge::Top && !HIDE_TITLEBAR;
+
+        #[cfg(feature = "csd-adwaita")]
+        let title = draw_header.then(|| self.title_text(state, canvas.scale())).flatten();
+        #[cfg(feature = "csd-adwaita")]
+        let title = title.map(|title| (title, title_position(title, &outline, &buttons, scale)));
+
+        let buffer = canvas.buffer_mut();
+        for row in 0..height {
+            for column in 0..width {
+                // The center of the pixel in logical pixels relative to the decorations.
+                let x = origin.x as f64 + (column as f64 + 0.5) / scale;
+                let y = origin.y as f64 + (row as f64 + 0.5) / scale;
+
+                let distance = outline.distance(x, y);
+                let coverage = (0.5 - distance * scale).clamp(0., 1.);
+                let mut pixel = Pixel::default();
+                if coverage < 1. {
+                    pixel = pixel.over(0x000000, colors.shadow(distance.max(0.)));
+                }
+
+                // The title bar above its bottom line, and the border elsewhere.
+                let fill = if !HIDE_TITLEBAR && y < outline.header - 1. / scale {
+                    colors.header
+                } else {
+                    colors.border
+                };
+                pixel = pixel.over(fill, coverage);
+
+                if draw_header {
+                    #[cfg(feature = "csd-adwaita")]
+                    if let Some((title, (left, top, right))) = title {
+                        let column = origin.x as isize * scale as isize + column as isize;
+                        let row = origin.y as isize * scale as isize + row as isize;
+                        if column < right {
+                            pixel =
+                                pixel.over(colors.font, title.coverage(column - left, row - top));
+                        }
+                    }
+
+                    for button in &buttons {
+                        pixel = button.draw(pixel, state, &colors, x, y, scale);
+                    }
+                }
+
+                let offset = (row * width + column) * 4;
+                buffer[offset..offset + 4].copy_from_slice(&pixel.to_argb().to_le_bytes());
+            }
+        }
+    }
+
+    fn hit_test(&self, state: &FrameState, position: LogicalPosition<f64>) -> FrameHit {
+        let outline = Outline::new(self, state);
+        let borders = self.borders(state);
+        let (x, y) = (position.x, position.y);
+
+        if let Some(button) = buttons(state, &outline).iter().find(|button| button.contains(x, y)) {
+            return button.hit;
+        }
+
+        // The window is resized from the border and the shadow, but moved from the title bar.
+        let header = if HIDE_TITLEBAR { borders.top as f64 } else { 0. };
+        let inside = x >= outline.left + borders.left as f64
+            && x < outline.right - borders.right as f64
+            && y >= outline.top + header
+            && y < outline.bottom - borders.bottom as f64;
+        if !inside {
+            if !state.resizable || state.maximized || state.tiled {
+                return FrameHit::None;
+            }
+
+            let west = x < outline.left + RESIZE_CORNER_SIZE;
+            let east = x >= outline.right - RESIZE_CORNER_SIZE;
+            let north = y < outline.top + RESIZE_CORNER_SIZE;
+            let south = y >= outline.bottom - RESIZE_CORNER_SIZE;
+            let direction = match (north, south, west, east) {
+                (true, _, true, _) => ResizeDirection::NorthWest,
+                (true, _, _, true) => ResizeDirection::NorthEast,
+                (_, true, true, _) => ResizeDirection::SouthWest,
+                (_, true, _, true) => ResizeDirection::SouthEast,
+                (true, ..) => ResizeDirection::North,
+                (_, true, ..) => ResizeDirection::South,
+                (_, _, true, _) => ResizeDirection::West,
+                _ => ResizeDirection::East,
+            };
+            return FrameHit::Resize(direction);
+        }
+
+        if y < outline.header { FrameHit::Move } else { FrameHit::None }
+    }
+}
+
+/// The outline of the window, title bar and border included, in logical pixels relative to the
+/// decorations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Outline {
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+
+    /// The bottom of the title bar.
+    header: f64,
+
+    /// The radius of the top corners.
+    radius: f64,
+}
+
+impl Outline {
+    fn new(frame: &BuiltinFrame, state: &FrameState) -> Self {
+        let borders = frame.borders(state);
+        let shadow = frame.shadow(state);
+        let width = borders.left + state.surface_size.width + borders.right;
+        let height = borders.top + state.surface_size.height + borders.bottom;
+        let square = HIDE_TITLEBAR || state.maximized || state.tiled;
+
+        Self {
+            left: shadow.left as f64,
+            top: shadow.top as f64,
+            right: (shadow.left + width) as f64,
+            bottom: (shadow.top + height) as f64,
+            header: (shadow.top + borders.top) as f64,
+            radius: if square { 0. } else { CORNER_RADIUS },
+        }
+    }
+
+    /// The distance of a point to the outline, negative inside of it.
+    fn distance(&self, x: f64, y: f64) -> f64 {
+        let corner_top = self.top + self.radius;
+        if y < corner_top && (x < self.left + self.radius || x > self.right - self.radius) {
+            let corner_x = x.clamp(self.left + self.radius, self.right - self.radius);
+            return (x - corner_x).hypot(y - corner_top) - self.radius;
+        }
+
+        let dx = (self.left - x).max(x - self.right);
+        let dy = (self.top - y).max(y - self.bottom);
+        if dx > 0. && dy > 0. { dx.hypot(dy) } else { dx.max(dy) }
+    }
+}
+
+/// A button of the title bar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Button {
+    /// What the button does, [`FrameHit::Close`], [`FrameHit::Maximize`] or
+    /// [`FrameHit::Minimize`].
+    hit: FrameHit,
+
+    /// The center of the button.
+    x: f64,
+    y: f64,
+}
+
+impl Button {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        (x - self.x).abs() < BUTTON_SIZE / 2. && (y - self.y).abs() < BUTTON_SIZE / 2.
+    }
+
+    /// Draw the part of the button over the center of a pixel at `x` and `y`.
+    fn draw(
+        &self,
+        pixel: Pixel,
+        state: &FrameState,
+        colors: &Colors,
+        x: f64,
+        y: f64,
+        scale: f64,
+    ) -> Pixel {
+        // Skip the pixels away from the button.
+        let (x, y) = (x - self.x, y - self.y);
+        if x.abs() > BUTTON_SIZE / 2. + 1. || y.abs() > BUTTON_SIZE / 2. + 1. {
+            return pixel;
+        }
+
+        let background = if state.hovered == self.hit { colors.button_hover } else { colors.idle };
+        let circle = (0.5 + (BUTTON_SIZE / 2. - x.hypot(y)) * scale).clamp(0., 1.);
+        let pixel = pixel.over(background, circle);
+
+        // The coverage of a line of the icon between two points relative to the center.
+        let line = |(x0, y0): (f64, f64), (x1, y1): (f64, f64), width: f64| {
+            let (dx, dy) = (x1 - x0, y1 - y0);
+            let t = (((x - x0) * dx + (y - y0) * dy) / (dx * dx + dy * dy)).clamp(0., 1.);
+            let distance = (x - x0 - t * dx).hypot(y - y0 - t * dy);
+            (0.5 + (width / 2. - distance) * scale).clamp(0., 1.)
+        };
+        let square = |left: f64, top: f64, size: f64| {
+            let (right, bottom) = (left + size, top + size);
+            line((left, top), (right, top), 1.)
+                .max(line((right, top), (right, bottom), 1.))
+                .max(line((right, bottom), (left, bottom), 1.))
+                .max(line((left, bottom), (left, top), 1.))
+        };
+
+        let icon = match self.hit {
+            FrameHit::Close => {
+                line((-3.5, -3.5), (3.5, 3.5), 1.1).max(line((-3.5, 3.5), (3.5, -3.5), 1.1))
+            },
+            // Two overlapping windows to restore the window.
+            FrameHit::Maximize if state.maximized => square(-2., -4., 6.).max(square(-4., -2., 6.)),
+            FrameHit::Maximize => square(-4., -4., 8.),
+            _ => line((-4., 4.), (4., 4.), 1.),
+        };
+        pixel.over(colors.icon, icon)
+    }
+}
+
+/// The buttons of the title bar, from the right edge of the window.
+fn buttons(state: &FrameState, outline: &Outline) -> Vec<Button> {
+    if HIDE_TITLEBAR {
+        return Vec::new();
+    }
+
+    let hits = [
+        (FrameHit::Close, true),
+        (FrameHit::Maximize, state.can_maximize && state.resizable),
+        (FrameHit::Minimize, state.can_minimize),
+    ];
+    let mut x = outline.right - BUTTON_MARGIN - BUTTON_SIZE / 2.;
+    let y = outline.top + BUTTON_MARGIN + BUTTON_SIZE / 2.;
+    let mut buttons = Vec::new();
+    for (hit, _) in hits.into_iter().filter(|(_, shown)| *shown) {
+        buttons.push(Button { hit, x, y });
+        x -= BUTTON_SIZE + BUTTON_SPACING;
+    }
+    buttons
+}
+
+/// The colors of the decorations as `0xRRGGBB`, from `sctk-adwaita`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Colors {
+    header: u32,
+    idle: u32,
+    button_hover: u32,
+    icon: u32,
+    border: u32,
+    #[cfg_attr(not(feature = "csd-adwaita"), allow(dead_code))]
+    font: u32,
+    activated: bool,
+}
+
+impl Colors {
+    fn new(state: &FrameState) -> Self {
+        let (header, idle, button_hover, icon, border, font) = match (state.theme, state.activated)
+        {
+            (Theme::Light, true) => (0xebebeb, 0xd8d8d8, 0xcfcfcf, 0x2a2a2a, 0xdcdcdc, 0x2f2f2f),
+            (Theme::Light, false) => (0xfafafa, 0xf0f0f0, 0xd8d8d8, 0x949494, 0xdcdcdc, 0x969696),
+            (Theme::Dark, true) => (0x322e2e, 0x474343, 0x4f4f4f, 0xffffff, 0x3a3a3a, 0xffffff),
+            (Theme::Dark, false) => (0x262222, 0x312d2d, 0x393939, 0x909090, 0x3a3a3a, 0x909090),
+        };
+
+        Self { header, idle, button_hover, icon, border, font, activated: state.activated }
+    }
+
+    /// The opacity of the shadow at a distance from the window.
+    fn shadow(&self, distance: f64) -> f64 {
+        let (a, b, c) = if self.activated {
+            (0.2065055, 0.10461753, -0.0005424462)
+        } else {
+            (0.16829729, 0.2042998, 0.0017697986)
+        };
+        (a * (-b * distance).exp() + c).clamp(0., 1.)
+    }
+}
+
+/// A premultiplied color with its components between 0 and 1.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Pixel {
+    a: f64,
+    r: f64,
+    g: f64,
+    b: f64,
+}
+
+impl Pixel {
+    /// Draw an opaque `0xRRGGBB` color over the pixel, covering `coverage` of it.
+    fn over(self, color: u32, coverage: f64) -> Self {
+        let component = |shift: u32| f64::from((color >> shift) & 0xff) / 255. * coverage;
+        let rest = 1. - coverage;
+        Self {
+            a: coverage + self.a * rest,
+            r: component(16) + self.r * rest,
+            g: component(8) + self.g * rest,
+            b: component(0) + self.b * rest,
+        }
+    }
+
+    fn to_argb(self) -> u32 {
+        let byte = |component: f64| (component.clamp(0., 1.) * 255.).round() as u32;
+        byte(self.a) << 24 | byte(self.r) << 16 | byte(self.g) << 8 | byte(self.b)
+    }
+}
+
+/// The coverage of the title rendered at a scale, in pixels.
+#[cfg(feature = "csd-adwaita")]
+#[derive(Debug)]
+struct TitleText {
+    title: String,
+    scale: u32,
+    width: usize,
+    height: usize,
+    coverage: Vec<f32>,
+}
+
+#[cfg(feature = "csd-adwaita")]
+impl TitleText {
+    fn new(font: &FontVec, title: &str, scale: u32) -> Self {
+        let font = font.as_scaled(PxScale::from(TITLE_SIZE * scale as f32));
+
+        let mut glyphs = Vec::new();
+        let mut caret = 0.;
+        let mut previous = None;
+        for character in title.chars().filter(|character| !character.is_control()) {
+            let id = font.glyph_id(character);
+            if let Some(previous) = previous {
+                caret += font.kern(previous, id);
+            }
+            glyphs.push(id.with_scale_and_position(font.scale(), point(caret, font.ascent())));
+            caret += font.h_advance(id);
+            previous = Some(id);
+        }
+
+        let width = caret.ceil().max(0.) as usize;
+        let height = font.height().ceil().max(0.) as usize;
+        let mut coverage = vec![0.; width * height];
+        for glyph in glyphs {
+            let Some(outline) = font.outline_glyph(glyph) else { continue };
+            let bounds = outline.px_bounds();
+            outline.draw(|x, y, value| {
+                let x = bounds.min.x as isize + x as isize;
+                let y = bounds.min.y as isize + y as isize;
+                if (0..width as isize).contains(&x) && (0..height as isize).contains(&y) {
+                    let pixel = &mut coverage[y as usize * width + x as usize];
+                    *pixel = (*pixel + value).min(1.);
+                }
+            });
+        }
+
+        Self { title: title.to_owned(), scale, width, height, coverage }
+    }
+
+    /// The coverage of a pixel relative to the top-left corner of the title.
+    fn coverage(&self, x: isize, y: isize) -> f64 {
+        if (0..self.width as isize).contains(&x) && (0..self.height as isize).contains(&y) {
+            f64::from(self.coverage[y as usize * self.width + x as usize])
+        } else {
+            0.
+        }
+    }
+}
+
+#[cfg(feature = "csd-adwaita")]
+impl BuiltinFrame {
+    /// The title rendered for the state, which is kept until the title or the scale change.
+    fn title_text(&mut self, state: &FrameState, scale: u32) -> Option<&TitleText> {
+        let font = title_font()?;
+        let outdated = self
+            .title
+            .as_ref()
+            .is_none_or(|title| title.title != state.title || title.scale != scale);
+        if outdated {
+            self.title = Some(TitleText::new(font, &state.title, scale));
+        }
+        self.title.as_ref()
+    }
+}
+
+/// The left, top and right end of the title in pixels, centered on the title bar unless it
+/// overlaps the buttons.
+#[cfg(feature = "csd-adwaita")]
+fn title_position(
+    title: &TitleText,
+    outline: &Outline,
+    buttons: &[Button],
+    scale: f64,
+) -> (isize, isize, isize) {
+    let start = (outline.left + TITLE_MARGIN) * scale;
+    let end =
+        buttons.iter().map(|button| button.x - BUTTON_SIZE / 2.).fold(outline.right, f64::min)
+            - TITLE_MARGIN;
+    let end = end * scale;
+
+    let width = title.width as f64;
+    let left = ((outline.left + outline.right) * scale - width) / 2.;
+    let left = left.min(end - width).max(start);
+    let top = ((outline.top + outline.header) * scale - title.height as f64) / 2.;
+    (left.round() as isize, top.round() as isize, end.round() as isize)
+}
+
+/// The font of the title, loaded once.
+#[cfg(feature = "csd-adwaita")]
+fn title_font() -> Option<&'static FontVec> {
+    static FONT: OnceLock<Option<FontVec>> = OnceLock::new();
+    FONT.get_or_init(|| {
+        let path = find_font(&font_dirs(), TITLE_FONTS)?;
+        let data = fs::read(&path)
+            .inspect_err(|err| tracing::warn!("Failed to read the title font {path:?}: {err}"))
+            .ok()?;
+        let mut font = FontVec::try_from_vec(data)
+            .inspect_err(|err| tracing::warn!("Failed to load the title font {path:?}: {err}"))
+            .ok()?;
+        // Make the variable fonts bold.
+        font.set_variation(b"wght", 700.);
+        Some(font)
+    })
+    .as_ref()
+}
+
+/// The directories the fonts are searched in.
+#[cfg(feature = "csd-adwaita")]
+fn font_dirs() -> Vec<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+    let data_dirs = env::var_os("XDG_DATA_DIRS")
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+
+    let mut dirs = Vec::new();
+    dirs.extend(data_home.map(|data_home| data_home.join("fonts")));
+    dirs.extend(home.map(|home| home.join(".fonts")));
+    dirs.extend(env::split_paths(&data_dirs).map(|dir| dir.join("fonts")));
+    dirs
+}
+
+/// Find the file of the first of `names` in the directories or their subdirectories.
+#[cfg(feature = "csd-adwaita")]
+fn find_font(dirs: &[PathBuf], names: &[&str]) -> Option<PathBuf> {
+    fn visit(dir: &Path, names: &[&str], depth: usize, best: &mut Option<(usize, PathBuf)>) {
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                if depth < MAX_FONT_DIR_DEPTH {
+                    visit(&path, names, depth + 1, best);
+                }
+                continue;
+            }
+
+            let name = path.file_name().and_then(|name| name.to_str());
+            let Some(rank) = names.iter().position(|font| Some(*font) == name) else { continue };
+            if best.as_ref().is_none_or(|(best, _)| rank < *best) {
+                *best = Some((rank, path));
+            }
+        }
+    }
+
+    let mut best = None;
+    for dir in dirs {
+        visit(dir, names, 0, &mut best);
+    }
+    best.map(|(_, path)| path)
+}
+
+// The tests expect the title bar.
+#[cfg(all(test, not(feature = "csd-adwaita-notitlebar")))]
+mod tests {
+    use dpi::LogicalSize;
+
+    use super::*;
+
+    fn state() -> FrameState {
+        FrameState {
+            title: String::from("Title"),
+            surface_size: LogicalSize::new(200, 100),
+            scale_factor: 1.,
+            activated: true,
+            maximized: false,
+            tiled: false,
+            resizable: true,
+            can_minimize: true,
+            can_maximize: true,
+            theme: Theme::Light,
+            hovered: FrameHit::None,
+        }
+    }
+
+    fn hit(state: &FrameState, x: f64, y: f64) -> FrameHit {
+        BuiltinFrame::new().hit_test(state, LogicalPosition::new(x, y))
+    }
+
+    /// Draw an edge, returning its pixels.
+    fn draw(state: &FrameState, edge: FrameEdge, position: LogicalPosition<u32>) -> Vec<u32> {
+        let (width, height) = (20, 20);
+        let mut buffer = vec![0; width as usize * height as usize * 4];
+        let mut canvas = FrameCanvas::new(&mut buffer, edge, position, width, height, 1);
+        BuiltinFrame::new().draw(state, &mut canvas);
+        buffer.chunks(4).map(|pixel| u32::from_le_bytes(pixel.try_into().unwrap())).collect()
+    }
+
+    #[test]
+    fn maximized_windows_have_no_border() {
+        let frame = BuiltinFrame::new();
+        let mut state = state();
+        assert_eq!(frame.borders(&state), LogicalInsets::new(HEADER_SIZE, 1, 1, 1));
+        assert_eq!(frame.shadow(&state), LogicalInsets::new(12, 12, 12, 12));
+
+        state.maximized = true;
+        assert_eq!(frame.borders(&state), LogicalInsets::new(HEADER_SIZE, 0, 0, 0));
+        assert_eq!(frame.shadow(&state), LogicalInsets::new(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn outline_distance() {
+        let outline = Outline::new(&BuiltinFrame::new(), &state());
+        assert_eq!(
+            (outline.left, outline.top, outline.right, outline.bottom),
+            (12., 12., 214., 148.)
+        );
+        assert_eq!(outline.distance(100., 80.), -68.);
+        assert_eq!(outline.distance(100., 2.), 10.);
+        assert_eq!(outline.distance(220., 150.), 40f64.sqrt());
+        // The top corners are rounded.
+        assert!((outline.distance(12., 12.) - (200f64.sqrt() - 10.)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hits_the_title_bar() {
+        let state = state();
+        assert_eq!(hit(&state, 100., 20.), FrameHit::Move);
+        // The buttons from the right edge.
+        assert_eq!(hit(&state, 214. - 5. - 12., 29.), FrameHit::Close);
+        assert_eq!(hit(&state, 214. - 5. - 12. - 37., 29.), FrameHit::Maximize);
+        assert_eq!(hit(&state, 214. - 5. - 12. - 74., 29.), FrameHit::Minimize);
+        // The content.
+        assert_eq!(hit(&state, 100., 100.), FrameHit::None);
+    }
+
+    #[test]
+    fn hides_unsupported_buttons() {
+        let mut state = state();
+        state.can_minimize = false;
+        state.resizable = false;
+        let outline = Outline::new(&BuiltinFrame::new(), &state);
+        let hits: Vec<_> = buttons(&state, &outline).iter().map(|button| button.hit).collect();
+        assert_eq!(hits, [FrameHit::Close]);
+    }
+
+    #[test]
+    fn resizes_from_the_shadow() {
+        let mut state = state();
+        assert_eq!(hit(&state, 5., 80.), FrameHit::Resize(ResizeDirection::West));
+        assert_eq!(hit(&state, 12.5, 80.), FrameHit::Resize(ResizeDirection::West));
+        assert_eq!(hit(&state, 220., 80.), FrameHit::Resize(ResizeDirection::East));
+        assert_eq!(hit(&state, 100., 5.), FrameHit::Resize(ResizeDirection::North));
+        assert_eq!(hit(&state, 100., 155.), FrameHit::Resize(ResizeDirection::South));
+        assert_eq!(hit(&state, 5., 30.), FrameHit::Resize(ResizeDirection::NorthWest));
+        assert_eq!(hit(&state, 30., 5.), FrameHit::Resize(ResizeDirection::NorthWest));
+        assert_eq!(hit(&state, 220., 155.), FrameHit::Resize(ResizeDirection::SouthEast));
+
+        state.resizable = false;
+        assert_eq!(hit(&state, 5., 80.), FrameHit::None);
+    }
+
+    #[test]
+    fn draws_the_adwaita_colors() {
+        let mut state = state();
+        let top = draw(&state, FrameEdge::Top, LogicalPosition::new(90, 10));
+        // The shadow above the title bar, which is opaque.
+        assert_eq!(top[0] & 0x00ffffff, 0);
+        assert!(top[0] >> 24 > 0);
+        assert_eq!(top[5 * 20], 0xffebebeb);
+
+        state.theme = Theme::Dark;
+        state.activated = false;
+        let top = draw(&state, FrameEdge::Top, LogicalPosition::new(90, 10));
+        assert_eq!(top[5 * 20], 0xff262222);
+
+        let left = draw(&state, FrameEdge::Left, LogicalPosition::new(0, 47));
+        assert_eq!(left[12], 0xff3a3a3a);
+        assert!(left[0] >> 24 < left[11] >> 24);
+    }
+
+    #[test]
+    #[cfg(feature = "csd-adwaita")]
+    fn finds_the_preferred_font() {
+        let root = env::temp_dir().join(format!("winit-fonts-{}", std::process::id()));
+        let user = root.join("user");
+        let system = root.join("system/truetype/dejavu");
+        fs::create_dir_all(&user).unwrap();
+        fs::create_dir_all(&system).unwrap();
+
+        fs::write(user.join("Other.ttf"), b"").unwrap();
+        fs::write(user.join("DejaVuSans-Bold.ttf"), b"").unwrap();
+        fs::write(system.join("NotoSans-Bold.ttf"), b"").unwrap();
+
+        let dirs = [user.clone(), root.join("system"), root.join("missing")];
+        assert_eq!(find_font(&dirs, TITLE_FONTS), Some(system.join("NotoSans-Bold.ttf")));
+        assert_eq!(find_font(&dirs, &["Missing.ttf"]), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,882 @@
+//! Client side decorations frames.
+
+use std::error::Error;
+use std::fmt;
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dpi::{LogicalInsets, LogicalPosition, LogicalSize};
+use sctk::reexports::client::backend::ObjectId;
+use sctk::reexports::client::protocol::wl_shm;
+use sctk::reexports::client::protocol::wl_subsurface::WlSubsurface;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Proxy, QueueHandle};
+use sctk::reexports::csd_frame::{
+    CursorIcon, DecorationsFrame, FrameAction, FrameClick, ResizeEdge, WindowManagerCapabilities,
+    WindowState as XdgWindowState,
+};
+use sctk::shell::WaylandSurface;
+use sctk::shm::Shm;
+use sctk::shm::slot::SlotPool;
+use sctk::subcompositor::SubcompositorState;
+use tracing::warn;
+use winit_core::window::{ResizeDirection, Theme};
+
+use crate::state::WinitState;
+
+/// The maximum delay between two clicks on the title bar to toggle the maximized state.
+const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(400);
+
+/// Client-side decorations drawn by the application.
+///
+/// The frame is passed with [`WindowAttributesWayland::with_frame`] and replaces the builtin
+/// decorations of winit, which are an implementation of this trait drawing the Adwaita look.
+///
+/// Each edge of the frame is drawn by winit on its own subsurface placed below the window. Winit
+/// handles the pointer input over it, asking the frame what's under the pointer to move, resize,
+/// minimize, maximize or close the window.
+///
+/// Nothing is drawn while the window is fullscreen.
+///
+/// [`WindowAttributesWayland::with_frame`]: crate::WindowAttributesWayland::with_frame
+pub trait Frame: fmt::Debug + Send {
+    /// The size of the decorations around the window content in logical pixels.
+    fn borders(&self, state: &FrameState) -> LogicalInsets<u32>;
+
+    /// The size of the shadow around the borders in logical pixels.
+    ///
+    /// The shadow is drawn and hit-tested like the borders, to resize the window from outside of
+    /// it for example, but isn't part of the window geometry used by the compositor to place the
+    /// window. There's no shadow by default.
+    fn shadow(&self, state: &FrameState) -> LogicalInsets<u32> {
+        let _ = state;
+        LogicalInsets::new(0, 0, 0, 0)
+    }
+
+    /// Draw an edge of the decorations.
+    ///
+    /// This is called for every edge with a non-zero size, see [`FrameEdge`], with the canvas
+    /// covering only that edge and cleared beforehand.
+    fn draw(&mut self, state: &FrameState, canvas: &mut FrameCanvas<'_>);
+
+    /// The part of the decorations at the given position.
+    ///
+    /// The position is in logical pixels relative to the top-left corner of the decorations,
+    /// shadow included.
+    fn hit_test(&self, state: &FrameState, position: LogicalPosition<f64>) -> FrameHit;
+}
+
+/// An edge of the decorations, drawn on its own surface.
+///
+/// The top and bottom edges span the whole width of the decorations, while the left and right
+/// edges span the height of the window content between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameEdge {
+    Top,
+    Left,
+    Right,
+    Bottom,
+}
+
+impl FrameEdge {
+    const ALL: [Self; 4] = [Self::Top, Self::Left, Self::Right, Self::Bottom];
+
+    /// The position and size of the edge relative to the top-left corner of the decorations,
+    /// where `borders` includes the shadow.
+    fn rect(
+        self,
+        borders: LogicalInsets<u32>,
+        surface_size: LogicalSize<u32>,
+    ) -> (LogicalPosition<u32>, LogicalSize<u32>) {
+        let width = borders.left + surface_size.width + borders.right;
+        match self {
+            Self::Top => (LogicalPosition::new(0, 0), LogicalSize::new(width, borders.top)),
+            Self::Left => (
+                LogicalPosition::new(0, borders.top),
+                LogicalSize::new(borders.left, surface_size.height),
+            ),
+            Self::Right => (
+                LogicalPosition::new(borders.left + surface_size.width, borders.top),
+                LogicalSize::new(borders.right, surface_size.height),
+            ),
+            Self::Bottom => (
+                LogicalPosition::new(0, borders.top + surface_size.height),
+                LogicalSize::new(width, borders.bottom),
+            ),
+        }
+    }
+}
+
+/// The part of the decorations under the pointer.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameHit {
+    /// Nothing interactive, like a shadow.
+    #[default]
+    None,
+
+    /// The title bar, to move the window.
+    ///
+    /// Double clicking it toggles the maximized state and right clicking it shows the window
+    /// menu.
+    Move,
+
+    /// A border or a corner, to resize the window in the given direction.
+    Resize(ResizeDirection),
+
+    /// The minimize button.
+    Minimize,
+
+    /// The maximize button, which toggles the maximized state.
+    Maximize,
+
+    /// The close button.
+    Close,
+}
+
+/// The state of the window to draw the decorations for.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameState {
+    /// The title of the window.
+    pub title: String,
+
+    /// The size of the window content in logical pixels.
+    pub surface_size: LogicalSize<u32>,
+
+    /// The scale factor of the window.
+    pub scale_factor: f64,
+
+    /// Whether the window is activated.
+    pub activated: bool,
+
+    /// Whether the window is maximized.
+    pub maximized: bool,
+
+    /// Whether the window is tiled.
+    pub tiled: bool,
+
+    /// Whether the window can be resized.
+    pub resizable: bool,
+
+    /// Whether the compositor can minimize the window.
+    pub can_minimize: bool,
+
+    /// Whether the compositor can maximize the window.
+    pub can_maximize: bool,
+
+    /// The theme of the window, following the system one unless the application picked one.
+    pub theme: Theme,
+
+    /// The part of the decorations under the pointer.
+    pub hovered: FrameHit,
+}
+
+/// The buffer to draw an edge of the decorations into.
+#[derive(Debug)]
+pub struct FrameCanvas<'a> {
+    buffer: &'a mut [u8],
+    edge: FrameEdge,
+    position: LogicalPosition<u32>,
+    width: u32,
+    height: u32,
+    scale: u32,
+}
+
+impl<'a> FrameCanvas<'a> {
+    pub(super) fn new(
+        buffer: &'a mut [u8],
+        edge: FrameEdge,
+        position: LogicalPosition<u32>,
+        width: u32,
+        height: u32,
+        scale: u32,
+    ) -> Self {
+        Self { buffer, edge, position, width, height, scale }
+    }
+
+    /// The edge of the decorations to draw.
+    #[inline]
+    pub fn edge(&self) -> FrameEdge {
+        self.edge
+    }
+
+    /// The position of the edge in logical pixels relative to the top-left corner of the
+    /// decorations, shadow included.
+    #[inline]
+    pub fn position(&self) -> LogicalPosition<u32> {
+        self.position
+    }
+
+    /// The width of the buffer in pixels.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the buffer in pixels.
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The number of pixels in the buffer for one logical pixel.
+    #[inline]
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// The pixels of the buffer, row by row.
+    ///
+    /// Every pixel is a premultiplied ARGB `u32` stored in little endian.
+    #[inline]
+    pub fn buffer_mut(&mut self) -> &mut [u8] {
+        self.buffer
+    }
+}
+
+/// Creates the frames of a window, which is done again when the decorations reappear.
+#[derive(Clone)]
+pub struct FrameFactory(Arc<dyn Fn() -> Box<dyn Frame> + Send + Sync>);
+
+impl FrameFactory {
+    pub fn new(frame: impl Fn() -> Box<dyn Frame> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(frame))
+    }
+
+    /// Create a frame.
+    pub(crate) fn create(&self) -> Box<dyn Frame> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for FrameFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameFactory").finish_non_exhaustive()
+    }
+}
+
+/// Who draws the client-side decorations of a window, see
+/// [`WindowAttributesWayland::with_frame_source`].
+///
+/// [`WindowAttributesWayland::with_frame_source`]: crate::WindowAttributesWayland::with_frame_source
+#[derive(Debug, Default, Clone)]
+pub enum FrameSource {
+    /// The builtin frame of winit.
+    #[default]
+    Builtin,
+
+    /// A [`Frame`] of the application, drawn and driven by winit.
+    Custom(FrameFactory),
+
+    /// The application itself, without a frame.
+    ///
+    /// The application draws its own decorations whenever the compositor asks for client-side
+    /// decorations, see [`WindowExtWayland::decoration_mode`]. Moving and resizing can be started
+    /// from them with [`Window::drag_window`], [`Window::drag_resize_window`] and
+    /// [`Window::show_window_menu`].
+    ///
+    /// [`WindowExtWayland::decoration_mode`]: crate::WindowExtWayland::decoration_mode
+    /// [`Window::drag_window`]: winit_core::window::Window::drag_window
+    /// [`Window::drag_resize_window`]: winit_core::window::Window::drag_resize_window
+    /// [`Window::show_window_menu`]: winit_core::window::Window::show_window_menu
+    Application,
+}
+
+/// The input handling of a [`Frame`], which doesn't depend on its surfaces.
+#[derive(Debug)]
+struct FrameInput {
+    /// The frame drawing the decorations.
+    frame: Box<dyn Frame>,
+
+    /// The state passed to the frame.
+    state: FrameState,
+
+    /// Whether the window is fullscreen, in which case the frame isn't shown.
+    fullscreen: bool,
+
+    /// The location of the pointer relative to the decorations.
+    pointer: LogicalPosition<f64>,
+
+    /// The time of the last click on the title bar.
+    last_click: Option<Duration>,
+
+    /// Whether the frame is waiting for redraw.
+    dirty: bool,
+}
+
+impl FrameInput {
+    fn new(frame: Box<dyn Frame>, theme: Theme) -> Self {
+        let state = FrameState {
+            title: String::new(),
+            surface_size: LogicalSize::new(1, 1),
+            scale_factor: 1.,
+            activated: false,
+            maximized: false,
+            tiled: false,
+            resizable: true,
+            can_minimize: true,
+            can_maximize: true,
+            theme,
+            hovered: FrameHit::None,
+        };
+
+        Self {
+            frame,
+            state,
+            fullscreen: false,
+            pointer: LogicalPosition::new(0., 0.),
+            last_click: None,
+            dirty: true,
+        }
+    }
+
+    fn borders(&self) -> LogicalInsets<u32> {
+        if self.fullscreen {
+            LogicalInsets::new(0, 0, 0, 0)
+        } else {
+            self.frame.borders(&self.state)
+        }
+    }
+
+    /// The size of the decorations with their shadow.
+    fn insets(&self) -> LogicalInsets<u32> {
+        if self.fullscreen {
+            return LogicalInsets::new(0, 0, 0, 0);
+        }
+
+        let borders = self.frame.borders(&self.state);
+        let shadow = self.frame.shadow(&self.state);
+        LogicalInsets::new(
+            borders.top + shadow.top,
+            borders.left + shadow.left,
+            borders.bottom + shadow.bottom,
+            borders.right + shadow.right,
+        )
+    }
+
+    fn on_click(
+        &mut self,
+        timestamp: Duration,
+        click: FrameClick,
+        pressed: bool,
+    ) -> Option<FrameAction> {
+        let hit = self.state.hovered;
+
+        if click == FrameClick::Alternate {
+            return (pressed && hit == FrameHit::Move).then(|| {
+                let insets = self.insets();
+                let x = self.pointer.x as i32 - insets.left as i32;
+                let y = self.pointer.y as i32 - insets.top as i32;
+                FrameAction::ShowMenu(x, y)
+            });
+        }
+
+        let maximize =
+            if self.state.maximized { FrameAction::UnMaximize } else { FrameAction::Maximize };
+        match hit {
+            FrameHit::Move if pressed => {
+                let last_click = self.last_click.replace(timestamp);
+                if last_click
+                    .is_some_and(|last| timestamp.saturating_sub(last) < DOUBLE_CLICK_DELAY)
+                {
+                    self.last_click = None;
+                    Some(maximize)
+                } else {
+                    Some(FrameAction::Move)
+                }
+            },
+            FrameHit::Resize(direction) if pressed && self.state.resizable => {
+                Some(FrameAction::Resize(resize_direction_to_edge(direction)))
+            },
+            FrameHit::Minimize if !pressed => Some(FrameAction::Minimize),
+            FrameHit::Maximize if !pressed => Some(maximize),
+            FrameHit::Close if !pressed => Some(FrameAction::Close),
+            _ => None,
+        }
+    }
+
+    /// The pointer moved to the given position relative to the decorations.
+    fn pointer_moved(&mut self, position: LogicalPosition<f64>) -> CursorIcon {
+        self.pointer = position;
+        let hovered = self.frame.hit_test(&self.state, self.pointer);
+        self.dirty |= self.state.hovered != hovered;
+        self.state.hovered = hovered;
+
+        match hovered {
+            FrameHit::Resize(direction) if self.state.resizable => direction.into(),
+            _ => CursorIcon::Default,
+        }
+    }
+
+    fn pointer_left(&mut self) {
+        self.dirty |= self.state.hovered != FrameHit::None;
+        self.state.hovered = FrameHit::None;
+    }
+
+    fn update_state(&mut self, state: XdgWindowState) {
+        let activated = state.contains(XdgWindowState::ACTIVATED);
+        let maximized = state.contains(XdgWindowState::MAXIMIZED);
+        let fullscreen = state.contains(XdgWindowState::FULLSCREEN);
+        let tiled = state.intersects(XdgWindowState::TILED);
+
+        self.dirty |= self.state.activated != activated
+            || self.state.maximized != maximized
+            || self.fullscreen != fullscreen
+            || self.state.tiled != tiled;
+
+        self.state.activated = activated;
+        self.state.maximized = maximized;
+        self.state.tiled = tiled;
+        self.fullscreen = fullscreen;
+    }
+
+    fn update_wm_capabilities(&mut self, capabilities: WindowManagerCapabilities) {
+        let can_minimize = capabilities.contains(WindowManagerCapabilities::MINIMIZE);
+        let can_maximize = capabilities.contains(WindowManagerCapabilities::MAXIMIZE);
+
+        self.dirty |=
+            self.state.can_minimize != can_minimize || self.state.can_maximize != can_maximize;
+
+        self.state.can_minimize = can_minimize;
+        self.state.can_maximize = can_maximize;
+    }
+}
+
+/// The decorations frame of a window, driving its [`Frame`].
+#[derive(Debug)]
+pub struct WinitFrame {
+    input: FrameInput,
+
+    /// The parent surface.
+    parent: WlSurface,
+
+    /// Whether the frame should sync with the parent.
+    should_sync: bool,
+
+    /// The surfaces of the edges, in the order of [`FrameEdge::ALL`]. When `None` the frame is
+    /// hidden.
+    parts: Option<[FramePart; 4]>,
+
+    /// The memory pool to use for drawing.
+    pool: SlotPool,
+
+    subcompositor: Arc<SubcompositorState>,
+    queue_handle: QueueHandle<WinitState>,
+}
+
+impl WinitFrame {
+    pub fn new(
+        frame: Box<dyn Frame>,
+        theme: Theme,
+        parent: &impl WaylandSurface,
+        shm: &Shm,
+        subcompositor: Arc<SubcompositorState>,
+        queue_handle: QueueHandle<WinitState>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let parent = parent.wl_surface().clone();
+        let pool = SlotPool::new(1, shm)?;
+        let parts = Some(FramePart::create_all(&parent, &subcompositor, &queue_handle));
+
+        Ok(Self {
+            input: FrameInput::new(frame, theme),
+            parent,
+            should_sync: true,
+            parts,
+            pool,
+            subcompositor,
+            queue_handle,
+        })
+    }
+
+    /// Set the theme passed to the frame.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.input.dirty |= self.input.state.theme != theme;
+        self.input.state.theme = theme;
+    }
+
+    fn borders(&self) -> LogicalInsets<u32> {
+        if self.parts.is_none() { LogicalInsets::new(0, 0, 0, 0) } else { self.input.borders() }
+    }
+}
+
+impl DecorationsFrame for WinitFrame {
+    fn on_click(
+        &mut self,
+        timestamp: Duration,
+        click: FrameClick,
+        pressed: bool,
+    ) -> Option<FrameAction> {
+        self.input.on_click(timestamp, click, pressed)
+    }
+
+    fn click_point_moved(
+        &mut self,
+        _timestamp: Duration,
+        surface_id: &ObjectId,
+        x: f64,
+        y: f64,
+    ) -> Option<CursorIcon> {
+        let index =
+            self.parts.as_ref()?.iter().position(|part| part.surface.id() == *surface_id)?;
+        let insets = self.input.insets();
+        let (position, _) = FrameEdge::ALL[index].rect(insets, self.input.state.surface_size);
+        let position = LogicalPosition::new(position.x as f64 + x, position.y as f64 + y);
+        Some(self.input.pointer_moved(position))
+    }
+
+    fn click_point_left(&mut self) {
+        self.input.pointer_left();
+    }
+
+    fn update_state(&mut self, state: XdgWindowState) {
+        self.input.update_state(state);
+    }
+
+    fn update_wm_capabilities(&mut self, wm_capabilities: WindowManagerCapabilities) {
+        self.input.update_wm_capabilities(wm_capabilities);
+    }
+
+    fn resize(&mut self, width: NonZeroU32, height: NonZeroU32) {
+        self.input.state.surface_size = LogicalSize::new(width.get(), height.get());
+        self.input.dirty = true;
+        self.should_sync = true;
+    }
+
+    fn set_scaling_factor(&mut self, scale_factor: f64) {
+        self.input.state.scale_factor = scale_factor;
+        self.input.dirty = true;
+        self.should_sync = true;
+    }
+
+    fn location(&self) -> (i32, i32) {
+        let borders = self.borders();
+        (-(borders.left as i32), -(borders.top as i32))
+    }
+
+    fn subtract_borders(
+        &self,
+        width: NonZeroU32,
+        height: NonZeroU32,
+    ) -> (Option<NonZeroU32>, Option<NonZeroU32>) {
+        let borders = self.borders();
+        (
+            NonZeroU32::new(width.get().saturating_sub(borders.left + borders.right)),
+            NonZeroU32::new(height.get().saturating_sub(borders.top + borders.bottom)),
+        )
+    }
+
+    fn add_borders(&self, width: u32, height: u32) -> (u32, u32) {
+        let borders = self.borders();
+        (width + borders.left + borders.right, height + borders.top + borders.bottom)
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.input.dirty
+    }
+
+    fn set_hidden(&mut self, hidden: bool) {
+        if self.is_hidden() == hidden {
+            return;
+        }
+
+        if hidden {
+            self.parts = None;
+        } else {
+            let _ = self.pool.resize(1);
+            self.parts =
+                Some(FramePart::create_all(&self.parent, &self.subcompositor, &self.queue_handle));
+            self.input.dirty = true;
+        }
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.parts.is_none()
+    }
+
+    fn set_resizable(&mut self, resizable: bool) {
+        self.input.dirty |= self.input.state.resizable != resizable;
+        self.input.state.resizable = resizable;
+    }
+
+    fn draw(&mut self) -> bool {
+        let parts = match self.parts.as_ref() {
+            Some(parts) => parts,
+            None => return false,
+        };
+
+        // Reset the dirty bit and sync option.
+        self.input.dirty = false;
+        let should_sync = std::mem::take(&mut self.should_sync);
+
+        // Fractional scaling isn't supported, so round up.
+        let scale = self.input.state.scale_factor.ceil() as u32;
+        let insets = self.input.insets();
+        for (edge, part) in FrameEdge::ALL.into_iter().zip(parts) {
+            let (position, size) = edge.rect(insets, self.input.state.surface_size);
+            if size.width == 0 || size.height == 0 {
+                // Don't draw the decorations for the full screen surface, nor the empty edges.
+                part.surface.attach(None, 0, 0);
+                part.surface.commit();
+                continue;
+            }
+
+            let (width, height) = (size.width * scale, size.height * scale);
+            let (buffer, canvas) = match self.pool.create_buffer(
+                width as i32,
+                height as i32,
+                width as i32 * 4,
+                wl_shm::Format::Argb8888,
+            ) {
+                Ok((buffer, canvas)) => (buffer, canvas),
+                Err(_) => continue,
+            };
+
+            canvas.fill(0);
+            let mut canvas = FrameCanvas::new(canvas, edge, position, width, height, scale);
+            self.input.frame.draw(&self.input.state, &mut canvas);
+
+            part.surface.set_buffer_scale(scale as i32);
+            if should_sync {
+                part.subsurface.set_sync();
+            } else {
+                part.subsurface.set_desync();
+            }
+
+            part.subsurface.set_position(
+                position.x as i32 - insets.left as i32,
+                position.y as i32 - insets.top as i32,
+            );
+
+            if let Err(err) = buffer.attach_to(&part.surface) {
+                warn!("Failed to attach the decorations buffer: {err}");
+                continue;
+            }
+
+            if part.surface.version() >= 4 {
+                part.surface.damage_buffer(0, 0, i32::MAX, i32::MAX);
+            } else {
+                part.surface.damage(0, 0, i32::MAX, i32::MAX);
+            }
+
+            part.surface.commit();
+        }
+
+        should_sync
+    }
+
+    fn set_title(&mut self, title: impl Into<String>) {
+        self.input.state.title = title.into();
+        self.input.dirty = true;
+    }
+}
+
+/// The surface an edge of the frame is drawn on.
+#[derive(Debug)]
+struct FramePart {
+    subsurface: WlSubsurface,
+    surface: WlSurface,
+}
+
+impl FramePart {
+    fn new(
+        parent: &WlSurface,
+        subcompositor: &SubcompositorState,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Self {
+        let (subsurface, surface) = subcompositor.create_subsurface(parent.clone(), queue_handle);
+        // The content is drawn over the frame.
+        subsurface.place_below(parent);
+        subsurface.set_sync();
+        Self { subsurface, surface }
+    }
+
+    fn create_all(
+        parent: &WlSurface,
+        subcompositor: &SubcompositorState,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> [Self; 4] {
+        FrameEdge::ALL.map(|_| Self::new(parent, subcompositor, queue_handle))
+    }
+}
+
+impl Drop for FramePart {
+    fn drop(&mut self) {
+        self.subsurface.destroy();
+        self.surface.destroy();
+    }
+}
+
+fn resize_direction_to_edge(direction: ResizeDirection) -> ResizeEdge {
+    match direction {
+        ResizeDirection::East => ResizeEdge::Right,
+        ResizeDirection::North => ResizeEdge::Top,
+        ResizeDirection::NorthEast => ResizeEdge::TopRight,
+        ResizeDirection::NorthWest => ResizeEdge::TopLeft,
+        ResizeDirection::South => ResizeEdge::Bottom,
+        ResizeDirection::SouthEast => ResizeEdge::BottomRight,
+        ResizeDirection::SouthWest => ResizeEdge::BottomLeft,
+        ResizeDirection::West => ResizeEdge::Left,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A title bar of 30 pixels with a close button on its left, and borders of 5 pixels.
+    #[derive(Debug)]
+    struct TestFrame;
+
+    impl Frame for TestFrame {
+        fn borders(&self, _: &FrameState) -> LogicalInsets<u32> {
+            LogicalInsets::new(30, 5, 5, 5)
+        }
+
+        fn draw(&mut self, _: &FrameState, _: &mut FrameCanvas<'_>) {}
+
+        fn hit_test(&self, state: &FrameState, position: LogicalPosition<f64>) -> FrameHit {
+            let width = state.surface_size.width as f64 + 10.;
+            match (position.x, position.y) {
+                (x, _) if x < 5. => FrameHit::Resize(ResizeDirection::West),
+                (x, _) if x >= width - 5. => FrameHit::Resize(ResizeDirection::East),
+                (x, y) if y < 30. && x < 30. => FrameHit::Close,
+                (_, y) if y < 30. => FrameHit::Move,
+                _ => FrameHit::None,
+            }
+        }
+    }
+
+    fn input() -> FrameInput {
+        let mut input = FrameInput::new(Box::new(TestFrame), Theme::Light);
+        input.state.surface_size = LogicalSize::new(100, 100);
+        input
+    }
+
+    fn click(input: &mut FrameInput, millis: u64, pressed: bool) -> Option<FrameAction> {
+        input.on_click(Duration::from_millis(millis), FrameClick::Normal, pressed)
+    }
+
+    #[test]
+    fn edges_cover_the_borders() {
+        let borders = LogicalInsets::new(30, 5, 5, 5);
+        let size = LogicalSize::new(100, 100);
+        let rects = FrameEdge::ALL.map(|edge| edge.rect(borders, size));
+        assert_eq!(rects, [
+            (LogicalPosition::new(0, 0), LogicalSize::new(110, 30)),
+            (LogicalPosition::new(0, 30), LogicalSize::new(5, 100)),
+            (LogicalPosition::new(105, 30), LogicalSize::new(5, 100)),
+            (LogicalPosition::new(0, 130), LogicalSize::new(110, 5)),
+        ]);
+    }
+
+    #[test]
+    fn hovering_picks_the_cursor() {
+        let mut input = input();
+        input.dirty = false;
+
+        assert_eq!(input.pointer_moved(LogicalPosition::new(2., 50.)), CursorIcon::WResize);
+        assert_eq!(input.state.hovered, FrameHit::Resize(ResizeDirection::West));
+        assert!(input.dirty);
+
+        assert_eq!(input.pointer_moved(LogicalPosition::new(50., 10.)), CursorIcon::Default);
+        assert_eq!(input.state.hovered, FrameHit::Move);
+
+        input.state.resizable = false;
+        assert_eq!(input.pointer_moved(LogicalPosition::new(2., 50.)), CursorIcon::Default);
+
+        input.pointer_left();
+        assert_eq!(input.state.hovered, FrameHit::None);
+    }
+
+    #[test]
+    fn title_bar_clicks() {
+        let mut input = input();
+        input.pointer_moved(LogicalPosition::new(50., 10.));
+
+        assert!(matches!(click(&mut input, 0, true), Some(FrameAction::Move)));
+        assert!(click(&mut input, 0, false).is_none());
+        // A double click toggles the maximized state.
+        assert!(matches!(click(&mut input, 100, true), Some(FrameAction::Maximize)));
+        assert!(matches!(click(&mut input, 1000, true), Some(FrameAction::Move)));
+        input.state.maximized = true;
+        assert!(matches!(click(&mut input, 1200, true), Some(FrameAction::UnMaximize)));
+
+        // The window menu is shown relative to the window content.
+        let menu = input.on_click(Duration::ZERO, FrameClick::Alternate, true);
+        assert!(matches!(menu, Some(FrameAction::ShowMenu(45, -20))));
+    }
+
+    #[test]
+    fn button_and_border_clicks() {
+        let mut input = input();
+
+        // The buttons act on release.
+        input.pointer_moved(LogicalPosition::new(10., 10.));
+        assert!(click(&mut input, 0, true).is_none());
+        assert!(matches!(click(&mut input, 0, false), Some(FrameAction::Close)));
+        assert!(input.on_click(Duration::ZERO, FrameClick::Alternate, true).is_none());
+
+        input.pointer_moved(LogicalPosition::new(108., 50.));
+        assert!(matches!(click(&mut input, 0, true), Some(FrameAction::Resize(ResizeEdge::Right))));
+        input.state.resizable = false;
+        assert!(click(&mut input, 0, true).is_none());
+
+        // Clicks on nothing interactive are ignored.
+        input.pointer_moved(LogicalPosition::new(50., 50.));
+        assert!(click(&mut input, 0, true).is_none());
+    }
+
+    #[test]
+    fn fullscreen_hides_the_borders() {
+        let mut input = input();
+        assert_eq!(input.borders(), LogicalInsets::new(30, 5, 5, 5));
+        input.update_state(XdgWindowState::FULLSCREEN);
+        assert_eq!(input.borders(), LogicalInsets::new(0, 0, 0, 0));
+        assert!(FrameEdge::ALL.iter().all(|edge| {
+            let (_, size) = edge.rect(input.borders(), input.state.surface_size);
+            size.width == 0 || size.height == 0
+        }));
+    }
+
+    #[test]
+    fn shadow_is_outside_the_geometry() {
+        /// The test frame with a shadow of 10 pixels.
+        #[derive(Debug)]
+        struct ShadowFrame;
+
+        impl Frame for ShadowFrame {
+            fn borders(&self, state: &FrameState) -> LogicalInsets<u32> {
+                TestFrame.borders(state)
+            }
+
+            fn shadow(&self, _: &FrameState) -> LogicalInsets<u32> {
+                LogicalInsets::new(10, 10, 10, 10)
+            }
+
+            fn draw(&mut self, _: &FrameState, _: &mut FrameCanvas<'_>) {}
+
+            fn hit_test(&self, _: &FrameState, _: LogicalPosition<f64>) -> FrameHit {
+                FrameHit::Move
+            }
+        }
+
+        let mut input = FrameInput::new(Box::new(ShadowFrame), Theme::Light);
+        input.state.surface_size = LogicalSize::new(100, 100);
+        assert_eq!(input.borders(), LogicalInsets::new(30, 5, 5, 5));
+        assert_eq!(input.insets(), LogicalInsets::new(40, 15, 15, 15));
+        assert_eq!(
+            FrameEdge::Top.rect(input.insets(), input.state.surface_size),
+            (LogicalPosition::new(0, 0), LogicalSize::new(130, 40))
+        );
+
+        // The window menu is still shown relative to the window content.
+        input.pointer_moved(LogicalPosition::new(60., 20.));
+        let menu = input.on_click(Duration::ZERO, FrameClick::Alternate, true);
+        assert!(matches!(menu, Some(FrameAction::ShowMenu(45, -20))));
+
+        input.update_state(XdgWindowState::FULLSCREEN);
+        assert_eq!(input.insets(), LogicalInsets::new(0, 0, 0, 0));
+    }
+}
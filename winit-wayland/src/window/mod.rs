@@ -5,24 +5,29 @@ use std::ptr::NonNull;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
-use dpi::{LogicalSize, PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
+use dpi::{
+    LogicalPosition, LogicalSize, PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size,
+};
 use sctk::compositor::{CompositorState, Region, SurfaceData};
 use sctk::reexports::client::protocol::wl_display::WlDisplay;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Proxy, QueueHandle};
 use sctk::reexports::protocols::xdg::activation::v1::client::xdg_activation_v1::XdgActivationV1;
+use sctk::reexports::protocols::xdg::shell::client::xdg_positioner;
 use sctk::shell::WaylandSurface;
-use sctk::shell::xdg::window::{Window as SctkWindow, WindowDecorations};
+use sctk::shell::xdg::XdgPositioner;
+use sctk::shell::xdg::popup::Popup;
+use sctk::shell::xdg::window::WindowDecorations;
 use tracing::warn;
 use winit_core::cursor::Cursor;
 use winit_core::error::{NotSupportedError, RequestError};
-use winit_core::event::{Ime, WindowEvent};
+use winit_core::event::{DragAction, Ime, WindowEvent};
 use winit_core::event_loop::AsyncRequestSerial;
 use winit_core::monitor::{Fullscreen, MonitorHandle as CoreMonitorHandle};
 use winit_core::window::{
-    CursorGrabMode, ImeCapabilities, ImeRequest, ImeRequestError, ResizeDirection, Theme,
-    UserAttentionType, Window as CoreWindow, WindowAttributes, WindowButtons, WindowId,
-    WindowLevel,
+    CursorGrabMode, DecorationMode, ImeCapabilities, ImeRequest, ImeRequestError, PopupAnchor,
+    PopupAttributes, ResizeDirection, Theme, UserAttentionType, Window as CoreWindow,
+    WindowAttributes, WindowButtons, WindowId, WindowLevel,
 };
 
 use super::ActiveEventLoop;
@@ -32,15 +37,18 @@ use super::state::WinitState;
 use super::types::xdg_activation::XdgActivationTokenData;
 use crate::{WindowAttributesWayland, output};
 
+pub(crate) mod builtin_frame;
+pub(crate) mod frame;
 pub(crate) mod state;
 
-pub use state::WindowState;
+pub use frame::{Frame, FrameCanvas, FrameEdge, FrameFactory, FrameHit, FrameSource, FrameState};
+pub use state::{ShellSurface, WindowState};
 
 /// The Wayland window.
 #[derive(Debug)]
 pub struct Window {
-    /// Reference to the underlying SCTK window.
-    window: SctkWindow,
+    /// Reference to the underlying SCTK window, layer surface or popup.
+    window: ShellSurface,
 
     /// Window id.
     window_id: WindowId,
@@ -95,23 +103,65 @@ impl Window {
 
         let size: Size = attributes.surface_size.unwrap_or(LogicalSize::new(800., 600.).into());
 
-        // We prefer server side decorations, however to not have decorations we ask for client
-        // side decorations instead.
-        let default_decorations = if attributes.decorations {
-            WindowDecorations::RequestServer
-        } else {
-            WindowDecorations::RequestClient
-        };
-
-        let window =
-            state.xdg_shell.create_window(surface.clone(), default_decorations, &queue_handle);
-
-        let WindowAttributesWayland { name: app_name, activation_token, prefer_csd } = *attributes
+        let WindowAttributesWayland {
+            name: app_name,
+            activation_token,
+            prefer_csd,
+            frame: frame_source,
+            layer_surface,
+        } = *attributes
             .platform
             .take()
             .and_then(|p| p.cast::<WindowAttributesWayland>().ok())
             .unwrap_or_default();
 
+        let window = match (attributes.popup.as_ref(), layer_surface) {
+            (Some(popup), _) => create_popup(&state, &queue_handle, surface.clone(), popup, size)?,
+            (None, Some(attributes)) => {
+                let layer_shell = state.layer_shell.as_ref().ok_or_else(|| {
+                    NotSupportedError::new("zwlr_layer_shell_v1 is not available")
+                })?;
+                let output = attributes.output.as_ref().and_then(|monitor| {
+                    monitor.cast_ref::<output::MonitorHandle>().map(|handle| &handle.proxy)
+                });
+                let namespace = app_name.as_ref().map(|name| name.general.clone());
+
+                let layer = layer_shell.create_layer_surface(
+                    &queue_handle,
+                    surface.clone(),
+                    attributes.layer.into(),
+                    namespace,
+                    output,
+                );
+                layer.set_anchor(attributes.anchor.into());
+                layer.set_exclusive_zone(attributes.exclusive_zone);
+                let margin = attributes.margin;
+                layer.set_margin(margin.top, margin.right, margin.bottom, margin.left);
+                layer.set_keyboard_interactivity(attributes.keyboard_interactivity.into());
+
+                ShellSurface::Layer(layer, attributes.anchor)
+            },
+            (None, None) => {
+                // We prefer server side decorations, however to not have decorations we ask for
+                // client side decorations instead.
+                let default_decorations = if attributes.decorations {
+                    WindowDecorations::RequestServer
+                } else {
+                    WindowDecorations::RequestClient
+                };
+
+                let window = state.xdg_shell.create_window(
+                    surface.clone(),
+                    default_decorations,
+                    &queue_handle,
+                );
+                ShellSurface::Toplevel(window)
+            },
+        };
+
+        // The size of layer surfaces must be set before the initial commit.
+        window.set_layer_size(size.to_logical(1.));
+
         let mut window_state = WindowState::new(
             event_loop_window_target.handle.clone(),
             &event_loop_window_target.queue_handle,
@@ -120,6 +170,7 @@ impl Window {
             window.clone(),
             attributes.preferred_theme,
             prefer_csd,
+            frame_source,
         );
 
         window_state.set_window_icon(attributes.window_icon);
@@ -133,8 +184,8 @@ impl Window {
         window_state.set_decorate(attributes.decorations);
 
         // Set the app_id.
-        if let Some(name) = app_name.map(|name| name.general) {
-            window.set_app_id(name);
+        if let (Some(name), Some(toplevel)) = (app_name, window.toplevel()) {
+            toplevel.set_app_id(name.general);
         }
 
         // Set the window title.
@@ -160,9 +211,15 @@ impl Window {
                     monitor.cast_ref::<output::MonitorHandle>().map(|handle| &handle.proxy)
                 });
 
-                window.set_fullscreen(output)
+                if let Some(window) = window.toplevel() {
+                    window.set_fullscreen(output)
+                }
+            },
+            _ if attributes.maximized => {
+                if let Some(window) = window.toplevel() {
+                    window.set_maximized()
+                }
             },
-            _ if attributes.maximized => window.set_maximized(),
             _ => (),
         };
 
@@ -193,6 +250,8 @@ impl Window {
         let window_requests = WindowRequests {
             redraw_requested: AtomicBool::new(true),
             closed: AtomicBool::new(false),
+            drag_data: Default::default(),
+            drag_action: Default::default(),
         };
         let window_requests = Arc::new(window_requests);
         state.window_requests.get_mut().insert(window_id, window_requests.clone());
@@ -232,7 +291,11 @@ impl Window {
     }
 
     pub(crate) fn xdg_toplevel(&self) -> Option<NonNull<c_void>> {
-        NonNull::new(self.window.xdg_toplevel().id().as_ptr().cast())
+        NonNull::new(self.window.toplevel()?.xdg_toplevel().id().as_ptr().cast())
+    }
+
+    pub(crate) fn decoration_mode(&self) -> DecorationMode {
+        self.window_state.lock().unwrap().decoration_mode()
     }
 }
 
@@ -435,7 +498,9 @@ impl CoreWindow for Window {
             return;
         }
 
-        self.window.set_minimized();
+        if let Some(window) = self.window.toplevel() {
+            window.set_minimized();
+        }
     }
 
     fn is_minimized(&self) -> Option<bool> {
@@ -444,7 +509,12 @@ impl CoreWindow for Window {
     }
 
     fn set_maximized(&self, maximized: bool) {
-        if maximized { self.window.set_maximized() } else { self.window.unset_maximized() }
+        let window = match self.window.toplevel() {
+            Some(window) => window,
+            None => return,
+        };
+
+        if maximized { window.set_maximized() } else { window.unset_maximized() }
     }
 
     fn is_maximized(&self) -> bool {
@@ -458,6 +528,11 @@ impl CoreWindow for Window {
     }
 
     fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        let window = match self.window.toplevel() {
+            Some(window) => window,
+            None => return,
+        };
+
         match fullscreen {
             Some(Fullscreen::Exclusive(..)) => {
                 warn!("`Fullscreen::Exclusive` is ignored on Wayland");
@@ -467,9 +542,9 @@ impl CoreWindow for Window {
                     monitor.cast_ref::<output::MonitorHandle>().map(|handle| &handle.proxy)
                 });
 
-                self.window.set_fullscreen(output)
+                window.set_fullscreen(output)
             },
-            None => self.window.unset_fullscreen(),
+            None => window.unset_fullscreen(),
         }
     }
 
@@ -613,6 +688,19 @@ impl CoreWindow for Window {
         self.window_state.lock().unwrap().drag_resize_window(direction)
     }
 
+    fn request_drag_data(&self, mime_type: &str) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = AsyncRequestSerial::get();
+        self.window_requests.drag_data.lock().unwrap().push((serial, mime_type.to_owned()));
+        self.event_loop_awakener.ping();
+        Ok(serial)
+    }
+
+    fn set_drag_action(&self, action: Option<DragAction>) -> Result<(), RequestError> {
+        *self.window_requests.drag_action.lock().unwrap() = Some(action);
+        self.event_loop_awakener.ping();
+        Ok(())
+    }
+
     fn show_window_menu(&self, position: Position) {
         let scale_factor = self.scale_factor();
         let position = position.to_logical(scale_factor);
@@ -668,6 +756,94 @@ impl CoreWindow for Window {
     }
 }
 
+/// Create a popup placed relative to its parent.
+fn create_popup(
+    state: &WinitState,
+    queue_handle: &QueueHandle<WinitState>,
+    surface: WlSurface,
+    attributes: &PopupAttributes,
+    size: Size,
+) -> Result<ShellSurface, RequestError> {
+    let windows = state.windows.borrow();
+    let parent = windows
+        .get(&attributes.parent)
+        .ok_or_else(|| os_error!("the parent of the popup doesn't exist"))?
+        .lock()
+        .unwrap();
+
+    // The popup is placed in the window geometry of the parent, which includes its frame.
+    let scale_factor = parent.scale_factor();
+    let offset = parent.surface_geometry_offset();
+    let anchor_position: LogicalPosition<i32> =
+        attributes.anchor_rect_position.to_logical(scale_factor);
+    let anchor_size: LogicalSize<i32> = attributes.anchor_rect_size.to_logical(scale_factor);
+    let popup_offset: LogicalPosition<i32> = attributes.offset.to_logical(scale_factor);
+    let size: LogicalSize<i32> = size.to_logical(scale_factor);
+
+    let positioner = XdgPositioner::new(&state.xdg_shell).map_err(|err| os_error!(err))?;
+    positioner.set_size(size.width.max(1), size.height.max(1));
+    positioner.set_anchor_rect(
+        anchor_position.x + offset.x,
+        anchor_position.y + offset.y,
+        anchor_size.width.max(1),
+        anchor_size.height.max(1),
+    );
+    positioner.set_anchor(popup_anchor_to_xdg(attributes.anchor));
+    positioner.set_gravity(popup_gravity_to_xdg(attributes.gravity));
+    positioner.set_constraint_adjustment(xdg_positioner::ConstraintAdjustment::from_bits_truncate(
+        attributes.constraint_adjustment.bits(),
+    ));
+    positioner.set_offset(popup_offset.x, popup_offset.y);
+
+    let popup = Popup::from_surface(
+        parent.window.xdg_surface(),
+        &positioner,
+        queue_handle,
+        surface,
+        &state.xdg_shell,
+    )
+    .map_err(|err| os_error!(err))?;
+
+    // Layer surfaces aren't `xdg_surface`s, and adopt the popup themselves.
+    if let ShellSurface::Layer(layer, _) = &parent.window {
+        layer.get_popup(popup.xdg_popup());
+    }
+
+    if attributes.grab {
+        parent.grab_popup(popup.xdg_popup());
+    }
+
+    Ok(ShellSurface::Popup(popup, Arc::new(positioner)))
+}
+
+fn popup_anchor_to_xdg(anchor: PopupAnchor) -> xdg_positioner::Anchor {
+    match anchor {
+        PopupAnchor::Center => xdg_positioner::Anchor::None,
+        PopupAnchor::Top => xdg_positioner::Anchor::Top,
+        PopupAnchor::Bottom => xdg_positioner::Anchor::Bottom,
+        PopupAnchor::Left => xdg_positioner::Anchor::Left,
+        PopupAnchor::Right => xdg_positioner::Anchor::Right,
+        PopupAnchor::TopLeft => xdg_positioner::Anchor::TopLeft,
+        PopupAnchor::BottomLeft => xdg_positioner::Anchor::BottomLeft,
+        PopupAnchor::TopRight => xdg_positioner::Anchor::TopRight,
+        PopupAnchor::BottomRight => xdg_positioner::Anchor::BottomRight,
+    }
+}
+
+fn popup_gravity_to_xdg(gravity: PopupAnchor) -> xdg_positioner::Gravity {
+    match gravity {
+        PopupAnchor::Center => xdg_positioner::Gravity::None,
+        PopupAnchor::Top => xdg_positioner::Gravity::Top,
+        PopupAnchor::Bottom => xdg_positioner::Gravity::Bottom,
+        PopupAnchor::Left => xdg_positioner::Gravity::Left,
+        PopupAnchor::Right => xdg_positioner::Gravity::Right,
+        PopupAnchor::TopLeft => xdg_positioner::Gravity::TopLeft,
+        PopupAnchor::BottomLeft => xdg_positioner::Gravity::BottomLeft,
+        PopupAnchor::TopRight => xdg_positioner::Gravity::TopRight,
+        PopupAnchor::BottomRight => xdg_positioner::Gravity::BottomRight,
+    }
+}
+
 /// The request from the window to the event loop.
 #[derive(Debug)]
 pub struct WindowRequests {
@@ -676,6 +852,12 @@ pub struct WindowRequests {
 
     /// Redraw Requested.
     pub redraw_requested: AtomicBool,
+
+    /// The data of the drag and drop operation requested with `Window::request_drag_data`.
+    pub drag_data: Mutex<Vec<(AsyncRequestSerial, String)>>,
+
+    /// The action set with `Window::set_drag_action`.
+    pub drag_action: Mutex<Option<Option<DragAction>>>,
 }
 
 impl WindowRequests {
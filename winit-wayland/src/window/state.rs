@@ -9,6 +9,7 @@ use foldhash::HashSet;
 use sctk::compositor::{CompositorState, Region, SurfaceData, SurfaceDataExt};
 use sctk::globals::GlobalData;
 use sctk::reexports::client::backend::ObjectId;
+use sctk::reexports::client::protocol::wl_callback::WlCallback;
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_shm::WlShm;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
@@ -20,10 +21,13 @@ use sctk::reexports::protocols::wp::fractional_scale::v1::client::wp_fractional_
 use sctk::reexports::protocols::wp::text_input::zv3::client::zwp_text_input_v3::ZwpTextInputV3;
 use sctk::reexports::protocols::wp::viewporter::client::wp_viewport::WpViewport;
 use sctk::reexports::protocols::xdg::shell::client::xdg_toplevel::ResizeEdge as XdgResizeEdge;
+use sctk::reexports::protocols::xdg::shell::client::{xdg_popup, xdg_surface};
 use sctk::seat::pointer::{PointerDataExt, ThemedPointer};
 use sctk::shell::WaylandSurface;
-use sctk::shell::xdg::XdgSurface;
+use sctk::shell::wlr_layer::LayerSurface;
+use sctk::shell::xdg::popup::Popup;
 use sctk::shell::xdg::window::{DecorationMode, Window, WindowConfigure};
+use sctk::shell::xdg::{XdgPositioner, XdgSurface};
 use sctk::shm::Shm;
 use sctk::shm::slot::SlotPool;
 use sctk::subcompositor::SubcompositorState;
@@ -33,24 +37,25 @@ use wayland_protocols_plasma::blur::client::org_kde_kwin_blur::OrgKdeKwinBlur;
 use winit_core::cursor::{CursorIcon, CustomCursor as CoreCustomCursor};
 use winit_core::error::{NotSupportedError, RequestError};
 use winit_core::window::{
-    CursorGrabMode, ImeCapabilities, ImeRequest, ImeRequestError, ResizeDirection, Theme, WindowId,
+    CursorGrabMode, DecorationMode as CoreDecorationMode, ImeCapabilities, ImeRequest,
+    ImeRequestError, ResizeDirection, Theme, WindowId,
 };
 
 use crate::event_loop::OwnedDisplayHandle;
-use crate::logical_to_physical_rounded;
 use crate::seat::{
     PointerConstraintsState, TextInputClientState, WinitPointerData, WinitPointerDataExt,
     ZwpTextInputV3Ext,
 };
 use crate::state::{WindowCompositorUpdate, WinitState};
-use crate::types::cursor::{CustomCursor, SelectedCursor, WaylandCustomCursor};
+use crate::types::cursor::{
+    AnimatedCursor, CursorFrameData, CustomCursor, SelectedCursor, ThemedCursors,
+    WaylandCustomCursor,
+};
 use crate::types::kwin_blur::KWinBlurManager;
 use crate::types::xdg_toplevel_icon_manager::ToplevelIcon;
-
-#[cfg(feature = "sctk-adwaita")]
-pub type WinitFrame = sctk_adwaita::AdwaitaFrame<WinitState>;
-#[cfg(not(feature = "sctk-adwaita"))]
-pub type WinitFrame = sctk::shell::xdg::fallback_frame::FallbackFrame<WinitState>;
+use crate::window::builtin_frame::BuiltinFrame;
+use crate::window::frame::{FrameSource, WinitFrame};
+use crate::{Anchor, logical_to_physical_rounded};
 
 // Minimum window surface size.
 const MIN_WINDOW_SIZE: LogicalSize<u32> = LogicalSize::new(2, 1);
@@ -72,6 +77,18 @@ pub struct WindowState {
 
     selected_cursor: SelectedCursor,
 
+    /// The custom cursor to pick again when the scale factor or cursor size changes.
+    scaled_cursor: Option<CoreCustomCursor>,
+
+    /// The size of cursors in logical pixels, from the cursor theme.
+    cursor_size: u32,
+
+    /// The named cursors of the theme set by the application.
+    themed_cursors: Arc<Mutex<ThemedCursors>>,
+
+    /// The named cursor drawn from the theme set by the application.
+    themed_icon: Option<CursorIcon>,
+
     /// Whether the cursor is visible.
     pub cursor_visible: bool,
 
@@ -84,6 +101,9 @@ pub struct WindowState {
     /// Theme variant.
     theme: Option<Theme>,
 
+    /// The system theme, used when no theme variant is set.
+    system_theme: Option<Theme>,
+
     /// The current window title.
     title: String,
 
@@ -137,6 +157,9 @@ pub struct WindowState {
     /// Whether we should tell the compositor that we prefer drawing decorations ourself.
     prefer_csd: bool,
 
+    /// Who draws the client side decorations.
+    frame_source: FrameSource,
+
     /// Min size.
     min_surface_size: LogicalSize<u32>,
     max_surface_size: Option<LogicalSize<u32>>,
@@ -164,8 +187,11 @@ pub struct WindowState {
     /// The value is the serial of the event triggered moved.
     has_pending_move: Option<u32>,
 
-    /// The underlying SCTK window.
-    pub window: Window,
+    /// The underlying SCTK window, layer surface or popup.
+    pub window: ShellSurface,
+
+    /// Whether the layer surface or popup received the initial configure.
+    surface_configured: bool,
 
     // NOTE: The spec says that destroying parent(`window` in our case), will unmap the
     // subsurfaces. Thus to achieve atomic unmap of the client, drop the decorations
@@ -177,14 +203,16 @@ pub struct WindowState {
 
 impl WindowState {
     /// Create new window state.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         handle: Arc<OwnedDisplayHandle>,
         queue_handle: &QueueHandle<WinitState>,
         winit_state: &WinitState,
         initial_size: Size,
-        window: Window,
+        window: ShellSurface,
         theme: Option<Theme>,
         prefer_csd: bool,
+        frame_source: FrameSource,
     ) -> Self {
         let compositor = winit_state.compositor_state.clone();
         let pointer_constraints = winit_state.pointer_constraints.clone();
@@ -212,9 +240,14 @@ impl WindowState {
             csd_fails: false,
             cursor_grab_mode: GrabState::new(),
             selected_cursor: Default::default(),
+            scaled_cursor: None,
+            cursor_size: winit_state.cursor_theme.size(),
+            themed_cursors: winit_state.themed_cursors.clone(),
+            themed_icon: None,
             cursor_visible: true,
             decorate: true,
             prefer_csd,
+            frame_source,
             fractional_scale,
             frame: None,
             frame_callback_state: FrameCallbackState::None,
@@ -237,10 +270,12 @@ impl WindowState {
             initial_size: Some(initial_size),
             text_inputs: Vec::new(),
             theme,
+            system_theme: winit_state.system_theme,
             title: String::default(),
             transparent: false,
             viewport,
             window,
+            surface_configured: false,
         }
     }
 
@@ -288,29 +323,28 @@ impl WindowState {
         shm: &Shm,
         subcompositor: &Option<Arc<SubcompositorState>>,
     ) -> bool {
-        // NOTE: when using fractional scaling or wl_compositor@v6 the scaling
-        // should be delivered before the first configure, thus apply it to
-        // properly scale the physical sizes provided by the users.
-        if let Some(initial_size) = self.initial_size.take() {
-            self.size = initial_size.to_logical(self.scale_factor());
-            self.stateless_size = self.size;
-        }
+        self.apply_initial_size();
 
         if let Some(subcompositor) = subcompositor.as_ref().filter(|_| {
             configure.decoration_mode == DecorationMode::Client
                 && self.frame.is_none()
                 && !self.csd_fails
+                && !matches!(self.frame_source, FrameSource::Application)
         }) {
-            match WinitFrame::new(
+            let frame = match &self.frame_source {
+                FrameSource::Custom(factory) => factory.create(),
+                _ => Box::new(BuiltinFrame::new()),
+            };
+            let frame = WinitFrame::new(
+                frame,
+                self.theme().unwrap_or(Theme::Light),
                 &self.window,
                 shm,
-                #[cfg(feature = "sctk-adwaita")]
-                self.compositor.clone(),
                 subcompositor.clone(),
                 self.queue_handle.clone(),
-                #[cfg(feature = "sctk-adwaita")]
-                create_sctk_adwaita_config(self.theme),
-            ) {
+            );
+
+            match frame {
                 Ok(mut frame) => {
                     frame.set_title(&self.title);
                     frame.set_scaling_factor(self.scale_factor);
@@ -423,6 +457,35 @@ impl WindowState {
         }
     }
 
+    /// Apply the configure of a layer surface or popup.
+    pub fn configure_surface(&mut self, (width, height): (u32, u32)) -> bool {
+        self.apply_initial_size();
+
+        // The size is ours to pick in the directions where it's zero.
+        let new_size = LogicalSize::new(
+            if width == 0 { self.size.width } else { width },
+            if height == 0 { self.size.height } else { height },
+        );
+
+        let initial_configure = !std::mem::replace(&mut self.surface_configured, true);
+        if initial_configure || new_size != self.surface_size() {
+            self.resize(new_size);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn apply_initial_size(&mut self) {
+        // NOTE: when using fractional scaling or wl_compositor@v6 the scaling
+        // should be delivered before the first configure, thus apply it to
+        // properly scale the physical sizes provided by the users.
+        if let Some(initial_size) = self.initial_size.take() {
+            self.size = initial_size.to_logical(self.scale_factor());
+            self.stateless_size = self.size;
+        }
+    }
+
     /// Compute the bounds for the surface size of the surface.
     fn surface_size_bounds(
         &self,
@@ -451,7 +514,12 @@ impl WindowState {
 
     /// Start interacting drag resize.
     pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), RequestError> {
-        let xdg_toplevel = self.window.xdg_toplevel();
+        let xdg_toplevel = match self.window.toplevel() {
+            Some(window) => window.xdg_toplevel(),
+            None => {
+                return Err(NotSupportedError::new("only regular windows can be resized").into());
+            },
+        };
 
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
@@ -463,9 +531,42 @@ impl WindowState {
         Ok(())
     }
 
+    /// The position of the surface in the window geometry, which popups are placed in.
+    pub fn surface_geometry_offset(&self) -> LogicalPosition<i32> {
+        match self.frame.as_ref() {
+            Some(frame) => {
+                let (x, y) = frame.location();
+                LogicalPosition::new(-x, -y)
+            },
+            None => LogicalPosition::new(0, 0),
+        }
+    }
+
+    /// Grab the input for the popup with the latest button press on the window.
+    pub fn grab_popup(&self, xdg_popup: &xdg_popup::XdgPopup) {
+        // TODO handle touch and keyboard serials.
+        let latest_press = self
+            .pointers
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|pointer| {
+                let data = pointer.pointer().winit_data();
+                (data.latest_button_serial(), data.seat().clone())
+            })
+            .max_by_key(|(serial, _)| *serial);
+
+        match latest_press {
+            Some((serial, seat)) => xdg_popup.grab(&seat, serial),
+            None => warn!("No button was pressed on the parent to grab the popup with"),
+        }
+    }
+
     /// Start the window drag.
     pub fn drag_window(&self) -> Result<(), RequestError> {
-        let xdg_toplevel = self.window.xdg_toplevel();
+        let xdg_toplevel = match self.window.toplevel() {
+            Some(window) => window.xdg_toplevel(),
+            None => return Err(NotSupportedError::new("only regular windows can be moved").into()),
+        };
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
             let serial = data.latest_button_serial();
@@ -488,10 +589,12 @@ impl WindowState {
         window_id: WindowId,
         updates: &mut Vec<WindowCompositorUpdate>,
     ) -> Option<bool> {
+        // Only toplevels have a frame.
+        let window = self.window.toplevel()?;
         match self.frame.as_mut()?.on_click(timestamp, click, pressed)? {
-            FrameAction::Minimize => self.window.set_minimized(),
-            FrameAction::Maximize => self.window.set_maximized(),
-            FrameAction::UnMaximize => self.window.unset_maximized(),
+            FrameAction::Minimize => window.set_minimized(),
+            FrameAction::Maximize => window.set_maximized(),
+            FrameAction::UnMaximize => window.unset_maximized(),
             FrameAction::Close => WinitState::queue_close(updates, window_id),
             FrameAction::Move => self.has_pending_move = Some(serial),
             FrameAction::Resize(edge) => {
@@ -507,9 +610,9 @@ impl WindowState {
                     ResizeEdge::BottomRight => XdgResizeEdge::BottomRight,
                     _ => return None,
                 };
-                self.window.resize(seat, serial, edge);
+                window.resize(seat, serial, edge);
             },
-            FrameAction::ShowMenu(x, y) => self.window.show_window_menu(seat, serial, (x, y)),
+            FrameAction::ShowMenu(x, y) => window.show_window_menu(seat, serial, (x, y)),
             _ => (),
         };
 
@@ -539,7 +642,9 @@ impl WindowState {
             // If we have a cursor change, that means that cursor is over the decorations,
             // so try to apply move.
             if let Some(serial) = cursor.is_some().then_some(serial).flatten() {
-                self.window.move_(seat, serial);
+                if let Some(window) = self.window.toplevel() {
+                    window.move_(seat, serial);
+                }
                 None
             } else {
                 cursor
@@ -606,11 +711,15 @@ impl WindowState {
     /// Whether the window received initial configure event from the compositor.
     #[inline]
     pub fn is_configured(&self) -> bool {
-        self.last_configure.is_some()
+        self.last_configure.is_some() || self.surface_configured
     }
 
     #[inline]
     pub fn is_decorated(&mut self) -> bool {
+        if self.window.toplevel().is_none() {
+            return false;
+        }
+
         let csd = self
             .last_configure
             .as_ref()
@@ -618,12 +727,24 @@ impl WindowState {
             .unwrap_or(false);
         if let Some(frame) = csd.then_some(self.frame.as_ref()).flatten() {
             !frame.is_hidden()
+        } else if csd && matches!(self.frame_source, FrameSource::Application) {
+            // Decorations drawn by the application.
+            self.decorate
         } else {
             // Server side decorations.
             true
         }
     }
 
+    /// The decoration mode from the last configure.
+    #[inline]
+    pub fn decoration_mode(&self) -> CoreDecorationMode {
+        match self.last_configure.as_ref().map(|configure| configure.decoration_mode) {
+            Some(DecorationMode::Server) => CoreDecorationMode::Server,
+            _ => CoreDecorationMode::Client,
+        }
+    }
+
     /// Get the outer size of the window.
     #[inline]
     pub fn outer_size(&self) -> LogicalSize<u32> {
@@ -642,6 +763,22 @@ impl WindowState {
         let _ = self.set_cursor_grab_inner(mode);
     }
 
+    /// The cursor theme set by the application changed, load the cursor from it again.
+    pub fn cursor_theme_changed(&mut self, cursor_size: u32) {
+        let changed = self.cursor_size != cursor_size;
+        self.cursor_size = cursor_size;
+
+        let icon = match &self.selected_cursor {
+            SelectedCursor::Named(icon) => Some(*icon),
+            _ => self.themed_icon,
+        };
+        if let Some(icon) = icon {
+            self.set_cursor(icon);
+        } else if let Some(cursor) = self.scaled_cursor.clone().filter(|_| changed) {
+            self.select_custom_cursor(cursor);
+        }
+    }
+
     /// Pointer has left the top-level.
     pub fn pointer_left(&mut self, removed: Weak<ThemedPointer<WinitPointerData>>) {
         let mut new_pointers = Vec::new();
@@ -673,6 +810,10 @@ impl WindowState {
             match &self.selected_cursor {
                 SelectedCursor::Named(icon) => self.set_cursor(*icon),
                 SelectedCursor::Custom(cursor) => self.apply_custom_cursor(cursor),
+                SelectedCursor::Animated(cursor) => {
+                    self.apply_custom_cursor(cursor.current_frame());
+                    self.request_cursor_frame();
+                },
             }
         } else {
             self.set_cursor_visible(self.cursor_visible);
@@ -696,7 +837,9 @@ impl WindowState {
     /// Try to resize the window when the user can do so.
     pub fn request_surface_size(&mut self, surface_size: Size) -> PhysicalSize<u32> {
         if self.last_configure.as_ref().map(Self::is_stateless).unwrap_or(true) {
-            self.resize(surface_size.to_logical(self.scale_factor()))
+            let surface_size = surface_size.to_logical(self.scale_factor());
+            self.window.reposition_popup(surface_size);
+            self.resize(surface_size)
         }
 
         logical_to_physical_rounded(self.surface_size(), self.scale_factor())
@@ -729,13 +872,22 @@ impl WindowState {
         // Reload the hint.
         self.reload_transparency_hint();
 
-        // Set the window geometry.
-        self.window.xdg_surface().set_window_geometry(
-            x,
-            y,
-            outer_size.width as i32,
-            outer_size.height as i32,
-        );
+        match &self.window {
+            // Set the window geometry.
+            ShellSurface::Toplevel(window) => window.xdg_surface().set_window_geometry(
+                x,
+                y,
+                outer_size.width as i32,
+                outer_size.height as i32,
+            ),
+            ShellSurface::Layer(..) => self.window.set_layer_size(self.size),
+            ShellSurface::Popup(popup, _) => popup.xdg_surface().set_window_geometry(
+                0,
+                0,
+                self.size.width as i32,
+                self.size.height as i32,
+            ),
+        }
 
         // Update the target viewport, this is used if and only if fractional scaling is in use.
         if let Some(viewport) = self.viewport.as_ref() {
@@ -752,6 +904,15 @@ impl WindowState {
 
     /// Set the cursor icon.
     pub fn set_cursor(&mut self, cursor_icon: CursorIcon) {
+        let themed_cursor = self.themed_cursors.lock().unwrap().get(cursor_icon);
+        if let Some(cursor) = themed_cursor {
+            self.select_custom_cursor(cursor);
+            self.themed_icon = Some(cursor_icon);
+            return;
+        }
+
+        self.themed_icon = None;
+        self.scaled_cursor = None;
         self.selected_cursor = SelectedCursor::Named(cursor_icon);
 
         if !self.cursor_visible {
@@ -766,8 +927,13 @@ impl WindowState {
     }
 
     /// Set the custom cursor icon.
-    pub(crate) fn set_custom_cursor(&mut self, cursor: CoreCustomCursor) {
-        let cursor = match cursor.cast_ref::<WaylandCustomCursor>() {
+    pub(crate) fn set_custom_cursor(&mut self, core_cursor: CoreCustomCursor) {
+        self.themed_icon = None;
+        self.select_custom_cursor(core_cursor);
+    }
+
+    fn select_custom_cursor(&mut self, core_cursor: CoreCustomCursor) {
+        let cursor = match core_cursor.cast_ref::<WaylandCustomCursor>() {
             Some(cursor) => cursor,
             None => {
                 tracing::error!("unrecognized cursor passed to Wayland backend");
@@ -775,16 +941,81 @@ impl WindowState {
             },
         };
 
-        let cursor = {
-            let mut pool = self.image_pool.lock().unwrap();
-            CustomCursor::new(&mut pool, cursor)
+        let mut pool = self.image_pool.lock().unwrap();
+        let selected_cursor = match cursor {
+            WaylandCustomCursor::Image(image) => {
+                SelectedCursor::Custom(CustomCursor::new(&mut pool, image))
+            },
+            WaylandCustomCursor::Animation(frames) => SelectedCursor::Animated(
+                AnimatedCursor::new(&mut pool, frames.iter().map(|(image, delay)| (image, *delay))),
+            ),
+            WaylandCustomCursor::File(file) => {
+                let size = (self.cursor_size as f64 * self.scale_factor).round() as u32;
+                match file.best_variant(size).frames() {
+                    [frame] => SelectedCursor::Custom(CustomCursor::new(&mut pool, frame.image())),
+                    frames => SelectedCursor::Animated(AnimatedCursor::new(
+                        &mut pool,
+                        frames.iter().map(|frame| (frame.image(), frame.delay())),
+                    )),
+                }
+            },
+            WaylandCustomCursor::Scaled(images) => {
+                let (image, scale) = images.best_image(self.scale_factor);
+                SelectedCursor::Custom(CustomCursor::new(&mut pool, image).with_scale(scale))
+            },
         };
+        drop(pool);
+
+        // Cursors depending on the scale factor are picked again when it changes.
+        self.scaled_cursor =
+            matches!(cursor, WaylandCustomCursor::File(_) | WaylandCustomCursor::Scaled(_))
+                .then(|| core_cursor.clone());
 
+        self.selected_cursor = selected_cursor;
         if self.cursor_visible {
-            self.apply_custom_cursor(&cursor);
+            self.reload_cursor_style();
         }
+    }
+
+    /// Request a frame callback on the pointer surface to time the next frame of the animated
+    /// cursor.
+    fn request_cursor_frame(&mut self) {
+        let cursor = match &mut self.selected_cursor {
+            SelectedCursor::Animated(cursor) if cursor.frame_callback.is_none() => cursor,
+            _ => return,
+        };
+
+        let pointer = match self.pointers.iter().find_map(Weak::upgrade) {
+            Some(pointer) => pointer,
+            None => return,
+        };
 
-        self.selected_cursor = SelectedCursor::Custom(cursor);
+        let surface = pointer.surface();
+        let data = CursorFrameData { window_id: crate::make_wid(self.window.wl_surface()) };
+        cursor.frame_callback = Some(surface.frame(&self.queue_handle, data));
+        surface.commit();
+    }
+
+    /// The pointer surface presented a frame of the animated cursor.
+    pub fn cursor_frame_done(&mut self, callback: &WlCallback, time: u32) {
+        let swap = match &mut self.selected_cursor {
+            SelectedCursor::Animated(cursor) => match cursor.frame_done(callback, time) {
+                Some(swap) => swap,
+                None => return,
+            },
+            _ => return,
+        };
+
+        // Hidden cursors stop the animation, it resumes once they're shown again.
+        if !self.cursor_visible {
+            return;
+        }
+
+        if swap {
+            self.reload_cursor_style();
+        } else {
+            self.request_cursor_frame();
+        }
     }
 
     /// Set the resize increments of the window.
@@ -804,12 +1035,15 @@ impl WindowState {
             let surface = pointer.surface();
 
             let scale = if let Some(viewport) = data.viewport() {
-                let scale = self.scale_factor();
+                let scale = cursor.scale.map_or_else(|| self.scale_factor(), f64::from);
                 let size = PhysicalSize::new(cursor.w, cursor.h).to_logical(scale);
                 viewport.set_destination(size.width, size.height);
                 scale
             } else {
-                let scale = surface.data::<SurfaceData>().unwrap().surface_data().scale_factor();
+                let scale = cursor.scale.map_or_else(
+                    || surface.data::<SurfaceData>().unwrap().surface_data().scale_factor(),
+                    |scale| scale as i32,
+                );
                 surface.set_buffer_scale(scale);
                 scale as f64
             };
@@ -850,7 +1084,9 @@ impl WindowState {
             .unwrap_or(size);
 
         self.min_surface_size = size;
-        self.window.set_min_size(Some(size.into()));
+        if let Some(window) = self.window.toplevel() {
+            window.set_min_size(Some(size.into()));
+        }
     }
 
     /// Set maximum inner window size.
@@ -863,22 +1099,42 @@ impl WindowState {
         });
 
         self.max_surface_size = size;
-        self.window.set_max_size(size.map(Into::into));
+        if let Some(window) = self.window.toplevel() {
+            window.set_max_size(size.map(Into::into));
+        }
     }
 
     /// Set the CSD theme.
     pub fn set_theme(&mut self, theme: Option<Theme>) {
         self.theme = theme;
-        #[cfg(feature = "sctk-adwaita")]
-        if let Some(frame) = self.frame.as_mut() {
-            frame.set_config(create_sctk_adwaita_config(theme))
-        }
+        self.refresh_frame_theme();
     }
 
-    /// The current theme for CSD decorations.
+    /// The current theme, falling back to the system theme.
     #[inline]
     pub fn theme(&self) -> Option<Theme> {
-        self.theme
+        self.theme.or(self.system_theme)
+    }
+
+    /// Update the system theme, returning whether the window follows it.
+    pub fn set_system_theme(&mut self, system_theme: Option<Theme>) -> bool {
+        self.system_theme = system_theme;
+        if self.theme.is_some() {
+            return false;
+        }
+
+        // Repaint the decorations with the new theme.
+        self.refresh_frame_theme();
+
+        true
+    }
+
+    /// Pass the current theme to the decorations frame.
+    fn refresh_frame_theme(&mut self) {
+        let theme = self.theme().unwrap_or(Theme::Light);
+        if let Some(frame) = self.frame.as_mut() {
+            frame.set_theme(theme);
+        }
     }
 
     /// Set the cursor grabbing state on the top-level.
@@ -960,11 +1216,16 @@ impl WindowState {
     }
 
     pub fn show_window_menu(&self, position: LogicalPosition<u32>) {
+        let window = match self.window.toplevel() {
+            Some(window) => window,
+            None => return,
+        };
+
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
             let serial = data.latest_button_serial();
             let seat = data.seat();
-            self.window.show_window_menu(seat, serial, position.into());
+            window.show_window_menu(seat, serial, position.into());
         });
     }
 
@@ -997,6 +1258,10 @@ impl WindowState {
             match &self.selected_cursor {
                 SelectedCursor::Named(icon) => self.set_cursor(*icon),
                 SelectedCursor::Custom(cursor) => self.apply_custom_cursor(cursor),
+                SelectedCursor::Animated(cursor) => {
+                    self.apply_custom_cursor(cursor.current_frame());
+                    self.request_cursor_frame();
+                },
             }
         } else {
             for pointer in self.pointers.iter().filter_map(|pointer| pointer.upgrade()) {
@@ -1016,15 +1281,21 @@ impl WindowState {
 
         self.decorate = decorate;
 
+        // Layer surfaces are never decorated.
+        let window = match self.window.toplevel() {
+            Some(window) => window,
+            None => return,
+        };
+
         match self.last_configure.as_ref().map(|configure| configure.decoration_mode) {
             Some(DecorationMode::Server) if !self.decorate => {
                 // To disable decorations we should request client and hide the frame.
-                self.window.request_decoration_mode(Some(DecorationMode::Client))
+                window.request_decoration_mode(Some(DecorationMode::Client))
             },
             _ if self.decorate && self.prefer_csd => {
-                self.window.request_decoration_mode(Some(DecorationMode::Client))
+                window.request_decoration_mode(Some(DecorationMode::Client))
             },
-            _ if self.decorate => self.window.request_decoration_mode(Some(DecorationMode::Server)),
+            _ if self.decorate => window.request_decoration_mode(Some(DecorationMode::Server)),
             _ => (),
         }
 
@@ -1101,8 +1372,13 @@ impl WindowState {
     /// Set the scale factor for the given window.
     #[inline]
     pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        let changed = self.scale_factor != scale_factor;
         self.scale_factor = scale_factor;
 
+        if let Some(cursor) = self.scaled_cursor.clone().filter(|_| changed) {
+            self.select_custom_cursor(cursor);
+        }
+
         // NOTE: When fractional scaling is not used update the buffer scale.
         if self.fractional_scale.is_none() {
             let _ = self.window.set_buffer_scale(self.scale_factor as _);
@@ -1149,12 +1425,19 @@ impl WindowState {
             frame.set_title(&title);
         }
 
-        self.window.set_title(&title);
+        if let Some(window) = self.window.toplevel() {
+            window.set_title(&title);
+        }
         self.title = title;
     }
 
     /// Set the window's icon
     pub fn set_window_icon(&mut self, window_icon: Option<winit_core::icon::Icon>) {
+        let xdg_toplevel = match self.window.toplevel() {
+            Some(window) => window.xdg_toplevel().clone(),
+            None => return,
+        };
+
         let xdg_toplevel_icon_manager = match self.xdg_toplevel_icon_manager.as_ref() {
             Some(xdg_toplevel_icon_manager) => xdg_toplevel_icon_manager,
             None => {
@@ -1184,7 +1467,7 @@ impl WindowState {
             None => (None, None),
         };
 
-        xdg_toplevel_icon_manager.set_icon(self.window.xdg_toplevel(), xdg_toplevel_icon.as_ref());
+        xdg_toplevel_icon_manager.set_icon(&xdg_toplevel, xdg_toplevel_icon.as_ref());
         self.toplevel_icon = toplevel_icon;
 
         if let Some(xdg_toplevel_icon) = xdg_toplevel_icon {
@@ -1257,6 +1540,73 @@ impl GrabState {
     }
 }
 
+/// The shell surface of a window.
+#[derive(Debug, Clone)]
+pub enum ShellSurface {
+    /// A regular window.
+    Toplevel(Window),
+
+    /// A layer-shell surface with the edges it's anchored to.
+    Layer(LayerSurface, Anchor),
+
+    /// A popup with the positioner it was placed with.
+    Popup(Popup, Arc<XdgPositioner>),
+}
+
+impl ShellSurface {
+    /// The toplevel of regular windows.
+    #[inline]
+    pub fn toplevel(&self) -> Option<&Window> {
+        match self {
+            Self::Toplevel(window) => Some(window),
+            Self::Layer(..) | Self::Popup(..) => None,
+        }
+    }
+
+    /// The `xdg_surface` of regular windows and popups, used as the parent of popups.
+    #[inline]
+    pub fn xdg_surface(&self) -> Option<&xdg_surface::XdgSurface> {
+        match self {
+            Self::Toplevel(window) => Some(window.xdg_surface()),
+            Self::Layer(..) => None,
+            Self::Popup(popup, _) => Some(popup.xdg_surface()),
+        }
+    }
+
+    /// Ask the compositor to place a popup again with a new size.
+    ///
+    /// Requires version 3 of `xdg_wm_base`, otherwise the popup keeps its position.
+    pub fn reposition_popup(&self, size: LogicalSize<u32>) {
+        if let Self::Popup(popup, positioner) = self {
+            if popup.xdg_popup().version() >= 3 {
+                positioner.set_size(size.width.max(1) as i32, size.height.max(1) as i32);
+                popup.reposition(positioner, 0);
+            }
+        }
+    }
+
+    /// Ask for the size of a layer surface.
+    pub fn set_layer_size(&self, size: LogicalSize<u32>) {
+        if let Self::Layer(layer, anchor) = self {
+            // Let the compositor pick the size in the directions the surface is stretched.
+            let width = if anchor.contains(Anchor::LEFT | Anchor::RIGHT) { 0 } else { size.width };
+            let height =
+                if anchor.contains(Anchor::TOP | Anchor::BOTTOM) { 0 } else { size.height };
+            layer.set_size(width, height);
+        }
+    }
+}
+
+impl WaylandSurface for ShellSurface {
+    fn wl_surface(&self) -> &WlSurface {
+        match self {
+            Self::Toplevel(window) => window.wl_surface(),
+            Self::Layer(layer, _) => layer.wl_surface(),
+            Self::Popup(popup, _) => popup.wl_surface(),
+        }
+    }
+}
+
 /// The state of the frame callback.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FrameCallbackState {
@@ -1281,15 +1631,3 @@ fn resize_direction_to_xdg(direction: ResizeDirection) -> XdgResizeEdge {
         ResizeDirection::South => XdgResizeEdge::Bottom,
     }
 }
-
-#[cfg(feature = "sctk-adwaita")]
-fn create_sctk_adwaita_config(theme: Option<Theme>) -> sctk_adwaita::FrameConfig {
-    let config = match theme {
-        Some(Theme::Light) => sctk_adwaita::FrameConfig::light(),
-        Some(Theme::Dark) => sctk_adwaita::FrameConfig::dark(),
-        None => sctk_adwaita::FrameConfig::auto(),
-    };
-    #[cfg(feature = "csd-adwaita-notitlebar")]
-    let config = config.hide_titlebar(true);
-    config
-}
@@ -54,6 +54,30 @@ impl CustomCursor {
                     true,
                 )
             },
+            CustomCursorSource::Scaled(images) => {
+                let (image, _) = &images.images()[0];
+                Self::build_spawn(
+                    event_loop,
+                    from_rgba(
+                        event_loop.runner.window(),
+                        event_loop.runner.document().clone(),
+                        image,
+                    ),
+                    false,
+                )
+            },
+            CustomCursorSource::File(file) => {
+                let image = file.best_variant(24).frames()[0].image();
+                Self::build_spawn(
+                    event_loop,
+                    from_rgba(
+                        event_loop.runner.window(),
+                        event_loop.runner.document().clone(),
+                        image,
+                    ),
+                    false,
+                )
+            },
         }
     }
 
@@ -136,6 +136,7 @@ pub fn pointer_source(event: &PointerEvent, kind: PointerKind) -> PointerSource
         PointerKind::Touch(id) => PointerSource::Touch {
             finger_id: id,
             force: Some(Force::Normalized(event.pressure().into())),
+            contact: None,
         },
         PointerKind::TabletTool(tool) => {
             let data = TabletToolData {
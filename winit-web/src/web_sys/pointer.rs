@@ -84,8 +84,8 @@ impl PointerHandler {
 
                 let source = match event::pointer_source(&event, kind) {
                     PointerSource::Mouse => event::mouse_button(button),
-                    PointerSource::Touch { finger_id, force } => {
-                        ButtonSource::Touch { finger_id, force }
+                    PointerSource::Touch { finger_id, force, contact } => {
+                        ButtonSource::Touch { finger_id, force, contact }
                     },
                     PointerSource::TabletTool { kind, data } => {
                         ButtonSource::TabletTool { kind, button: event::tool_button(button), data }
@@ -139,8 +139,8 @@ impl PointerHandler {
 
                         event::mouse_button(button)
                     },
-                    PointerSource::Touch { finger_id, force } => {
-                        ButtonSource::Touch { finger_id, force }
+                    PointerSource::Touch { finger_id, force, contact } => {
+                        ButtonSource::Touch { finger_id, force, contact }
                     },
                     PointerSource::TabletTool { kind, data } => {
                         // Error is swallowed here since the error would occur every time the
@@ -217,12 +217,12 @@ impl PointerHandler {
 
                     let button = match event::pointer_source(&event, kind) {
                         PointerSource::Mouse => event::mouse_button(button),
-                        PointerSource::Touch { finger_id, force } => {
+                        PointerSource::Touch { finger_id, force, contact } => {
                             if button != 0 {
                                 tracing::error!("unexpected touch button id: {button}");
                             }
 
-                            ButtonSource::Touch { finger_id, force }
+                            ButtonSource::Touch { finger_id, force, contact }
                         },
                         PointerSource::TabletTool { kind, data } => ButtonSource::TabletTool {
                             kind,
@@ -12,7 +12,7 @@ use windows_sys::Win32::System::Com::{DVASPECT_CONTENT, FORMATETC, TYMED_HGLOBAL
 use windows_sys::Win32::System::Ole::{CF_HDROP, DROPEFFECT_COPY, DROPEFFECT_NONE};
 use windows_sys::Win32::UI::Shell::{DragFinish, DragQueryFileW, HDROP};
 use windows_sys::core::{GUID, HRESULT};
-use winit_core::event::WindowEvent;
+use winit_core::event::{DragPayload, WindowEvent};
 
 use crate::definitions::{
     IDataObject, IDataObjectVtbl, IDropTarget, IDropTargetVtbl, IUnknown, IUnknownVtbl,
@@ -91,7 +91,11 @@ impl FileDropHandler {
         let hdrop = unsafe { Self::iterate_filenames(pDataObj, |path| paths.push(path)) };
         drop_handler.valid = hdrop.is_some();
         if drop_handler.valid {
-            (drop_handler.send_event)(WindowEvent::DragEntered { paths, position });
+            (drop_handler.send_event)(WindowEvent::DragEntered {
+                paths,
+                payload: DragPayload::default(),
+                position,
+            });
         }
         drop_handler.cursor_effect =
             if drop_handler.valid { DROPEFFECT_COPY } else { DROPEFFECT_NONE };
@@ -149,7 +153,11 @@ impl FileDropHandler {
             let position = PhysicalPosition::new(pt.x as f64, pt.y as f64);
             let mut paths = Vec::new();
             let hdrop = unsafe { Self::iterate_filenames(pDataObj, |path| paths.push(path)) };
-            (drop_handler.send_event)(WindowEvent::DragDropped { paths, position });
+            (drop_handler.send_event)(WindowEvent::DragDropped {
+                paths,
+                payload: DragPayload::default(),
+                position,
+            });
             if let Some(hdrop) = hdrop {
                 unsafe {
                     DragFinish(hdrop);
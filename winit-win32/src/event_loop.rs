@@ -427,7 +427,10 @@ impl RootActiveEventLoop for ActiveEventLoop {
     ) -> Result<CustomCursor, RequestError> {
         let cursor = match source {
             CustomCursorSource::Image(cursor) => cursor,
-            CustomCursorSource::Animation { .. } | CustomCursorSource::Url { .. } => {
+            CustomCursorSource::Animation { .. }
+            | CustomCursorSource::Url { .. }
+            | CustomCursorSource::File(_)
+            | CustomCursorSource::Scaled(_) => {
                 return Err(NotSupportedError::new("unsupported cursor kind").into());
             },
         };
@@ -1878,7 +1881,7 @@ unsafe fn public_window_callback_inner(
                             primary,
                             state: Pressed,
                             position,
-                            button: Touch { finger_id, force: None },
+                            button: Touch { finger_id, force: None, contact: None },
                         });
                     } else if util::has_flag(input.dwFlags, TOUCHEVENTF_UP) {
                         userdata.send_window_event(window, WindowEvent::PointerButton {
@@ -1886,7 +1889,7 @@ unsafe fn public_window_callback_inner(
                             primary,
                             state: Released,
                             position,
-                            button: Touch { finger_id, force: None },
+                            button: Touch { finger_id, force: None, contact: None },
                         });
                         userdata.send_window_event(window, WindowEvent::PointerLeft {
                             device_id: None,
@@ -1899,7 +1902,7 @@ unsafe fn public_window_callback_inner(
                             device_id: None,
                             primary,
                             position,
-                            source: PointerSource::Touch { finger_id, force: None },
+                            source: PointerSource::Touch { finger_id, force: None, contact: None },
                         });
                     } else {
                         continue;
@@ -2011,6 +2014,7 @@ unsafe fn public_window_callback_inner(
                             PT_TOUCH => (PointerKind::Touch(finger_id), ButtonSource::Touch {
                                 finger_id,
                                 force: force_for_touch(pointer_info.pointerId),
+                                contact: None,
                             }),
                             PT_PEN => {
                                 let kind = PointerKind::TabletTool(TabletToolKind::Pen);
@@ -2069,6 +2073,7 @@ unsafe fn public_window_callback_inner(
                             PT_TOUCH => PointerSource::Touch {
                                 finger_id,
                                 force: force_for_touch(pointer_info.pointerId),
+                                contact: None,
                             },
                             PT_PEN => PointerSource::TabletTool {
                                 kind: TabletToolKind::Pen,
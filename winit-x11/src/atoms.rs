@@ -34,6 +34,10 @@ macro_rules! atom_manager {
 atom_manager! {
     // General Use Atoms
     CARD32,
+    Clipboard: b"CLIPBOARD",
+    Edid: b"EDID",
+    Incr: b"INCR",
+    Targets: b"TARGETS",
     UTF8_STRING,
     WM_CHANGE_STATE,
     WM_CLIENT_MACHINE,
@@ -57,7 +61,13 @@ atom_manager! {
     _NET_WM_STATE_HIDDEN,
     _NET_WM_STATE_MAXIMIZED_HORZ,
     _NET_WM_STATE_MAXIMIZED_VERT,
+    _NET_WM_STATE_SKIP_PAGER,
+    _NET_WM_STATE_SKIP_TASKBAR,
+    _NET_WM_STATE_STICKY,
     _NET_WM_WINDOW_TYPE,
+    _NET_WM_DESKTOP,
+    _NET_WM_STRUT,
+    _NET_WM_STRUT_PARTIAL,
 
     // Activation atoms.
     _NET_STARTUP_INFO_BEGIN,
@@ -87,7 +97,9 @@ atom_manager! {
     XdndDrop,
     XdndPosition,
     XdndStatus,
-    XdndActionPrivate,
+    XdndActionCopy,
+    XdndActionMove,
+    XdndActionLink,
     XdndSelection,
     XdndFinished,
     XdndTypeList,
@@ -99,18 +111,36 @@ atom_manager! {
     _MOTIF_WM_HINTS,
     _NET_ACTIVE_WINDOW,
     _NET_CLIENT_LIST,
+    _NET_CURRENT_DESKTOP,
     _NET_FRAME_EXTENTS,
     _NET_SUPPORTED,
     _NET_SUPPORTING_WM_CHECK,
+    _NET_WORKAREA,
     _XEMBED,
     _XSETTINGS_SETTINGS,
+    _WINIT_SELECTION,
 
     // Stylus Atoms
-    ABS_X: b"Abs X",
-    ABS_Y: b"Abs Y",
     ABS_PRESSURE: b"Abs Pressure",
     ABS_TILT_X: b"Abs Tilt X",
-    ABS_TILT_Y: b"Abs Tilt Y"
+    ABS_TILT_Y: b"Abs Tilt Y",
+
+    // Touch Atoms
+    ABS_MT_POSITION_X: b"Abs MT Position X",
+    ABS_MT_PRESSURE: b"Abs MT Pressure",
+    ABS_MT_TOUCH_MAJOR: b"Abs MT Touch Major",
+    ABS_MT_TOUCH_MINOR: b"Abs MT Touch Minor",
+
+    // XInput device properties
+    DEVICE_PRODUCT_ID: b"Device Product ID",
+
+    // XInput device types
+    Eraser: b"ERASER",
+    Keyboard: b"KEYBOARD",
+    Stylus: b"STYLUS",
+    Tablet: b"TABLET",
+    Touchpad: b"TOUCHPAD",
+    Touchscreen: b"TOUCHSCREEN"
 }
 
 impl Index<AtomName> for Atoms {
@@ -0,0 +1,317 @@
+//! Clipboard and primary selection access.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Instant;
+
+use winit_core::clipboard::ClipboardKind;
+use winit_core::event::{ClipboardEvent, MimeData};
+use winit_core::event_loop::AsyncRequestSerial;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{self, ConnectionExt};
+
+use crate::atoms::*;
+use crate::event_loop::X11Error;
+use crate::util::{SELECTION_TIMEOUT, SelectionTargets};
+use crate::xdisplay::XConnection;
+
+const UTF8_TEXT_MIME_TYPE: &str = "text/plain;charset=utf-8";
+
+/// A request for the content of a selection, converted into our window.
+#[derive(Debug)]
+struct ClipboardRequest {
+    serial: AsyncRequestSerial,
+    kind: ClipboardKind,
+    // `None` when requesting the MIME types
+    mime_type: Option<String>,
+    target: xproto::Atom,
+    // The data received so far, when the owner sends it in chunks with `INCR`
+    incr: Option<Vec<u8>>,
+    // When the request is abandoned if the owner doesn't answer, once it's converted
+    deadline: Option<Instant>,
+}
+
+#[derive(Debug)]
+pub struct Clipboard {
+    xconn: Arc<XConnection>,
+    // The unmapped window owning our selections and receiving the requested content
+    window: xproto::Window,
+    // The content of the selections we own
+    contents: HashMap<xproto::Atom, SelectionTargets>,
+    // The requests, the first one is being converted
+    requests: VecDeque<ClipboardRequest>,
+}
+
+impl Clipboard {
+    pub fn new(xconn: Arc<XConnection>, root: xproto::Window) -> Result<Self, X11Error> {
+        let window = xconn.xcb_connection().generate_id()?;
+        xconn
+            .xcb_connection()
+            .create_window(
+                x11rb::COPY_DEPTH_FROM_PARENT,
+                window,
+                root,
+                0,
+                0,
+                1,
+                1,
+                0,
+                xproto::WindowClass::INPUT_ONLY,
+                x11rb::COPY_FROM_PARENT,
+                // Receive the chunks of `INCR` transfers.
+                &xproto::CreateWindowAux::new().event_mask(xproto::EventMask::PROPERTY_CHANGE),
+            )?
+            .check()?;
+
+        Ok(Self { xconn, window, contents: HashMap::new(), requests: VecDeque::new() })
+    }
+
+    /// The window owning our selections.
+    pub fn window(&self) -> xproto::Window {
+        self.window
+    }
+
+    fn selection(&self, kind: ClipboardKind) -> xproto::Atom {
+        match kind {
+            ClipboardKind::Clipboard => self.xconn.atoms()[Clipboard],
+            ClipboardKind::Primary => xproto::AtomEnum::PRIMARY.into(),
+        }
+    }
+
+    /// Take the ownership of the selection, serving the content until another client takes it.
+    pub fn set(&mut self, kind: ClipboardKind, content: &MimeData) -> Result<(), X11Error> {
+        let selection = self.selection(kind);
+        let targets = self.xconn.selection_targets(content)?;
+
+        let conn = self.xconn.xcb_connection();
+        conn.set_selection_owner(self.window, selection, self.xconn.timestamp())?.ignore_error();
+        if conn.get_selection_owner(selection)?.reply()?.owner != self.window {
+            return Err(X11Error::SelectionOwnership);
+        }
+
+        self.contents.insert(selection, targets);
+        Ok(())
+    }
+
+    /// Request the content of the selection, or its MIME types when `mime_type` is `None`.
+    pub fn request(
+        &mut self,
+        kind: ClipboardKind,
+        mime_type: Option<&str>,
+    ) -> Result<AsyncRequestSerial, X11Error> {
+        let atoms = self.xconn.atoms();
+        let target = match mime_type {
+            None => atoms[Targets],
+            Some(UTF8_TEXT_MIME_TYPE) => atoms[UTF8_STRING],
+            Some(mime_type) => {
+                self.xconn.xcb_connection().intern_atom(false, mime_type.as_bytes())?.reply()?.atom
+            },
+        };
+
+        let serial = AsyncRequestSerial::get();
+        let mime_type = mime_type.map(ToOwned::to_owned);
+        self.requests.push_back(ClipboardRequest {
+            serial,
+            kind,
+            mime_type,
+            target,
+            incr: None,
+            deadline: None,
+        });
+        if self.requests.len() == 1 {
+            self.convert_request()?;
+        }
+
+        Ok(serial)
+    }
+
+    fn convert_request(&mut self) -> Result<(), X11Error> {
+        let request = match self.requests.front_mut() {
+            Some(request) => request,
+            None => return Ok(()),
+        };
+        request.deadline = Some(Instant::now() + SELECTION_TIMEOUT);
+        let (kind, target) = (request.kind, request.target);
+
+        let atoms = self.xconn.atoms();
+        self.xconn
+            .xcb_connection()
+            .convert_selection(
+                self.window,
+                self.selection(kind),
+                target,
+                atoms[_WINIT_SELECTION],
+                self.xconn.timestamp(),
+            )?
+            .ignore_error();
+        self.xconn.flush_requests()?;
+        Ok(())
+    }
+
+    /// Read the converted content of the first request, `property` is `NONE` when the selection
+    /// couldn't be converted.
+    pub fn selection_notify(
+        &mut self,
+        target: xproto::Atom,
+        property: xproto::Atom,
+    ) -> Option<ClipboardEvent> {
+        // Skip the late answers to the requests which timed out.
+        let request = self.requests.front()?;
+        if request.target != target || request.incr.is_some() {
+            return None;
+        }
+
+        if property == x11rb::NONE {
+            return self.finish_request(None);
+        }
+
+        // Deleting the property tells the owner to send the first chunk of `INCR` transfers.
+        let reply = self.read_property(property);
+        if reply.as_ref().is_some_and(|reply| reply.type_ == self.xconn.atoms()[Incr]) {
+            let request = self.requests.front_mut().unwrap();
+            request.incr = Some(Vec::new());
+            request.deadline = Some(Instant::now() + SELECTION_TIMEOUT);
+            return None;
+        }
+
+        self.finish_request(reply.map(|reply| reply.value))
+    }
+
+    /// Read the next chunk of an `INCR` transfer, written to `property` of our window.
+    pub fn property_notify(&mut self, property: xproto::Atom) -> Option<ClipboardEvent> {
+        if property != self.xconn.atoms()[_WINIT_SELECTION]
+            || self.requests.front().is_none_or(|request| request.incr.is_none())
+        {
+            return None;
+        }
+
+        let chunk = match self.read_property(property) {
+            Some(reply) => reply.value,
+            None => return self.finish_request(None),
+        };
+
+        let request = self.requests.front_mut().unwrap();
+        // The empty chunk ends the transfer.
+        if chunk.is_empty() {
+            let data = request.incr.take();
+            return self.finish_request(data);
+        }
+
+        request.incr.as_mut().unwrap().extend_from_slice(&chunk);
+        request.deadline = Some(Instant::now() + SELECTION_TIMEOUT);
+        None
+    }
+
+    /// When the owner of the selection has to answer the current request by.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.requests.front()?.deadline
+    }
+
+    /// Abandon the current request when the owner of the selection didn't answer in time.
+    pub fn check_timeout(&mut self) -> Option<ClipboardEvent> {
+        self.xconn.expire_incr_transfers();
+        if self.deadline().is_none_or(|deadline| deadline > Instant::now()) {
+            return None;
+        }
+
+        tracing::warn!("The owner of the selection didn't answer in time");
+        self.finish_request(None)
+    }
+
+    /// Read and delete the property of our window.
+    fn read_property(&self, property: xproto::Atom) -> Option<xproto::GetPropertyReply> {
+        self.xconn
+            .xcb_connection()
+            .get_property(true, self.window, property, xproto::AtomEnum::ANY, 0, u32::MAX)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+    }
+
+    /// Answer the first request with its data, `None` when it couldn't be converted, and convert
+    /// the next one.
+    fn finish_request(&mut self, data: Option<Vec<u8>>) -> Option<ClipboardEvent> {
+        let request = self.requests.pop_front()?;
+        let event = match request.mime_type {
+            Some(mime_type) => {
+                ClipboardEvent::Data { serial: request.serial, kind: request.kind, mime_type, data }
+            },
+            None => {
+                let targets = parse_atoms(&data.unwrap_or_default());
+                let names = self.xconn.atom_names(&targets);
+                let has_utf8_string = targets.contains(&self.xconn.atoms()[UTF8_STRING]);
+                let mime_types = target_mime_types(names, has_utf8_string);
+                ClipboardEvent::MimeTypes { serial: request.serial, kind: request.kind, mime_types }
+            },
+        };
+
+        if let Err(err) = self.convert_request() {
+            tracing::warn!("Failed to request the clipboard content: {err}");
+        }
+
+        Some(event)
+    }
+
+    /// Answer the request of another client for the content of a selection we own.
+    pub fn selection_request(
+        &self,
+        requestor: xproto::Window,
+        selection: xproto::Atom,
+        target: xproto::Atom,
+        property: xproto::Atom,
+        time: xproto::Timestamp,
+    ) -> Result<(), X11Error> {
+        let targets = self.contents.get(&selection).map(Vec::as_slice);
+        self.xconn.send_selection(targets, requestor, selection, target, property, time)
+    }
+
+    /// Another client took the ownership of the selection.
+    pub fn selection_clear(&mut self, selection: xproto::Atom) {
+        self.contents.remove(&selection);
+    }
+}
+
+/// Read the atoms of a property in the 32-bit format.
+fn parse_atoms(data: &[u8]) -> Vec<xproto::Atom> {
+    data.chunks_exact(4).map(|atom| u32::from_ne_bytes(atom.try_into().unwrap())).collect()
+}
+
+/// The MIME types among the names of the targets of a selection, along with UTF-8 text under its
+/// MIME type.
+fn target_mime_types(mut names: Vec<String>, has_utf8_string: bool) -> Vec<String> {
+    names.retain(|name| name.contains('/'));
+    if has_utf8_string && !names.iter().any(|name| name == UTF8_TEXT_MIME_TYPE) {
+        names.insert(0, UTF8_TEXT_MIME_TYPE.to_owned());
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_atoms() {
+        let data: Vec<u8> =
+            [1u32, 42, u32::MAX].iter().flat_map(|atom| atom.to_ne_bytes()).collect();
+        assert_eq!(parse_atoms(&data), [1, 42, u32::MAX]);
+        // A truncated atom is skipped.
+        assert_eq!(parse_atoms(&data[..6]), [1]);
+        assert!(parse_atoms(&[]).is_empty());
+    }
+
+    #[test]
+    fn keeps_mime_types() {
+        let names = ["TARGETS", "UTF8_STRING", "text/html", "TIMESTAMP", "image/png"];
+        let names = names.map(String::from).to_vec();
+        assert_eq!(target_mime_types(names.clone(), true), [
+            UTF8_TEXT_MIME_TYPE,
+            "text/html",
+            "image/png"
+        ]);
+        assert_eq!(target_mime_types(names, false), ["text/html", "image/png"]);
+
+        // UTF-8 text offered under its MIME type isn't listed twice.
+        let names = vec![String::from("text/html"), String::from(UTF8_TEXT_MIME_TYPE)];
+        assert_eq!(target_mime_types(names, true), ["text/html", UTF8_TEXT_MIME_TYPE]);
+    }
+}
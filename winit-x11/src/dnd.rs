@@ -1,11 +1,17 @@
-use std::io;
+use std::collections::VecDeque;
 use std::os::raw::*;
 use std::path::{Path, PathBuf};
 use std::str::Utf8Error;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{io, mem};
 
 use dpi::PhysicalPosition;
 use percent_encoding::percent_decode;
+use winit_core::event::{DragAction, DragPayload, DragSource};
+use winit_core::event_loop::AsyncRequestSerial;
+use winit_core::window::WindowId;
+use x11rb::protocol::xinput::{self, ConnectionExt as _};
 use x11rb::protocol::xproto::{self, ConnectionExt};
 
 use crate::atoms::AtomName::None as DndNone;
@@ -16,10 +22,55 @@ use crate::xdisplay::XConnection;
 
 #[derive(Debug, Clone, Copy)]
 pub enum DndState {
-    Accepted,
+    Accepted(DragAction),
     Rejected,
 }
 
+/// Data requested with `Window::request_drag_data`.
+#[derive(Debug)]
+pub struct DragDataRequest {
+    pub window_id: WindowId,
+    pub serial: AsyncRequestSerial,
+    pub mime_type: String,
+    pub target: xproto::Atom,
+}
+
+/// The XDND protocol version we speak as a source.
+const SOURCE_VERSION: u32 = 5;
+
+/// How long the target has to answer our `XdndPosition` and `XdndDrop` before the drag is
+/// cancelled.
+const SOURCE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A drag and drop operation started from one of our windows with `Window::start_drag`.
+#[derive(Debug)]
+pub struct DragSourceState {
+    pub window: xproto::Window,
+    pub serial: AsyncRequestSerial,
+    device_id: xinput::DeviceId,
+    targets: util::SelectionTargets,
+    action: xproto::Atom,
+    time: xproto::Timestamp,
+    target: Option<DragTarget>,
+    dropped: bool,
+    // The pointer is released once the data is dropped
+    grabbed: bool,
+    // When the drag is cancelled unless the target answers
+    deadline: Option<Instant>,
+}
+
+/// The `XdndAware` window under the pointer.
+#[derive(Debug)]
+struct DragTarget {
+    window: xproto::Window,
+    version: u32,
+    // The action accepted by the last `XdndStatus`
+    accepted: Option<xproto::Atom>,
+    // Only one `XdndPosition` is sent until the target answers with `XdndStatus`
+    waiting_status: bool,
+    pending_position: Option<(i16, i16)>,
+}
+
 #[derive(Debug)]
 pub enum DndDataParseError {
     EmptyData,
@@ -55,6 +106,18 @@ pub struct Dnd {
     pub result: Option<Result<Vec<PathBuf>, DndDataParseError>>,
     // Populated by SelectionNotify event handler (triggered by XdndPosition event handler)
     pub dragging: bool,
+    // Populated by XdndEnter event handler
+    pub payload: DragPayload,
+    // Populated by XdndPosition event handler
+    pub window: Option<xproto::Window>,
+    // Populated by XdndPosition and XdndDrop event handlers
+    pub time: xproto::Timestamp,
+    // Populated by XdndDrop event handler, the drop is finished once the requests are answered
+    pub dropped: bool,
+    // The data requests, the first one is being converted
+    pub requests: VecDeque<DragDataRequest>,
+    // Populated by `Window::start_drag`
+    pub source: Option<DragSourceState>,
 }
 
 impl Dnd {
@@ -67,15 +130,54 @@ impl Dnd {
             position: PhysicalPosition::default(),
             result: None,
             dragging: false,
+            payload: DragPayload::default(),
+            window: None,
+            time: x11rb::CURRENT_TIME,
+            dropped: false,
+            requests: VecDeque::new(),
+            source: None,
         })
     }
 
-    pub fn reset(&mut self) {
+    /// Reset the state, returning the data requests left unanswered.
+    pub fn reset(&mut self) -> VecDeque<DragDataRequest> {
         self.version = None;
         self.type_list = None;
         self.source_window = None;
         self.result = None;
         self.dragging = false;
+        self.payload = DragPayload::default();
+        self.window = None;
+        self.time = x11rb::CURRENT_TIME;
+        self.dropped = false;
+        mem::take(&mut self.requests)
+    }
+
+    /// Get the MIME types of the offered targets.
+    pub fn mime_types(&self, type_list: &[xproto::Atom]) -> Vec<String> {
+        self.xconn.atom_names(type_list)
+    }
+
+    /// Get the action requested by the source.
+    pub fn action(&self, action: xproto::Atom) -> DragAction {
+        let atoms = self.xconn.atoms();
+        if action == atoms[XdndActionMove] {
+            DragAction::Move
+        } else if action == atoms[XdndActionLink] {
+            DragAction::Link
+        } else {
+            // Fallback to copy for the private and ask actions.
+            DragAction::Copy
+        }
+    }
+
+    fn action_atom(&self, action: DragAction) -> xproto::Atom {
+        let atoms = self.xconn.atoms();
+        match action {
+            DragAction::Copy => atoms[XdndActionCopy],
+            DragAction::Move => atoms[XdndActionMove],
+            DragAction::Link => atoms[XdndActionLink],
+        }
     }
 
     pub unsafe fn send_status(
@@ -86,7 +188,7 @@ impl Dnd {
     ) -> Result<(), X11Error> {
         let atoms = self.xconn.atoms();
         let (accepted, action) = match state {
-            DndState::Accepted => (1, atoms[XdndActionPrivate]),
+            DndState::Accepted(action) => (1, self.action_atom(action)),
             DndState::Rejected => (0, atoms[DndNone]),
         };
         self.xconn
@@ -110,7 +212,7 @@ impl Dnd {
     ) -> Result<(), X11Error> {
         let atoms = self.xconn.atoms();
         let (accepted, action) = match state {
-            DndState::Accepted => (1, atoms[XdndActionPrivate]),
+            DndState::Accepted(action) => (1, self.action_atom(action)),
             DndState::Rejected => (0, atoms[DndNone]),
         };
         self.xconn
@@ -152,6 +254,40 @@ impl Dnd {
             .expect_then_ignore_error("Failed to send XdndSelection event")
     }
 
+    /// Convert the selection into the target of the first data request.
+    pub fn convert_request(&self, window: xproto::Window) {
+        let request = match self.requests.front() {
+            Some(request) => request,
+            None => return,
+        };
+
+        let atoms = self.xconn.atoms();
+        self.xconn
+            .xcb_connection()
+            .convert_selection(
+                window,
+                atoms[XdndSelection],
+                request.target,
+                request.target,
+                self.time,
+            )
+            .expect_then_ignore_error("Failed to send XdndSelection event")
+    }
+
+    /// Read the converted data of the first data request.
+    pub fn read_request_data(
+        &self,
+        window: xproto::Window,
+        property: xproto::Atom,
+    ) -> Result<Vec<u8>, util::GetPropertyError> {
+        let reply = self
+            .xconn
+            .xcb_connection()
+            .get_property(true, window, property, xproto::AtomEnum::ANY, 0, u32::MAX)?
+            .reply()?;
+        Ok(reply.value)
+    }
+
     pub unsafe fn read_data(
         &self,
         window: xproto::Window,
@@ -188,4 +324,305 @@ impl Dnd {
             Err(DndDataParseError::EmptyData)
         }
     }
+
+    /// Start dragging the data from the window, grabbing the pointer until the data is dropped.
+    pub fn start_drag(
+        &mut self,
+        window: xproto::Window,
+        serial: AsyncRequestSerial,
+        source: &DragSource,
+    ) -> Result<(), X11Error> {
+        let atoms = self.xconn.atoms();
+        let conn = self.xconn.xcb_connection();
+
+        let targets = self.xconn.selection_targets(source.data())?;
+
+        let type_list: Vec<xproto::Atom> = targets.iter().map(|&(atom, _)| atom).collect();
+        self.xconn
+            .change_property(
+                window,
+                atoms[XdndTypeList],
+                xproto::AtomEnum::ATOM.into(),
+                xproto::PropMode::REPLACE,
+                &type_list,
+            )?
+            .ignore_error();
+        conn.set_selection_owner(window, atoms[XdndSelection], x11rb::CURRENT_TIME)?.ignore_error();
+
+        let device_id = conn.xinput_xi_get_client_pointer(window)?.reply()?.deviceid;
+        let mask = xinput::XIEventMask::MOTION
+            | xinput::XIEventMask::BUTTON_PRESS
+            | xinput::XIEventMask::BUTTON_RELEASE;
+        let status = conn
+            .xinput_xi_grab_device(
+                window,
+                x11rb::CURRENT_TIME,
+                x11rb::NONE,
+                device_id,
+                xproto::GrabMode::ASYNC,
+                xproto::GrabMode::ASYNC,
+                xinput::GrabOwner::OWNER,
+                &[u32::from(mask)],
+            )?
+            .reply()?
+            .status;
+        if status != xproto::GrabStatus::SUCCESS {
+            return Err(X11Error::GrabFailed(status));
+        }
+
+        self.source = Some(DragSourceState {
+            window,
+            serial,
+            device_id,
+            targets,
+            action: self.action_atom(source.action()),
+            time: x11rb::CURRENT_TIME,
+            target: None,
+            dropped: false,
+            grabbed: true,
+            deadline: None,
+        });
+
+        Ok(())
+    }
+
+    /// Whether the data dragged from our window hasn't been dropped yet.
+    pub fn is_dragging(&self) -> bool {
+        self.source.as_ref().is_some_and(|source| !source.dropped)
+    }
+
+    /// When the drag should be cancelled because the target didn't answer.
+    pub fn source_deadline(&self) -> Option<Instant> {
+        self.source.as_ref()?.deadline
+    }
+
+    /// Track the pointer while dragging, the position is relative to the root window.
+    pub fn source_motion(
+        &mut self,
+        root: xproto::Window,
+        position: (i16, i16),
+        time: xproto::Timestamp,
+    ) -> Result<(), X11Error> {
+        let target = self.find_target(root, position)?;
+        let source = match self.source.as_mut() {
+            Some(source) if !source.dropped => source,
+            _ => return Ok(()),
+        };
+        source.time = time;
+
+        let current = source.target.as_ref().map(|target| target.window);
+        if current != target.map(|(window, _)| window) {
+            if let Some(old) = source.target.take() {
+                source.deadline = None;
+                self.send_source_message(old.window, XdndLeave, [0, 0, 0, 0])?;
+            }
+
+            if let Some((window, version)) = target {
+                let source = self.source.as_mut().unwrap();
+                let version = version.min(SOURCE_VERSION);
+                source.target = Some(DragTarget {
+                    window,
+                    version,
+                    accepted: None,
+                    waiting_status: false,
+                    pending_position: None,
+                });
+
+                let types: Vec<xproto::Atom> =
+                    source.targets.iter().map(|&(atom, _)| atom).collect();
+                let flags = (version << 24) | u32::from(types.len() > 3);
+                let get = |index: usize| types.get(index).copied().unwrap_or(x11rb::NONE);
+                self.send_source_message(window, XdndEnter, [flags, get(0), get(1), get(2)])?;
+            }
+        }
+
+        let source = self.source.as_mut().unwrap();
+        match source.target.as_mut() {
+            Some(target) if target.waiting_status => {
+                target.pending_position = Some(position);
+                Ok(())
+            },
+            Some(_) => self.send_position(position),
+            None => Ok(()),
+        }
+    }
+
+    /// Handle the `XdndStatus` sent by the target.
+    pub fn source_status(
+        &mut self,
+        target_window: xproto::Window,
+        accepted: bool,
+        action: xproto::Atom,
+    ) -> Result<(), X11Error> {
+        let source = match self.source.as_mut() {
+            Some(source) if !source.dropped => source,
+            _ => return Ok(()),
+        };
+        let target = match source.target.as_mut() {
+            Some(target) if target.window == target_window => target,
+            _ => return Ok(()),
+        };
+
+        source.deadline = None;
+        target.waiting_status = false;
+        target.accepted = accepted.then_some(action);
+        match target.pending_position.take() {
+            Some(position) => self.send_position(position),
+            None => Ok(()),
+        }
+    }
+
+    /// Drop the data on the target under the pointer, releasing the pointer.
+    ///
+    /// Returns `false` when there's no target accepting the data, in which case the drag should be
+    /// ended right away.
+    pub fn source_drop(&mut self, time: xproto::Timestamp) -> Result<bool, X11Error> {
+        let source = match self.source.as_mut() {
+            Some(source) if !source.dropped => source,
+            _ => return Ok(true),
+        };
+        source.time = time;
+
+        match source.target.as_ref().map(|target| (target.window, target.accepted)) {
+            Some((window, Some(_))) => {
+                source.dropped = true;
+                source.deadline = Some(Instant::now() + SOURCE_TIMEOUT);
+                self.ungrab();
+                self.send_source_message(window, XdndDrop, [0, time, 0, 0])?;
+                Ok(true)
+            },
+            Some((_, None)) | None => Ok(false),
+        }
+    }
+
+    /// Handle the `XdndFinished` sent by the target, returning the performed action.
+    pub fn source_finished(
+        &self,
+        target_window: xproto::Window,
+        succeeded: bool,
+        action: xproto::Atom,
+    ) -> Option<Option<DragAction>> {
+        let source = self.source.as_ref().filter(|source| source.dropped)?;
+        let target = source.target.as_ref().filter(|target| target.window == target_window)?;
+
+        // The result of the drop is only reported since version 5.
+        let action =
+            if target.version >= 5 { succeeded.then_some(action) } else { target.accepted };
+        Some(action.map(|action| self.action(action)))
+    }
+
+    /// Stop dragging, releasing the pointer and the data.
+    pub fn end_drag(&mut self) -> Option<DragSourceState> {
+        self.ungrab();
+        let source = self.source.take()?;
+        if !source.dropped {
+            if let Some(target) = source.target.as_ref() {
+                let _ = self.send_message(source.window, target.window, XdndLeave, [0, 0, 0, 0]);
+            }
+        }
+
+        let atoms = self.xconn.atoms();
+        let conn = self.xconn.xcb_connection();
+        conn.set_selection_owner(x11rb::NONE, atoms[XdndSelection], source.time)
+            .expect_then_ignore_error("Failed to release the XdndSelection");
+        conn.delete_property(source.window, atoms[XdndTypeList])
+            .expect_then_ignore_error("Failed to delete the XdndTypeList");
+        Some(source)
+    }
+
+    /// Release the pointer grabbed while dragging.
+    fn ungrab(&mut self) {
+        let source = match self.source.as_mut() {
+            Some(source) if source.grabbed => source,
+            _ => return,
+        };
+
+        source.grabbed = false;
+        self.xconn
+            .xcb_connection()
+            .xinput_xi_ungrab_device(source.time, source.device_id)
+            .expect_then_ignore_error("Failed to ungrab the pointer");
+    }
+
+    /// Answer a conversion request of the data being dragged.
+    pub fn serve_selection_request(
+        &self,
+        requestor: xproto::Window,
+        target: xproto::Atom,
+        property: xproto::Atom,
+        time: xproto::Timestamp,
+    ) -> Result<(), X11Error> {
+        let atoms = self.xconn.atoms();
+        let targets = self.source.as_ref().map(|source| source.targets.as_slice());
+        self.xconn.send_selection(targets, requestor, atoms[XdndSelection], target, property, time)
+    }
+
+    /// Find the `XdndAware` window under the given root position along with its version.
+    fn find_target(
+        &self,
+        root: xproto::Window,
+        (x, y): (i16, i16),
+    ) -> Result<Option<(xproto::Window, u32)>, X11Error> {
+        let atoms = self.xconn.atoms();
+        let conn = self.xconn.xcb_connection();
+        let mut window = root;
+        loop {
+            let child = conn.translate_coordinates(root, window, x, y)?.reply()?.child;
+            if child == x11rb::NONE {
+                return Ok(None);
+            }
+
+            let version = self
+                .xconn
+                .get_property::<u32>(child, atoms[XdndAware], xproto::AtomEnum::ATOM.into())
+                .ok()
+                .and_then(|version| version.first().copied());
+            if let Some(version) = version {
+                return Ok(Some((child, version)));
+            }
+
+            window = child;
+        }
+    }
+
+    fn send_position(&mut self, (x, y): (i16, i16)) -> Result<(), X11Error> {
+        let source = self.source.as_mut().unwrap();
+        let (time, action) = (source.time, source.action);
+        source.deadline = Some(Instant::now() + SOURCE_TIMEOUT);
+        let target = source.target.as_mut().unwrap();
+        target.waiting_status = true;
+        let window = target.window;
+        let position = ((x as u16 as u32) << 16) | y as u16 as u32;
+        self.send_source_message(window, XdndPosition, [0, position, time, action])
+    }
+
+    fn send_source_message(
+        &self,
+        target_window: xproto::Window,
+        message: AtomName,
+        data: [u32; 4],
+    ) -> Result<(), X11Error> {
+        let window = self.source.as_ref().unwrap().window;
+        self.send_message(window, target_window, message, data)
+    }
+
+    fn send_message(
+        &self,
+        this_window: xproto::Window,
+        target_window: xproto::Window,
+        message: AtomName,
+        [a, b, c, d]: [u32; 4],
+    ) -> Result<(), X11Error> {
+        let atoms = self.xconn.atoms();
+        self.xconn
+            .send_client_msg(target_window, target_window, atoms[message], None, [
+                this_window,
+                a,
+                b,
+                c,
+                d,
+            ])?
+            .ignore_error();
+        Ok(())
+    }
 }
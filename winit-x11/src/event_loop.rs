@@ -16,15 +16,20 @@ use calloop::ping::Ping;
 use calloop::{EventLoop as Loop, Readiness};
 use libc::{LC_CTYPE, setlocale};
 use tracing::warn;
+use winit_common::xdg_desktop_portal::{Settings as PortalSettings, SettingsWatcher};
 use winit_common::xkb::Context;
 use winit_core::application::ApplicationHandler;
+use winit_core::clipboard::ClipboardKind;
 use winit_core::cursor::{CustomCursor as CoreCustomCursor, CustomCursorSource};
 use winit_core::error::{EventLoopError, RequestError};
-use winit_core::event::{DeviceId, StartCause, WindowEvent};
+use winit_core::event::{
+    DeviceId, DeviceInfo as CoreDeviceInfo, DeviceKind, Force, MimeData, StartCause,
+    TabletToolData, TabletToolKind, TouchContact, WindowEvent,
+};
 use winit_core::event_loop::pump_events::PumpStatus;
 use winit_core::event_loop::{
-    ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents,
-    EventLoopProxy as CoreEventLoopProxy, EventLoopProxyProvider,
+    ActiveEventLoop as RootActiveEventLoop, AsyncRequestSerial, ControlFlow, DesktopSettings,
+    DeviceEvents, EventLoopProxy as CoreEventLoopProxy, EventLoopProxyProvider,
     OwnedDisplayHandle as CoreOwnedDisplayHandle,
 };
 use winit_core::monitor::MonitorHandle as CoreMonitorHandle;
@@ -32,18 +37,21 @@ use winit_core::window::{Theme, Window as CoreWindow, WindowAttributes, WindowId
 use x11rb::connection::RequestConnection;
 use x11rb::errors::{ConnectError, ConnectionError, IdsExhausted, ReplyError};
 use x11rb::protocol::xinput::{self, ConnectionExt as _};
-use x11rb::protocol::{xkb, xproto};
+use x11rb::protocol::xkb;
+use x11rb::protocol::xproto::{self, ConnectionExt as _};
 use x11rb::x11_utils::X11Error as LogicalError;
 use x11rb::xcb_ffi::ReplyOrIdError;
 
 use crate::atoms::*;
+use crate::clipboard::Clipboard;
 use crate::dnd::Dnd;
 use crate::event_processor::{EventProcessor, MAX_MOD_REPLAY_LEN};
 use crate::ime::{self, Ime, ImeCreationError, ImeSender};
-use crate::util::{self, CustomCursor};
+use crate::util::{self, CursorTheme, CustomCursor};
 use crate::window::{UnownedWindow, Window};
 use crate::xdisplay::{XConnection, XError, XNotSupported};
-use crate::{XlibErrorHook, ffi, xsettings};
+use crate::xsettings::XSettings;
+use crate::{XlibErrorHook, ffi, monitor, xsettings};
 
 // Xinput constants not defined in x11rb
 pub(crate) const ALL_DEVICES: u16 = 0;
@@ -177,8 +185,23 @@ pub struct ActiveEventLoop {
     pub(crate) root: xproto::Window,
     pub(crate) ime: Option<RefCell<Ime>>,
     pub(crate) windows: RefCell<HashMap<WindowId, Weak<UnownedWindow>>>,
+    pub(crate) devices: RefCell<HashMap<DeviceId, Device>>,
+    /// The chain of popups grabbing the pointer, from the outermost one, with the grabbed device.
+    ///
+    /// Only the innermost popup holds the grab.
+    pub(crate) popup_grabs: RefCell<Vec<(WindowId, xinput::DeviceId)>>,
     pub(crate) redraw_sender: WakeSender<WindowId>,
     pub(crate) activation_sender: WakeSender<ActivationItem>,
+    pub(crate) drag_data_sender: WakeSender<DragDataItem>,
+    pub(crate) start_drag_sender: WakeSender<StartDragItem>,
+    pub(crate) clipboard: RefCell<Clipboard>,
+    pub(crate) xsettings: RefCell<XSettings>,
+    /// The settings of the XDG Desktop Portal, as last reported by its helper thread.
+    pub(crate) portal_settings: RefCell<PortalSettings>,
+    /// The cursor theme set by the application, taking precedence over the one of XSettings.
+    cursor_theme: RefCell<CursorTheme>,
+    // Whether the server sends the touchpad gestures of XInput 2.4.
+    pub(crate) xi2_gestures: bool,
     event_loop_proxy: CoreEventLoopProxy,
     device_events: Cell<DeviceEvents>,
 }
@@ -190,6 +213,12 @@ pub struct EventLoop {
     event_processor: EventProcessor,
     redraw_receiver: PeekableReceiver<WindowId>,
     activation_receiver: PeekableReceiver<ActivationItem>,
+    drag_data_receiver: PeekableReceiver<DragDataItem>,
+    start_drag_receiver: PeekableReceiver<StartDragItem>,
+    portal_settings_receiver: PeekableReceiver<PortalSettings>,
+
+    /// The helper thread following the settings of the XDG Desktop Portal.
+    _portal_settings_watcher: SettingsWatcher,
 
     /// The current state of the event loop.
     state: EventLoopState,
@@ -197,6 +226,11 @@ pub struct EventLoop {
 
 pub(crate) type ActivationItem = (WindowId, winit_core::event_loop::AsyncRequestSerial);
 
+pub(crate) type DragDataItem = (WindowId, winit_core::event_loop::AsyncRequestSerial, String);
+
+pub(crate) type StartDragItem =
+    (WindowId, winit_core::event_loop::AsyncRequestSerial, winit_core::event::DragSource);
+
 #[derive(Debug)]
 struct EventLoopState {
     /// The latest readiness state for the x11 file descriptor
@@ -222,6 +256,8 @@ impl EventLoop {
         let root = xconn.default_root().root;
         let atoms = xconn.atoms();
 
+        xconn.refresh_system_theme(&PortalSettings::default());
+
         let wm_delete_window = atoms[WM_DELETE_WINDOW];
         let net_wm_ping = atoms[_NET_WM_PING];
         let net_wm_sync_request = atoms[_NET_WM_SYNC_REQUEST];
@@ -267,6 +303,25 @@ impl EventLoop {
         let randr_event_offset =
             xconn.select_xrandr_input(root).expect("Failed to query XRandR extension");
 
+        // Get notified when the work area of the monitors changes.
+        if let Err(err) = xconn
+            .xcb_connection()
+            .change_window_attributes(
+                root,
+                &xproto::ChangeWindowAttributesAux::new()
+                    .event_mask(xproto::EventMask::PROPERTY_CHANGE),
+            )
+            .map_err(X11Error::from)
+            .and_then(|cookie| cookie.check().map_err(X11Error::from))
+        {
+            tracing::warn!("Failed to select the root window properties: {err}");
+        }
+
+        // Cache the monitors, to know which ones changed when RandR notifies us.
+        if let Err(err) = xconn.available_monitors() {
+            tracing::warn!("Failed to get the monitor list: {err}");
+        }
+
         let xi2ext = xconn
             .xcb_connection()
             .extension_information(xinput::X11_EXTENSION_NAME)
@@ -278,13 +333,14 @@ impl EventLoop {
             .expect("Failed to query XKB extension")
             .expect("X server missing XKB extension");
 
-        // Check for XInput2 support.
-        xconn
+        // Check for XInput2 support, version 2.4 adds the touchpad gestures.
+        let xinput_version = xconn
             .xcb_connection()
-            .xinput_xi_query_version(2, 3)
+            .xinput_xi_query_version(2, 4)
             .expect("Failed to send XInput2 query version request")
             .reply()
             .expect("Error while checking for XInput2 query version reply");
+        let xi2_gestures = (xinput_version.major_version, xinput_version.minor_version) >= (2, 4);
 
         xconn.update_cached_wm_info(root);
 
@@ -322,6 +378,19 @@ impl EventLoop {
         // Create a channel for sending activation tokens.
         let (activation_token_sender, activation_token_channel) = mpsc::channel();
 
+        // Create a channel for requesting drag and drop data.
+        let (drag_data_sender, drag_data_channel) = mpsc::channel();
+
+        // Create a channel for starting drag and drop operations.
+        let (start_drag_sender, start_drag_channel) = mpsc::channel();
+
+        // Create a channel for receiving the settings of the XDG Desktop Portal.
+        let (portal_settings_sender, portal_settings_channel) = mpsc::channel();
+        let portal_settings_sender =
+            WakeSender { sender: portal_settings_sender, waker: waker.clone() };
+        let portal_settings_watcher =
+            SettingsWatcher::spawn(move |settings| portal_settings_sender.send(settings));
+
         // Create a channel for sending user events.
         let (user_waker, user_waker_source) =
             calloop::ping::make_ping().expect("Failed to create user event loop waker.");
@@ -340,12 +409,22 @@ impl EventLoop {
         let mut xmodmap = util::ModifierKeymap::new();
         xmodmap.reload_from_x_connection(&xconn);
 
+        let clipboard = Clipboard::new(xconn.clone(), root).map_err(|err| os_error!(err))?;
+        let xsettings = xconn.xsettings_snapshot();
+
         let window_target = ActiveEventLoop {
+            clipboard: RefCell::new(clipboard),
+            xsettings: RefCell::new(xsettings),
+            portal_settings: Default::default(),
+            cursor_theme: Default::default(),
+            xi2_gestures,
             ime,
             root,
             control_flow: Cell::new(ControlFlow::default()),
             exit: Cell::new(None),
             windows: Default::default(),
+            devices: Default::default(),
+            popup_grabs: Default::default(),
             ime_sender,
             xconn,
             wm_delete_window,
@@ -359,17 +438,25 @@ impl EventLoop {
                 sender: activation_token_sender, // not used again so no clone
                 waker: waker.clone(),
             },
+            drag_data_sender: WakeSender {
+                sender: drag_data_sender, // not used again so no clone
+                waker: waker.clone(),
+            },
+            start_drag_sender: WakeSender {
+                sender: start_drag_sender, // not used again so no clone
+                waker: waker.clone(),
+            },
             event_loop_proxy: event_loop_proxy.into(),
             device_events: Default::default(),
         };
 
         // Set initial device event filter.
         window_target.update_listen_device_events(true);
+        window_target.update_cursor_theme();
 
         let event_processor = EventProcessor {
             target: window_target,
             dnd,
-            devices: Default::default(),
             randr_event_offset,
             ime_receiver,
             ime_event_receiver,
@@ -379,6 +466,7 @@ impl EventLoop {
             xkbext,
             xkb_context,
             num_touch: 0,
+            pinch_scale: 1.0,
             held_key_press: None,
             first_touch: None,
             active_window: None,
@@ -391,11 +479,9 @@ impl EventLoop {
         event_processor
             .target
             .xconn
-            .select_xinput_events(
-                root,
-                ALL_DEVICES,
+            .select_xinput_events(root, ALL_DEVICES, &[
                 x11rb::protocol::xinput::XIEventMask::HIERARCHY,
-            )
+            ])
             .expect_then_ignore_error("Failed to register for XInput2 device hotplug events");
 
         event_processor
@@ -417,6 +503,10 @@ impl EventLoop {
             event_processor,
             redraw_receiver: PeekableReceiver::from_recv(redraw_channel),
             activation_receiver: PeekableReceiver::from_recv(activation_token_channel),
+            drag_data_receiver: PeekableReceiver::from_recv(drag_data_channel),
+            start_drag_receiver: PeekableReceiver::from_recv(start_drag_channel),
+            portal_settings_receiver: PeekableReceiver::from_recv(portal_settings_channel),
+            _portal_settings_watcher: portal_settings_watcher,
             state: EventLoopState { x11_readiness: Readiness::EMPTY, proxy_wake_up: false },
         };
 
@@ -489,6 +579,9 @@ impl EventLoop {
         self.event_processor.poll()
             || self.state.proxy_wake_up
             || self.redraw_receiver.has_incoming()
+            || self.drag_data_receiver.has_incoming()
+            || self.start_drag_receiver.has_incoming()
+            || self.portal_settings_receiver.has_incoming()
     }
 
     fn poll_events_with_timeout<A: ApplicationHandler>(
@@ -512,7 +605,24 @@ impl EventLoop {
                 },
             };
 
-            min_timeout(control_flow_timeout, timeout)
+            // Wake up to cancel the drag when its target doesn't answer.
+            let drag_timeout = self
+                .event_processor
+                .dnd
+                .source_deadline()
+                .map(|deadline| deadline.saturating_duration_since(start));
+
+            // Wake up to abandon the clipboard request when the selection owner doesn't answer.
+            let clipboard_timeout = self
+                .event_processor
+                .target
+                .clipboard
+                .borrow()
+                .deadline()
+                .map(|deadline| deadline.saturating_duration_since(start));
+
+            let timeout = min_timeout(control_flow_timeout, timeout);
+            min_timeout(min_timeout(timeout, drag_timeout), clipboard_timeout)
         };
 
         self.state.x11_readiness = Readiness::EMPTY;
@@ -563,7 +673,13 @@ impl EventLoop {
         // NB: For consistency all platforms must call `can_create_surfaces` even though X11
         // applications don't themselves have a formal surface destroy/create lifecycle.
         if cause == StartCause::Init {
-            app.can_create_surfaces(&self.event_processor.target)
+            app.can_create_surfaces(&self.event_processor.target);
+
+            // Report the monitors connected at startup, like the ones connected later.
+            let monitors = self.event_processor.target.xconn.available_monitors();
+            for event in monitor::monitor_events(&[], &monitors.unwrap_or_default()) {
+                app.monitor_event(&self.event_processor.target, event);
+            }
         }
 
         // Process all pending events
@@ -592,6 +708,27 @@ impl EventLoop {
             }
         }
 
+        // Request the drag and drop data, which could've been requested while handling the drop.
+        while let Ok((window_id, serial, mime_type)) = self.drag_data_receiver.try_recv() {
+            self.event_processor.request_drag_data(window_id, serial, mime_type, app);
+        }
+        self.event_processor.finish_drop();
+        self.event_processor.check_drag_timeout(app);
+        self.event_processor.check_clipboard_timeout(app);
+
+        while let Ok((window_id, serial, source)) = self.start_drag_receiver.try_recv() {
+            self.event_processor.start_drag(window_id, serial, source, app);
+        }
+
+        // Only the latest settings matter.
+        let mut portal_settings = None;
+        while let Ok(settings) = self.portal_settings_receiver.try_recv() {
+            portal_settings = Some(settings);
+        }
+        if let Some(settings) = portal_settings {
+            self.event_processor.process_portal_settings(settings, app);
+        }
+
         // Empty the user event buffer
         if mem::take(&mut self.state.proxy_wake_up) {
             app.proxy_wake_up(&self.event_processor.target);
@@ -678,10 +815,31 @@ impl ActiveEventLoop {
         }
 
         self.xconn
-            .select_xinput_events(self.root, ALL_MASTER_DEVICES, mask)
+            .select_xinput_events(self.root, ALL_MASTER_DEVICES, &[mask])
             .expect_then_ignore_error("Failed to update device event filter");
     }
 
+    /// Load the named cursors from the theme set by the application or XSettings, reloading the
+    /// cursors of the windows when it changed.
+    pub(crate) fn update_cursor_theme(&self) {
+        let theme = {
+            let cursor_theme = self.cursor_theme.borrow();
+            let xsettings = self.xsettings.borrow();
+            CursorTheme {
+                name: cursor_theme.name.clone().or_else(|| xsettings.cursor_theme_name.clone()),
+                size: cursor_theme.size.or(xsettings.cursor_theme_size).filter(|&size| size > 0),
+            }
+        };
+
+        if !self.xconn.set_cursor_theme(theme) {
+            return;
+        }
+
+        for window in self.windows.borrow().values().filter_map(Weak::upgrade) {
+            window.reload_cursor_icon();
+        }
+    }
+
     pub(crate) fn clear_exit(&self) {
         self.exit.set(None)
     }
@@ -729,13 +887,53 @@ impl RootActiveEventLoop for ActiveEventLoop {
     }
 
     fn system_theme(&self) -> Option<Theme> {
-        None
+        self.xconn.system_theme()
+    }
+
+    fn desktop_settings(&self) -> DesktopSettings {
+        let portal_settings = self.portal_settings.borrow().desktop_settings();
+        self.xsettings.borrow().desktop_settings(portal_settings)
     }
 
     fn listen_device_events(&self, allowed: DeviceEvents) {
         self.device_events.set(allowed);
     }
 
+    fn available_devices(&self) -> Box<dyn Iterator<Item = CoreDeviceInfo>> {
+        let devices = self.devices.borrow();
+        let devices: Vec<_> = devices.iter().filter_map(|(&id, device)| device.info(id)).collect();
+        Box::new(devices.into_iter())
+    }
+
+    fn set_cursor_theme(&self, name: Option<&str>, size: Option<u32>) -> Result<(), RequestError> {
+        *self.cursor_theme.borrow_mut() =
+            CursorTheme { name: name.map(Into::into), size: size.filter(|&size| size > 0) };
+        self.update_cursor_theme();
+        Ok(())
+    }
+
+    fn set_clipboard(&self, kind: ClipboardKind, content: MimeData) -> Result<(), RequestError> {
+        self.clipboard.borrow_mut().set(kind, &content).map_err(|err| os_error!(err).into())
+    }
+
+    fn request_clipboard_mime_types(
+        &self,
+        kind: ClipboardKind,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        self.clipboard.borrow_mut().request(kind, None).map_err(|err| os_error!(err).into())
+    }
+
+    fn request_clipboard_data(
+        &self,
+        kind: ClipboardKind,
+        mime_type: &str,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        self.clipboard
+            .borrow_mut()
+            .request(kind, Some(mime_type))
+            .map_err(|err| os_error!(err).into())
+    }
+
     fn set_control_flow(&self, control_flow: ControlFlow) {
         self.control_flow.set(control_flow)
     }
@@ -865,6 +1063,12 @@ pub enum X11Error {
 
     /// Could not find an ARGB32 pict format.
     NoArgb32Format,
+
+    /// Failed to grab a device.
+    GrabFailed(xproto::GrabStatus),
+
+    /// Failed to take the ownership of a selection.
+    SelectionOwnership,
 }
 
 impl fmt::Display for X11Error {
@@ -892,6 +1096,8 @@ impl fmt::Display for X11Error {
             X11Error::NoArgb32Format => {
                 f.write_str("winit only supports X11 displays with ARGB32 picture formats")
             },
+            X11Error::GrabFailed(status) => write!(f, "Failed to grab device: {status:?}"),
+            X11Error::SelectionOwnership => f.write_str("Failed to take the selection ownership"),
         }
     }
 }
@@ -997,12 +1203,20 @@ pub(crate) fn mkdid(w: xinput::DeviceId) -> DeviceId {
 
 #[derive(Debug)]
 pub struct Device {
-    _name: String,
+    name: String,
+    // The kind of physical devices, master devices aggregating them have none.
+    kind: Option<DeviceKind>,
+    // The vendor and product IDs.
+    pub(crate) product_id: Option<(u16, u16)>,
     pub(crate) scroll_axes: Vec<(i32, ScrollAxis)>,
     // For master devices, this is the paired device (pointer <-> keyboard).
     // For slave devices, this is the master.
     pub(crate) attachment: c_int,
     pub(crate) r#type: DeviceType,
+    // The axes of pens and erasers along with their latest values.
+    pub(crate) tablet: TabletAxes,
+    // The axes of touchscreens along with the latest values of their touches.
+    pub(crate) touch_axes: TouchAxes,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -1013,6 +1227,16 @@ pub(crate) enum DeviceType {
     Eraser,
 }
 
+impl DeviceType {
+    pub(crate) fn tablet_tool_kind(self) -> Option<TabletToolKind> {
+        match self {
+            DeviceType::Pen => Some(TabletToolKind::Pen),
+            DeviceType::Eraser => Some(TabletToolKind::Eraser),
+            DeviceType::Mouse | DeviceType::Touch => None,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct ScrollAxis {
     pub(crate) increment: f64,
@@ -1026,11 +1250,156 @@ pub(crate) enum ScrollOrientation {
     Horizontal,
 }
 
+/// An absolute valuator of a tablet or touchscreen.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct AbsoluteAxis {
+    number: i32,
+    min: f64,
+    max: f64,
+    /// The units per meter, 0 when unknown.
+    resolution: f64,
+}
+
+impl AbsoluteAxis {
+    /// The value scaled to the range of the valuator, between 0 and 1.
+    pub(crate) fn normalize(&self, value: f64) -> f64 {
+        let range = self.max - self.min;
+        if range > 0.0 { ((value - self.min) / range).clamp(0.0, 1.0) } else { 0.0 }
+    }
+
+    /// The value scaled to the range of the valuator, between -1 and 1.
+    fn normalize_signed(&self, value: f64) -> f64 {
+        if self.max > self.min { self.normalize(value) * 2.0 - 1.0 } else { 0.0 }
+    }
+
+    /// The value of a tilt valuator in degrees, between -90 and 90.
+    ///
+    /// The wacom and libinput drivers report the tilt in degrees, like -64 to 63, while the
+    /// ranges which can't be degrees are scaled.
+    fn tilt_degrees(&self, value: f64) -> i8 {
+        let degrees = if self.min >= -90.0 && self.max <= 90.0 {
+            value.clamp(-90.0, 90.0)
+        } else {
+            self.normalize_signed(value) * 90.0
+        };
+        degrees.round() as i8
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct TabletAxes {
+    pressure: Option<AbsoluteAxis>,
+    tilt_x: Option<AbsoluteAxis>,
+    tilt_y: Option<AbsoluteAxis>,
+    // XI2 events only carry the valuators which changed.
+    pub(crate) data: TabletToolData,
+}
+
+impl TabletAxes {
+    fn is_empty(&self) -> bool {
+        self.pressure.is_none() && self.tilt_x.is_none() && self.tilt_y.is_none()
+    }
+
+    /// Update the data with the value of a valuator, returning whether it changed.
+    pub(crate) fn update(&mut self, number: i32, value: f64) -> bool {
+        let old = self.data.clone();
+        let axis = |axis: Option<AbsoluteAxis>| axis.filter(|axis| axis.number == number);
+
+        if let Some(pressure) = axis(self.pressure) {
+            self.data.force = Some(Force::Normalized(pressure.normalize(value)));
+        } else if let Some(tilt_x) = axis(self.tilt_x) {
+            self.data.tilt.get_or_insert_with(Default::default).x = tilt_x.tilt_degrees(value);
+        } else if let Some(tilt_y) = axis(self.tilt_y) {
+            self.data.tilt.get_or_insert_with(Default::default).y = tilt_y.tilt_degrees(value);
+        }
+
+        self.data != old
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct TouchAxes {
+    pressure: Option<AbsoluteAxis>,
+    major: Option<AbsoluteAxis>,
+    minor: Option<AbsoluteAxis>,
+    // The sizes are in the units of the position when they have no resolution of their own.
+    position_x: Option<AbsoluteAxis>,
+    // XI2 events only carry the valuators which changed, thus the latest values of each touch.
+    touches: HashMap<u32, TouchValues>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct TouchValues {
+    pressure: Option<f64>,
+    major: Option<f64>,
+    minor: Option<f64>,
+}
+
+impl TouchAxes {
+    /// Update the touch with the valuators of its event, returning its force and its contact on
+    /// a monitor with the given density.
+    ///
+    /// The contact is only known when both the resolution of the valuators and the density of the
+    /// monitor are.
+    pub(crate) fn update(
+        &mut self,
+        id: u32,
+        valuators: impl Iterator<Item = (i32, f64)>,
+        pixels_per_meter: Option<f64>,
+    ) -> (Option<Force>, Option<TouchContact>) {
+        let is_axis =
+            |axis: Option<AbsoluteAxis>, number| axis.is_some_and(|axis| axis.number == number);
+        let values = self.touches.entry(id).or_default();
+        for (number, value) in valuators {
+            if is_axis(self.pressure, number) {
+                values.pressure = Some(value);
+            } else if is_axis(self.major, number) {
+                values.major = Some(value);
+            } else if is_axis(self.minor, number) {
+                values.minor = Some(value);
+            }
+        }
+
+        let force = self
+            .pressure
+            .zip(values.pressure)
+            .map(|(axis, value)| Force::Normalized(axis.normalize(value)));
+        let resolution = |axis: Option<AbsoluteAxis>| {
+            axis.map(|axis| axis.resolution).filter(|resolution| *resolution > 0.0)
+        };
+        let scale = resolution(self.major)
+            .or_else(|| resolution(self.position_x))
+            .zip(pixels_per_meter)
+            .map(|(resolution, pixels_per_meter)| pixels_per_meter / resolution)
+            .filter(|scale| scale.is_finite() && *scale > 0.0);
+        let contact = values.major.zip(scale).map(|(major, scale)| {
+            let major = major * scale;
+            // Circular contacts only have a major axis.
+            let minor = values.minor.map_or(major, |minor| minor * scale);
+            TouchContact { major, minor }
+        });
+
+        (force, contact)
+    }
+
+    /// Forget the values of the touch once it ended.
+    pub(crate) fn end(&mut self, id: u32) {
+        self.touches.remove(&id);
+    }
+}
+
 impl Device {
-    pub(crate) fn new(info: &ffi::XIDeviceInfo, atoms: &Atoms) -> Self {
+    pub(crate) fn new(
+        info: &ffi::XIDeviceInfo,
+        device_type: Option<xproto::Atom>,
+        atoms: &Atoms,
+    ) -> Self {
         let name = unsafe { CStr::from_ptr(info.name).to_string_lossy() };
         let mut scroll_axes = Vec::new();
-        let mut r#type = None;
+        let mut tablet = TabletAxes::default();
+        let mut touch_axes = TouchAxes::default();
+        let mut touch = false;
+        let mut dependent_touch = false;
 
         if Device::physical_device(info) {
             // Identify scroll axes
@@ -1048,37 +1417,103 @@ impl Device {
                         position: 0.0,
                     }));
                 } else if ty == ffi::XITouchClass {
-                    r#type = Some(DeviceType::Touch);
-                } else if r#type.is_none() && ty == ffi::XIValuatorClass {
+                    let info = unsafe { &*(class_ptr as *const ffi::XITouchClassInfo) };
+                    touch = true;
+                    dependent_touch = info.mode == ffi::XIDependentTouch;
+                } else if ty == ffi::XIValuatorClass {
                     let info = unsafe { &*(class_ptr as *const ffi::XIValuatorClassInfo) };
                     let atom = info.label as xproto::Atom;
-
-                    if atom == atoms[ABS_X]
-                        || atom == atoms[ABS_Y]
-                        || atom == atoms[ABS_PRESSURE]
-                        || atom == atoms[ABS_TILT_X]
-                        || atom == atoms[ABS_TILT_Y]
-                    {
-                        if name.contains("eraser") {
-                            r#type = Some(DeviceType::Eraser);
-                        } else {
-                            r#type = Some(DeviceType::Pen);
-                        }
+                    let axis = Some(AbsoluteAxis {
+                        number: info.number,
+                        min: info.min,
+                        max: info.max,
+                        resolution: info.resolution as f64,
+                    });
+
+                    if atom == atoms[ABS_MT_PRESSURE] {
+                        touch_axes.pressure = axis;
+                        continue;
+                    } else if atom == atoms[ABS_MT_TOUCH_MAJOR] {
+                        touch_axes.major = axis;
+                        continue;
+                    } else if atom == atoms[ABS_MT_TOUCH_MINOR] {
+                        touch_axes.minor = axis;
+                        continue;
+                    } else if atom == atoms[ABS_MT_POSITION_X] {
+                        touch_axes.position_x = axis;
+                        continue;
+                    } else if atom == atoms[ABS_PRESSURE] {
+                        tablet.pressure = axis;
+                    } else if atom == atoms[ABS_TILT_X] {
+                        tablet.tilt_x = axis;
+                    } else if atom == atoms[ABS_TILT_Y] {
+                        tablet.tilt_y = axis;
+                    } else {
+                        continue;
                     }
+
+                    tablet.update(info.number, info.value);
                 }
             }
         }
 
+        // Absolute pointers like touchscreens and virtual machine tablets also have `Abs X` and
+        // `Abs Y` valuators, only pens have a pressure or tilt.
+        let r#type = if touch {
+            DeviceType::Touch
+        } else if device_type == Some(atoms[Eraser])
+            || (!tablet.is_empty() && name.to_lowercase().contains("eraser"))
+        {
+            DeviceType::Eraser
+        } else if device_type == Some(atoms[Stylus]) || !tablet.is_empty() {
+            DeviceType::Pen
+        } else {
+            DeviceType::Mouse
+        };
+
+        let kind = if !Device::physical_device(info) {
+            None
+        } else if info._use == ffi::XISlaveKeyboard || device_type == Some(atoms[Keyboard]) {
+            Some(DeviceKind::Keyboard)
+        } else if dependent_touch || device_type == Some(atoms[Touchpad]) {
+            Some(DeviceKind::Touchpad)
+        } else if touch || device_type == Some(atoms[Touchscreen]) {
+            Some(DeviceKind::Touchscreen)
+        } else if r#type.tablet_tool_kind().is_some() || device_type == Some(atoms[Tablet]) {
+            Some(DeviceKind::Tablet)
+        } else {
+            Some(DeviceKind::Mouse)
+        };
+
         let mut device = Device {
-            _name: name.into_owned(),
+            name: name.into_owned(),
+            kind,
+            product_id: None,
             scroll_axes,
             attachment: info.attachment,
-            r#type: r#type.unwrap_or(DeviceType::Mouse),
+            r#type,
+            tablet,
+            touch_axes,
         };
         device.reset_scroll_position(info);
         device
     }
 
+    /// The description of the device, if it's a physical one.
+    pub(crate) fn info(&self, id: DeviceId) -> Option<CoreDeviceInfo> {
+        let mut info = CoreDeviceInfo::new(id, self.name.clone(), self.kind?);
+        if let Some((vendor_id, product_id)) = self.product_id {
+            info.vendor_id = Some(vendor_id);
+            info.product_id = Some(product_id);
+        }
+        Some(info)
+    }
+
+    /// Whether the device is a physical one, listed in the devices of the event loop.
+    pub(crate) fn is_physical(&self) -> bool {
+        self.kind.is_some()
+    }
+
     pub(crate) fn reset_scroll_position(&mut self, info: &ffi::XIDeviceInfo) {
         if Device::physical_device(info) {
             for &class_ptr in Device::classes(info) {
@@ -1125,3 +1560,119 @@ pub(crate) fn xinput_fp1616_to_float(fp: xinput::Fp1616) -> f64 {
 fn min_timeout(a: Option<Duration>, b: Option<Duration>) -> Option<Duration> {
     a.map_or(b, |a_timeout| b.map_or(Some(a_timeout), |b_timeout| Some(a_timeout.min(b_timeout))))
 }
+
+#[cfg(test)]
+mod tests {
+    use winit_core::event::TabletToolTilt;
+
+    use super::*;
+
+    fn axis(number: i32, min: f64, max: f64) -> Option<AbsoluteAxis> {
+        Some(AbsoluteAxis { number, min, max, resolution: 0.0 })
+    }
+
+    fn axis_with_resolution(number: i32, max: f64, resolution: f64) -> Option<AbsoluteAxis> {
+        Some(AbsoluteAxis { number, min: 0.0, max, resolution })
+    }
+
+    #[test]
+    fn tablet_axes_normalize_pressure_and_tilt() {
+        let mut axes = TabletAxes {
+            pressure: axis(2, 0.0, 2048.0),
+            tilt_x: axis(3, -64.0, 64.0),
+            tilt_y: axis(4, 0.0, 180.0),
+            ..Default::default()
+        };
+
+        assert!(axes.update(2, 512.0));
+        assert_eq!(axes.data.force, Some(Force::Normalized(0.25)));
+        assert!(axes.update(3, 32.0));
+        assert!(axes.update(4, 0.0));
+        assert_eq!(axes.data.tilt, Some(TabletToolTilt { x: 32, y: -90 }));
+
+        // Out of range values are clamped.
+        assert!(axes.update(2, 4096.0));
+        assert_eq!(axes.data.force, Some(Force::Normalized(1.0)));
+        assert!(axes.update(3, -100.0));
+        assert_eq!(axes.data.tilt, Some(TabletToolTilt { x: -90, y: -90 }));
+    }
+
+    #[test]
+    fn tablet_tilt_in_degrees() {
+        // The range of the wacom driver, which isn't centered.
+        let tilt = axis(3, -64.0, 63.0).unwrap();
+        assert_eq!(tilt.tilt_degrees(0.0), 0);
+        assert_eq!(tilt.tilt_degrees(-64.0), -64);
+        assert_eq!(tilt.tilt_degrees(63.0), 63);
+        assert_eq!(tilt.tilt_degrees(-120.0), -90);
+
+        // Ranges which aren't degrees are scaled.
+        let tilt = axis(3, 0.0, 127.0).unwrap();
+        assert_eq!(tilt.tilt_degrees(0.0), -90);
+        assert_eq!(tilt.tilt_degrees(127.0), 90);
+        let tilt = axis(3, -9000.0, 9000.0).unwrap();
+        assert_eq!(tilt.tilt_degrees(4500.0), 45);
+    }
+
+    #[test]
+    fn tablet_axes_report_changes() {
+        let mut axes = TabletAxes { pressure: axis(2, 0.0, 1.0), ..Default::default() };
+
+        assert!(axes.update(2, 0.5));
+        assert!(!axes.update(2, 0.5));
+        // Valuators which aren't tablet axes are ignored.
+        assert!(!axes.update(0, 100.0));
+        assert_eq!(axes.data.tilt, None);
+    }
+
+    #[test]
+    fn touch_axes_keep_the_values_of_each_touch() {
+        // 10 units per millimeter on a monitor of 4 pixels per millimeter.
+        let mut axes = TouchAxes {
+            pressure: axis(2, 0.0, 255.0),
+            major: axis_with_resolution(3, 1000.0, 10_000.0),
+            position_x: axis(0, 0.0, 1000.0),
+            ..Default::default()
+        };
+        let density = Some(4000.0);
+
+        let (force, contact) =
+            axes.update(1, [(0, 10.0), (2, 51.0), (3, 50.0)].into_iter(), density);
+        assert_eq!(force, Some(Force::Normalized(0.2)));
+        assert_eq!(contact, Some(TouchContact { major: 20.0, minor: 20.0 }));
+
+        // The event of another touch doesn't change the values of the first one.
+        let (force, contact) = axes.update(2, [(2, 255.0)].into_iter(), density);
+        assert_eq!(force, Some(Force::Normalized(1.0)));
+        assert_eq!(contact, None);
+
+        // Only the position changed.
+        let (force, contact) = axes.update(1, [(0, 20.0)].into_iter(), density);
+        assert_eq!(force, Some(Force::Normalized(0.2)));
+        assert_eq!(contact, Some(TouchContact { major: 20.0, minor: 20.0 }));
+
+        // The touch ID is reused once the touch ended.
+        axes.end(1);
+        assert_eq!(axes.update(1, [(0, 20.0)].into_iter(), density), (None, None));
+    }
+
+    #[test]
+    fn touch_contact_needs_a_resolution_and_density() {
+        let mut axes = TouchAxes {
+            major: axis(3, 0.0, 1000.0),
+            minor: axis(4, 0.0, 1000.0),
+            position_x: axis(0, 0.0, 1000.0),
+            ..Default::default()
+        };
+        // The range of the position isn't the size of the monitor.
+        assert_eq!(axes.update(1, [(3, 10.0), (4, 5.0)].into_iter(), Some(4000.0)), (None, None));
+
+        // The sizes have the resolution of the position.
+        axes.position_x = axis_with_resolution(0, 1000.0, 20_000.0);
+        let (_, contact) = axes.update(1, [].into_iter(), Some(4000.0));
+        assert_eq!(contact, Some(TouchContact { major: 2.0, minor: 1.0 }));
+
+        // The monitor has no physical size.
+        assert_eq!(axes.update(1, [].into_iter(), None), (None, None));
+    }
+}
@@ -1,18 +1,21 @@
-use std::cell::{Cell, RefCell};
+use std::cell::Cell;
 use std::collections::{HashMap, VecDeque};
 use std::os::raw::{c_char, c_int, c_long, c_ulong};
 use std::slice;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Instant;
 
 use dpi::{PhysicalPosition, PhysicalSize};
+use winit_common::xdg_desktop_portal::Settings as PortalSettings;
 use winit_common::xkb::{self, Context, XkbState};
 use winit_core::application::ApplicationHandler;
 use winit_core::event::{
-    ButtonSource, DeviceEvent, DeviceId, ElementState, FingerId, Ime, MouseButton,
-    MouseScrollDelta, PointerKind, PointerSource, RawKeyEvent, SurfaceSizeWriter, TouchPhase,
-    WindowEvent,
+    ButtonSource, DeviceEvent, DragAction, DragPayload, DragSource, ElementState, FingerId, Force,
+    Ime, MouseButton, MouseScrollDelta, PointerKind, PointerSource, RawKeyEvent, SurfaceSizeWriter,
+    TabletToolButton, TouchContact, TouchPhase, WindowEvent,
 };
-use winit_core::keyboard::ModifiersState;
+use winit_core::event_loop::{ActiveEventLoop as _, AsyncRequestSerial};
+use winit_core::keyboard::{Key, ModifiersState, NamedKey};
 use winit_core::window::WindowId;
 use x11_dl::xinput2::{
     self, XIDeviceEvent, XIEnterEvent, XIFocusInEvent, XIFocusOutEvent, XIHierarchyEvent,
@@ -21,25 +24,28 @@ use x11_dl::xinput2::{
 use x11_dl::xlib::{
     self, Display as XDisplay, Window as XWindow, XAnyEvent, XClientMessageEvent, XConfigureEvent,
     XDestroyWindowEvent, XEvent, XExposeEvent, XKeyEvent, XMapEvent, XPropertyEvent,
-    XReparentEvent, XSelectionEvent, XVisibilityEvent, XkbAnyEvent, XkbStateRec,
+    XReparentEvent, XSelectionClearEvent, XSelectionEvent, XSelectionRequestEvent,
+    XVisibilityEvent, XkbAnyEvent, XkbStateRec,
 };
+use x11_dl::xrandr::XRRNotifyEvent;
+use x11rb::protocol::randr;
 use x11rb::protocol::sync::{ConnectionExt, Int64};
-use x11rb::protocol::xinput;
+use x11rb::protocol::xinput::{self, ConnectionExt as _};
 use x11rb::protocol::xkb::ID as XkbId;
 use x11rb::protocol::xproto::{self, ConnectionExt as _, ModMask};
 use x11rb::x11_utils::{ExtensionInformation, Serialize};
 use xkbcommon_dl::xkb_mod_mask_t;
 
 use crate::atoms::*;
-use crate::dnd::{Dnd, DndState};
+use crate::dnd::{Dnd, DndState, DragDataRequest};
 use crate::event_loop::{
     ALL_DEVICES, ActiveEventLoop, CookieResultExt, Device, DeviceInfo, DeviceType,
-    ScrollOrientation, mkdid, mkwid,
+    ScrollOrientation, X11Error, mkdid, mkwid,
 };
 use crate::ime::{ImeEvent, ImeEventReceiver, ImeReceiver, ImeRequest};
-use crate::util;
 use crate::util::cookie::GenericEventCookie;
 use crate::window::UnownedWindow;
+use crate::{ffi, monitor, util};
 
 /// The maximum amount of X modifiers to replay.
 pub const MAX_MOD_REPLAY_LEN: usize = 32;
@@ -53,13 +59,14 @@ pub struct EventProcessor {
     pub ime_receiver: ImeReceiver,
     pub ime_event_receiver: ImeEventReceiver,
     pub randr_event_offset: u8,
-    pub devices: RefCell<HashMap<DeviceId, Device>>,
     pub xi2ext: ExtensionInformation,
     pub xkbext: ExtensionInformation,
     pub target: ActiveEventLoop,
     pub xkb_context: Context,
     // Number of touch events currently in progress
     pub num_touch: u32,
+    // The scale of the pinch gesture in progress, the events give it relative to its start.
+    pub pinch_scale: f64,
     // This is the last pressed key that is repeatable (if it hasn't been
     // released).
     //
@@ -182,6 +189,8 @@ impl EventProcessor {
         match event_type {
             xlib::ClientMessage => self.client_message(xev.as_ref(), app),
             xlib::SelectionNotify => self.selection_notify(xev.as_ref(), app),
+            xlib::SelectionRequest => self.selection_request(xev.as_ref()),
+            xlib::SelectionClear => self.selection_clear(xev.as_ref()),
             xlib::ConfigureNotify => self.configure_notify(xev.as_ref(), app),
             xlib::ReparentNotify => self.reparent_notify(xev.as_ref()),
             xlib::MapNotify => self.map_notify(xev.as_ref(), app),
@@ -218,11 +227,16 @@ impl EventProcessor {
 
                         let xev: &XIDeviceEvent = unsafe { xev.as_event() };
                         self.update_mods_from_xinput2_event(&xev.mods, &xev.group, false, app);
+                        if state == ElementState::Pressed && self.popup_grab_input(xev, app) {
+                            return;
+                        }
+                        self.drag_source_input(xev, ty, app);
                         self.xinput2_button_input(xev, state, app);
                     },
                     xinput2::XI_Motion => {
                         let xev: &XIDeviceEvent = unsafe { xev.as_event() };
                         self.update_mods_from_xinput2_event(&xev.mods, &xev.group, false, app);
+                        self.drag_source_input(xev, evtype, app);
                         self.xinput2_mouse_motion(xev, app);
                     },
                     xinput2::XI_Enter => {
@@ -246,6 +260,20 @@ impl EventProcessor {
                         let xev: &XIDeviceEvent = unsafe { xev.as_event() };
                         self.xinput2_touch(xev, evtype, app);
                     },
+                    ffi::XI_GesturePinchBegin
+                    | ffi::XI_GesturePinchUpdate
+                    | ffi::XI_GesturePinchEnd => {
+                        let xev: &ffi::XIGesturePinchEvent = unsafe { xev.as_event() };
+                        self.update_mods_from_xinput2_event(&xev.mods, &xev.group, false, app);
+                        self.xinput2_gesture_pinch(xev, app);
+                    },
+                    ffi::XI_GestureSwipeBegin
+                    | ffi::XI_GestureSwipeUpdate
+                    | ffi::XI_GestureSwipeEnd => {
+                        let xev: &ffi::XIGestureSwipeEvent = unsafe { xev.as_event() };
+                        self.update_mods_from_xinput2_event(&xev.mods, &xev.group, false, app);
+                        self.xinput2_gesture_swipe(xev, app);
+                    },
                     xinput2::XI_RawButtonPress | xinput2::XI_RawButtonRelease => {
                         let state = match evtype {
                             xinput2::XI_RawButtonPress => ElementState::Pressed,
@@ -273,7 +301,7 @@ impl EventProcessor {
 
                     xinput2::XI_HierarchyChanged => {
                         let xev: &XIHierarchyEvent = unsafe { xev.as_event() };
-                        self.xinput2_hierarchy_changed(xev);
+                        self.xinput2_hierarchy_changed(xev, app);
                     },
                     _ => {},
                 }
@@ -285,6 +313,16 @@ impl EventProcessor {
                 }
                 if event_type == self.randr_event_offset as c_int {
                     self.process_dpi_change(app);
+                } else if event_type == (self.randr_event_offset + randr::NOTIFY_EVENT) as c_int {
+                    let xev: &XRRNotifyEvent =
+                        unsafe { &*(xev as *const _ as *const XRRNotifyEvent) };
+                    // Output property changes, like the backlight, don't affect the monitors.
+                    let subtype = randr::Notify::from(xev.subtype as u8);
+                    if subtype == randr::Notify::CRTC_CHANGE
+                        || subtype == randr::Notify::OUTPUT_CHANGE
+                    {
+                        self.process_dpi_change(app);
+                    }
                 }
             },
         }
@@ -321,16 +359,58 @@ impl EventProcessor {
     }
 
     pub fn init_device(&self, device: xinput::DeviceId) {
-        let mut devices = self.devices.borrow_mut();
+        let mut devices = self.target.devices.borrow_mut();
         if let Some(info) = DeviceInfo::get(&self.target.xconn, device as _) {
             let atoms = self.target.x_connection().atoms();
 
+            // The type of the device, like `STYLUS` for the pens of Wacom tablets, is only
+            // available through XInput 1.
+            let device_types: HashMap<_, _> = self
+                .target
+                .xconn
+                .xcb_connection()
+                .xinput_list_input_devices()
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .map(|reply| {
+                    reply
+                        .devices
+                        .iter()
+                        .map(|device| (device.device_id as c_int, device.device_type))
+                        .collect()
+                })
+                .unwrap_or_default();
+
             for info in info.iter() {
-                devices.insert(mkdid(info.deviceid as xinput::DeviceId), Device::new(info, atoms));
+                let device_type = device_types.get(&info.deviceid).copied();
+                let mut device = Device::new(info, device_type, atoms);
+                if device.is_physical() {
+                    device.product_id = self
+                        .target
+                        .xconn
+                        .device_product_id(info.deviceid as xinput::DeviceId)
+                        .unwrap_or_else(|err| {
+                            tracing::warn!("failed to read the product ID of a device: {err}");
+                            None
+                        });
+                }
+                devices.insert(mkdid(info.deviceid as xinput::DeviceId), device);
             }
         }
     }
 
+    fn device_type(&self, device: xinput::DeviceId) -> Option<DeviceType> {
+        self.target.devices.borrow().get(&mkdid(device)).map(|device| device.r#type)
+    }
+
+    /// The kind of pointer of the device, masters are reported as a mouse.
+    fn pointer_kind(&self, device: xinput::DeviceId) -> PointerKind {
+        match self.device_type(device).and_then(DeviceType::tablet_tool_kind) {
+            Some(kind) => PointerKind::TabletTool(kind),
+            None => PointerKind::Mouse,
+        }
+    }
+
     pub fn with_window<F, Ret>(&self, window_id: xproto::Window, callback: F) -> Option<Ret>
     where
         F: Fn(&Arc<UnownedWindow>) -> Ret,
@@ -422,7 +502,33 @@ impl EventProcessor {
             return;
         }
 
+        if xev.message_type == atoms[XdndStatus] as c_ulong {
+            // The target of the drag started from our window answered our `XdndPosition`.
+            let target_window = xev.data.get_long(0) as xproto::Window;
+            let accepted = xev.data.get_long(1) & 1 == 1;
+            let action = xev.data.get_long(4) as xproto::Atom;
+            if let Err(err) = self.dnd.source_status(target_window, accepted, action) {
+                tracing::warn!("Failed to update the drag and drop target: {err}");
+                self.end_drag(None, app);
+            }
+            return;
+        }
+
+        if xev.message_type == atoms[XdndFinished] as c_ulong {
+            let target_window = xev.data.get_long(0) as xproto::Window;
+            let succeeded = xev.data.get_long(1) & 1 == 1;
+            let action = xev.data.get_long(2) as xproto::Atom;
+            if let Some(action) = self.dnd.source_finished(target_window, succeeded, action) {
+                self.end_drag(action, app);
+            }
+            return;
+        }
+
         if xev.message_type == atoms[XdndEnter] as c_ulong {
+            // A new drag replaces the one we never got a drop or a leave for.
+            self.cancel_drag_requests(app);
+            self.with_window(window, |window| window.shared_state_lock().drag_action = None);
+
             let source_window = xev.data.get_long(0) as xproto::Window;
             let flags = xev.data.get_long(1);
             let version = flags >> 24;
@@ -438,6 +544,9 @@ impl EventProcessor {
             } else if let Ok(more_types) = unsafe { self.dnd.get_type_list(source_window) } {
                 self.dnd.type_list = Some(more_types);
             }
+
+            let mime_types = self.dnd.mime_types(self.dnd.type_list.as_deref().unwrap_or_default());
+            self.dnd.payload = DragPayload::new(mime_types, DragAction::default());
             return;
         }
 
@@ -469,26 +578,33 @@ impl EventProcessor {
             // By our own state flow, `version` should never be `None` at this point.
             let version = self.dnd.version.unwrap_or(5);
 
-            // Action is specified in versions 2 and up, though we don't need it anyway.
-            // let action = xev.data.get_long(4);
-
-            let accepted = if let Some(ref type_list) = self.dnd.type_list {
-                type_list.contains(&atoms[TextUriList])
+            // Action is specified in versions 2 and up.
+            let action = if version >= 2 {
+                self.dnd.action(xev.data.get_long(4) as xproto::Atom)
             } else {
-                false
+                DragAction::Copy
             };
 
+            let accepted = !self.dnd.payload.mime_types().is_empty();
             if !accepted {
                 unsafe {
                     self.dnd
                         .send_status(window, source_window, DndState::Rejected)
                         .expect("Failed to send `XdndStatus` message.");
                 }
-                self.dnd.reset();
+                for request in self.dnd.reset() {
+                    Self::drag_data_unavailable(&self.target, request, app);
+                }
                 return;
             }
 
+            if action != self.dnd.payload.action() {
+                let mime_types = self.dnd.payload.mime_types().to_vec();
+                self.dnd.payload = DragPayload::new(mime_types, action);
+            }
+
             self.dnd.source_window = Some(source_window);
+            self.dnd.window = Some(window);
             let time = if version == 0 {
                 // In version 0, time isn't specified
                 x11rb::CURRENT_TIME
@@ -498,53 +614,346 @@ impl EventProcessor {
 
             // Log this timestamp.
             self.target.xconn.set_timestamp(time);
+            self.dnd.time = time;
 
-            // This results in the `SelectionNotify` event below
-            unsafe {
-                self.dnd.convert_selection(window, time);
+            if self.dnd.type_list.as_ref().is_some_and(|types| types.contains(&atoms[TextUriList]))
+            {
+                // This results in the `SelectionNotify` event below
+                unsafe {
+                    self.dnd.convert_selection(window, time);
+                }
+            } else {
+                // The data is not a list of files, thus there's nothing to wait for.
+                let event = if self.dnd.dragging {
+                    WindowEvent::DragMoved { position: self.dnd.position }
+                } else {
+                    self.dnd.dragging = true;
+                    WindowEvent::DragEntered {
+                        paths: Vec::new(),
+                        payload: self.dnd.payload.clone(),
+                        position: self.dnd.position,
+                    }
+                };
+                app.window_event(&self.target, window_id, event);
             }
 
+            let state = match self.drag_action(window) {
+                Some(action) => DndState::Accepted(action),
+                None => DndState::Rejected,
+            };
             unsafe {
                 self.dnd
-                    .send_status(window, source_window, DndState::Accepted)
+                    .send_status(window, source_window, state)
                     .expect("Failed to send `XdndStatus` message.");
             }
             return;
         }
 
         if xev.message_type == atoms[XdndDrop] as c_ulong {
-            let (source_window, state) = if let Some(source_window) = self.dnd.source_window {
-                if let Some(Ok(ref path_list)) = self.dnd.result {
-                    let event = WindowEvent::DragDropped {
-                        paths: path_list.iter().map(Into::into).collect(),
-                        position: self.dnd.position,
-                    };
-                    app.window_event(&self.target, window_id, event);
+            if self.dnd.source_window.is_some()
+                && self.dnd.dragging
+                && self.drag_action(window).is_some()
+            {
+                if self.dnd.version.unwrap_or(5) >= 1 {
+                    self.dnd.time = xev.data.get_long(2) as xproto::Timestamp;
                 }
-                (source_window, DndState::Accepted)
+
+                let paths = match self.dnd.result {
+                    Some(Ok(ref path_list)) => path_list.clone(),
+                    _ => Vec::new(),
+                };
+                let event = WindowEvent::DragDropped {
+                    paths,
+                    payload: self.dnd.payload.clone(),
+                    position: self.dnd.position,
+                };
+
+                // The drop is finished once the data requested while handling the drop is
+                // transferred.
+                self.dnd.dropped = true;
+                app.window_event(&self.target, window_id, event);
             } else {
+                // The application rejected the drop after the drag entered.
+                if self.dnd.dragging {
+                    let event = WindowEvent::DragLeft { position: Some(self.dnd.position) };
+                    app.window_event(&self.target, window_id, event);
+                }
+
                 // `source_window` won't be part of our DND state if we already rejected the drop in
                 // our `XdndPosition` handler.
                 let source_window = xev.data.get_long(0) as xproto::Window;
-                (source_window, DndState::Rejected)
-            };
+                unsafe {
+                    self.dnd
+                        .send_finished(window, source_window, DndState::Rejected)
+                        .expect("Failed to send `XdndFinished` message.");
+                }
 
+                for request in self.dnd.reset() {
+                    Self::drag_data_unavailable(&self.target, request, app);
+                }
+            }
+            return;
+        }
+
+        if xev.message_type == atoms[XdndLeave] as c_ulong {
+            if self.dnd.dragging {
+                let event = WindowEvent::DragLeft { position: Some(self.dnd.position) };
+                app.window_event(&self.target, window_id, event);
+            }
+            self.cancel_drag_requests(app);
+        }
+    }
+
+    /// Request the data of the current drag and drop operation.
+    pub(crate) fn request_drag_data(
+        &mut self,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        mime_type: String,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        let window = window_id.into_raw() as xproto::Window;
+        let target = if self.dnd.window == Some(window)
+            && self.dnd.dragging
+            && self.dnd.payload.has_mime_type(&mime_type)
+        {
+            self.target
+                .xconn
+                .xcb_connection()
+                .intern_atom(false, mime_type.as_bytes())
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .map(|reply| reply.atom)
+        } else {
+            None
+        };
+
+        let request = DragDataRequest { window_id, serial, mime_type, target: target.unwrap_or(0) };
+        if target.is_none() {
+            Self::drag_data_unavailable(&self.target, request, app);
+            return;
+        }
+
+        self.dnd.requests.push_back(request);
+        if self.dnd.requests.len() == 1 {
+            self.dnd.convert_request(window);
+        }
+    }
+
+    /// Finish the drop once all the data requests are answered.
+    pub(crate) fn finish_drop(&mut self) {
+        if !self.dnd.dropped || !self.dnd.requests.is_empty() {
+            return;
+        }
+
+        if let (Some(window), Some(source_window)) = (self.dnd.window, self.dnd.source_window) {
+            let state = match self.drag_action(window) {
+                Some(action) => DndState::Accepted(action),
+                None => DndState::Rejected,
+            };
             unsafe {
                 self.dnd
                     .send_finished(window, source_window, state)
                     .expect("Failed to send `XdndFinished` message.");
             }
+        }
+
+        self.dnd.reset();
+    }
+
+    /// The action performed once the drag over the window is dropped, `None` when the drop is
+    /// rejected.
+    fn drag_action(&self, window: xproto::Window) -> Option<DragAction> {
+        let action = self.with_window(window, |window| window.shared_state_lock().drag_action);
+        action.flatten().unwrap_or(Some(self.dnd.payload.action()))
+    }
 
-            self.dnd.reset();
+    /// Answer the pending data requests of the drag and drop operation which is gone.
+    fn cancel_drag_requests(&mut self, app: &mut dyn ApplicationHandler) {
+        for request in self.dnd.reset() {
+            Self::drag_data_unavailable(&self.target, request, app);
+        }
+    }
+
+    fn drag_data_unavailable(
+        target: &ActiveEventLoop,
+        request: DragDataRequest,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        let event = WindowEvent::DragData {
+            serial: request.serial,
+            mime_type: request.mime_type,
+            data: None,
+        };
+        app.window_event(target, request.window_id, event);
+    }
+
+    /// Start dragging the data from the window.
+    pub(crate) fn start_drag(
+        &mut self,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        source: DragSource,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        // Only one drag can be started at a time.
+        self.end_drag(None, app);
+
+        let window = window_id.into_raw() as xproto::Window;
+        if let Err(err) = self.dnd.start_drag(window, serial, &source) {
+            tracing::warn!("Failed to start dragging: {err}");
+            let event = WindowEvent::DragFinished { serial, action: None };
+            app.window_event(&self.target, window_id, event);
+        }
+    }
+
+    /// Cancel the drag started from our window when the target stopped answering.
+    pub(crate) fn check_drag_timeout(&mut self, app: &mut dyn ApplicationHandler) {
+        if self.dnd.source_deadline().is_some_and(|deadline| deadline <= Instant::now()) {
+            tracing::warn!("The drag and drop target didn't answer in time");
+            self.end_drag(None, app);
+        }
+    }
+
+    /// Abandon the clipboard request when the owner of the selection stopped answering.
+    pub(crate) fn check_clipboard_timeout(&mut self, app: &mut dyn ApplicationHandler) {
+        let event = self.target.clipboard.borrow_mut().check_timeout();
+        if let Some(event) = event {
+            app.clipboard_event(&self.target, event);
+        }
+    }
+
+    /// Finish the drag started from our window.
+    fn end_drag(&mut self, action: Option<DragAction>, app: &mut dyn ApplicationHandler) {
+        if let Some(source) = self.dnd.end_drag() {
+            let event = WindowEvent::DragFinished { serial: source.serial, action };
+            app.window_event(&self.target, mkwid(source.window), event);
+        }
+    }
+
+    /// Dismiss the popups grabbing the pointer when a button is pressed outside of them.
+    ///
+    /// A press in one of the parent popups only dismisses the popups opened from it. Returns
+    /// whether the press was outside of all the popups, in which case it's consumed.
+    fn popup_grab_input(&self, event: &XIDeviceEvent, app: &mut dyn ApplicationHandler) -> bool {
+        let windows = self.target.windows.borrow();
+        let popup = |popup_id: &WindowId| windows.get(popup_id).and_then(Weak::upgrade);
+
+        let mut grabs = self.target.popup_grabs.borrow_mut();
+        let Some(&innermost) = grabs.last() else {
+            return false;
+        };
+
+        // The grab was released when destroying the popup.
+        grabs.retain(|(popup_id, _)| popup(popup_id).is_some());
+
+        // The events outside of the windows of the application are sent to the innermost popup.
+        let hit = grabs.iter().rposition(|(popup_id, _)| {
+            let (width, height): (u32, u32) = popup(popup_id).unwrap().surface_size().into();
+            mkwid(event.event as xproto::Window) == *popup_id
+                && (0.0..width as f64).contains(&event.event_x)
+                && (0.0..height as f64).contains(&event.event_y)
+        });
+        let dismissed: Vec<_> = grabs
+            .drain(hit.map_or(0, |index| index + 1)..)
+            .rev()
+            .filter_map(|(popup_id, device_id)| Some((popup_id, device_id, popup(&popup_id)?)))
+            .collect();
+        let regrab = grabs.last().filter(|&&grab| grab != innermost).and_then(|(id, _)| popup(id));
+        drop(grabs);
+        drop(windows);
+
+        for (popup_id, device_id, popup) in dismissed {
+            popup.dismiss_popup(device_id);
+            app.window_event(&self.target, popup_id, WindowEvent::PopupDismissed);
+        }
+
+        // Hand the grab to the innermost popup left.
+        if let Some(popup) = regrab {
+            match popup.grab_popup_pointer() {
+                Ok(device_id) => {
+                    if let Some(grab) = self.target.popup_grabs.borrow_mut().last_mut() {
+                        grab.1 = device_id;
+                    }
+                },
+                Err(err) => tracing::warn!("Failed to grab the pointer for the popup: {err}"),
+            }
+        }
+
+        hit.is_none()
+    }
+
+    /// Move the drag started from our window with the pointer, dropping the data once the button
+    /// is released.
+    fn drag_source_input(
+        &mut self,
+        event: &XIDeviceEvent,
+        evtype: c_int,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        if self.dnd.source.is_none() {
             return;
         }
 
-        if xev.message_type == atoms[XdndLeave] as c_ulong {
-            if self.dnd.dragging {
-                let event = WindowEvent::DragLeft { position: Some(self.dnd.position) };
-                app.window_event(&self.target, window_id, event);
+        // The scroll wheel doesn't affect the drag.
+        if evtype != xinput2::XI_Motion && (4..=7).contains(&event.detail) {
+            return;
+        }
+
+        let time = event.time as xproto::Timestamp;
+        let result = match evtype {
+            xinput2::XI_Motion => {
+                let position = (event.root_x as i16, event.root_y as i16);
+                self.dnd.source_motion(self.target.root, position, time).map(|_| true)
+            },
+            xinput2::XI_ButtonRelease => self.dnd.source_drop(time),
+            _ => Ok(false),
+        };
+
+        match result {
+            Ok(true) => (),
+            Ok(false) => self.end_drag(None, app),
+            Err(err) => {
+                tracing::warn!("Failed to drag: {err}");
+                self.end_drag(None, app);
+            },
+        }
+    }
+
+    fn selection_request(&mut self, xev: &XSelectionRequestEvent) {
+        let atoms = self.target.xconn.atoms();
+        if xev.selection != atoms[XdndSelection] as c_ulong {
+            let clipboard = self.target.clipboard.borrow();
+            if xev.owner != clipboard.window() as c_ulong {
+                return;
             }
-            self.dnd.reset();
+
+            if let Err(err) = clipboard.selection_request(
+                xev.requestor as xproto::Window,
+                xev.selection as xproto::Atom,
+                xev.target as xproto::Atom,
+                xev.property as xproto::Atom,
+                xev.time as xproto::Timestamp,
+            ) {
+                tracing::warn!("Failed to send the clipboard content: {err}");
+            }
+            return;
+        }
+
+        if let Err(err) = self.dnd.serve_selection_request(
+            xev.requestor as xproto::Window,
+            xev.target as xproto::Atom,
+            xev.property as xproto::Atom,
+            xev.time as xproto::Timestamp,
+        ) {
+            tracing::warn!("Failed to send the dragged data: {err}");
+        }
+    }
+
+    fn selection_clear(&mut self, xev: &XSelectionClearEvent) {
+        let mut clipboard = self.target.clipboard.borrow_mut();
+        if xev.window == clipboard.window() as c_ulong {
+            clipboard.selection_clear(xev.selection as xproto::Atom);
         }
     }
 
@@ -557,6 +966,45 @@ impl EventProcessor {
         // Set the timestamp.
         self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
 
+        if window == self.target.clipboard.borrow().window() {
+            let event = self
+                .target
+                .clipboard
+                .borrow_mut()
+                .selection_notify(xev.target as xproto::Atom, xev.property as xproto::Atom);
+            if let Some(event) = event {
+                app.clipboard_event(&self.target, event);
+            }
+            return;
+        }
+
+        if xev.selection != atoms[XdndSelection] as c_ulong {
+            return;
+        }
+
+        // The data requested by the application.
+        if self.dnd.requests.front().is_some_and(|request| {
+            request.target as c_ulong == xev.target
+                && (xev.property == request.target as c_ulong || xev.property == 0)
+        }) {
+            let request = self.dnd.requests.pop_front().unwrap();
+            let data = if xev.property != 0 {
+                self.dnd.read_request_data(window, request.target).ok()
+            } else {
+                None
+            };
+
+            let event = WindowEvent::DragData {
+                serial: request.serial,
+                mime_type: request.mime_type,
+                data,
+            };
+            app.window_event(&self.target, request.window_id, event);
+
+            self.dnd.convert_request(window);
+            return;
+        }
+
         if xev.property != atoms[XdndSelection] as c_ulong {
             return;
         }
@@ -572,7 +1020,11 @@ impl EventProcessor {
                 } else {
                     let paths = path_list.iter().map(Into::into).collect();
                     self.dnd.dragging = true;
-                    WindowEvent::DragEntered { paths, position: self.dnd.position }
+                    WindowEvent::DragEntered {
+                        paths,
+                        payload: self.dnd.payload.clone(),
+                        position: self.dnd.position,
+                    }
                 };
 
                 app.window_event(&self.target, window_id, event);
@@ -802,12 +1254,35 @@ impl EventProcessor {
     fn property_notify(&mut self, xev: &XPropertyEvent, app: &mut dyn ApplicationHandler) {
         let atoms = self.target.x_connection().atoms();
         let atom = xev.atom as xproto::Atom;
+        let window = xev.window as xproto::Window;
+
+        // The chunks of the `INCR` selection transfers.
+        if window == self.target.clipboard.borrow().window() {
+            if xev.state == xlib::PropertyNewValue {
+                let event = self.target.clipboard.borrow_mut().property_notify(atom);
+                if let Some(event) = event {
+                    app.clipboard_event(&self.target, event);
+                }
+            }
+            return;
+        }
+        if xev.state == xlib::PropertyDelete {
+            if let Err(err) = self.target.xconn.continue_incr_transfer(window, atom) {
+                tracing::warn!("Failed to send the selection: {err}");
+            }
+        }
 
         if atom == xproto::Atom::from(xproto::AtomEnum::RESOURCE_MANAGER)
             || atom == atoms[_XSETTINGS_SETTINGS]
+            || atom == atoms[_NET_WORKAREA]
         {
             self.process_dpi_change(app);
         }
+
+        if atom == atoms[_XSETTINGS_SETTINGS] {
+            self.process_theme_change(app);
+            self.process_xsettings_change(app);
+        }
     }
 
     fn visibility_notify(&self, xev: &XVisibilityEvent, app: &mut dyn ApplicationHandler) {
@@ -908,6 +1383,16 @@ impl EventProcessor {
 
             if let Some(mut key_processor) = self.xkb_context.key_context() {
                 let event = key_processor.process_key_event(keycode, state, repeat);
+
+                // Escape cancels the drag started from our window.
+                if state == ElementState::Pressed
+                    && event.logical_key == Key::Named(NamedKey::Escape)
+                    && self.dnd.is_dragging()
+                {
+                    self.end_drag(None, app);
+                    return;
+                }
+
                 let event =
                     WindowEvent::KeyboardInput { device_id: None, event, is_synthetic: false };
                 app.window_event(&self.target, window_id, event);
@@ -976,13 +1461,10 @@ impl EventProcessor {
         // Set the timestamp.
         self.target.xconn.set_timestamp(event.time as xproto::Timestamp);
 
-        let Some(DeviceType::Mouse) = self
-            .devices
-            .borrow()
-            .get(&mkdid(event.sourceid as xinput::DeviceId))
-            .map(|device| device.r#type)
-        else {
-            return;
+        let kind = match self.device_type(event.sourceid as xinput::DeviceId) {
+            Some(DeviceType::Mouse) => None,
+            Some(r#type @ (DeviceType::Pen | DeviceType::Eraser)) => r#type.tablet_tool_kind(),
+            Some(DeviceType::Touch) | None => return,
         };
 
         // Deliver multi-touch events instead of emulated mouse events.
@@ -992,27 +1474,41 @@ impl EventProcessor {
 
         let position = PhysicalPosition::new(event.event_x, event.event_y);
 
+        // The tip of pens is reported as the first button, the buttons on their side as the
+        // second and third ones, the latter being the secondary barrel button.
+        let button = |mouse: MouseButton, tool: TabletToolButton| match kind {
+            Some(kind) => {
+                let devices = self.target.devices.borrow();
+                let data = devices
+                    .get(&mkdid(event.sourceid as xinput::DeviceId))
+                    .map(|device| device.tablet.data.clone())
+                    .unwrap_or_default();
+                ButtonSource::TabletTool { kind, button: tool, data }
+            },
+            None => mouse.into(),
+        };
+
         let event = match event.detail as u32 {
             xlib::Button1 => WindowEvent::PointerButton {
                 device_id,
                 primary: true,
                 state,
                 position,
-                button: MouseButton::Left.into(),
+                button: button(MouseButton::Left, TabletToolButton::Contact),
             },
             xlib::Button2 => WindowEvent::PointerButton {
                 device_id,
                 primary: true,
                 state,
                 position,
-                button: MouseButton::Middle.into(),
+                button: button(MouseButton::Middle, TabletToolButton::Other(1)),
             },
             xlib::Button3 => WindowEvent::PointerButton {
                 device_id,
                 primary: true,
                 state,
                 position,
-                button: MouseButton::Right.into(),
+                button: button(MouseButton::Right, TabletToolButton::Barrel),
             },
 
             // Suppress emulated scroll wheel clicks, since we handle the real motion events for
@@ -1061,13 +1557,10 @@ impl EventProcessor {
         // Set the timestamp.
         self.target.xconn.set_timestamp(event.time as xproto::Timestamp);
 
-        let Some(DeviceType::Mouse) = self
-            .devices
-            .borrow()
-            .get(&mkdid(event.sourceid as xinput::DeviceId))
-            .map(|device| device.r#type)
-        else {
-            return;
+        let kind = match self.device_type(event.sourceid as xinput::DeviceId) {
+            Some(DeviceType::Mouse) => None,
+            Some(r#type @ (DeviceType::Pen | DeviceType::Eraser)) => r#type.tablet_tool_kind(),
+            Some(DeviceType::Touch) | None => return,
         };
 
         let device_id = Some(mkdid(event.deviceid as xinput::DeviceId));
@@ -1080,25 +1573,38 @@ impl EventProcessor {
             util::maybe_change(&mut shared_state_lock.cursor_pos, new_cursor_pos)
         });
 
-        if cursor_moved == Some(true) {
+        // Pens also report a motion when only their pressure or tilt changed.
+        let (data_changed, source) = match kind {
+            Some(kind) => {
+                let mut devices = self.target.devices.borrow_mut();
+                let Some(device) = devices.get_mut(&mkdid(event.sourceid as xinput::DeviceId))
+                else {
+                    return;
+                };
+                let mut changed = false;
+                for (number, value) in valuators(&event.valuators) {
+                    changed |= device.tablet.update(number, value);
+                }
+                let data = device.tablet.data.clone();
+                (changed, PointerSource::TabletTool { kind, data })
+            },
+            None => (false, PointerSource::Mouse),
+        };
+
+        if cursor_moved == Some(true) || (cursor_moved.is_some() && data_changed) {
             let position = PhysicalPosition::new(event.event_x, event.event_y);
 
-            let event = WindowEvent::PointerMoved {
-                device_id,
-                primary: true,
-                position,
-                source: PointerSource::Mouse,
-            };
+            let event = WindowEvent::PointerMoved { device_id, primary: true, position, source };
             app.window_event(&self.target, window_id, event);
         } else if cursor_moved.is_none() {
             return;
         }
 
-        // More gymnastics, for self.devices
+        // More gymnastics, for self.target.devices
         let mask = unsafe {
             slice::from_raw_parts(event.valuators.mask, event.valuators.mask_len as usize)
         };
-        let mut devices = self.devices.borrow_mut();
+        let mut devices = self.target.devices.borrow_mut();
         let physical_device = match devices.get_mut(&mkdid(event.sourceid as xinput::DeviceId)) {
             Some(device) => device,
             None => return,
@@ -1147,7 +1653,7 @@ impl EventProcessor {
         let device_id = mkdid(event.deviceid as xinput::DeviceId);
 
         if let Some(all_info) = DeviceInfo::get(&self.target.xconn, ALL_DEVICES.into()) {
-            let mut devices = self.devices.borrow_mut();
+            let mut devices = self.target.devices.borrow_mut();
             for device_info in all_info.iter() {
                 // The second expression is need for resetting to work correctly on i3, and
                 // presumably some other WMs. On those, `XI_Enter` doesn't include the physical
@@ -1171,7 +1677,7 @@ impl EventProcessor {
                 device_id,
                 primary: true,
                 position,
-                kind: PointerKind::Mouse,
+                kind: self.pointer_kind(event.sourceid as xinput::DeviceId),
             };
             app.window_event(&self.target, window_id, event);
         }
@@ -1191,7 +1697,7 @@ impl EventProcessor {
                 device_id: Some(mkdid(event.deviceid as xinput::DeviceId)),
                 primary: true,
                 position: Some(PhysicalPosition::new(event.event_x, event.event_y)),
-                kind: PointerKind::Mouse,
+                kind: self.pointer_kind(event.sourceid as xinput::DeviceId),
             };
             app.window_event(&self.target, window_id, event);
         }
@@ -1238,6 +1744,7 @@ impl EventProcessor {
         // The deviceid for this event is for a keyboard instead of a pointer,
         // so we have to do a little extra work.
         let device_id = self
+            .target
             .devices
             .borrow()
             .get(&mkdid(xev.deviceid as xinput::DeviceId))
@@ -1299,10 +1806,39 @@ impl EventProcessor {
         }
     }
 
+    /// The force and contact of the touch, when the touchscreen reports them.
+    fn touch_axes(&self, xev: &XIDeviceEvent, phase: i32) -> (Option<Force>, Option<TouchContact>) {
+        let mut devices = self.target.devices.borrow_mut();
+        let axes = match devices.get_mut(&mkdid(xev.sourceid as xinput::DeviceId)) {
+            Some(device) => &mut device.touch_axes,
+            None => return (None, None),
+        };
+
+        // The contact is converted to the pixels of the monitor under the touch.
+        let pixels_per_meter = self.target.xconn.available_monitors().ok().and_then(|monitors| {
+            let (x, y) = (xev.root_x as i64, xev.root_y as i64);
+            monitors
+                .iter()
+                .find(|monitor| monitor.rect.contains_point(x, y))
+                .and_then(|monitor| monitor.pixels_per_meter())
+        });
+
+        let id = xev.detail as u32;
+        let values = axes.update(id, valuators(&xev.valuators), pixels_per_meter);
+        if phase == xinput2::XI_TouchEnd {
+            axes.end(id);
+        }
+
+        values
+    }
+
     fn xinput2_touch(&mut self, xev: &XIDeviceEvent, phase: i32, app: &mut dyn ApplicationHandler) {
         // Set the timestamp.
         self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
 
+        // The values of the touch are tracked even outside of our windows.
+        let (force, contact) = self.touch_axes(xev, phase);
+
         let window = xev.event as xproto::Window;
         if self.window_exists(window) {
             let window_id = mkwid(window);
@@ -1340,7 +1876,7 @@ impl EventProcessor {
                         primary: is_first_touch,
                         state: ElementState::Pressed,
                         position,
-                        button: ButtonSource::Touch { finger_id, force: None },
+                        button: ButtonSource::Touch { finger_id, force, contact },
                     };
                     app.window_event(&self.target, window_id, event);
                 },
@@ -1349,7 +1885,7 @@ impl EventProcessor {
                         device_id,
                         primary: is_first_touch,
                         position,
-                        source: PointerSource::Touch { finger_id, force: None },
+                        source: PointerSource::Touch { finger_id, force, contact },
                     };
                     app.window_event(&self.target, window_id, event);
                 },
@@ -1359,7 +1895,7 @@ impl EventProcessor {
                         primary: is_first_touch,
                         state: ElementState::Released,
                         position,
-                        button: ButtonSource::Touch { finger_id, force: None },
+                        button: ButtonSource::Touch { finger_id, force, contact },
                     };
                     app.window_event(&self.target, window_id, event);
                     let event = WindowEvent::PointerLeft {
@@ -1375,6 +1911,85 @@ impl EventProcessor {
         }
     }
 
+    fn xinput2_gesture_pinch(
+        &mut self,
+        xev: &ffi::XIGesturePinchEvent,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        // Set the timestamp.
+        self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
+
+        let window = xev.event as xproto::Window;
+        if !self.window_exists(window) {
+            return;
+        }
+
+        let (phase, pan_delta, pinch_delta, rotation_delta) = match xev.evtype {
+            ffi::XI_GesturePinchBegin => {
+                self.pinch_scale = 1.0;
+                (TouchPhase::Started, PhysicalPosition::new(0., 0.), 0., 0.)
+            },
+            ffi::XI_GesturePinchUpdate => {
+                let pan_delta = PhysicalPosition::new(xev.delta_x as f32, xev.delta_y as f32);
+                let pinch_delta = xev.scale - self.pinch_scale;
+                self.pinch_scale = xev.scale;
+                // XInput provides rotation in degrees cw, opposite of winit's degrees ccw.
+                (TouchPhase::Moved, pan_delta, pinch_delta, -xev.delta_angle as f32)
+            },
+            _ => {
+                self.pinch_scale = 1.0;
+                let phase = if xev.flags & ffi::XIGesturePinchEventCancelled != 0 {
+                    TouchPhase::Cancelled
+                } else {
+                    TouchPhase::Ended
+                };
+                (phase, PhysicalPosition::new(0., 0.), 0., 0.)
+            },
+        };
+
+        let window_id = mkwid(window);
+        let device_id = Some(mkdid(xev.deviceid as xinput::DeviceId));
+
+        // The chance of only one of these events being necessary is extremely small,
+        // so it is easier to just send all three
+        let event = WindowEvent::PanGesture { device_id, delta: pan_delta, phase };
+        app.window_event(&self.target, window_id, event);
+        let event = WindowEvent::PinchGesture { device_id, delta: pinch_delta, phase };
+        app.window_event(&self.target, window_id, event);
+        let event = WindowEvent::RotationGesture { device_id, delta: rotation_delta, phase };
+        app.window_event(&self.target, window_id, event);
+    }
+
+    fn xinput2_gesture_swipe(
+        &mut self,
+        xev: &ffi::XIGestureSwipeEvent,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        // Set the timestamp.
+        self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
+
+        let window = xev.event as xproto::Window;
+        if !self.window_exists(window) {
+            return;
+        }
+
+        let (phase, delta) = match xev.evtype {
+            ffi::XI_GestureSwipeBegin => (TouchPhase::Started, PhysicalPosition::new(0., 0.)),
+            ffi::XI_GestureSwipeUpdate => {
+                (TouchPhase::Moved, PhysicalPosition::new(xev.delta_x as f32, xev.delta_y as f32))
+            },
+            _ if xev.flags & ffi::XIGestureSwipeEventCancelled != 0 => {
+                (TouchPhase::Cancelled, PhysicalPosition::new(0., 0.))
+            },
+            _ => (TouchPhase::Ended, PhysicalPosition::new(0., 0.)),
+        };
+
+        let device_id = Some(mkdid(xev.deviceid as xinput::DeviceId));
+        let fingers = xev.detail as u32;
+        let event = WindowEvent::SwipeGesture { device_id, fingers, delta, phase };
+        app.window_event(&self.target, mkwid(window), event);
+    }
+
     fn xinput2_raw_button_input(
         &self,
         xev: &XIRawEvent,
@@ -1420,6 +2035,7 @@ impl EventProcessor {
         }
 
         let Some(DeviceType::Mouse) = self
+            .target
             .devices
             .borrow()
             .get(&mkdid(xev.sourceid as xinput::DeviceId))
@@ -1460,16 +2076,26 @@ impl EventProcessor {
         app.device_event(&self.target, device_id, event);
     }
 
-    fn xinput2_hierarchy_changed(&mut self, xev: &XIHierarchyEvent) {
+    fn xinput2_hierarchy_changed(
+        &mut self,
+        xev: &XIHierarchyEvent,
+        app: &mut dyn ApplicationHandler,
+    ) {
         // Set the timestamp.
         self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
         let infos = unsafe { slice::from_raw_parts(xev.info, xev.num_info as usize) };
         for info in infos {
+            let device_id = mkdid(info.deviceid as xinput::DeviceId);
             if 0 != info.flags & (xinput2::XISlaveAdded | xinput2::XIMasterAdded) {
                 self.init_device(info.deviceid as xinput::DeviceId);
+                if self.target.devices.borrow().get(&device_id).is_some_and(Device::is_physical) {
+                    app.device_event(&self.target, Some(device_id), DeviceEvent::Added);
+                }
             } else if 0 != info.flags & (xinput2::XISlaveRemoved | xinput2::XIMasterRemoved) {
-                let mut devices = self.devices.borrow_mut();
-                devices.remove(&mkdid(info.deviceid as xinput::DeviceId));
+                let device = self.target.devices.borrow_mut().remove(&device_id);
+                if device.is_some_and(|device| device.is_physical()) {
+                    app.device_event(&self.target, Some(device_id), DeviceEvent::Removed);
+                }
             }
         }
     }
@@ -1736,19 +2362,32 @@ impl EventProcessor {
     }
 
     fn process_dpi_change(&self, app: &mut dyn ApplicationHandler) {
-        self.target.xconn.reload_database().expect("failed to reload Xft database");
+        if let Err(err) = self.refresh_monitors(app) {
+            tracing::warn!("Failed to refresh the monitors: {err}");
+        }
+    }
+
+    fn refresh_monitors(&self, app: &mut dyn ApplicationHandler) -> Result<(), X11Error> {
+        self.target.xconn.reload_database()?;
 
-        // In the future, it would be quite easy to emit monitor hotplug events.
         let prev_list = {
             let prev_list = self.target.xconn.invalidate_cached_monitor_list();
             match prev_list {
                 Some(prev_list) => prev_list,
-                None => return,
+                None => return Ok(()),
             }
         };
 
-        let new_list = self.target.xconn.available_monitors().expect("Failed to get monitor list");
-        for new_monitor in new_list {
+        let new_list = match self.target.xconn.available_monitors() {
+            Ok(new_list) => new_list,
+            Err(err) => {
+                // Keep the old list to compare against once the monitors can be queried again.
+                self.target.xconn.restore_cached_monitor_list(prev_list);
+                return Err(err);
+            },
+        };
+
+        for new_monitor in &new_list {
             // Previous list may be empty, in case of disconnecting and
             // reconnecting the only one monitor. We still need to emit events in
             // this case.
@@ -1757,9 +2396,9 @@ impl EventProcessor {
                 .find(|prev_monitor| prev_monitor.name == new_monitor.name)
                 .map(|prev_monitor| prev_monitor.scale_factor);
             if Some(new_monitor.scale_factor) != maybe_prev_scale_factor {
-                for window in self.target.windows.borrow().iter().filter_map(|(_, w)| w.upgrade()) {
+                for window in self.target.windows.borrow().values().filter_map(|w| w.upgrade()) {
                     window.refresh_dpi_for_monitor(
-                        &new_monitor,
+                        new_monitor,
                         maybe_prev_scale_factor,
                         app,
                         &self.target,
@@ -1767,6 +2406,78 @@ impl EventProcessor {
                 }
             }
         }
+
+        for event in monitor::monitor_events(&prev_list, &new_list) {
+            app.monitor_event(&self.target, event);
+        }
+
+        Ok(())
+    }
+
+    /// Handle new settings of the XDG Desktop Portal.
+    pub(crate) fn process_portal_settings(
+        &mut self,
+        settings: PortalSettings,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        if *self.target.portal_settings.borrow() == settings {
+            return;
+        }
+
+        let prev_desktop_settings = self.target.desktop_settings();
+        self.target.portal_settings.replace(settings);
+        self.process_theme_change(app);
+
+        if self.target.desktop_settings() != prev_desktop_settings {
+            app.desktop_settings_changed(&self.target);
+        }
+    }
+
+    fn process_theme_change(&self, app: &mut dyn ApplicationHandler) {
+        if !self.target.xconn.refresh_system_theme(&self.target.portal_settings.borrow()) {
+            return;
+        }
+
+        let theme = match self.target.xconn.system_theme() {
+            Some(theme) => theme,
+            None => return,
+        };
+
+        // Only the windows following the system theme are affected.
+        let windows: Vec<_> = self
+            .target
+            .windows
+            .borrow()
+            .values()
+            .filter_map(|window| window.upgrade())
+            .filter(|window| window.shared_state_lock().theme.is_none())
+            .collect();
+        for window in windows {
+            if let Ok(cookie) = window.set_theme_inner(None) {
+                cookie.ignore_error();
+            }
+            app.window_event(&self.target, window.id(), WindowEvent::ThemeChanged(theme));
+        }
+
+        let _ = self.target.xconn.flush_requests();
+    }
+
+    fn process_xsettings_change(&self, app: &mut dyn ApplicationHandler) {
+        let xsettings = self.target.xconn.xsettings_snapshot();
+        if *self.target.xsettings.borrow() == xsettings {
+            return;
+        }
+
+        let prev_desktop_settings = self.target.desktop_settings();
+        self.target.xsettings.replace(xsettings);
+        self.target.update_cursor_theme();
+        if let Some(handler) = app.x11_handler() {
+            handler.xsettings_changed(&self.target);
+        }
+
+        if self.target.desktop_settings() != prev_desktop_settings {
+            app.desktop_settings_changed(&self.target);
+        }
     }
 
     fn window_exists(&self, window_id: xproto::Window) -> bool {
@@ -1793,3 +2504,11 @@ fn is_first_touch(first: &mut Option<u32>, num: &mut u32, id: u32, phase: i32) -
 
     *first == Some(id)
 }
+
+/// The numbers and values of the valuators set in the event.
+fn valuators(state: &xinput2::XIValuatorState) -> impl Iterator<Item = (i32, f64)> + '_ {
+    let mask = unsafe { slice::from_raw_parts(state.mask, state.mask_len as usize) };
+    let set = (0..state.mask_len * 8).filter(|&i| xinput2::XIMaskIsSet(mask, i));
+    // The values are only given for the valuators which are set.
+    set.enumerate().map(|(index, number)| (number, unsafe { *state.values.add(index) }))
+}
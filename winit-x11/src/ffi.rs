@@ -2,3 +2,84 @@ pub use x11_dl::error::OpenError;
 pub use x11_dl::xinput2::*;
 pub use x11_dl::xlib::*;
 pub use x11_dl::xlib_xcb::*;
+
+// The touchpad gestures of XInput 2.4, which `x11_dl` doesn't have yet.
+#[allow(non_upper_case_globals)]
+pub const XI_GesturePinchBegin: i32 = 27;
+#[allow(non_upper_case_globals)]
+pub const XI_GesturePinchUpdate: i32 = 28;
+#[allow(non_upper_case_globals)]
+pub const XI_GesturePinchEnd: i32 = 29;
+#[allow(non_upper_case_globals)]
+pub const XI_GestureSwipeBegin: i32 = 30;
+#[allow(non_upper_case_globals)]
+pub const XI_GestureSwipeUpdate: i32 = 31;
+#[allow(non_upper_case_globals)]
+pub const XI_GestureSwipeEnd: i32 = 32;
+
+#[allow(non_upper_case_globals)]
+pub const XIGesturePinchEventCancelled: i32 = 1 << 0;
+#[allow(non_upper_case_globals)]
+pub const XIGestureSwipeEventCancelled: i32 = 1 << 0;
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct XIGesturePinchEvent {
+    pub _type: i32,
+    pub serial: std::os::raw::c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: i32,
+    pub evtype: i32,
+    pub time: Time,
+    pub deviceid: i32,
+    pub sourceid: i32,
+    // The number of touches
+    pub detail: i32,
+    pub root: Window,
+    pub event: Window,
+    pub child: Window,
+    pub root_x: f64,
+    pub root_y: f64,
+    pub event_x: f64,
+    pub event_y: f64,
+    pub delta_x: f64,
+    pub delta_y: f64,
+    pub delta_unaccel_x: f64,
+    pub delta_unaccel_y: f64,
+    pub scale: f64,
+    pub delta_angle: f64,
+    pub flags: i32,
+    pub mods: XIModifierState,
+    pub group: XIGroupState,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct XIGestureSwipeEvent {
+    pub _type: i32,
+    pub serial: std::os::raw::c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: i32,
+    pub evtype: i32,
+    pub time: Time,
+    pub deviceid: i32,
+    pub sourceid: i32,
+    // The number of touches
+    pub detail: i32,
+    pub root: Window,
+    pub event: Window,
+    pub child: Window,
+    pub root_x: f64,
+    pub root_y: f64,
+    pub event_x: f64,
+    pub event_y: f64,
+    pub delta_x: f64,
+    pub delta_y: f64,
+    pub delta_unaccel_x: f64,
+    pub delta_unaccel_y: f64,
+    pub flags: i32,
+    pub mods: XIModifierState,
+    pub group: XIGroupState,
+}
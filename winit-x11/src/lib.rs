@@ -3,11 +3,13 @@
 use dpi::Size;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+pub use winit_core::application::x11::ApplicationHandlerExtX11;
 use winit_core::event_loop::ActiveEventLoop as CoreActiveEventLoop;
 use winit_core::window::{ActivationToken, PlatformWindowAttributes, Window as CoreWindow};
 
 pub use crate::event_loop::{ActiveEventLoop, EventLoop};
 pub use crate::window::Window;
+pub use crate::xsettings::XSettings;
 
 macro_rules! os_error {
     ($error:expr) => {{ winit_core::error::OsError::new(line!(), file!(), $error) }};
@@ -15,6 +17,7 @@ macro_rules! os_error {
 
 mod activation;
 mod atoms;
+mod clipboard;
 mod dnd;
 mod event_loop;
 mod event_processor;
@@ -71,6 +74,62 @@ pub enum WindowType {
     Normal,
 }
 
+/// Space reserved by a window at the edges of the screen, like a panel. Maps directly to
+/// [`_NET_WM_STRUT_PARTIAL`](https://specifications.freedesktop.org/wm-spec/wm-spec-1.5.html).
+///
+/// The sizes are physical pixels from the edges of the root window, spanning all of the monitors,
+/// and the ranges are the physical coordinates of the edge along which the space is reserved. The
+/// window manager keeps other windows out of the reserved space, e.g. when maximizing them.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Strut {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+    pub left_start_y: u32,
+    pub left_end_y: u32,
+    pub right_start_y: u32,
+    pub right_end_y: u32,
+    pub top_start_x: u32,
+    pub top_end_x: u32,
+    pub bottom_start_x: u32,
+    pub bottom_end_x: u32,
+}
+
+impl Strut {
+    /// Reserve `width` pixels at the left edge, from `start_y` to `end_y` inclusive.
+    pub fn left(width: u32, start_y: u32, end_y: u32) -> Self {
+        Self { left: width, left_start_y: start_y, left_end_y: end_y, ..Default::default() }
+    }
+
+    /// Reserve `width` pixels at the right edge, from `start_y` to `end_y` inclusive.
+    pub fn right(width: u32, start_y: u32, end_y: u32) -> Self {
+        Self { right: width, right_start_y: start_y, right_end_y: end_y, ..Default::default() }
+    }
+
+    /// Reserve `height` pixels at the top edge, from `start_x` to `end_x` inclusive.
+    pub fn top(height: u32, start_x: u32, end_x: u32) -> Self {
+        Self { top: height, top_start_x: start_x, top_end_x: end_x, ..Default::default() }
+    }
+
+    /// Reserve `height` pixels at the bottom edge, from `start_x` to `end_x` inclusive.
+    pub fn bottom(height: u32, start_x: u32, end_x: u32) -> Self {
+        Self { bottom: height, bottom_start_x: start_x, bottom_end_x: end_x, ..Default::default() }
+    }
+}
+
+/// The virtual desktop showing a window. Maps directly to
+/// [`_NET_WM_DESKTOP`](https://specifications.freedesktop.org/wm-spec/wm-spec-1.5.html).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Desktop {
+    /// The desktop at this index, starting from zero.
+    Index(u32),
+    /// Every desktop.
+    All,
+}
+
 /// The first argument in the provided hook will be the pointer to `XDisplay`
 /// and the second one the pointer to [`XErrorEvent`]. The returned `bool` is an
 /// indicator whether the error was handled by the callback.
@@ -108,6 +167,11 @@ pub fn register_xlib_error_hook(hook: XlibErrorHook) {
 pub trait ActiveEventLoopExtX11 {
     /// True if the event loop uses X11.
     fn is_x11(&self) -> bool;
+
+    /// The settings shared by the XSettings manager, `None` if the event loop doesn't use X11.
+    ///
+    /// Changes are reported with [`ApplicationHandlerExtX11::xsettings_changed`].
+    fn xsettings(&self) -> Option<XSettings>;
 }
 
 impl ActiveEventLoopExtX11 for dyn CoreActiveEventLoop + '_ {
@@ -115,6 +179,11 @@ impl ActiveEventLoopExtX11 for dyn CoreActiveEventLoop + '_ {
     fn is_x11(&self) -> bool {
         self.cast_ref::<ActiveEventLoop>().is_some()
     }
+
+    #[inline]
+    fn xsettings(&self) -> Option<XSettings> {
+        self.cast_ref::<ActiveEventLoop>().map(|event_loop| event_loop.xsettings.borrow().clone())
+    }
 }
 
 /// Additional methods on [`EventLoop`] that are specific to X11.
@@ -137,10 +206,72 @@ pub trait EventLoopBuilderExtX11 {
 
 /// Additional methods on [`Window`] that are specific to X11.
 ///
+/// They have no effect when the window isn't an X11 window.
+///
 /// [`Window`]: crate::window::Window
-pub trait WindowExtX11 {}
+pub trait WindowExtX11 {
+    /// Reserve space at the edges of the screen, or release it with [`None`].
+    ///
+    /// See [`WindowAttributesX11::with_strut`].
+    fn set_strut(&self, strut: Option<Strut>);
+
+    /// Hide the window from the taskbar.
+    ///
+    /// See [`WindowAttributesX11::with_skip_taskbar`].
+    fn set_skip_taskbar(&self, skip: bool);
+
+    /// Hide the window from the pager.
+    ///
+    /// See [`WindowAttributesX11::with_skip_pager`].
+    fn set_skip_pager(&self, skip: bool);
+
+    /// Keep the window at the same place when switching desktops.
+    ///
+    /// See [`WindowAttributesX11::with_sticky`].
+    fn set_sticky(&self, sticky: bool);
 
-impl WindowExtX11 for dyn CoreWindow {}
+    /// Move the window to another desktop.
+    ///
+    /// See [`WindowAttributesX11::with_desktop`].
+    fn set_desktop(&self, desktop: Desktop);
+}
+
+impl WindowExtX11 for dyn CoreWindow + '_ {
+    #[inline]
+    fn set_strut(&self, strut: Option<Strut>) {
+        if let Some(window) = self.cast_ref::<Window>() {
+            window.set_strut(strut);
+        }
+    }
+
+    #[inline]
+    fn set_skip_taskbar(&self, skip: bool) {
+        if let Some(window) = self.cast_ref::<Window>() {
+            window.set_skip_taskbar(skip);
+        }
+    }
+
+    #[inline]
+    fn set_skip_pager(&self, skip: bool) {
+        if let Some(window) = self.cast_ref::<Window>() {
+            window.set_skip_pager(skip);
+        }
+    }
+
+    #[inline]
+    fn set_sticky(&self, sticky: bool) {
+        if let Some(window) = self.cast_ref::<Window>() {
+            window.set_sticky(sticky);
+        }
+    }
+
+    #[inline]
+    fn set_desktop(&self, desktop: Desktop) {
+        if let Some(window) = self.cast_ref::<Window>() {
+            window.set_desktop(desktop);
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct ApplicationName {
@@ -157,6 +288,11 @@ pub struct WindowAttributesX11 {
     pub(crate) base_size: Option<Size>,
     pub(crate) override_redirect: bool,
     pub(crate) x11_window_types: Vec<WindowType>,
+    pub(crate) strut: Option<Strut>,
+    pub(crate) skip_taskbar: bool,
+    pub(crate) skip_pager: bool,
+    pub(crate) sticky: bool,
+    pub(crate) desktop: Option<Desktop>,
 
     /// The parent window to embed this window into.
     pub(crate) embed_window: Option<XWindow>,
@@ -172,6 +308,11 @@ impl Default for WindowAttributesX11 {
             base_size: None,
             override_redirect: false,
             x11_window_types: vec![WindowType::Normal],
+            strut: None,
+            skip_taskbar: false,
+            skip_pager: false,
+            sticky: false,
+            desktop: None,
             embed_window: None,
         }
     }
@@ -214,6 +355,47 @@ impl WindowAttributesX11 {
         self
     }
 
+    /// Build window reserving space at the edges of the screen with `_NET_WM_STRUT_PARTIAL`,
+    /// usually together with the [`WindowType::Dock`] type.
+    ///
+    /// ```
+    /// # use winit::platform::x11::{Strut, WindowAttributesX11, WindowType};
+    /// // A 32 pixels high panel at the top of a 1920 pixels wide screen.
+    /// WindowAttributesX11::default()
+    ///     .with_x11_window_type(vec![WindowType::Dock])
+    ///     .with_strut(Strut::top(32, 0, 1919));
+    /// ```
+    pub fn with_strut(mut self, strut: Strut) -> Self {
+        self.strut = Some(strut);
+        self
+    }
+
+    /// Build window hidden from the taskbar with `_NET_WM_STATE_SKIP_TASKBAR`; defaults to false.
+    pub fn with_skip_taskbar(mut self, skip: bool) -> Self {
+        self.skip_taskbar = skip;
+        self
+    }
+
+    /// Build window hidden from the pager with `_NET_WM_STATE_SKIP_PAGER`; defaults to false.
+    pub fn with_skip_pager(mut self, skip: bool) -> Self {
+        self.skip_pager = skip;
+        self
+    }
+
+    /// Build window staying at the same place when switching desktops or viewports with
+    /// `_NET_WM_STATE_STICKY`; defaults to false.
+    pub fn with_sticky(mut self, sticky: bool) -> Self {
+        self.sticky = sticky;
+        self
+    }
+
+    /// Build window on the given desktop with `_NET_WM_DESKTOP`; defaults to the one picked by
+    /// the window manager, usually the current desktop.
+    pub fn with_desktop(mut self, desktop: Desktop) -> Self {
+        self.desktop = Some(desktop);
+        self
+    }
+
     /// Build window with base size hint.
     ///
     /// ```
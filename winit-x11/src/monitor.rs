@@ -1,11 +1,18 @@
+use std::borrow::Cow;
 use std::num::NonZeroU32;
-
-use dpi::PhysicalPosition;
-use winit_core::monitor::{MonitorHandleProvider, VideoMode};
+use std::sync::Arc;
+
+use dpi::{PhysicalPosition, PhysicalSize};
+use winit_core::event::MonitorEvent;
+use winit_core::monitor::{
+    MonitorHandle as CoreMonitorHandle, MonitorHandleProvider, MonitorTransform, SubpixelLayout,
+    VideoMode,
+};
 use x11rb::connection::RequestConnection;
 use x11rb::protocol::randr::{self, ConnectionExt as _};
 use x11rb::protocol::xproto;
 
+use crate::atoms::*;
 use crate::event_loop::X11Error;
 use crate::util;
 use crate::xdisplay::XConnection;
@@ -18,6 +25,37 @@ impl XConnection {
         // We update this lazily.
         self.monitor_handles.lock().unwrap().take()
     }
+
+    /// Put back the list taken by [`Self::invalidate_cached_monitor_list`], when querying the new
+    /// one failed.
+    pub fn restore_cached_monitor_list(&self, monitors: Vec<MonitorHandle>) {
+        self.monitor_handles.lock().unwrap().get_or_insert(monitors);
+    }
+}
+
+/// The events describing how the monitors changed from `prev_list` to `new_list`.
+pub(crate) fn monitor_events(
+    prev_list: &[MonitorHandle],
+    new_list: &[MonitorHandle],
+) -> Vec<MonitorEvent> {
+    let wrap = |monitor: &MonitorHandle| CoreMonitorHandle(Arc::new(monitor.clone()));
+
+    let removed = prev_list
+        .iter()
+        .filter(|monitor| !new_list.contains(monitor))
+        .map(|monitor| MonitorEvent::Removed(wrap(monitor)));
+
+    let added_or_changed = new_list.iter().filter_map(|new_monitor| {
+        match prev_list.iter().find(|monitor| *monitor == new_monitor) {
+            None => Some(MonitorEvent::Added(wrap(new_monitor))),
+            Some(prev_monitor) if !prev_monitor.same_configuration(new_monitor) => {
+                Some(MonitorEvent::Changed(wrap(new_monitor)))
+            },
+            Some(_) => None,
+        }
+    });
+
+    removed.chain(added_or_changed).collect()
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -49,6 +87,15 @@ pub struct MonitorHandle {
     pub(crate) rect: util::AaRect,
     /// Supported video modes on this monitor
     pub(crate) video_modes: Vec<VideoModeHandle>,
+    /// The physical size in millimeters
+    size_mm: Option<(u32, u32)>,
+    /// The area not covered by panels and docks
+    work_area: Option<util::AaRect>,
+    subpixel: Option<SubpixelLayout>,
+    transform: MonitorTransform,
+    /// The PNP ID of the manufacturer
+    make: Option<String>,
+    model: Option<String>,
 }
 
 impl MonitorHandleProvider for MonitorHandle {
@@ -79,6 +126,57 @@ impl MonitorHandleProvider for MonitorHandle {
     fn video_modes(&self) -> Box<dyn Iterator<Item = VideoMode>> {
         Box::new(self.video_modes.clone().into_iter().map(|mode| mode.into()))
     }
+
+    fn size_millimeters(&self) -> Option<(u32, u32)> {
+        self.size_mm
+    }
+
+    fn work_area(&self) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+        let work_area = self.work_area.as_ref()?;
+        Some((work_area.position().into(), work_area.size().into()))
+    }
+
+    fn subpixel_layout(&self) -> Option<SubpixelLayout> {
+        self.subpixel
+    }
+
+    fn transform(&self) -> Option<MonitorTransform> {
+        Some(self.transform)
+    }
+
+    fn make(&self) -> Option<Cow<'_, str>> {
+        self.make.as_deref().map(Cow::Borrowed)
+    }
+
+    fn model(&self) -> Option<Cow<'_, str>> {
+        self.model.as_deref().map(Cow::Borrowed)
+    }
+}
+
+impl MonitorHandle {
+    /// The number of pixels per meter, when the physical size of the monitor is known.
+    pub(crate) fn pixels_per_meter(&self) -> Option<f64> {
+        let (width_mm, height_mm) = self.size_mm?;
+        let (width, height) = self.rect.size();
+        // Compare the areas, which don't depend on the rotation of the monitor.
+        let pixels = f64::from(width) * f64::from(height);
+        let millimeters = f64::from(width_mm) * f64::from(height_mm);
+        Some((pixels / millimeters).sqrt() * 1000.0)
+    }
+
+    /// Whether the monitor has the same configuration as the other one, which is the same monitor.
+    pub(crate) fn same_configuration(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.position == other.position
+            && self.primary == other.primary
+            && self.scale_factor == other.scale_factor
+            && self.rect == other.rect
+            && self.current_video_mode() == other.current_video_mode()
+            && self.size_mm == other.size_mm
+            && self.work_area == other.work_area
+            && self.subpixel == other.subpixel
+            && self.transform == other.transform
+    }
 }
 
 impl PartialEq for MonitorHandle {
@@ -126,14 +224,30 @@ impl MonitorHandle {
         id: randr::Crtc,
         crtc: &randr::GetCrtcInfoReply,
         primary: bool,
+        desktop_work_area: Option<&util::AaRect>,
     ) -> Option<Self> {
-        let (name, scale_factor, video_modes) = xconn.get_output_info(resources, crtc)?;
+        let info = xconn.get_output_info(resources, crtc)?;
         let dimensions = (crtc.width as u32, crtc.height as u32);
         let position = (crtc.x as i32, crtc.y as i32);
 
         let rect = util::AaRect::new(position, dimensions);
-
-        Some(MonitorHandle { id, name, scale_factor, position, primary, rect, video_modes })
+        let work_area = desktop_work_area.and_then(|work_area| work_area.intersection(&rect));
+
+        Some(MonitorHandle {
+            id,
+            name: info.name,
+            scale_factor: info.scale_factor,
+            position,
+            primary,
+            rect,
+            video_modes: info.video_modes,
+            size_mm: info.size_mm,
+            work_area,
+            subpixel: info.subpixel,
+            transform: util::monitor_transform(crtc.rotation),
+            make: info.make,
+            model: info.model,
+        })
     }
 
     pub fn dummy() -> Self {
@@ -145,6 +259,12 @@ impl MonitorHandle {
             primary: true,
             rect: util::AaRect::new((0, 0), (1, 1)),
             video_modes: Vec::new(),
+            size_mm: None,
+            work_area: None,
+            subpixel: None,
+            transform: MonitorTransform::Normal,
+            make: None,
+            model: None,
         }
     }
 
@@ -201,6 +321,8 @@ impl XConnection {
         // Do this here so we do all of our requests in one shot.
         let primary = self.xcb_connection().randr_get_output_primary(root.root)?.reply()?.output;
 
+        let work_area = self.desktop_work_area(root.root);
+
         let mut crtc_infos = Vec::with_capacity(crtc_cookies.len());
         for cookie in crtc_cookies {
             let reply = cookie.reply()?;
@@ -216,7 +338,14 @@ impl XConnection {
 
             let is_primary = crtc.outputs[0] == primary;
             has_primary |= is_primary;
-            let monitor = MonitorHandle::new(self, &resources, *crtc_id, crtc, is_primary);
+            let monitor = MonitorHandle::new(
+                self,
+                &resources,
+                *crtc_id,
+                crtc,
+                is_primary,
+                work_area.as_ref(),
+            );
             available_monitors.extend(monitor);
         }
 
@@ -231,6 +360,25 @@ impl XConnection {
         Ok(available_monitors)
     }
 
+    /// The `_NET_WORKAREA` of the current desktop, spanning all of the monitors.
+    fn desktop_work_area(&self, root: xproto::Window) -> Option<util::AaRect> {
+        let atoms = self.atoms();
+        let cardinal = xproto::AtomEnum::CARDINAL.into();
+        let work_areas = self.get_property::<u32>(root, atoms[_NET_WORKAREA], cardinal).ok()?;
+        let desktop = self
+            .get_property::<u32>(root, atoms[_NET_CURRENT_DESKTOP], cardinal)
+            .ok()
+            .and_then(|desktop| desktop.first().copied())
+            .unwrap_or(0) as usize;
+
+        // Each desktop has a `x, y, width, height` geometry.
+        let geometry = work_areas.chunks_exact(4).nth(desktop)?;
+        Some(util::AaRect::new(
+            (geometry[0] as i32, geometry[1] as i32),
+            (geometry[2], geometry[3]),
+        ))
+    }
+
     pub fn available_monitors(&self) -> Result<Vec<MonitorHandle>, X11Error> {
         let mut monitors_lock = self.monitor_handles.lock().unwrap();
         match *monitors_lock {
@@ -265,7 +413,7 @@ impl XConnection {
 
         // Select input data.
         let event_mask =
-            NotifyMask::CRTC_CHANGE | NotifyMask::OUTPUT_PROPERTY | NotifyMask::SCREEN_CHANGE;
+            NotifyMask::CRTC_CHANGE | NotifyMask::OUTPUT_CHANGE | NotifyMask::SCREEN_CHANGE;
         self.xcb_connection().randr_select_input(root, event_mask)?;
 
         Ok(info.first_event)
@@ -313,3 +461,48 @@ impl ScreenResources {
         Self { modes: reply.modes, crtcs: reply.crtcs }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(id: randr::Crtc, position: (i32, i32)) -> MonitorHandle {
+        MonitorHandle { id, position, ..MonitorHandle::dummy() }
+    }
+
+    fn ids(events: &[MonitorEvent]) -> Vec<(&'static str, u64)> {
+        events
+            .iter()
+            .map(|event| match event {
+                MonitorEvent::Added(monitor) => ("added", monitor.native_id()),
+                MonitorEvent::Removed(monitor) => ("removed", monitor.native_id()),
+                MonitorEvent::Changed(monitor) => ("changed", monitor.native_id()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unchanged_monitors_emit_nothing() {
+        let list = [monitor(1, (0, 0)), monitor(2, (1920, 0))];
+        assert!(monitor_events(&list, &list).is_empty());
+    }
+
+    #[test]
+    fn hotplug_emits_added_and_removed() {
+        let prev = [monitor(1, (0, 0)), monitor(2, (1920, 0))];
+        let new = [monitor(1, (0, 0)), monitor(3, (1920, 0))];
+        assert_eq!(ids(&monitor_events(&prev, &new)), [("removed", 2), ("added", 3)]);
+    }
+
+    #[test]
+    fn reconfiguration_emits_changed() {
+        let prev = [monitor(1, (0, 0)), monitor(2, (1920, 0))];
+        let new = [monitor(1, (0, 0)), monitor(2, (2560, 0))];
+        assert_eq!(ids(&monitor_events(&prev, &new)), [("changed", 2)]);
+    }
+
+    #[test]
+    fn reconnecting_the_only_monitor_emits_added() {
+        assert_eq!(ids(&monitor_events(&[], &[monitor(1, (0, 0))])), [("added", 1)]);
+    }
+}
@@ -1,17 +1,50 @@
 use std::collections::hash_map::Entry;
+use std::fmt::Write;
 use std::hash::{Hash, Hasher};
-use std::iter;
 use std::sync::Arc;
+use std::{env, iter};
 
-use winit_core::cursor::{CursorIcon, CustomCursorProvider, CustomCursorSource};
+use winit_common::xcursor::{self, DEFAULT_CURSOR_SIZE};
+use winit_core::cursor::{
+    CursorAnimation, CursorFile, CursorIcon, CursorImage, CustomCursorProvider, CustomCursorSource,
+};
 use winit_core::error::{NotSupportedError, RequestError};
 use x11rb::connection::Connection;
 use x11rb::protocol::render::{self, ConnectionExt as _};
 use x11rb::protocol::xproto;
+use x11rb::resource_manager::Database;
 
 use super::super::ActiveEventLoop;
 use super::*;
 
+/// The theme of the named cursors, overriding the one of the resource database.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CursorTheme {
+    pub name: Option<String>,
+    pub size: Option<u32>,
+}
+
+impl CursorTheme {
+    /// Build the database the cursors are loaded with, taking what isn't set from `database`.
+    fn database(&self, database: &Database) -> Database {
+        let mut data = String::new();
+        let name = self.name.as_deref().or_else(|| database.get_string("Xcursor.theme", ""));
+        // Names spanning several lines would be read as other resources.
+        if let Some(name) = name.filter(|name| !name.contains('\n')) {
+            let _ = writeln!(data, "Xcursor.theme: {name}");
+        }
+        let size = self.size.or_else(|| database.get_value("Xcursor.size", "").ok().flatten());
+        if let Some(size) = size {
+            let _ = writeln!(data, "Xcursor.size: {size}");
+        }
+        if let Some(dpi) = database.get_string("Xft.dpi", "") {
+            let _ = writeln!(data, "Xft.dpi: {dpi}");
+        }
+
+        Database::new_from_data(data.as_bytes())
+    }
+}
+
 impl XConnection {
     pub fn set_cursor_icon(
         &self,
@@ -30,6 +63,35 @@ impl XConnection {
         self.update_cursor(window, cursor)
     }
 
+    /// Change the theme of the named cursors, returning whether it changed.
+    ///
+    /// The cursors loaded from the previous theme are freed, the windows using them keep them
+    /// until their cursor is set again.
+    pub fn set_cursor_theme(&self, theme: CursorTheme) -> bool {
+        let mut current = self.cursor_theme.lock().unwrap_or_else(|e| e.into_inner());
+        if *current == theme {
+            return false;
+        }
+        *current = theme;
+        drop(current);
+
+        let mut cache = self.cursor_cache.lock().unwrap_or_else(|e| e.into_inner());
+        // The hidden cursor doesn't depend on the theme.
+        cache.retain(|icon, cursor| {
+            if icon.is_some() {
+                self.xcb_connection().free_cursor(*cursor).map(|r| r.ignore_error()).ok();
+            }
+            icon.is_none()
+        });
+
+        true
+    }
+
+    /// The theme of the named cursors.
+    pub fn cursor_theme(&self) -> CursorTheme {
+        self.cursor_theme.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
     pub(crate) fn set_custom_cursor(
         &self,
         window: xproto::Window,
@@ -94,6 +156,74 @@ impl XConnection {
         Ok(cursor)
     }
 
+    /// Create a cursor from an RGBA image.
+    fn create_cursor_from_rgba(&self, image: &CursorImage) -> Result<xproto::Cursor, X11Error> {
+        let mut buffer = image.buffer().to_vec();
+        // Reverse RGBA order to BGRA.
+        buffer.chunks_mut(4).for_each(|chunk| {
+            let chunk: &mut [u8; 4] = chunk.try_into().unwrap();
+            chunk[0..3].reverse();
+
+            // Byteswap if we need to.
+            if self.needs_endian_swap() {
+                let value = u32::from_ne_bytes(*chunk).swap_bytes();
+                *chunk = value.to_ne_bytes();
+            }
+        });
+
+        self.create_cursor_from_image(
+            image.width(),
+            image.height(),
+            image.hotspot_x(),
+            image.hotspot_y(),
+            &buffer,
+        )
+    }
+
+    /// Create a cursor from the variant of the file closest to `size`.
+    fn create_cursor_from_file(
+        &self,
+        file: &CursorFile,
+        size: u32,
+    ) -> Result<xproto::Cursor, X11Error> {
+        let variant = file.best_variant(size);
+        if !variant.is_animated() {
+            return self.create_cursor_from_rgba(variant.frames()[0].image());
+        }
+
+        let mut elements = Vec::with_capacity(variant.frames().len());
+        let mut result = Ok(());
+        for frame in variant.frames() {
+            match self.create_cursor_from_rgba(frame.image()) {
+                Ok(cursor) => elements.push(render::Animcursorelt {
+                    cursor,
+                    delay: frame.delay().as_millis().clamp(1, u32::MAX as u128) as u32,
+                }),
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                },
+            }
+        }
+
+        let cursor = result.and_then(|_| self.create_anim_cursor(&elements));
+        // The animated cursor keeps its own reference to the frames.
+        for element in elements {
+            self.xcb_connection().free_cursor(element.cursor).map(|r| r.ignore_error()).ok();
+        }
+        cursor
+    }
+
+    /// Create a cursor cycling through the given cursors.
+    fn create_anim_cursor(
+        &self,
+        elements: &[render::Animcursorelt],
+    ) -> Result<xproto::Cursor, X11Error> {
+        let cursor = self.xcb_connection().generate_id()?;
+        self.xcb_connection().render_create_anim_cursor(cursor, elements)?.check()?;
+        Ok(cursor)
+    }
+
     /// Find the render format that corresponds to ARGB32.
     fn find_argb32_format(&self) -> Result<render::Pictformat, X11Error> {
         macro_rules! direct {
@@ -125,7 +255,22 @@ impl XConnection {
             None => return self.create_empty_cursor(),
         };
 
-        let database = self.database();
+        // The cursor loader of x11rb reads `XCURSOR_THEME` and `XCURSOR_SIZE` first, so the theme
+        // set by the application is resolved here to take precedence over them.
+        let theme = self.cursor_theme();
+        if theme != CursorTheme::default() {
+            let name = theme
+                .name
+                .clone()
+                .or_else(|| env::var("XCURSOR_THEME").ok())
+                .or_else(|| self.database().get_string("Xcursor.theme", "").map(Into::into))
+                .unwrap_or_else(|| String::from("default"));
+            if let Some(file) = xcursor::load_cursor(&name, cursor) {
+                return self.create_cursor_from_file(&file, cursor_size(self));
+            }
+        }
+
+        let database = theme.database(&self.database());
         let handle = x11rb::cursor::Handle::new(
             self.xcb_connection(),
             self.default_screen_index(),
@@ -177,6 +322,7 @@ impl Default for SelectedCursor {
 pub struct CustomCursor {
     xconn: Arc<XConnection>,
     cursor: xproto::Cursor,
+    animated: bool,
 }
 
 impl Hash for CustomCursor {
@@ -197,40 +343,96 @@ impl CustomCursor {
         event_loop: &ActiveEventLoop,
         cursor: CustomCursorSource,
     ) -> Result<CustomCursor, RequestError> {
-        let mut cursor = match cursor {
-            CustomCursorSource::Image(cursor_image) => cursor_image,
-            CustomCursorSource::Animation { .. } | CustomCursorSource::Url { .. } => {
-                return Err(NotSupportedError::new("unsupported cursor kind").into());
+        match cursor {
+            CustomCursorSource::Image(cursor_image) => Self::from_image(event_loop, cursor_image),
+            CustomCursorSource::Animation(animation) => Self::from_animation(event_loop, animation),
+            CustomCursorSource::File(file) => Self::from_file(event_loop, &file),
+            CustomCursorSource::Scaled(images) => {
+                let (image, _) = images.best_image(cursor_scale_factor(&event_loop.xconn));
+                Self::from_image(event_loop, image.clone())
             },
-        };
+            CustomCursorSource::Url { .. } => {
+                Err(NotSupportedError::new("unsupported cursor kind").into())
+            },
+        }
+    }
 
-        // Reverse RGBA order to BGRA.
-        cursor.buffer_mut().chunks_mut(4).for_each(|chunk| {
-            let chunk: &mut [u8; 4] = chunk.try_into().unwrap();
-            chunk[0..3].reverse();
+    fn from_image(
+        event_loop: &ActiveEventLoop,
+        cursor: CursorImage,
+    ) -> Result<CustomCursor, RequestError> {
+        let xconn = &event_loop.xconn;
+        let cursor = xconn.create_cursor_from_rgba(&cursor).map_err(|err| os_error!(err))?;
+        Ok(Self { xconn: xconn.clone(), cursor, animated: false })
+    }
 
-            // Byteswap if we need to.
-            if event_loop.xconn.needs_endian_swap() {
-                let value = u32::from_ne_bytes(*chunk).swap_bytes();
-                *chunk = value.to_ne_bytes();
-            }
-        });
+    /// Create an animated cursor, letting the server cycle through the frames.
+    fn from_animation(
+        event_loop: &ActiveEventLoop,
+        animation: CursorAnimation,
+    ) -> Result<CustomCursor, RequestError> {
+        let (duration, frames) = animation.into_raw();
+
+        // The duration covers the whole animation, while the server wants the delay of each
+        // frame in milliseconds.
+        let delay = (duration.as_millis() / frames.len() as u128).clamp(1, u32::MAX as u128) as u32;
+        let elements = frames
+            .iter()
+            .map(|frame| {
+                frame
+                    .cast_ref::<CustomCursor>()
+                    .map(|frame| render::Animcursorelt { cursor: frame.cursor, delay })
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| NotSupportedError::new("cursor frames from another backend"))?;
+
+        Self::from_elements(event_loop, &elements)
+    }
+
+    /// Create a cursor from the size of the file closest to the cursor size of the system.
+    fn from_file(
+        event_loop: &ActiveEventLoop,
+        file: &CursorFile,
+    ) -> Result<CustomCursor, RequestError> {
+        let xconn = &event_loop.xconn;
+        let size = cursor_size(xconn);
+        let cursor = xconn.create_cursor_from_file(file, size).map_err(|err| os_error!(err))?;
+        let animated = file.best_variant(size).is_animated();
+        Ok(Self { xconn: xconn.clone(), cursor, animated })
+    }
+
+    fn from_elements(
+        event_loop: &ActiveEventLoop,
+        elements: &[render::Animcursorelt],
+    ) -> Result<CustomCursor, RequestError> {
+        // The animated cursor keeps its own reference to the frames, so they can be freed
+        // once it is created.
+        let xconn = &event_loop.xconn;
+        let cursor = xconn.create_anim_cursor(elements).map_err(|err| os_error!(err))?;
+        Ok(Self { xconn: xconn.clone(), cursor, animated: true })
+    }
+}
 
-        let cursor = event_loop
-            .xconn
-            .create_cursor_from_image(
-                cursor.width(),
-                cursor.height(),
-                cursor.hotspot_x(),
-                cursor.hotspot_y(),
-                cursor.buffer(),
-            )
-            .map_err(|err| os_error!(err))?;
+/// The nominal size of cursors in pixels, following the rules of libXcursor.
+fn cursor_size(xconn: &XConnection) -> u32 {
+    if let Some(size) = xconn.cursor_theme().size {
+        return size;
+    }
 
-        Ok(Self { xconn: event_loop.xconn.clone(), cursor })
+    let size = env::var("XCURSOR_SIZE").ok().and_then(|size| size.parse().ok());
+    let size =
+        size.or_else(|| xconn.database().get_value::<u32>("Xcursor.size", "").ok().flatten());
+    match size {
+        Some(size) if size > 0 => size,
+        _ => (DEFAULT_CURSOR_SIZE as f64 * cursor_scale_factor(xconn)).round() as u32,
     }
 }
 
+/// The scale factor of cursors, which are shared by all monitors.
+fn cursor_scale_factor(xconn: &XConnection) -> f64 {
+    xconn.get_xft_dpi().map_or(1., |dpi| dpi / 96.)
+}
+
 impl Drop for CustomCursor {
     fn drop(&mut self) {
         self.xconn.xcb_connection().free_cursor(self.cursor).map(|r| r.ignore_error()).ok();
@@ -239,6 +441,26 @@ impl Drop for CustomCursor {
 
 impl CustomCursorProvider for CustomCursor {
     fn is_animated(&self) -> bool {
-        false
+        self.animated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn themed_database() {
+        let database = Database::new_from_data(b"Xcursor.theme: Adwaita\nXcursor.size: 32\n");
+
+        let theme = CursorTheme { name: Some("breeze".into()), size: None };
+        let themed = theme.database(&database);
+        assert_eq!(themed.get_string("Xcursor.theme", ""), Some("breeze"));
+        assert_eq!(themed.get_value::<u32>("Xcursor.size", ""), Ok(Some(32)));
+
+        let theme = CursorTheme { name: None, size: Some(48) };
+        let themed = theme.database(&database);
+        assert_eq!(themed.get_string("Xcursor.theme", ""), Some("Adwaita"));
+        assert_eq!(themed.get_value::<u32>("Xcursor.size", ""), Ok(Some(48)));
     }
 }
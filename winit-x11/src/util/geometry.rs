@@ -1,5 +1,7 @@
 use std::cmp;
 
+use winit_core::window::{PopupAnchor, PopupConstraintAdjustment};
+
 use super::*;
 
 // Friendly neighborhood axis-aligned rectangle
@@ -33,6 +35,168 @@ impl AaRect {
         );
         x_overlap * y_overlap
     }
+
+    /// The area covered by both rectangles, `None` when they don't overlap.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let x = cmp::max(self.x, other.x);
+        let y = cmp::max(self.y, other.y);
+        let width = cmp::min(self.x + self.width, other.x + other.width) - x;
+        let height = cmp::min(self.y + self.height, other.y + other.height) - y;
+        (width > 0 && height > 0).then_some(AaRect { x, y, width, height })
+    }
+
+    pub fn position(&self) -> (i32, i32) {
+        (self.x as i32, self.y as i32)
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width as u32, self.height as u32)
+    }
+}
+
+/// Where a popup is anchored along one axis, from the start to the end of the axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edge {
+    Start,
+    Center,
+    End,
+}
+
+impl Edge {
+    fn split(anchor: PopupAnchor) -> (Self, Self) {
+        match anchor {
+            PopupAnchor::Center => (Edge::Center, Edge::Center),
+            PopupAnchor::Top => (Edge::Center, Edge::Start),
+            PopupAnchor::Bottom => (Edge::Center, Edge::End),
+            PopupAnchor::Left => (Edge::Start, Edge::Center),
+            PopupAnchor::Right => (Edge::End, Edge::Center),
+            PopupAnchor::TopLeft => (Edge::Start, Edge::Start),
+            PopupAnchor::BottomLeft => (Edge::Start, Edge::End),
+            PopupAnchor::TopRight => (Edge::End, Edge::Start),
+            PopupAnchor::BottomRight => (Edge::End, Edge::End),
+        }
+    }
+
+    fn flip(self) -> Self {
+        match self {
+            Edge::Start => Edge::End,
+            Edge::Center => Edge::Center,
+            Edge::End => Edge::Start,
+        }
+    }
+}
+
+/// A popup placed along one axis, following the rules of `xdg_positioner`.
+struct PopupAxis {
+    /// The start and the length of the anchor rectangle.
+    anchor_rect: (i64, i64),
+    anchor: Edge,
+    gravity: Edge,
+    offset: i64,
+    /// The start and the length of the area to fit the popup in.
+    bounds: (i64, i64),
+    flip: bool,
+    slide: bool,
+    resize: bool,
+}
+
+impl PopupAxis {
+    fn start(&self, anchor: Edge, gravity: Edge, offset: i64, length: i64) -> i64 {
+        let (rect_start, rect_length) = self.anchor_rect;
+        let point = match anchor {
+            Edge::Start => rect_start,
+            Edge::Center => rect_start + rect_length / 2,
+            Edge::End => rect_start + rect_length,
+        };
+
+        // The gravity is the direction the popup extends to from the anchor point.
+        let start = match gravity {
+            Edge::Start => point - length,
+            Edge::Center => point - length / 2,
+            Edge::End => point,
+        };
+
+        start + offset
+    }
+
+    fn fits(&self, start: i64, length: i64) -> bool {
+        let (bounds_start, bounds_length) = self.bounds;
+        start >= bounds_start && start + length <= bounds_start + bounds_length
+    }
+
+    /// The start and the length of the popup.
+    fn place(&self, length: i64) -> (i64, i64) {
+        let start = self.start(self.anchor, self.gravity, self.offset, length);
+        if self.fits(start, length) {
+            return (start, length);
+        }
+
+        if self.flip {
+            let flipped = self.start(self.anchor.flip(), self.gravity.flip(), -self.offset, length);
+            if self.fits(flipped, length) {
+                return (flipped, length);
+            }
+        }
+
+        let (bounds_start, bounds_length) = self.bounds;
+        let bounds_end = bounds_start + bounds_length;
+        let start = if self.slide {
+            // Align with the start of the bounds when the popup is too large for them.
+            cmp::max(cmp::min(start, bounds_end - length), bounds_start)
+        } else {
+            start
+        };
+
+        if self.resize && !self.fits(start, length) {
+            let end = cmp::min(start + length, bounds_end);
+            let start = cmp::max(start, bounds_start);
+            if end > start {
+                return (start, end - start);
+            }
+        }
+
+        (start, length)
+    }
+}
+
+/// Place a popup of the given size next to the anchor rectangle, while fitting it in the bounds
+/// with the constraint adjustment.
+pub fn place_popup(
+    anchor_rect: &AaRect,
+    anchor: PopupAnchor,
+    gravity: PopupAnchor,
+    constraint_adjustment: PopupConstraintAdjustment,
+    (offset_x, offset_y): (i32, i32),
+    (width, height): (u32, u32),
+    bounds: &AaRect,
+) -> AaRect {
+    let (anchor_x, anchor_y) = Edge::split(anchor);
+    let (gravity_x, gravity_y) = Edge::split(gravity);
+
+    let (x, width) = PopupAxis {
+        anchor_rect: (anchor_rect.x, anchor_rect.width),
+        anchor: anchor_x,
+        gravity: gravity_x,
+        offset: offset_x as i64,
+        bounds: (bounds.x, bounds.width),
+        flip: constraint_adjustment.contains(PopupConstraintAdjustment::FLIP_X),
+        slide: constraint_adjustment.contains(PopupConstraintAdjustment::SLIDE_X),
+        resize: constraint_adjustment.contains(PopupConstraintAdjustment::RESIZE_X),
+    }
+    .place(width as i64);
+    let (y, height) = PopupAxis {
+        anchor_rect: (anchor_rect.y, anchor_rect.height),
+        anchor: anchor_y,
+        gravity: gravity_y,
+        offset: offset_y as i64,
+        bounds: (bounds.y, bounds.height),
+        flip: constraint_adjustment.contains(PopupConstraintAdjustment::FLIP_Y),
+        slide: constraint_adjustment.contains(PopupConstraintAdjustment::SLIDE_Y),
+        resize: constraint_adjustment.contains(PopupConstraintAdjustment::RESIZE_Y),
+    }
+    .place(height as i64);
+
+    AaRect { x, y, width, height }
 }
 
 #[derive(Debug, Clone)]
@@ -281,3 +445,73 @@ impl XConnection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: AaRect = AaRect { x: 0, y: 0, width: 1000, height: 800 };
+
+    fn place(
+        anchor_rect: ((i32, i32), (u32, u32)),
+        constraint_adjustment: PopupConstraintAdjustment,
+        size: (u32, u32),
+    ) -> AaRect {
+        place_popup(
+            &AaRect::new(anchor_rect.0, anchor_rect.1),
+            PopupAnchor::BottomLeft,
+            PopupAnchor::BottomRight,
+            constraint_adjustment,
+            (0, 0),
+            size,
+            &SCREEN,
+        )
+    }
+
+    #[test]
+    fn popup_below_anchor_rect() {
+        let rect = place(((100, 50), (80, 20)), PopupConstraintAdjustment::all(), (200, 300));
+        assert_eq!(rect, AaRect::new((100, 70), (200, 300)));
+    }
+
+    #[test]
+    fn popup_anchor_and_gravity() {
+        let rect = place_popup(
+            &AaRect::new((100, 100), (100, 100)),
+            PopupAnchor::Center,
+            PopupAnchor::TopLeft,
+            PopupConstraintAdjustment::empty(),
+            (-5, 10),
+            (20, 30),
+            &SCREEN,
+        );
+        assert_eq!(rect, AaRect::new((125, 130), (20, 30)));
+    }
+
+    #[test]
+    fn popup_flip() {
+        // Not enough room below, so the popup goes above the anchor rectangle.
+        let rect = place(((100, 700), (80, 20)), PopupConstraintAdjustment::FLIP_Y, (200, 300));
+        assert_eq!(rect, AaRect::new((100, 400), (200, 300)));
+
+        // Without room above either, the popup isn't flipped.
+        let rect = place(((100, 200), (80, 20)), PopupConstraintAdjustment::FLIP_Y, (200, 700));
+        assert_eq!(rect, AaRect::new((100, 220), (200, 700)));
+    }
+
+    #[test]
+    fn popup_slide() {
+        let rect = place(((900, 50), (80, 20)), PopupConstraintAdjustment::SLIDE_X, (200, 300));
+        assert_eq!(rect, AaRect::new((800, 70), (200, 300)));
+
+        // Too large for the bounds, so aligned with their start.
+        let rect = place(((900, 50), (80, 20)), PopupConstraintAdjustment::SLIDE_X, (1200, 300));
+        assert_eq!(rect, AaRect::new((0, 70), (1200, 300)));
+    }
+
+    #[test]
+    fn popup_resize() {
+        let rect = place(((100, 700), (80, 20)), PopupConstraintAdjustment::RESIZE_Y, (200, 300));
+        assert_eq!(rect, AaRect::new((100, 720), (200, 80)));
+    }
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use super::*;
-use crate::WindowType;
+use crate::{Strut, WindowType};
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -42,6 +42,26 @@ impl WindowType {
     }
 }
 
+impl Strut {
+    /// The `_NET_WM_STRUT_PARTIAL` data, the first four of which are the `_NET_WM_STRUT` data.
+    pub(crate) fn as_cardinals(&self) -> [Cardinal; 12] {
+        [
+            self.left,
+            self.right,
+            self.top,
+            self.bottom,
+            self.left_start_y,
+            self.left_end_y,
+            self.right_start_y,
+            self.right_end_y,
+            self.top_start_x,
+            self.top_end_x,
+            self.bottom_start_x,
+            self.bottom_end_x,
+        ]
+    }
+}
+
 pub struct MotifHints {
     hints: MwmHints,
 }
@@ -162,4 +182,29 @@ impl XConnection {
             &hints_data,
         )
     }
+
+    /// Set both `_NET_WM_STRUT_PARTIAL` and the older `_NET_WM_STRUT`, or remove them.
+    pub fn set_strut(
+        &self,
+        window: xproto::Window,
+        strut: Option<&Strut>,
+    ) -> Result<VoidCookie<'_>, X11Error> {
+        let atoms = self.atoms();
+        let strut_atom = atoms[_NET_WM_STRUT];
+        let strut_partial_atom = atoms[_NET_WM_STRUT_PARTIAL];
+
+        let Some(strut) = strut else {
+            self.xcb_connection().delete_property(window, strut_atom)?.ignore_error();
+            return self
+                .xcb_connection()
+                .delete_property(window, strut_partial_atom)
+                .map_err(Into::into);
+        };
+
+        let cardinal = xproto::Atom::from(xproto::AtomEnum::CARDINAL);
+        let data = strut.as_cardinals();
+        self.change_property(window, strut_atom, cardinal, xproto::PropMode::REPLACE, &data[..4])?
+            .ignore_error();
+        self.change_property(window, strut_partial_atom, cardinal, xproto::PropMode::REPLACE, &data)
+    }
 }
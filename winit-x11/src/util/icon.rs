@@ -1,6 +1,6 @@
 #![allow(clippy::assertions_on_constants)]
 
-use winit_core::icon::RgbaIcon;
+use winit_core::icon::{Icon, RgbaIcon, RgbaIconSet};
 
 use super::*;
 
@@ -44,3 +44,13 @@ pub(crate) fn rgba_to_cardinals(icon: &RgbaIcon) -> Vec<Cardinal> {
     }
     data
 }
+
+/// The `_NET_WM_ICON` data of the icon, holding every bitmap of icon sets.
+pub(crate) fn icon_to_cardinals(icon: &Icon) -> Option<Vec<Cardinal>> {
+    if let Some(icon) = icon.cast_ref::<RgbaIcon>() {
+        return Some(rgba_to_cardinals(icon));
+    }
+
+    let icons = icon.cast_ref::<RgbaIconSet>()?;
+    Some(icons.icons().iter().flat_map(|(icon, _)| rgba_to_cardinals(icon)).collect())
+}
@@ -13,16 +13,17 @@ pub const VIRTUAL_CORE_POINTER: u16 = 2;
 const TEXT_BUFFER_SIZE: usize = 1024;
 
 impl XConnection {
+    /// Select the events of the mask, made of one word for every 32 events.
     pub fn select_xinput_events(
         &self,
         window: xproto::Window,
         device_id: u16,
-        mask: xinput::XIEventMask,
+        mask: &[xinput::XIEventMask],
     ) -> Result<VoidCookie<'_>, X11Error> {
         self.xcb_connection()
             .xinput_xi_select_events(window, &[xinput::EventMask {
                 deviceid: device_id,
-                mask: vec![mask],
+                mask: mask.to_vec(),
             }])
             .map_err(Into::into)
     }
@@ -56,6 +57,32 @@ impl XConnection {
             .map_err(Into::into)
     }
 
+    /// The vendor and product IDs of the device, from the property set by the libinput and evdev
+    /// drivers.
+    pub fn device_product_id(&self, device_id: u16) -> Result<Option<(u16, u16)>, X11Error> {
+        let reply = self
+            .xcb_connection()
+            .xinput_xi_get_property(
+                device_id,
+                false,
+                self.atoms()[DEVICE_PRODUCT_ID],
+                xproto::AtomEnum::ANY.into(),
+                0,
+                2,
+            )?
+            .reply()?;
+
+        let ids = match reply.items {
+            xinput::XIGetPropertyItems::Data32(ids) => ids,
+            _ => return Ok(None),
+        };
+
+        Ok(match ids[..] {
+            [vendor, product] => u16::try_from(vendor).ok().zip(u16::try_from(product).ok()),
+            _ => None,
+        })
+    }
+
     fn lookup_utf8_inner(
         &self,
         ic: ffi::XIC,
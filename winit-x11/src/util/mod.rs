@@ -16,6 +16,7 @@ pub mod keys;
 pub(crate) mod memory;
 mod mouse;
 mod randr;
+mod selection;
 mod window_property;
 mod wm;
 mod xmodmap;
@@ -25,9 +26,11 @@ use x11rb::protocol::xproto::{self, ConnectionExt as _};
 pub use self::cursor::*;
 pub use self::geometry::*;
 pub use self::hint::*;
-pub(crate) use self::icon::rgba_to_cardinals;
+pub(crate) use self::icon::icon_to_cardinals;
 pub use self::input::*;
 pub use self::mouse::*;
+pub use self::randr::monitor_transform;
+pub use self::selection::*;
 pub use self::window_property::*;
 pub use self::wm::*;
 pub use self::xmodmap::ModifierKeymap;
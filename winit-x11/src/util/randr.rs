@@ -4,8 +4,9 @@ use std::{env, str};
 
 use dpi::validate_scale_factor;
 use tracing::warn;
-use winit_core::monitor::VideoMode;
+use winit_core::monitor::{MonitorTransform, SubpixelLayout, VideoMode};
 use x11rb::protocol::randr::{self, ConnectionExt as _};
+use x11rb::protocol::render;
 
 use super::*;
 use crate::monitor::{self, VideoModeHandle};
@@ -17,6 +18,18 @@ pub enum EnvVarDPI {
     NotSet,
 }
 
+/// The properties of the output driven by a CRTC.
+#[derive(Debug)]
+pub struct OutputInfo {
+    pub name: String,
+    pub scale_factor: f64,
+    pub video_modes: Vec<VideoModeHandle>,
+    pub size_mm: Option<(u32, u32)>,
+    pub subpixel: Option<SubpixelLayout>,
+    pub make: Option<String>,
+    pub model: Option<String>,
+}
+
 pub fn calc_dpi_factor(
     (width_px, height_px): (u32, u32),
     (width_mm, height_mm): (u64, u64),
@@ -55,7 +68,7 @@ impl XConnection {
         &self,
         resources: &monitor::ScreenResources,
         crtc: &randr::GetCrtcInfoReply,
-    ) -> Option<(String, f64, Vec<VideoModeHandle>)> {
+    ) -> Option<OutputInfo> {
         let output_info = match self
             .xcb_connection()
             .randr_get_output_info(crtc.outputs[0], x11rb::CURRENT_TIME)
@@ -149,7 +162,43 @@ impl XConnection {
             },
         };
 
-        Some((name, scale_factor, modes))
+        let size_mm = (output_info.mm_width != 0 && output_info.mm_height != 0)
+            .then_some((output_info.mm_width, output_info.mm_height));
+        let (make, model) = match self.get_output_edid(crtc.outputs[0]) {
+            Ok(edid) => parse_edid(&edid),
+            Err(err) => {
+                warn!("Failed to get output EDID: {:?}", err);
+                (None, None)
+            },
+        };
+
+        Some(OutputInfo {
+            name,
+            scale_factor,
+            video_modes: modes,
+            size_mm,
+            subpixel: subpixel_layout(output_info.subpixel_order),
+            make,
+            model,
+        })
+    }
+
+    /// The EDID of the monitor plugged to the output, empty when the driver doesn't expose it.
+    fn get_output_edid(&self, output: randr::Output) -> Result<Vec<u8>, X11Error> {
+        // Only the base block holds the make and model, which is 128 bytes long.
+        let reply = self
+            .xcb_connection()
+            .randr_get_output_property(
+                output,
+                self.atoms()[Edid],
+                xproto::AtomEnum::ANY,
+                0,
+                32,
+                false,
+                false,
+            )?
+            .reply()?;
+        Ok(reply.data)
     }
 
     pub fn set_crtc_config(
@@ -180,3 +229,133 @@ impl XConnection {
         Ok(self.xcb_connection().randr_get_crtc_info(crtc_id, x11rb::CURRENT_TIME)?.reply()?.mode)
     }
 }
+
+fn subpixel_layout(subpixel: render::SubPixel) -> Option<SubpixelLayout> {
+    match subpixel {
+        render::SubPixel::NONE => Some(SubpixelLayout::None),
+        render::SubPixel::HORIZONTAL_RGB => Some(SubpixelLayout::HorizontalRgb),
+        render::SubPixel::HORIZONTAL_BGR => Some(SubpixelLayout::HorizontalBgr),
+        render::SubPixel::VERTICAL_RGB => Some(SubpixelLayout::VerticalRgb),
+        render::SubPixel::VERTICAL_BGR => Some(SubpixelLayout::VerticalBgr),
+        _ => None,
+    }
+}
+
+/// Convert the rotation and reflection of a CRTC.
+///
+/// Reflecting along the Y axis is the same as reflecting along the X axis and rotating by 180
+/// degrees.
+pub fn monitor_transform(rotation: randr::Rotation) -> MonitorTransform {
+    let mut quarter_turns = if rotation.contains(randr::Rotation::ROTATE90) {
+        1
+    } else if rotation.contains(randr::Rotation::ROTATE180) {
+        2
+    } else if rotation.contains(randr::Rotation::ROTATE270) {
+        3
+    } else {
+        0
+    };
+
+    let reflect_x = rotation.contains(randr::Rotation::REFLECT_X);
+    let reflect_y = rotation.contains(randr::Rotation::REFLECT_Y);
+    if reflect_y {
+        quarter_turns += 2;
+    }
+
+    match (reflect_x != reflect_y, quarter_turns % 4) {
+        (false, 0) => MonitorTransform::Normal,
+        (false, 1) => MonitorTransform::Rotate90,
+        (false, 2) => MonitorTransform::Rotate180,
+        (false, _) => MonitorTransform::Rotate270,
+        (true, 0) => MonitorTransform::Flipped,
+        (true, 1) => MonitorTransform::Flipped90,
+        (true, 2) => MonitorTransform::Flipped180,
+        (true, _) => MonitorTransform::Flipped270,
+    }
+}
+
+/// Parse the make and model out of the base block of an EDID.
+///
+/// The make is the three letter PNP ID of the manufacturer, the model is the monitor name
+/// descriptor when there's one.
+fn parse_edid(edid: &[u8]) -> (Option<String>, Option<String>) {
+    const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+    if edid.len() < 128 || edid[..8] != HEADER {
+        return (None, None);
+    }
+
+    // Three letters of five bits each, where `1` is `A`.
+    let id = u16::from_be_bytes([edid[8], edid[9]]);
+    let make: Option<String> = [10, 5, 0]
+        .into_iter()
+        .map(|shift| match (id >> shift) & 0x1f {
+            letter @ 1..=26 => Some(char::from(b'@' + letter as u8)),
+            _ => None,
+        })
+        .collect();
+
+    // The four display descriptors, the monitor name has the `0xfc` tag.
+    let model = edid[54..126]
+        .chunks_exact(18)
+        .find(|descriptor| descriptor[..3] == [0, 0, 0] && descriptor[3] == 0xfc)
+        .and_then(|descriptor| {
+            let name = &descriptor[5..];
+            let end = name.iter().position(|&byte| byte == b'\n').unwrap_or(name.len());
+            let name =
+                String::from_utf8_lossy(&name[..end]).trim_end_matches([' ', '\0']).to_owned();
+            (!name.is_empty()).then_some(name)
+        });
+
+    (make, model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edid(name: &[u8]) -> Vec<u8> {
+        let mut edid = vec![0; 128];
+        edid[..8].copy_from_slice(&[0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00]);
+        // "DEL"
+        edid[8..10].copy_from_slice(&[0x10, 0xac]);
+        // A range limits descriptor followed by the monitor name.
+        edid[72..76].copy_from_slice(&[0, 0, 0, 0xfd]);
+        edid[90..94].copy_from_slice(&[0, 0, 0, 0xfc]);
+        edid[95..95 + name.len()].copy_from_slice(name);
+        edid
+    }
+
+    #[test]
+    fn edid_make_model() {
+        let (make, model) = parse_edid(&edid(b"DELL U2720Q\n "));
+        assert_eq!(make.as_deref(), Some("DEL"));
+        assert_eq!(model.as_deref(), Some("DELL U2720Q"));
+
+        let (make, model) = parse_edid(&edid(b""));
+        assert_eq!(make.as_deref(), Some("DEL"));
+        assert_eq!(model, None);
+
+        assert_eq!(parse_edid(&[0; 128]), (None, None));
+        assert_eq!(parse_edid(&[]), (None, None));
+    }
+
+    #[test]
+    fn transform() {
+        use randr::Rotation;
+
+        assert_eq!(monitor_transform(Rotation::ROTATE0), MonitorTransform::Normal);
+        assert_eq!(monitor_transform(Rotation::ROTATE270), MonitorTransform::Rotate270);
+        assert_eq!(
+            monitor_transform(Rotation::ROTATE90 | Rotation::REFLECT_X),
+            MonitorTransform::Flipped90
+        );
+        assert_eq!(
+            monitor_transform(Rotation::ROTATE0 | Rotation::REFLECT_Y),
+            MonitorTransform::Flipped180
+        );
+        assert_eq!(
+            monitor_transform(Rotation::ROTATE0 | Rotation::REFLECT_X | Rotation::REFLECT_Y),
+            MonitorTransform::Rotate180
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use std::time::{Duration, Instant};
+
+use winit_core::event::MimeData;
+use x11rb::connection::RequestConnection;
+
+use super::*;
+
+/// How long the other client has to answer during a selection transfer.
+pub const SELECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The data of a selection we own, in each of the offered targets.
+pub type SelectionTargets = Vec<(xproto::Atom, Vec<u8>)>;
+
+/// The data of a selection sent in chunks with an `INCR` transfer, as it's too large for a single
+/// request.
+#[derive(Debug)]
+pub struct IncrTransfer {
+    requestor: xproto::Window,
+    property: xproto::Atom,
+    target: xproto::Atom,
+    data: Vec<u8>,
+    /// The length of the data sent so far.
+    sent: usize,
+    /// When the transfer is abandoned if the requestor doesn't read the last chunk.
+    deadline: Instant,
+}
+
+impl IncrTransfer {
+    fn new(
+        requestor: xproto::Window,
+        property: xproto::Atom,
+        target: xproto::Atom,
+        data: Vec<u8>,
+    ) -> Self {
+        let deadline = Instant::now() + SELECTION_TIMEOUT;
+        Self { requestor, property, target, data, sent: 0, deadline }
+    }
+
+    /// The next chunk of at most `max_len` bytes, empty once all the data was sent.
+    fn next_chunk(&mut self, max_len: usize) -> &[u8] {
+        let start = self.sent;
+        self.sent = (start + max_len).min(self.data.len());
+        self.deadline = Instant::now() + SELECTION_TIMEOUT;
+        &self.data[start..self.sent]
+    }
+}
+
+impl XConnection {
+    /// Intern the MIME types of the data offered by a selection we own.
+    pub fn selection_targets(&self, data: &MimeData) -> Result<SelectionTargets, X11Error> {
+        let atoms = self.atoms();
+        let mut targets = Vec::new();
+        for mime_type in data.mime_types() {
+            let data = data.data(mime_type).unwrap_or_default();
+            let atom =
+                self.xcb_connection().intern_atom(false, mime_type.as_bytes())?.reply()?.atom;
+            // Older clients only know about the X11 name of UTF-8 text.
+            if mime_type == "text/plain;charset=utf-8" {
+                targets.push((atoms[UTF8_STRING], data.to_vec()));
+            }
+            targets.push((atom, data.to_vec()));
+        }
+
+        Ok(targets)
+    }
+
+    /// Get the names of the given atoms, skipping the ones without one.
+    ///
+    /// All the requests are sent before waiting for the replies, to only make a single round trip.
+    pub fn atom_names(&self, atoms: &[xproto::Atom]) -> Vec<String> {
+        let cookies: Vec<_> = atoms
+            .iter()
+            .filter(|&&atom| atom != x11rb::NONE)
+            .filter_map(|&atom| self.xcb_connection().get_atom_name(atom).ok())
+            .collect();
+        cookies
+            .into_iter()
+            .filter_map(|cookie| String::from_utf8(cookie.reply().ok()?.name).ok())
+            .collect()
+    }
+
+    /// The largest data written in a single request, larger data is sent with `INCR`.
+    fn max_selection_chunk(&self) -> usize {
+        self.xcb_connection().maximum_request_bytes() / 4
+    }
+
+    /// Answer a `SelectionRequest` for a selection we own with the data in the requested target.
+    ///
+    /// Data too large for a single request is sent in chunks, each time the requestor deletes the
+    /// property, see [`XConnection::continue_incr_transfer`].
+    pub fn send_selection(
+        &self,
+        targets: Option<&[(xproto::Atom, Vec<u8>)]>,
+        requestor: xproto::Window,
+        selection: xproto::Atom,
+        target: xproto::Atom,
+        property: xproto::Atom,
+        time: xproto::Timestamp,
+    ) -> Result<(), X11Error> {
+        let atoms = self.atoms();
+        // Obsolete clients don't specify the property.
+        let property = if property == x11rb::NONE { target } else { property };
+
+        let written = match targets {
+            Some(targets) if target == atoms[Targets] => {
+                let mut supported: Vec<xproto::Atom> =
+                    targets.iter().map(|&(atom, _)| atom).collect();
+                supported.push(atoms[Targets]);
+                self.change_property(
+                    requestor,
+                    property,
+                    xproto::AtomEnum::ATOM.into(),
+                    xproto::PropMode::REPLACE,
+                    &supported,
+                )?
+                .ignore_error();
+                true
+            },
+            Some(targets) => match targets.iter().find(|&&(atom, _)| atom == target) {
+                Some((_, data)) if data.len() > self.max_selection_chunk() => {
+                    self.start_incr_transfer(requestor, property, target, data)?;
+                    true
+                },
+                Some((_, data)) => {
+                    self.change_property(
+                        requestor,
+                        property,
+                        target,
+                        xproto::PropMode::REPLACE,
+                        data,
+                    )?
+                    .ignore_error();
+                    true
+                },
+                None => false,
+            },
+            None => false,
+        };
+
+        let event = xproto::SelectionNotifyEvent {
+            response_type: xproto::SELECTION_NOTIFY_EVENT,
+            sequence: 0,
+            time,
+            requestor,
+            selection,
+            target,
+            property: if written { property } else { x11rb::NONE },
+        };
+        self.xcb_connection()
+            .send_event(false, requestor, xproto::EventMask::NO_EVENT, event)?
+            .ignore_error();
+
+        Ok(())
+    }
+
+    /// Announce an `INCR` transfer of `data`, its chunks are sent once the property is deleted.
+    fn start_incr_transfer(
+        &self,
+        requestor: xproto::Window,
+        property: xproto::Atom,
+        target: xproto::Atom,
+        data: &[u8],
+    ) -> Result<(), X11Error> {
+        // Keep the events selected on our own windows.
+        let conn = self.xcb_connection();
+        let event_mask = conn.get_window_attributes(requestor)?.reply()?.your_event_mask;
+        conn.change_window_attributes(
+            requestor,
+            &xproto::ChangeWindowAttributesAux::new()
+                .event_mask(event_mask | xproto::EventMask::PROPERTY_CHANGE),
+        )?
+        .ignore_error();
+
+        let len = u32::try_from(data.len()).unwrap_or(u32::MAX);
+        self.change_property(
+            requestor,
+            property,
+            self.atoms()[Incr],
+            xproto::PropMode::REPLACE,
+            &[len],
+        )?
+        .ignore_error();
+
+        let mut transfers = self.incr_transfers.lock().unwrap();
+        transfers
+            .retain(|transfer| (transfer.requestor, transfer.property) != (requestor, property));
+        transfers.push(IncrTransfer::new(requestor, property, target, data.to_vec()));
+        Ok(())
+    }
+
+    /// Send the next chunk of the `INCR` transfer to `requestor`, once it deleted the previous one
+    /// from `property`.
+    pub fn continue_incr_transfer(
+        &self,
+        requestor: xproto::Window,
+        property: xproto::Atom,
+    ) -> Result<(), X11Error> {
+        let mut transfers = self.incr_transfers.lock().unwrap();
+        let index = match transfers
+            .iter()
+            .position(|transfer| (transfer.requestor, transfer.property) == (requestor, property))
+        {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+
+        let transfer = &mut transfers[index];
+        let target = transfer.target;
+        let chunk = transfer.next_chunk(self.max_selection_chunk());
+        let done = chunk.is_empty();
+        self.change_property(requestor, property, target, xproto::PropMode::REPLACE, chunk)?
+            .ignore_error();
+
+        // The empty chunk ends the transfer.
+        if done {
+            transfers.remove(index);
+        }
+        self.flush_requests()?;
+        Ok(())
+    }
+
+    /// Abandon the `INCR` transfers whose requestor stopped reading the chunks.
+    pub fn expire_incr_transfers(&self) {
+        let now = Instant::now();
+        self.incr_transfers.lock().unwrap().retain(|transfer| {
+            let expired = transfer.deadline <= now;
+            if expired {
+                tracing::warn!("The requestor of the selection didn't read it in time");
+            }
+            !expired
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incr_transfer_chunks() {
+        let mut transfer = IncrTransfer::new(1, 2, 3, (0..10).collect());
+        assert_eq!(transfer.next_chunk(4), [0, 1, 2, 3]);
+        assert_eq!(transfer.next_chunk(4), [4, 5, 6, 7]);
+        assert_eq!(transfer.next_chunk(4), [8, 9]);
+        // The transfer ends with an empty chunk.
+        assert!(transfer.next_chunk(4).is_empty());
+        assert!(transfer.next_chunk(4).is_empty());
+    }
+}
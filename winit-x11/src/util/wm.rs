@@ -1,6 +1,7 @@
 use std::sync::Mutex;
 
 use super::*;
+use crate::Desktop;
 
 // https://specifications.freedesktop.org/wm-spec/latest/ar01s04.html#idm46075117309248
 pub const MOVERESIZE_TOPLEFT: isize = 0;
@@ -13,6 +14,18 @@ pub const MOVERESIZE_BOTTOMLEFT: isize = 6;
 pub const MOVERESIZE_LEFT: isize = 7;
 pub const MOVERESIZE_MOVE: isize = 8;
 
+// https://specifications.freedesktop.org/wm-spec/latest/ar01s05.html#idm46075117189104
+const ALL_DESKTOPS: Cardinal = 0xffffffff;
+
+impl Desktop {
+    pub(crate) fn as_cardinal(self) -> Cardinal {
+        match self {
+            Desktop::Index(index) => index,
+            Desktop::All => ALL_DESKTOPS,
+        }
+    }
+}
+
 // This info is global to the window manager.
 static SUPPORTED_HINTS: Mutex<Vec<xproto::Atom>> = Mutex::new(Vec::new());
 static WM_NAME: Mutex<Option<String>> = Mutex::new(None);
@@ -5,7 +5,7 @@ use std::num::NonZeroU32;
 use std::ops::Deref;
 use std::os::raw::*;
 use std::path::Path;
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
 use std::{cmp, env};
 
 use dpi::{PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
@@ -13,34 +13,35 @@ use tracing::{debug, info, warn};
 use winit_core::application::ApplicationHandler;
 use winit_core::cursor::Cursor;
 use winit_core::error::{NotSupportedError, RequestError};
-use winit_core::event::{SurfaceSizeWriter, WindowEvent};
+use winit_core::event::{DragAction, DragSource, SurfaceSizeWriter, WindowEvent};
 use winit_core::event_loop::AsyncRequestSerial;
-use winit_core::icon::RgbaIcon;
+use winit_core::icon::Icon;
 use winit_core::monitor::{
     Fullscreen, MonitorHandle as CoreMonitorHandle, MonitorHandleProvider, VideoMode,
 };
 use winit_core::window::{
     CursorGrabMode, ImeCapabilities, ImeRequest as CoreImeRequest, ImeRequestError,
-    ResizeDirection, Theme, UserAttentionType, Window as CoreWindow, WindowAttributes,
-    WindowButtons, WindowId, WindowLevel,
+    PopupAttributes, PopupConstraintAdjustment, ResizeDirection, Theme, UserAttentionType,
+    Window as CoreWindow, WindowAttributes, WindowButtons, WindowId, WindowLevel,
 };
 use x11rb::connection::{Connection, RequestConnection};
 use x11rb::properties::{WmHints, WmSizeHints, WmSizeHintsSpecification};
+use x11rb::protocol::randr;
 use x11rb::protocol::shape::{ConnectionExt as ShapeExt, SK, SO};
 use x11rb::protocol::sync::{ConnectionExt as _, Int64};
+use x11rb::protocol::xinput::{self, ConnectionExt as _};
 use x11rb::protocol::xproto::{self, ClipOrdering, ConnectionExt as _, Rectangle};
-use x11rb::protocol::{randr, xinput};
 
 use crate::atoms::*;
 use crate::event_loop::{
-    ALL_MASTER_DEVICES, ActivationItem, ActiveEventLoop, CookieResultExt, ICONIC_STATE, VoidCookie,
-    WakeSender, X11Error, xinput_fp1616_to_float,
+    ALL_MASTER_DEVICES, ActivationItem, ActiveEventLoop, CookieResultExt, DragDataItem,
+    ICONIC_STATE, StartDragItem, VoidCookie, WakeSender, X11Error, xinput_fp1616_to_float,
 };
 use crate::ime::{ImeRequest, ImeSender};
 use crate::monitor::MonitorHandle as X11MonitorHandle;
-use crate::util::{self, CustomCursor, SelectedCursor, rgba_to_cardinals};
+use crate::util::{self, CustomCursor, SelectedCursor, icon_to_cardinals};
 use crate::xdisplay::XConnection;
-use crate::{WindowAttributesX11, WindowType, ffi};
+use crate::{Desktop, Strut, WindowAttributesX11, WindowType, ffi};
 
 #[derive(Debug)]
 pub struct Window(Arc<UnownedWindow>);
@@ -203,11 +204,7 @@ impl CoreWindow for Window {
     }
 
     fn set_window_icon(&self, window_icon: Option<winit_core::icon::Icon>) {
-        let icon = match window_icon.as_ref() {
-            Some(icon) => icon.cast_ref::<RgbaIcon>(),
-            None => None,
-        };
-        self.0.set_window_icon(icon)
+        self.0.set_window_icon(window_icon.as_ref())
     }
 
     fn request_ime_update(&self, action: CoreImeRequest) -> Result<(), ImeRequestError> {
@@ -270,6 +267,18 @@ impl CoreWindow for Window {
         self.0.drag_resize_window(direction)
     }
 
+    fn request_drag_data(&self, mime_type: &str) -> Result<AsyncRequestSerial, RequestError> {
+        self.0.request_drag_data(mime_type)
+    }
+
+    fn set_drag_action(&self, action: Option<DragAction>) -> Result<(), RequestError> {
+        self.0.set_drag_action(action)
+    }
+
+    fn start_drag(&self, source: DragSource) -> Result<AsyncRequestSerial, RequestError> {
+        self.0.start_drag(source)
+    }
+
     fn show_window_menu(&self, position: Position) {
         self.0.show_window_menu(position);
     }
@@ -362,8 +371,13 @@ pub struct SharedState {
     pub base_size: Option<Size>,
     pub visibility: Visibility,
     pub has_focus: bool,
+    // The theme set by the user, `None` to follow the system theme.
+    pub theme: Option<Theme>,
     // Use `Option` to not apply hittest logic when it was never requested.
     pub cursor_hittest: Option<bool>,
+    // The action set by the user for the drag over the window, `None` to perform the one
+    // requested by the source.
+    pub drag_action: Option<Option<DragAction>>,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -402,7 +416,9 @@ impl SharedState {
             surface_resize_increments: None,
             base_size: None,
             has_focus: false,
+            theme: window_attributes.preferred_theme,
             cursor_hittest: None,
+            drag_action: None,
         })
     }
 }
@@ -428,6 +444,8 @@ pub struct UnownedWindow {
     pub shared_state: Mutex<SharedState>,
     redraw_sender: WakeSender<WindowId>,
     activation_sender: WakeSender<ActivationItem>,
+    drag_data_sender: WakeSender<DragDataItem>,
+    start_drag_sender: WakeSender<StartDragItem>,
 }
 macro_rules! leap {
     ($e:expr) => {
@@ -450,6 +468,19 @@ impl UnownedWindow {
             .and_then(|attrs| attrs.cast::<WindowAttributesX11>().ok())
             .unwrap_or_default();
 
+        // The parent of a popup, which it's placed relative to.
+        let popup_parent = match window_attrs.popup.as_ref() {
+            Some(popup) => Some(
+                event_loop
+                    .windows
+                    .borrow()
+                    .get(&popup.parent)
+                    .and_then(Weak::upgrade)
+                    .ok_or_else(|| os_error!("the parent of the popup doesn't exist"))?,
+            ),
+            None => None,
+        };
+
         let screen_id = match x11_attributes.screen_id {
             Some(id) => id,
             None => xconn.default_screen_index() as c_int,
@@ -523,6 +554,14 @@ impl UnownedWindow {
             dimensions
         };
 
+        let (position, dimensions) = match (window_attrs.popup.as_ref(), popup_parent.as_ref()) {
+            (Some(popup), Some(parent)) => {
+                let rect = parent.popup_rect(popup, dimensions);
+                (Some(rect.position().into()), rect.size())
+            },
+            _ => (position, dimensions),
+        };
+
         // An iterator over the visuals matching screen id combined with their depths.
         let mut all_visuals = screen
             .allowed_depths
@@ -575,7 +614,8 @@ impl UnownedWindow {
 
             aux = aux.event_mask(event_mask).border_pixel(0);
 
-            if x11_attributes.override_redirect {
+            // Popups are placed by winit instead of the window manager.
+            if x11_attributes.override_redirect || window_attrs.popup.is_some() {
                 aux = aux.override_redirect(true as u32);
             }
 
@@ -653,6 +693,8 @@ impl UnownedWindow {
             shared_state: SharedState::new(guessed_monitor, &window_attrs),
             redraw_sender: event_loop.redraw_sender.clone(),
             activation_sender: event_loop.activation_sender.clone(),
+            drag_data_sender: event_loop.drag_data_sender.clone(),
+            start_drag_sender: event_loop.start_drag_sender.clone(),
         };
 
         // Title must be set before mapping. Some tiling window managers (i.e. i3) use the window
@@ -718,7 +760,61 @@ impl UnownedWindow {
                 flusher.ignore_error()
             }
 
-            leap!(window.set_window_types(x11_attributes.x11_window_types)).ignore_error();
+            let window_types = match window_attrs.popup {
+                Some(_) if x11_attributes.x11_window_types == [WindowType::Normal] => {
+                    vec![WindowType::PopupMenu]
+                },
+                _ => x11_attributes.x11_window_types,
+            };
+            leap!(window.set_window_types(window_types)).ignore_error();
+
+            if let Some(parent) = popup_parent.as_ref() {
+                leap!(xconn.change_property(
+                    window.xwindow,
+                    xproto::Atom::from(xproto::AtomEnum::WM_TRANSIENT_FOR),
+                    xproto::Atom::from(xproto::AtomEnum::WINDOW),
+                    xproto::PropMode::REPLACE,
+                    &[parent.xwindow],
+                ))
+                .ignore_error();
+            }
+
+            if let Some(strut) = x11_attributes.strut.as_ref() {
+                leap!(xconn.set_strut(window.xwindow, Some(strut))).ignore_error();
+            }
+
+            // The window manager reads the initial states and desktop when mapping the window, and
+            // only follows client messages afterwards.
+            let initial_states: Vec<_> = [
+                (x11_attributes.skip_taskbar, _NET_WM_STATE_SKIP_TASKBAR),
+                (x11_attributes.skip_pager, _NET_WM_STATE_SKIP_PAGER),
+                (x11_attributes.sticky, _NET_WM_STATE_STICKY),
+            ]
+            .into_iter()
+            .filter(|&(enabled, _)| enabled)
+            .map(|(_, atom_name)| atoms[atom_name])
+            .collect();
+            if !initial_states.is_empty() {
+                leap!(xconn.change_property(
+                    window.xwindow,
+                    atoms[_NET_WM_STATE],
+                    xproto::Atom::from(xproto::AtomEnum::ATOM),
+                    xproto::PropMode::REPLACE,
+                    &initial_states,
+                ))
+                .ignore_error();
+            }
+
+            if let Some(desktop) = x11_attributes.desktop {
+                leap!(xconn.change_property(
+                    window.xwindow,
+                    atoms[_NET_WM_DESKTOP],
+                    xproto::Atom::from(xproto::AtomEnum::CARDINAL),
+                    xproto::PropMode::REPLACE,
+                    &[desktop.as_cardinal()],
+                ))
+                .ignore_error();
+            }
 
             // Set size hints.
             let mut min_surface_size =
@@ -771,10 +867,8 @@ impl UnownedWindow {
             );
 
             // Set window icons
-            if let Some(icon) =
-                window_attrs.window_icon.as_ref().and_then(|icon| icon.cast_ref::<RgbaIcon>())
-            {
-                leap!(window.set_icon_inner(icon)).ignore_error();
+            if let Some(icon) = window_attrs.window_icon.as_ref().and_then(icon_to_cardinals) {
+                leap!(window.set_icon_inner(&icon)).ignore_error();
             }
 
             // Opt into handling window close and resize synchronization
@@ -826,7 +920,24 @@ impl UnownedWindow {
                 | xinput::XIEventMask::TOUCH_BEGIN
                 | xinput::XIEventMask::TOUCH_UPDATE
                 | xinput::XIEventMask::TOUCH_END;
-            leap!(xconn.select_xinput_events(window.xwindow, ALL_MASTER_DEVICES, mask))
+            // The gestures end in the second word of the mask.
+            let mut mask = vec![mask];
+            if event_loop.xi2_gestures {
+                let gestures = [
+                    ffi::XI_GesturePinchBegin,
+                    ffi::XI_GesturePinchUpdate,
+                    ffi::XI_GesturePinchEnd,
+                    ffi::XI_GestureSwipeBegin,
+                    ffi::XI_GestureSwipeUpdate,
+                    ffi::XI_GestureSwipeEnd,
+                ];
+                mask.push(0u32.into());
+                for event in gestures {
+                    let word = &mut mask[event as usize / 32];
+                    *word |= xinput::XIEventMask::from(1u32 << (event % 32));
+                }
+            }
+            leap!(xconn.select_xinput_events(window.xwindow, ALL_MASTER_DEVICES, &mask))
                 .ignore_error();
 
             // Set visibility (map window)
@@ -839,6 +950,16 @@ impl UnownedWindow {
                 .ignore_error();
             }
 
+            let grab = window_attrs.popup.as_ref().is_some_and(|popup| popup.grab);
+            if grab && window_attrs.visible {
+                match window.grab_popup_pointer() {
+                    Ok(device_id) => {
+                        event_loop.popup_grabs.borrow_mut().push((window.id(), device_id))
+                    },
+                    Err(err) => warn!("Failed to grab the pointer for the popup: {err}"),
+                }
+            }
+
             // Attempt to make keyboard input repeat detectable
             unsafe {
                 let mut supported_ptr = ffi::False;
@@ -961,10 +1082,9 @@ impl UnownedWindow {
         let atoms = self.xconn.atoms();
         let hint_atom = atoms[_GTK_THEME_VARIANT];
         let utf8_atom = atoms[UTF8_STRING];
-        let variant = match theme {
-            Some(Theme::Dark) => "dark",
+        let variant = match theme.or_else(|| self.xconn.system_theme()) {
+            Some(Theme::Dark) | None => "dark",
             Some(Theme::Light) => "light",
-            None => "dark",
         };
         let variant = CString::new(variant).expect("`_GTK_THEME_VARIANT` contained null byte");
         self.xconn.change_property(
@@ -978,6 +1098,7 @@ impl UnownedWindow {
 
     #[inline]
     pub fn set_theme(&self, theme: Option<Theme>) {
+        self.shared_state_lock().theme = theme;
         self.set_theme_inner(theme).expect("Failed to change window theme").ignore_error();
 
         self.xconn.flush_requests().expect("Failed to change window theme");
@@ -1410,16 +1531,63 @@ impl UnownedWindow {
         self.xconn.flush_requests().expect("Failed to set window-level state");
     }
 
-    fn set_icon_inner(&self, icon: &RgbaIcon) -> Result<VoidCookie<'_>, X11Error> {
+    #[inline]
+    pub fn set_strut(&self, strut: Option<Strut>) {
+        self.xconn
+            .set_strut(self.xwindow, strut.as_ref())
+            .expect_then_ignore_error("Failed to set window strut");
+        self.xconn.flush_requests().expect("Failed to set window strut");
+    }
+
+    #[inline]
+    pub fn set_skip_taskbar(&self, skip: bool) {
+        self.toggle_atom(_NET_WM_STATE_SKIP_TASKBAR, skip)
+            .expect_then_ignore_error("Failed to change skip-taskbar state");
+        self.xconn.flush_requests().expect("Failed to change skip-taskbar state");
+    }
+
+    #[inline]
+    pub fn set_skip_pager(&self, skip: bool) {
+        self.toggle_atom(_NET_WM_STATE_SKIP_PAGER, skip)
+            .expect_then_ignore_error("Failed to change skip-pager state");
+        self.xconn.flush_requests().expect("Failed to change skip-pager state");
+    }
+
+    #[inline]
+    pub fn set_sticky(&self, sticky: bool) {
+        self.toggle_atom(_NET_WM_STATE_STICKY, sticky)
+            .expect_then_ignore_error("Failed to change sticky state");
+        self.xconn.flush_requests().expect("Failed to change sticky state");
+    }
+
+    #[inline]
+    pub fn set_desktop(&self, desktop: Desktop) {
+        let atoms = self.xconn.atoms();
+        // The source indication of a normal application is 1.
+        self.xconn
+            .send_client_msg(
+                self.xwindow,
+                self.root,
+                atoms[_NET_WM_DESKTOP],
+                Some(
+                    xproto::EventMask::SUBSTRUCTURE_REDIRECT
+                        | xproto::EventMask::SUBSTRUCTURE_NOTIFY,
+                ),
+                [desktop.as_cardinal(), 1, 0, 0, 0],
+            )
+            .expect_then_ignore_error("Failed to change window desktop");
+        self.xconn.flush_requests().expect("Failed to change window desktop");
+    }
+
+    fn set_icon_inner(&self, data: &[util::Cardinal]) -> Result<VoidCookie<'_>, X11Error> {
         let atoms = self.xconn.atoms();
         let icon_atom = atoms[_NET_WM_ICON];
-        let data = rgba_to_cardinals(icon);
         self.xconn.change_property(
             self.xwindow,
             icon_atom,
             xproto::Atom::from(xproto::AtomEnum::CARDINAL),
             xproto::PropMode::REPLACE,
-            data.as_slice(),
+            data,
         )
     }
 
@@ -1437,9 +1605,9 @@ impl UnownedWindow {
     }
 
     #[inline]
-    pub(crate) fn set_window_icon(&self, icon: Option<&RgbaIcon>) {
-        match icon {
-            Some(icon) => self.set_icon_inner(icon),
+    pub(crate) fn set_window_icon(&self, icon: Option<&Icon>) {
+        match icon.and_then(icon_to_cardinals) {
+            Some(icon) => self.set_icon_inner(&icon),
             None => self.unset_icon_inner(),
         }
         .expect_then_ignore_error("Failed to set icons");
@@ -1519,6 +1687,78 @@ impl UnownedWindow {
         }
     }
 
+    /// The rectangle of a popup of this window, in the coordinates of the root window.
+    fn popup_rect(&self, popup: &PopupAttributes, size: (u32, u32)) -> util::AaRect {
+        let scale_factor = self.scale_factor();
+        let (x, y) = self.inner_position_physical();
+        let position: PhysicalPosition<i32> = popup.anchor_rect_position.to_physical(scale_factor);
+        let anchor_size: PhysicalSize<u32> = popup.anchor_rect_size.to_physical(scale_factor);
+        let offset: PhysicalPosition<i32> = popup.offset.to_physical(scale_factor);
+        let (x, y) = (x + position.x, y + position.y);
+        let anchor_rect = util::AaRect::new((x, y), anchor_size.into());
+
+        // Fit the popup in the monitor showing the anchor rectangle.
+        let monitors = self.xconn.available_monitors().unwrap_or_default();
+        let monitor = monitors
+            .iter()
+            .find(|monitor| monitor.rect.contains_point(x as i64, y as i64))
+            .or_else(|| monitors.first());
+        let (bounds, constraint_adjustment) = match monitor {
+            Some(monitor) => (&monitor.rect, popup.constraint_adjustment),
+            None => (&anchor_rect, PopupConstraintAdjustment::empty()),
+        };
+
+        util::place_popup(
+            &anchor_rect,
+            popup.anchor,
+            popup.gravity,
+            constraint_adjustment,
+            offset.into(),
+            size,
+            bounds,
+        )
+    }
+
+    /// Grab the pointer until the popup is dismissed, returning the grabbed device.
+    pub(crate) fn grab_popup_pointer(&self) -> Result<xinput::DeviceId, X11Error> {
+        let conn = self.xconn.xcb_connection();
+        let device_id = conn.xinput_xi_get_client_pointer(self.xwindow)?.reply()?.deviceid;
+        let mask = xinput::XIEventMask::MOTION
+            | xinput::XIEventMask::BUTTON_PRESS
+            | xinput::XIEventMask::BUTTON_RELEASE
+            | xinput::XIEventMask::ENTER
+            | xinput::XIEventMask::LEAVE;
+        // With the owner events, the other windows of the application keep their input, and the
+        // popup only gets the input outside of them.
+        let status = conn
+            .xinput_xi_grab_device(
+                self.xwindow,
+                x11rb::CURRENT_TIME,
+                x11rb::NONE,
+                device_id,
+                xproto::GrabMode::ASYNC,
+                xproto::GrabMode::ASYNC,
+                xinput::GrabOwner::OWNER,
+                &[u32::from(mask)],
+            )?
+            .reply()?
+            .status;
+        if status != xproto::GrabStatus::SUCCESS {
+            return Err(X11Error::GrabFailed(status));
+        }
+
+        Ok(device_id)
+    }
+
+    /// Release the pointer grabbed for the popup, and hide it.
+    pub(crate) fn dismiss_popup(&self, device_id: xinput::DeviceId) {
+        self.xconn
+            .xcb_connection()
+            .xinput_xi_ungrab_device(x11rb::CURRENT_TIME, device_id)
+            .expect_then_ignore_error("Failed to ungrab the pointer");
+        self.set_visible(false);
+    }
+
     fn inner_position_physical(&self) -> (i32, i32) {
         // This should be okay to unwrap since the only error XTranslateCoordinates can return
         // is BadWindow, and if the window handle is bad we have bigger problems.
@@ -1836,6 +2076,20 @@ impl UnownedWindow {
         }
     }
 
+    /// Set the named cursor again, after the cursor theme changed.
+    pub(crate) fn reload_cursor_icon(&self) {
+        #[allow(clippy::mutex_atomic)]
+        if !*self.cursor_visible.lock().unwrap() {
+            return;
+        }
+
+        if let SelectedCursor::Named(icon) = *self.selected_cursor.lock().unwrap() {
+            if let Err(err) = self.xconn.set_cursor_icon(self.xwindow, Some(icon)) {
+                tracing::error!("failed to set cursor icon: {err}");
+            }
+        }
+    }
+
     #[inline]
     pub fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), RequestError> {
         // We don't support the locked cursor yet, so ignore it early on.
@@ -2225,6 +2479,26 @@ impl UnownedWindow {
         Ok(serial)
     }
 
+    #[inline]
+    pub fn request_drag_data(&self, mime_type: &str) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = AsyncRequestSerial::get();
+        self.drag_data_sender.send((self.id(), serial, mime_type.to_owned()));
+        Ok(serial)
+    }
+
+    #[inline]
+    pub fn set_drag_action(&self, action: Option<DragAction>) -> Result<(), RequestError> {
+        self.shared_state_lock().drag_action = Some(action);
+        Ok(())
+    }
+
+    #[inline]
+    pub fn start_drag(&self, source: DragSource) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = AsyncRequestSerial::get();
+        self.start_drag_sender.send((self.id(), serial, source));
+        Ok(serial)
+    }
+
     #[inline]
     pub fn id(&self) -> WindowId {
         WindowId::from_raw(self.xwindow as _)
@@ -2267,7 +2541,7 @@ impl UnownedWindow {
 
     #[inline]
     pub fn theme(&self) -> Option<Theme> {
-        None
+        self.shared_state_lock().theme.or_else(|| self.xconn.system_theme())
     }
 
     pub fn set_content_protected(&self, _protected: bool) {}
@@ -6,7 +6,9 @@ use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard};
 use std::{fmt, ptr};
 
 use rwh_06::HasDisplayHandle;
+use winit_common::xdg_desktop_portal::Settings as PortalSettings;
 use winit_core::cursor::CursorIcon;
+use winit_core::window::Theme;
 use x11rb::connection::Connection;
 use x11rb::protocol::randr::ConnectionExt as _;
 use x11rb::protocol::render;
@@ -18,6 +20,7 @@ use super::atoms::Atoms;
 use super::ffi;
 use super::monitor::MonitorHandle;
 use crate::event_loop::X11Error;
+use crate::util::{CursorTheme, IncrTransfer};
 
 /// A connection to an X server.
 pub struct XConnection {
@@ -58,11 +61,18 @@ pub struct XConnection {
     /// Atom for the XSettings screen.
     xsettings_screen: Option<xproto::Atom>,
 
+    /// The system theme, as last read by `refresh_system_theme`.
+    system_theme: Mutex<Option<Theme>>,
+
     /// XRender format information.
     render_formats: render::QueryPictFormatsReply,
 
     pub latest_error: Mutex<Option<XError>>,
     pub cursor_cache: Mutex<HashMap<Option<CursorIcon>, xproto::Cursor>>,
+    /// The theme the cached cursors are loaded from.
+    pub cursor_theme: Mutex<CursorTheme>,
+    /// The selections being sent to other clients in chunks.
+    pub incr_transfers: Mutex<Vec<IncrTransfer>>,
 }
 
 impl HasDisplayHandle for XConnection {
@@ -156,9 +166,12 @@ impl XConnection {
             monitor_handles: Mutex::new(None),
             database: RwLock::new(database),
             cursor_cache: Default::default(),
+            cursor_theme: Default::default(),
+            incr_transfers: Default::default(),
             randr_version: (randr_version.major_version, randr_version.minor_version),
             render_formats: formats,
             xsettings_screen,
+            system_theme: Mutex::new(None),
         })
     }
 
@@ -272,6 +285,31 @@ impl XConnection {
         self.xsettings_screen
     }
 
+    /// Get the system theme.
+    #[inline]
+    pub fn system_theme(&self) -> Option<Theme> {
+        *self.system_theme.lock().unwrap()
+    }
+
+    /// Read the system theme again, returning whether it has changed.
+    ///
+    /// The `color-scheme` of the XDG Desktop Portal takes precedence over the name of the GTK
+    /// theme in XSettings.
+    pub fn refresh_system_theme(&self, portal_settings: &PortalSettings) -> bool {
+        let theme = portal_settings.color_scheme().or_else(|| {
+            let xsettings_screen = self.xsettings_screen?;
+            self.xsettings_theme(xsettings_screen)
+                .inspect_err(|err| tracing::warn!("failed to fetch XSettings: {err}"))
+                .ok()
+                .flatten()
+        });
+
+        let mut system_theme = self.system_theme.lock().unwrap();
+        let changed = *system_theme != theme;
+        *system_theme = theme;
+        changed
+    }
+
     /// Get the data containing our rendering formats.
     #[inline]
     pub fn render_formats(&self) -> &render::QueryPictFormatsReply {
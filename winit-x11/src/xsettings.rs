@@ -6,7 +6,10 @@
 
 use std::iter;
 use std::num::NonZeroUsize;
+use std::time::Duration;
 
+use winit_core::event_loop::DesktopSettings;
+use winit_core::window::Theme;
 use x11rb::protocol::xproto::{self, ConnectionExt};
 
 use super::atoms::*;
@@ -16,6 +19,15 @@ use crate::xdisplay::XConnection;
 type Result<T> = core::result::Result<T, ParserError>;
 
 const DPI_NAME: &[u8] = b"Xft/DPI";
+const DOUBLE_CLICK_TIME_NAME: &[u8] = b"Net/DoubleClickTime";
+const DOUBLE_CLICK_DISTANCE_NAME: &[u8] = b"Net/DoubleClickDistance";
+const DRAG_THRESHOLD_NAME: &[u8] = b"Net/DndDragThreshold";
+const CURSOR_BLINK_NAME: &[u8] = b"Net/CursorBlink";
+const CURSOR_BLINK_TIME_NAME: &[u8] = b"Net/CursorBlinkTime";
+const CURSOR_THEME_NAME: &[u8] = b"Gtk/CursorThemeName";
+const CURSOR_THEME_SIZE_NAME: &[u8] = b"Gtk/CursorThemeSize";
+const THEME_NAME: &[u8] = b"Net/ThemeName";
+const ICON_THEME_NAME: &[u8] = b"Net/IconThemeName";
 const DPI_MULTIPLIER: f64 = 1024.0;
 const LITTLE_ENDIAN: u8 = b'l';
 const BIG_ENDIAN: u8 = b'B';
@@ -26,19 +38,10 @@ impl XConnection {
         &self,
         xsettings_screen: xproto::Atom,
     ) -> core::result::Result<Option<f64>, X11Error> {
-        let atoms = self.atoms();
-
-        // Get the current owner of the screen's settings.
-        let owner = self.xcb_connection().get_selection_owner(xsettings_screen)?.reply()?;
-
-        // Read the _XSETTINGS_SETTINGS property.
-        let data: Vec<u8> =
-            self.get_property(owner.owner, atoms[_XSETTINGS_SETTINGS], atoms[_XSETTINGS_SETTINGS])?;
+        let data = self.xsettings_data(xsettings_screen)?;
 
         // Parse the property.
-        let dpi_setting = read_settings(&data)?
-            .find(|res| res.as_ref().map_or(true, |s| s.name == DPI_NAME))
-            .transpose()?;
+        let dpi_setting = find_setting(&data, DPI_NAME)?;
         if let Some(dpi_setting) = dpi_setting {
             let base_dpi = match dpi_setting.data {
                 SettingData::Integer(dpi) => dpi as f64,
@@ -55,6 +58,162 @@ impl XConnection {
             Ok(None)
         }
     }
+
+    /// Get the theme from the name of the GTK theme in XSettings.
+    pub(crate) fn xsettings_theme(
+        &self,
+        xsettings_screen: xproto::Atom,
+    ) -> core::result::Result<Option<Theme>, X11Error> {
+        let settings = self.xsettings(xsettings_screen)?;
+        Ok(settings.theme_name.map(|name| theme_from_name(name.as_bytes())))
+    }
+
+    /// Get a snapshot of all the settings we know about from XSettings.
+    pub(crate) fn xsettings(
+        &self,
+        xsettings_screen: xproto::Atom,
+    ) -> core::result::Result<XSettings, X11Error> {
+        let data = self.xsettings_data(xsettings_screen)?;
+        Ok(XSettings::parse(&data)?)
+    }
+
+    /// Get a snapshot of the settings, empty when there's no XSettings manager.
+    pub(crate) fn xsettings_snapshot(&self) -> XSettings {
+        let xsettings_screen = match self.xsettings_screen() {
+            Some(xsettings_screen) => xsettings_screen,
+            None => return XSettings::default(),
+        };
+
+        self.xsettings(xsettings_screen).unwrap_or_else(|err| {
+            tracing::warn!("failed to fetch XSettings: {err}");
+            XSettings::default()
+        })
+    }
+
+    /// Read the `_XSETTINGS_SETTINGS` property of the current owner of the screen's settings.
+    fn xsettings_data(
+        &self,
+        xsettings_screen: xproto::Atom,
+    ) -> core::result::Result<Vec<u8>, X11Error> {
+        let atoms = self.atoms();
+
+        // Get the current owner of the screen's settings.
+        let owner = self.xcb_connection().get_selection_owner(xsettings_screen)?.reply()?;
+
+        // Read the _XSETTINGS_SETTINGS property.
+        self.get_property(owner.owner, atoms[_XSETTINGS_SETTINGS], atoms[_XSETTINGS_SETTINGS])
+            .map_err(Into::into)
+    }
+}
+
+/// A snapshot of the settings shared by the XSettings manager, usually the settings daemon of the
+/// desktop environment.
+///
+/// The settings which aren't provided by the manager are `None`.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct XSettings {
+    /// The DPI of the fonts, from `Xft/DPI`.
+    pub dpi: Option<f64>,
+
+    /// The maximum time between the clicks of a double click, from `Net/DoubleClickTime`.
+    pub double_click_time: Option<Duration>,
+
+    /// The maximum distance in pixels between the clicks of a double click, from
+    /// `Net/DoubleClickDistance`.
+    pub double_click_distance: Option<u32>,
+
+    /// The distance in pixels the pointer has to move to start a drag, from
+    /// `Net/DndDragThreshold`.
+    pub drag_threshold: Option<u32>,
+
+    /// Whether the text cursor blinks, from `Net/CursorBlink`.
+    pub cursor_blink: Option<bool>,
+
+    /// The duration of a blink cycle of the text cursor, from `Net/CursorBlinkTime`.
+    pub cursor_blink_time: Option<Duration>,
+
+    /// The name of the cursor theme, from `Gtk/CursorThemeName`.
+    pub cursor_theme_name: Option<String>,
+
+    /// The size of the cursors in pixels, from `Gtk/CursorThemeSize`.
+    pub cursor_theme_size: Option<u32>,
+
+    /// The name of the GTK theme, from `Net/ThemeName`.
+    pub theme_name: Option<String>,
+
+    /// The name of the icon theme, from `Net/IconThemeName`.
+    pub icon_theme_name: Option<String>,
+}
+
+impl XSettings {
+    /// Parse the settings from the block of data, ignoring the ones with an unexpected type.
+    fn parse(data: &[u8]) -> Result<Self> {
+        let mut settings = Self::default();
+        for setting in read_settings(data)? {
+            let Setting { name, data } = setting?;
+            let integer = match data {
+                SettingData::Integer(integer) => Some(integer),
+                _ => None,
+            };
+            let string = match data {
+                SettingData::String(string) => Some(String::from_utf8_lossy(string).into_owned()),
+                _ => None,
+            };
+            let millis = integer.and_then(|millis| u64::try_from(millis).ok());
+            let pixels = integer.and_then(|pixels| u32::try_from(pixels).ok());
+
+            match name {
+                DPI_NAME => settings.dpi = integer.map(|dpi| dpi as f64 / DPI_MULTIPLIER),
+                DOUBLE_CLICK_TIME_NAME => {
+                    settings.double_click_time = millis.map(Duration::from_millis)
+                },
+                DOUBLE_CLICK_DISTANCE_NAME => settings.double_click_distance = pixels,
+                DRAG_THRESHOLD_NAME => settings.drag_threshold = pixels,
+                CURSOR_BLINK_NAME => settings.cursor_blink = integer.map(|blink| blink != 0),
+                CURSOR_BLINK_TIME_NAME => {
+                    settings.cursor_blink_time = millis.map(Duration::from_millis)
+                },
+                CURSOR_THEME_NAME => settings.cursor_theme_name = string,
+                CURSOR_THEME_SIZE_NAME => settings.cursor_theme_size = pixels,
+                THEME_NAME => settings.theme_name = string,
+                ICON_THEME_NAME => settings.icon_theme_name = string,
+                _ => (),
+            }
+        }
+
+        Ok(settings)
+    }
+
+    /// The input settings shared with the other platforms, taking the ones missing from
+    /// XSettings from `fallback`.
+    pub(crate) fn desktop_settings(&self, fallback: DesktopSettings) -> DesktopSettings {
+        let mut settings = DesktopSettings::default();
+        settings.double_click_time = self.double_click_time.or(fallback.double_click_time);
+        settings.double_click_distance =
+            self.double_click_distance.or(fallback.double_click_distance);
+        settings.drag_threshold = self.drag_threshold.or(fallback.drag_threshold);
+        settings.caret_blink = self.cursor_blink.or(fallback.caret_blink);
+        settings.caret_blink_time = self.cursor_blink_time.or(fallback.caret_blink_time);
+        // XSettings has no setting for it.
+        settings.wheel_scroll_lines = fallback.wheel_scroll_lines;
+        settings
+    }
+}
+
+/// Find the setting with the given name in the block of data.
+fn find_setting<'a>(data: &'a [u8], name: &[u8]) -> Result<Option<Setting<'a>>> {
+    read_settings(data)?.find(|res| res.as_ref().map_or(true, |s| s.name == name)).transpose()
+}
+
+/// Guess the theme from the name of a GTK theme, dark variants are suffixed with `-dark`, as in
+/// `Adwaita-dark`, or have it in their name, as in `Yaru-dark` or `Breeze-Dark`.
+fn theme_from_name(name: &[u8]) -> Theme {
+    if name.to_ascii_lowercase().windows(4).any(|word| word == b"dark") {
+        Theme::Dark
+    } else {
+        Theme::Light
+    }
 }
 
 /// Read over the settings in the block of data.
@@ -82,7 +241,7 @@ struct Setting<'a> {
 /// The data contained in a setting.
 enum SettingData<'a> {
     Integer(i32),
-    String(#[allow(dead_code)] &'a [u8]),
+    String(&'a [u8]),
     Color(#[allow(dead_code)] [i16; 4]),
 }
 
@@ -288,15 +447,7 @@ mod tests {
 
     #[test]
     fn parse_xsettings() {
-        let data = XSETTINGS
-            .trim()
-            .split(',')
-            .map(|tok| {
-                let val = tok.strip_prefix("0x").unwrap();
-                u8::from_str_radix(val, 16).unwrap()
-            })
-            .collect::<Vec<_>>();
-
+        let data = test_data();
         let settings = read_settings(&data).unwrap().collect::<Result<Vec<_>>>().unwrap();
 
         let dpi = settings.iter().find(|s| s.name == b"Xft/DPI").unwrap();
@@ -308,6 +459,43 @@ mod tests {
         assert_string(&rgba.data, "rgb");
         let lcd = settings.iter().find(|s| s.name == b"Xft/Lcdfilter").unwrap();
         assert_string(&lcd.data, "lcddefault");
+        let theme = settings.iter().find(|s| s.name == THEME_NAME).unwrap();
+        assert_string(&theme.data, "Greybird");
+    }
+
+    #[test]
+    fn snapshot() {
+        let settings = XSettings::parse(&test_data()).unwrap();
+
+        assert_eq!(settings.dpi, Some(96.0));
+        assert_eq!(settings.double_click_time, Some(Duration::from_millis(400)));
+        assert_eq!(settings.double_click_distance, Some(5));
+        assert_eq!(settings.drag_threshold, Some(8));
+        assert_eq!(settings.cursor_blink, Some(true));
+        assert_eq!(settings.cursor_blink_time, Some(Duration::from_millis(1200)));
+        assert_eq!(settings.cursor_theme_name.as_deref(), Some("DMZ-White"));
+        assert_eq!(settings.cursor_theme_size, Some(24));
+        assert_eq!(settings.theme_name.as_deref(), Some("Greybird"));
+        assert_eq!(settings.icon_theme_name.as_deref(), Some("elementary-xfce-dark"));
+    }
+
+    #[test]
+    fn theme_name() {
+        assert_eq!(theme_from_name(b"Adwaita"), Theme::Light);
+        assert_eq!(theme_from_name(b"Adwaita-dark"), Theme::Dark);
+        assert_eq!(theme_from_name(b"Breeze-Dark"), Theme::Dark);
+        assert_eq!(theme_from_name(b"Yaru"), Theme::Light);
+    }
+
+    fn test_data() -> Vec<u8> {
+        XSETTINGS
+            .trim()
+            .split(',')
+            .map(|tok| {
+                let val = tok.strip_prefix("0x").unwrap();
+                u8::from_str_radix(val, 16).unwrap()
+            })
+            .collect()
     }
 
     fn assert_string(dat: &SettingData<'_>, s: &str) {
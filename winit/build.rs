@@ -20,6 +20,7 @@ fn main() {
         // Native displays.
         x11_platform: { all(feature = "x11", free_unix, not(redox)) },
         wayland_platform: { all(feature = "wayland", free_unix, not(redox)) },
+        headless_platform: { all(feature = "headless", any(free_unix, windows_platform, macos_platform), not(redox)) },
         orbital_platform: { redox },
     }
 
@@ -24,7 +24,9 @@ use winit::application::ApplicationHandler;
 use winit::cursor::{Cursor, CustomCursor, CustomCursorSource};
 use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
 use winit::error::RequestError;
-use winit::event::{DeviceEvent, DeviceId, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event::{
+    DeviceEvent, DeviceId, MonitorEvent, MouseButton, MouseScrollDelta, WindowEvent,
+};
 use winit::event_loop::{ActiveEventLoop, EventLoop};
 use winit::icon::{Icon, RgbaIcon};
 use winit::keyboard::{Key, ModifiersState};
@@ -39,7 +41,9 @@ use winit::platform::wayland::{ActiveEventLoopExtWayland, WindowAttributesWaylan
 use winit::platform::web::{ActiveEventLoopExtWeb, WindowAttributesWeb};
 #[cfg(x11_platform)]
 use winit::platform::x11::{ActiveEventLoopExtX11, WindowAttributesX11};
-use winit::window::{CursorGrabMode, ResizeDirection, Theme, Window, WindowAttributes, WindowId};
+use winit::window::{
+    CursorGrabMode, PopupAttributes, ResizeDirection, Theme, Window, WindowAttributes, WindowId,
+};
 use winit_core::application::macos::ApplicationHandlerExtMacOS;
 
 #[path = "util/tracing.rs"]
@@ -184,6 +188,27 @@ impl Application {
         Ok(window_id)
     }
 
+    fn create_popup(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        parent: WindowId,
+        position: PhysicalPosition<f64>,
+    ) -> Result<WindowId, Box<dyn Error>> {
+        let popup = PopupAttributes::new(parent, position.into(), PhysicalSize::new(0, 0).into())
+            .with_grab(true);
+        let window_attributes = WindowAttributes::default()
+            .with_title("Winit popup")
+            .with_surface_size(LogicalSize::new(200, 300))
+            .with_popup(popup);
+
+        let window = event_loop.create_window(window_attributes)?;
+        let window_state = WindowState::new(self, window)?;
+        let window_id = window_state.window.id();
+        info!("Created new popup with id={window_id:?}");
+        self.windows.insert(window_id, window_state);
+        Ok(window_id)
+    }
+
     fn handle_action_from_proxy(&mut self, _event_loop: &dyn ActiveEventLoop, action: Action) {
         match action {
             #[cfg(web_platform)]
@@ -229,6 +254,12 @@ impl Application {
                     error!("Error creating new window: {err}");
                 }
             },
+            Action::CreatePopup => {
+                let position = window.cursor_position.unwrap_or_default();
+                if let Err(err) = self.create_popup(event_loop, window_id, position) {
+                    error!("Error creating popup: {err}");
+                }
+            },
             Action::ToggleResizeIncrements => window.toggle_resize_increments(),
             Action::ToggleCursorVisibility => window.toggle_cursor_visibility(),
             Action::ToggleResizable => window.toggle_resizable(),
@@ -443,6 +474,9 @@ impl ApplicationHandler for Application {
                 info!("Theme changed to {theme:?}");
                 window.set_draw_theme(theme);
             },
+            WindowEvent::DecorationModeChanged(mode) => {
+                info!("Decoration mode changed to {mode:?}");
+            },
             WindowEvent::RedrawRequested => {
                 if let Err(err) = window.draw() {
                     error!("Error drawing window: {err}");
@@ -458,6 +492,10 @@ impl ApplicationHandler for Application {
                 info!("Closing Window={window_id:?}");
                 self.windows.remove(&window_id);
             },
+            WindowEvent::PopupDismissed => {
+                info!("Popup dismissed Window={window_id:?}");
+                self.windows.remove(&window_id);
+            },
             WindowEvent::ModifiersChanged(modifiers) => {
                 window.modifiers = modifiers.state();
                 info!("Modifiers changed to {:?}", window.modifiers);
@@ -538,9 +576,30 @@ impl ApplicationHandler for Application {
                 window.panned.y += delta.y;
                 info!("Panned ({delta:?})) (now: {:?}), {phase:?}", window.panned);
             },
+            WindowEvent::SwipeGesture { fingers, delta, phase, .. } => {
+                info!("Swiped with {fingers} fingers ({delta:?}), {phase:?}");
+            },
+            WindowEvent::HoldGesture { fingers, phase, .. } => {
+                info!("Held {fingers} fingers, {phase:?}");
+            },
             WindowEvent::DoubleTapGesture { .. } => {
                 info!("Smart zoom");
             },
+            WindowEvent::TabletPadButton { button, state, .. } => {
+                info!("Tablet pad button {button} {state:?}");
+            },
+            WindowEvent::TabletPadRing { ring, angle, .. } => {
+                info!("Tablet pad ring {ring} at {angle:?}");
+            },
+            WindowEvent::TabletPadStrip { strip, position, .. } => {
+                info!("Tablet pad strip {strip} at {position:?}");
+            },
+            WindowEvent::TabletPadDial { dial, delta, .. } => {
+                info!("Tablet pad dial {dial} turned by {delta}");
+            },
+            WindowEvent::TabletPadModeSwitch { group, mode, .. } => {
+                info!("Tablet pad group {group} switched to mode {mode}");
+            },
             WindowEvent::TouchpadPressure { .. }
             | WindowEvent::DragLeft { .. }
             | WindowEvent::KeyboardInput { .. }
@@ -548,6 +607,8 @@ impl ApplicationHandler for Application {
             | WindowEvent::DragEntered { .. }
             | WindowEvent::DragMoved { .. }
             | WindowEvent::DragDropped { .. }
+            | WindowEvent::DragData { .. }
+            | WindowEvent::DragFinished { .. }
             | WindowEvent::Destroyed
             | WindowEvent::Ime(_)
             | WindowEvent::Moved(_) => (),
@@ -563,6 +624,10 @@ impl ApplicationHandler for Application {
         info!("Device {device_id:?} event: {event:?}");
     }
 
+    fn monitor_event(&mut self, _event_loop: &dyn ActiveEventLoop, event: MonitorEvent) {
+        info!("Monitor event: {event:?}");
+    }
+
     fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
         info!("Ready to create surfaces");
         self.dump_monitors(event_loop);
@@ -999,6 +1064,7 @@ enum Action {
     CloseWindow,
     ToggleCursorVisibility,
     CreateNewWindow,
+    CreatePopup,
     ToggleResizeIncrements,
     ToggleDecorations,
     ToggleResizable,
@@ -1039,6 +1105,7 @@ impl Action {
             Action::CloseWindow => "Close window",
             Action::ToggleCursorVisibility => "Hide cursor",
             Action::CreateNewWindow => "Create new window",
+            Action::CreatePopup => "Create a popup at the cursor",
             Action::ToggleDecorations => "Toggle decorations",
             Action::ToggleResizable => "Toggle window resizable state",
             Action::ToggleFullscreen => "Toggle fullscreen",
@@ -1307,4 +1374,5 @@ const MOUSE_BINDINGS: &[Binding<MouseButton>] = &[
     Binding::new(MouseButton::Left, ModifiersState::ALT, Action::DragResizeWindow),
     Binding::new(MouseButton::Left, ModifiersState::CONTROL, Action::DragWindow),
     Binding::new(MouseButton::Right, ModifiersState::CONTROL, Action::ShowWindowMenu),
+    Binding::new(MouseButton::Right, ModifiersState::SHIFT, Action::CreatePopup),
 ];
@@ -2,7 +2,9 @@ use std::error::Error;
 
 use tracing::info;
 use winit::application::ApplicationHandler;
-use winit::event::WindowEvent;
+use winit::event::{
+    ButtonSource, DragAction, DragSource, ElementState, MimeData, MouseButton, WindowEvent,
+};
 use winit::event_loop::{ActiveEventLoop, EventLoop};
 use winit::window::{Window, WindowAttributes, WindowId};
 
@@ -34,8 +36,8 @@ impl Application {
 
 impl ApplicationHandler for Application {
     fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
-        let window_attributes =
-            WindowAttributes::default().with_title("Drag and drop files on me!");
+        let window_attributes = WindowAttributes::default()
+            .with_title("Drag and drop files or text on me, or drag text from me!");
         self.window = Some(event_loop.create_window(window_attributes).unwrap());
     }
 
@@ -46,10 +48,43 @@ impl ApplicationHandler for Application {
         event: WindowEvent,
     ) {
         match event {
+            WindowEvent::DragDropped { ref payload, .. } => {
+                info!("{event:?}");
+                // Fetch the dropped text.
+                if payload.has_mime_type("text/plain;charset=utf-8") {
+                    let window = self.window.as_ref().unwrap();
+                    if let Err(err) = window.request_drag_data("text/plain;charset=utf-8") {
+                        info!("Failed to request the dropped text: {err}");
+                    }
+                }
+            },
+            WindowEvent::DragEntered { .. } => {
+                info!("{event:?}");
+                // Copy the data even when the source asks to move it.
+                let window = self.window.as_ref().unwrap();
+                if let Err(err) = window.set_drag_action(Some(DragAction::Copy)) {
+                    info!("Failed to set the drag action: {err}");
+                }
+            },
+            WindowEvent::DragData { data: Some(ref data), .. } => {
+                info!("Dropped text: {:?}", String::from_utf8_lossy(data));
+            },
+            WindowEvent::PointerButton {
+                state: ElementState::Pressed,
+                button: ButtonSource::Mouse(MouseButton::Left),
+                ..
+            } => {
+                // Drag some text out of the window.
+                let window = self.window.as_ref().unwrap();
+                let source = DragSource::new(MimeData::new().with_text("Hello from winit!"));
+                if let Err(err) = window.start_drag(source) {
+                    info!("Failed to start dragging: {err}");
+                }
+            },
             WindowEvent::DragLeft { .. }
-            | WindowEvent::DragEntered { .. }
             | WindowEvent::DragMoved { .. }
-            | WindowEvent::DragDropped { .. } => {
+            | WindowEvent::DragData { .. }
+            | WindowEvent::DragFinished { .. } => {
                 info!("{event:?}");
             },
             WindowEvent::RedrawRequested => {
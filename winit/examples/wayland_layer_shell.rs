@@ -0,0 +1,70 @@
+//! A demonstration of a panel at the top of the screen, made with a Wayland layer-shell surface.
+use std::error::Error;
+
+#[cfg(wayland_platform)]
+fn main() -> Result<(), Box<dyn Error>> {
+    use winit::application::ApplicationHandler;
+    use winit::event::WindowEvent;
+    use winit::event_loop::{ActiveEventLoop, EventLoop};
+    use winit::platform::wayland::{
+        Anchor, Layer, LayerSurfaceAttributes, WindowAttributesWayland,
+    };
+    use winit::window::{Window, WindowAttributes, WindowId};
+
+    #[path = "util/fill.rs"]
+    mod fill;
+    #[path = "util/tracing.rs"]
+    mod tracing;
+
+    const PANEL_HEIGHT: u32 = 32;
+
+    #[derive(Debug, Default)]
+    pub struct Panel {
+        window: Option<Box<dyn Window>>,
+    }
+
+    impl ApplicationHandler for Panel {
+        fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+            let window_attributes = WindowAttributes::default()
+                .with_title("A panel")
+                .with_surface_size(winit::dpi::LogicalSize::new(0, PANEL_HEIGHT));
+            let layer_surface = LayerSurfaceAttributes::new(Layer::Top)
+                .with_anchor(Anchor::TOP | Anchor::LEFT | Anchor::RIGHT)
+                .with_exclusive_zone(PANEL_HEIGHT as i32);
+            let wayland_attrs = WindowAttributesWayland::default()
+                .with_name("winit-panel", "")
+                .with_layer_surface(layer_surface);
+            let window_attributes =
+                window_attributes.with_platform_attributes(Box::new(wayland_attrs));
+
+            self.window = Some(event_loop.create_window(window_attributes).unwrap());
+        }
+
+        fn window_event(
+            &mut self,
+            event_loop: &dyn ActiveEventLoop,
+            _window_id: WindowId,
+            event: WindowEvent,
+        ) {
+            let window = self.window.as_ref().unwrap();
+            match event {
+                WindowEvent::CloseRequested => event_loop.exit(),
+                WindowEvent::RedrawRequested => {
+                    window.pre_present_notify();
+                    fill::fill_window(window.as_ref());
+                },
+                _ => (),
+            }
+        }
+    }
+
+    tracing::init();
+    let event_loop = EventLoop::new()?;
+
+    Ok(event_loop.run_app(Panel::default())?)
+}
+
+#[cfg(not(wayland_platform))]
+fn main() -> Result<(), Box<dyn Error>> {
+    panic!("This example is only supported on Wayland platforms.")
+}
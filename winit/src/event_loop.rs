@@ -9,7 +9,7 @@
 //! See the root-level documentation for information on how to create and use an event loop to
 //! handle events.
 use std::marker::PhantomData;
-#[cfg(any(x11_platform, wayland_platform))]
+#[cfg(any(x11_platform, wayland_platform, all(headless_platform, free_unix)))]
 use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
 
 use rwh_06::{DisplayHandle, HandleError, HasDisplayHandle};
@@ -225,6 +225,7 @@ impl EventLoop {
             orbital_platform,
             x11_platform,
             wayland_platform,
+            headless_platform,
         ))]
         {
             let result = self.event_loop.run_app_on_demand(&mut app);
@@ -294,7 +295,7 @@ impl HasDisplayHandle for EventLoop {
     }
 }
 
-#[cfg(any(x11_platform, wayland_platform))]
+#[cfg(any(x11_platform, wayland_platform, all(headless_platform, free_unix)))]
 impl AsFd for EventLoop {
     /// Get the underlying [EventLoop]'s `fd` which you can register
     /// into other event loop, like [`calloop`] or [`mio`]. When doing so, the
@@ -308,7 +309,7 @@ impl AsFd for EventLoop {
     }
 }
 
-#[cfg(any(x11_platform, wayland_platform))]
+#[cfg(any(x11_platform, wayland_platform, all(headless_platform, free_unix)))]
 impl AsRawFd for EventLoop {
     /// Get the underlying [EventLoop]'s raw `fd` which you can register
     /// into other event loop, like [`calloop`] or [`mio`]. When doing so, the
@@ -328,6 +329,7 @@ impl AsRawFd for EventLoop {
     android_platform,
     x11_platform,
     wayland_platform,
+    headless_platform,
     docsrs,
 ))]
 impl winit_core::event_loop::pump_events::EventLoopExtPumpEvents for EventLoop {
@@ -348,6 +350,7 @@ impl winit_core::event_loop::pump_events::EventLoopExtPumpEvents for EventLoop {
     orbital_platform,
     x11_platform,
     wayland_platform,
+    headless_platform,
     docsrs,
 ))]
 impl winit_core::event_loop::run_on_demand::EventLoopExtRunOnDemand for EventLoop {
@@ -489,7 +492,7 @@ impl winit_win32::EventLoopBuilderExtWindows for EventLoopBuilder {
 impl winit_x11::EventLoopExtX11 for EventLoop {
     #[inline]
     fn is_x11(&self) -> bool {
-        !self.event_loop.is_wayland()
+        self.event_loop.is_x11()
     }
 }
 
@@ -508,6 +511,28 @@ impl winit_x11::EventLoopBuilderExtX11 for EventLoopBuilder {
     }
 }
 
+#[cfg(headless_platform)]
+impl winit_headless::EventLoopExtHeadless for EventLoop {
+    #[inline]
+    fn is_headless(&self) -> bool {
+        self.event_loop.headless().is_some()
+    }
+
+    #[inline]
+    fn event_injector(&self) -> Option<winit_headless::EventInjector> {
+        self.event_loop.headless().map(winit_headless::EventLoop::event_injector)
+    }
+}
+
+#[cfg(headless_platform)]
+impl winit_headless::EventLoopBuilderExtHeadless for EventLoopBuilder {
+    #[inline]
+    fn with_headless(&mut self) -> &mut Self {
+        self.platform_specific.forced_backend = Some(crate::platform_impl::Backend::Headless);
+        self
+    }
+}
+
 /// ```compile_error
 /// use winit::event_loop::run_on_demand::EventLoopExtRunOnDemand;
 /// use winit::event_loop::EventLoop;
@@ -192,6 +192,10 @@
 //!
 //! * `x11` (enabled by default): On Unix platforms, enables the X11 backend.
 //! * `wayland` (enabled by default): On Unix platforms, enables the Wayland backend.
+//! * `dbus` (enabled by default): On Unix platforms, reads the color scheme and the input settings
+//!   of the [XDG Desktop Portal](https://flatpak.github.io/xdg-desktop-portal/) over D-Bus.
+//! * `headless`: On Windows, macOS and Unix platforms, enables the in-memory backend for testing,
+//!   which has to be selected explicitly when building the event loop.
 //! * `rwh_06`: Implement `raw-window-handle v0.6` traits.
 //! * `serde`: Enables serialization/deserialization of certain types with [Serde](https://crates.io/crates/serde).
 //! * `mint`: Enables mint (math interoperability standard types) conversions.
@@ -292,7 +296,9 @@ pub use rwh_06 as raw_window_handle;
 #[cfg(any(doc, doctest, test))]
 pub mod changelog;
 pub mod event_loop;
-pub use winit_core::{application, cursor, error, event, icon, keyboard, monitor, window};
+pub use winit_core::{
+    application, clipboard, cursor, error, event, icon, keyboard, monitor, window,
+};
 #[macro_use]
 mod os_error;
 mod platform_impl;
@@ -15,6 +15,7 @@
 #![doc = concat!("| Web | [`winit-web`](https://docs.rs/winit-web/", env!("CARGO_PKG_VERSION"), "/) | `winit::platform::web` |")]
 #![doc = concat!("| Windows | [`winit-win32`](https://docs.rs/winit-win32/", env!("CARGO_PKG_VERSION"), "/) | `winit::platform::windows` |")]
 #![doc = concat!("| X11 | [`winit-x11`](https://docs.rs/winit-x11/", env!("CARGO_PKG_VERSION"), "/) | `winit::platform::x11` |")]
+#![doc = concat!("| Headless (Windows/macOS/Unix) | [`winit-headless`](https://docs.rs/winit-headless/", env!("CARGO_PKG_VERSION"), "/) | `winit::platform::headless` |")]
 //! ## Common modules
 //!
 //! Furthermore, we provide two modules for common functionality:
@@ -25,6 +26,8 @@
 pub use winit_android as android;
 #[cfg(macos_platform)]
 pub use winit_appkit as macos;
+#[cfg(headless_platform)]
+pub use winit_headless as headless;
 #[cfg(orbital_platform)]
 pub use winit_orbital as orbital;
 #[cfg(ios_platform)]
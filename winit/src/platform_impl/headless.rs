@@ -0,0 +1,105 @@
+//! The native backend of Windows and macOS, with the headless backend next to it.
+
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+#[cfg(macos_platform)]
+use winit_appkit as native;
+use winit_core::application::ApplicationHandler;
+use winit_core::error::EventLoopError;
+use winit_core::event_loop::ActiveEventLoop;
+use winit_core::event_loop::pump_events::PumpStatus;
+pub(crate) use winit_headless as headless;
+#[cfg(windows_platform)]
+use winit_win32 as native;
+
+// NOTE: The local `EventLoop` and `PlatformSpecificEventLoopAttributes` shadow the native
+// ones.
+#[allow(unused_imports)]
+pub use self::native::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Backend {
+    Headless,
+}
+
+/// The attributes of the native backend, which the builder extensions set through `Deref`.
+#[derive(Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct PlatformSpecificEventLoopAttributes {
+    native: native::PlatformSpecificEventLoopAttributes,
+    pub(crate) forced_backend: Option<Backend>,
+}
+
+impl Deref for PlatformSpecificEventLoopAttributes {
+    type Target = native::PlatformSpecificEventLoopAttributes;
+
+    fn deref(&self) -> &Self::Target {
+        &self.native
+    }
+}
+
+impl DerefMut for PlatformSpecificEventLoopAttributes {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.native
+    }
+}
+
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum EventLoop {
+    Native(native::EventLoop),
+    Headless(headless::EventLoop),
+}
+
+impl EventLoop {
+    pub(crate) fn new(
+        attributes: &mut PlatformSpecificEventLoopAttributes,
+    ) -> Result<Self, EventLoopError> {
+        // NOTE: The headless backend doesn't talk to the system, thus it's fine to use it from
+        // any thread, e.g. from tests.
+        if attributes.forced_backend == Some(Backend::Headless) {
+            return headless::EventLoop::new().map(EventLoop::Headless);
+        }
+
+        #[cfg(windows_platform)]
+        let evlp = native::EventLoop::new(&mut attributes.native);
+        #[cfg(macos_platform)]
+        let evlp = native::EventLoop::new(&attributes.native);
+        evlp.map(EventLoop::Native)
+    }
+
+    pub fn headless(&self) -> Option<&headless::EventLoop> {
+        match self {
+            EventLoop::Headless(evlp) => Some(evlp),
+            EventLoop::Native(_) => None,
+        }
+    }
+
+    pub fn run_app_on_demand<A: ApplicationHandler>(
+        &mut self,
+        app: A,
+    ) -> Result<(), EventLoopError> {
+        match self {
+            EventLoop::Native(evlp) => evlp.run_app_on_demand(app),
+            EventLoop::Headless(evlp) => evlp.run_app_on_demand(app),
+        }
+    }
+
+    pub fn pump_app_events<A: ApplicationHandler>(
+        &mut self,
+        timeout: Option<Duration>,
+        app: A,
+    ) -> PumpStatus {
+        match self {
+            EventLoop::Native(evlp) => evlp.pump_app_events(timeout, app),
+            EventLoop::Headless(evlp) => evlp.pump_app_events(timeout, app),
+        }
+    }
+
+    pub fn window_target(&self) -> &dyn ActiveEventLoop {
+        match self {
+            EventLoop::Native(evlp) => evlp.window_target(),
+            EventLoop::Headless(evlp) => evlp.window_target(),
+        }
+    }
+}
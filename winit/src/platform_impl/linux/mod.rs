@@ -1,7 +1,7 @@
 #![cfg(free_unix)]
 
-#[cfg(all(not(x11_platform), not(wayland_platform)))]
-compile_error!("Please select a feature to build for unix: `x11`, `wayland`");
+#[cfg(all(not(x11_platform), not(wayland_platform), not(headless_platform)))]
+compile_error!("Please select a feature to build for unix: `x11`, `wayland`, `headless`");
 
 use std::env;
 use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
@@ -12,6 +12,8 @@ use winit_core::application::ApplicationHandler;
 use winit_core::error::{EventLoopError, NotSupportedError};
 use winit_core::event_loop::ActiveEventLoop;
 use winit_core::event_loop::pump_events::PumpStatus;
+#[cfg(headless_platform)]
+pub(crate) use winit_headless as headless;
 #[cfg(wayland_platform)]
 pub(crate) use winit_wayland as wayland;
 #[cfg(x11_platform)]
@@ -23,6 +25,8 @@ pub(crate) enum Backend {
     X,
     #[cfg(wayland_platform)]
     Wayland,
+    #[cfg(headless_platform)]
+    Headless,
 }
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
@@ -37,6 +41,7 @@ pub(crate) struct PlatformSpecificEventLoopAttributes {
 /// match self {
 ///    Enum::X(foo) => foo.something(),
 ///    Enum::Wayland(foo) => foo.something(),
+///    Enum::Headless(foo) => foo.something(),
 /// }
 /// ```
 /// The result can be converted to another enum by adding `; as AnotherEnum`
@@ -47,6 +52,8 @@ macro_rules! x11_or_wayland {
             $enum::X($($c1)*) => $enum2::X($x),
             #[cfg(wayland_platform)]
             $enum::Wayland($($c1)*) => $enum2::Wayland($x),
+            #[cfg(headless_platform)]
+            $enum::Headless($($c1)*) => $enum2::Headless($x),
         }
     };
     (match $what:expr; $enum:ident ( $($c1:tt)* ) => $x:expr) => {
@@ -55,6 +62,8 @@ macro_rules! x11_or_wayland {
             $enum::X($($c1)*) => $x,
             #[cfg(wayland_platform)]
             $enum::Wayland($($c1)*) => $x,
+            #[cfg(headless_platform)]
+            $enum::Headless($($c1)*) => $x,
         }
     };
 }
@@ -66,12 +75,21 @@ pub enum EventLoop {
     Wayland(Box<wayland::EventLoop>),
     #[cfg(x11_platform)]
     X(x11::EventLoop),
+    #[cfg(headless_platform)]
+    Headless(headless::EventLoop),
 }
 
 impl EventLoop {
     pub(crate) fn new(
         attributes: &PlatformSpecificEventLoopAttributes,
     ) -> Result<Self, EventLoopError> {
+        // NOTE: The headless backend doesn't talk to the system, thus it's fine to use it from
+        // any thread, e.g. from tests.
+        #[cfg(headless_platform)]
+        if attributes.forced_backend == Some(Backend::Headless) {
+            return EventLoop::new_headless_any_thread();
+        }
+
         if !attributes.any_thread && !is_main_thread() {
             panic!(
                 "Initializing the event loop outside of the main thread is a significant \
@@ -123,6 +141,8 @@ impl EventLoop {
             Backend::Wayland => EventLoop::new_wayland_any_thread(),
             #[cfg(x11_platform)]
             Backend::X => EventLoop::new_x11_any_thread(),
+            #[cfg(headless_platform)]
+            Backend::Headless => EventLoop::new_headless_any_thread(),
         }
     }
 
@@ -136,17 +156,42 @@ impl EventLoop {
         x11::EventLoop::new().map(EventLoop::X)
     }
 
+    #[cfg(headless_platform)]
+    fn new_headless_any_thread() -> Result<EventLoop, EventLoopError> {
+        headless::EventLoop::new().map(EventLoop::Headless)
+    }
+
     #[inline]
     #[allow(dead_code)]
     pub fn is_wayland(&self) -> bool {
         match *self {
             #[cfg(wayland_platform)]
             EventLoop::Wayland(_) => true,
+            #[cfg(any(x11_platform, headless_platform))]
+            _ => false,
+        }
+    }
+
+    #[inline]
+    #[allow(dead_code)]
+    pub fn is_x11(&self) -> bool {
+        match *self {
             #[cfg(x11_platform)]
+            EventLoop::X(_) => true,
+            #[cfg(any(wayland_platform, headless_platform))]
             _ => false,
         }
     }
 
+    #[cfg(headless_platform)]
+    pub fn headless(&self) -> Option<&headless::EventLoop> {
+        match self {
+            EventLoop::Headless(evlp) => Some(evlp),
+            #[cfg(any(x11_platform, wayland_platform))]
+            _ => None,
+        }
+    }
+
     pub fn run_app_on_demand<A: ApplicationHandler>(
         &mut self,
         app: A,
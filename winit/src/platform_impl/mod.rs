@@ -1,8 +1,10 @@
 #[cfg(android_platform)]
 pub(crate) use winit_android as platform;
-#[cfg(macos_platform)]
+#[cfg(all(macos_platform, not(headless_platform)))]
 pub(crate) use winit_appkit as platform;
-#[cfg(any(x11_platform, wayland_platform))]
+#[cfg(all(any(windows_platform, macos_platform), headless_platform))]
+mod headless;
+#[cfg(any(x11_platform, wayland_platform, all(headless_platform, free_unix)))]
 mod linux;
 #[cfg(orbital_platform)]
 pub(crate) use winit_orbital as platform;
@@ -10,10 +12,12 @@ pub(crate) use winit_orbital as platform;
 pub(crate) use winit_uikit as platform;
 #[cfg(web_platform)]
 pub(crate) use winit_web as platform;
-#[cfg(windows_platform)]
+#[cfg(all(windows_platform, not(headless_platform)))]
 pub(crate) use winit_win32 as platform;
 
-#[cfg(any(x11_platform, wayland_platform))]
+#[cfg(all(any(windows_platform, macos_platform), headless_platform))]
+use self::headless as platform;
+#[cfg(any(x11_platform, wayland_platform, all(headless_platform, free_unix)))]
 use self::linux as platform;
 #[allow(unused_imports)]
 pub use self::platform::*;
@@ -25,6 +29,7 @@ pub use self::platform::*;
     not(android_platform),
     not(x11_platform),
     not(wayland_platform),
+    not(headless_platform),
     not(web_platform),
     not(orbital_platform),
 ))]